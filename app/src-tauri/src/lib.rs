@@ -646,6 +646,13 @@ fn launch_task(
 ) -> Result<SessionInfo, String> {
     let store = state.store.lock().map_err(|e| e.to_string())?;
     let task = store.get_task(&task_id).map_err(map_err)?;
+    if store
+        .get_task_schedule(&task_id)
+        .map_err(map_err)?
+        .is_some()
+    {
+        return Err("scheduled task is a template; its clones launch on schedule".into());
+    }
     let cfg = claustre::config::load().map_err(map_err)?;
 
     // Generate branch name from task title
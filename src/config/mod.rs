@@ -3,12 +3,18 @@
 //! Reads `~/.claustre/config.toml`, provides paths for the database, worktrees,
 //! hooks, and sockets, and handles merging global + project `CLAUDE.md` files.
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
 
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::notify::{
+    Notification, NotificationBackend, NotificationBackendConfig, NotifyEvent, SystemBackend,
+    VoiceBackend,
+};
 
 #[derive(Debug, Default, Deserialize, Clone)]
 pub struct Config {
@@ -29,6 +35,11 @@ pub struct Config {
     #[serde(default)]
     pub layout: Option<LayoutConfig>,
 
+    /// Named pane commands (`[[panes]]`) for layout leaves and the
+    /// split-with-command picker.
+    #[serde(default)]
+    pub panes: Vec<PaneCommand>,
+
     /// Custom theme colours. All fields are optional; missing fields keep
     /// their default values.
     #[serde(default)]
@@ -38,6 +49,18 @@ pub struct Config {
     #[serde(default)]
     pub review_loop: ReviewLoopConfig,
 
+    /// CI fix loop settings (opt-in).
+    #[serde(default)]
+    pub ci_fix: CiFixConfig,
+
+    /// Automatic merge-conflict resolution settings (opt-in).
+    #[serde(default)]
+    pub conflict_resolution: ConflictResolutionConfig,
+
+    /// Auto-merge settings for approved, green PRs.
+    #[serde(default)]
+    pub auto_merge: AutoMergeConfig,
+
     /// Recommended Claude Code permissions for `claustre configure`.
     #[serde(default)]
     pub permissions: RecommendedPermissions,
@@ -57,6 +80,44 @@ pub struct Config {
     /// Sprint board column configuration.
     #[serde(default)]
     pub board: BoardConfig,
+
+    /// Outbound webhooks for task lifecycle events (`[[webhooks]]`).
+    #[serde(default)]
+    pub webhooks: Vec<crate::webhook::WebhookConfig>,
+
+    /// Saved task filter views (`[[views]]`), listed in the command palette.
+    #[serde(default)]
+    pub views: Vec<SavedView>,
+
+    /// Best-of-N fan-out variants and the test command used to compare them.
+    #[serde(default)]
+    pub fanout: FanoutConfig,
+
+    /// Verification gate run before a PR is accepted (`claustre verify`).
+    #[serde(default)]
+    pub verify: VerifyConfig,
+
+    /// Stuck-session detection for working tasks.
+    #[serde(default)]
+    pub watchdog: WatchdogConfig,
+
+    /// Task archiving and `claustre gc` settings.
+    #[serde(default)]
+    pub gc: GcConfig,
+}
+
+/// A named task filter query.
+///
+/// ```toml
+/// [[views]]
+/// name = "Needs review"
+/// query = "status:in_review has:pr -ci:failed"
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+pub struct SavedView {
+    pub name: String,
+    /// Query in the task filter language (see `store::TaskQuery`).
+    pub query: String,
 }
 
 /// Sprint board column configuration.
@@ -171,6 +232,242 @@ impl Default for ClaudeConfig {
     }
 }
 
+/// Verification gate settings. The commands themselves are per project
+/// (`claustre verify <project> <command>...`).
+///
+/// ```toml
+/// [verify]
+/// max_attempts = 3
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+pub struct VerifyConfig {
+    /// Failed verifications per session before the task moves to `error`
+    /// instead of Claude being asked to fix it. Default: 3
+    #[serde(default = "default_verify_max_attempts")]
+    pub max_attempts: u32,
+}
+
+impl Default for VerifyConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_verify_max_attempts(),
+        }
+    }
+}
+
+fn default_verify_max_attempts() -> u32 {
+    3
+}
+
+/// What the watchdog does to a session it finds stuck.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchdogAction {
+    /// Mark the session stuck and notify; leave it running.
+    #[default]
+    Flag,
+    /// Also paste `nudge_message` into the Claude pane.
+    Nudge,
+    /// Also press Esc in the Claude pane to interrupt the current turn.
+    Interrupt,
+    /// Stop the Claude process and move its task to `error`. The session
+    /// and worktree are kept for inspection.
+    Kill,
+}
+
+impl WatchdogAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Flag => "flag",
+            Self::Nudge => "nudge",
+            Self::Interrupt => "interrupt",
+            Self::Kill => "kill",
+        }
+    }
+}
+
+/// Stuck-session watchdog for sessions with a working task.
+///
+/// ```toml
+/// [watchdog]
+/// enabled = true
+/// idle_minutes = 20
+/// repeat_threshold = 5
+/// token_budget = 500000
+/// action = "nudge"
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+pub struct WatchdogConfig {
+    /// Default: false
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Minutes without Claude pane output or hook activity before a session
+    /// counts as stuck. 0 disables the check. Default: 20
+    #[serde(default = "default_watchdog_idle_minutes")]
+    pub idle_minutes: u64,
+
+    /// Identical tool calls in a row (same tool and arguments) that count as
+    /// a loop. 0 disables the check. Default: 5
+    #[serde(default = "default_watchdog_repeat_threshold")]
+    pub repeat_threshold: usize,
+
+    /// Tokens spent since the worktree's diff last changed that count as
+    /// burning without progress. 0 disables the check. Default: 500000
+    #[serde(default = "default_watchdog_token_budget")]
+    pub token_budget: i64,
+
+    /// `flag`, `nudge`, `interrupt` or `kill`. Default: flag
+    #[serde(default)]
+    pub action: WatchdogAction,
+
+    /// Message pasted into the Claude pane for `action = "nudge"`.
+    /// `{reason}` is replaced with why the session was flagged.
+    #[serde(default)]
+    pub nudge_message: Option<String>,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_minutes: default_watchdog_idle_minutes(),
+            repeat_threshold: default_watchdog_repeat_threshold(),
+            token_budget: default_watchdog_token_budget(),
+            action: WatchdogAction::default(),
+            nudge_message: None,
+        }
+    }
+}
+
+fn default_watchdog_idle_minutes() -> u64 {
+    20
+}
+
+fn default_watchdog_repeat_threshold() -> usize {
+    5
+}
+
+fn default_watchdog_token_budget() -> i64 {
+    500_000
+}
+
+/// Archiving of finished tasks and cleanup limits for `claustre gc`.
+///
+/// ```toml
+/// [gc]
+/// archive_after_days = 30
+/// max_log_kb = 1024
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+pub struct GcConfig {
+    /// Days after completion before a done task is archived. 0 disables
+    /// archiving. Default: 30
+    #[serde(default = "default_gc_archive_after_days")]
+    pub archive_after_days: u32,
+
+    /// Logs under ~/.claustre/ larger than this are cut down to their
+    /// newest `max_log_kb` KiB. Default: 1024
+    #[serde(default = "default_gc_max_log_kb")]
+    pub max_log_kb: u64,
+}
+
+impl Default for GcConfig {
+    fn default() -> Self {
+        Self {
+            archive_after_days: default_gc_archive_after_days(),
+            max_log_kb: default_gc_max_log_kb(),
+        }
+    }
+}
+
+impl GcConfig {
+    /// Done tasks completed before this are due for archiving, or `None`
+    /// when archiving is disabled.
+    pub fn archive_cutoff(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<chrono::DateTime<chrono::Utc>> {
+        (self.archive_after_days > 0)
+            .then(|| now - chrono::Duration::days(i64::from(self.archive_after_days)))
+    }
+}
+
+fn default_gc_archive_after_days() -> u32 {
+    30
+}
+
+fn default_gc_max_log_kb() -> u64 {
+    1024
+}
+
+/// Best-of-N fan-out settings.
+///
+/// Each variant runs the task's prompt in its own worktree; unset fields
+/// fall back to `[claude]`.
+///
+/// ```toml
+/// [fanout]
+/// test_command = "cargo test"
+///
+/// [[fanout.variants]]
+/// model = "claude-opus-4-6"
+/// effort = "max"
+///
+/// [[fanout.variants]]
+/// effort = "medium"
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+pub struct FanoutConfig {
+    /// Variants offered by the fan-out dialog. Default: `[claude]` at
+    /// `max`, `high` and `medium` effort
+    #[serde(default = "default_fanout_variants")]
+    pub variants: Vec<FanoutVariant>,
+
+    /// Shell command run in each variant's worktree from the compare view.
+    /// Default: none
+    #[serde(default)]
+    pub test_command: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct FanoutVariant {
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub effort: Option<String>,
+}
+
+impl Default for FanoutConfig {
+    fn default() -> Self {
+        Self {
+            variants: default_fanout_variants(),
+            test_command: None,
+        }
+    }
+}
+
+impl ClaudeConfig {
+    /// This config with a fan-out variant's model and effort applied.
+    #[must_use]
+    pub fn with_overrides(&self, model: Option<&str>, effort: Option<&str>) -> Self {
+        Self {
+            model: model.map_or_else(|| self.model.clone(), str::to_string),
+            effort: effort.map_or_else(|| self.effort.clone(), str::to_string),
+        }
+    }
+}
+
+fn default_fanout_variants() -> Vec<FanoutVariant> {
+    ["max", "high", "medium"]
+        .into_iter()
+        .map(|effort| FanoutVariant {
+            model: None,
+            effort: Some(effort.to_string()),
+        })
+        .collect()
+}
+
 /// Sync settings for automatic state pushing.
 ///
 /// ```toml
@@ -245,6 +542,50 @@ pub struct RecommendedPermissions {
     pub ask: Vec<String>,
 }
 
+impl RecommendedPermissions {
+    /// Whether a `deny` rule blocks running `command` through the Bash tool.
+    ///
+    /// Matches rules the way Claude Code does: a bare `Bash` denies every
+    /// command, `Bash(prefix:*)` matches by prefix, and `*` elsewhere in the
+    /// pattern matches any run of characters.
+    pub fn denies_bash(&self, command: &str) -> bool {
+        self.deny.iter().any(|rule| {
+            if rule == "Bash" {
+                return true;
+            }
+            let Some(pattern) = rule.strip_prefix("Bash(").and_then(|r| r.strip_suffix(')')) else {
+                return false;
+            };
+            match pattern.strip_suffix(":*") {
+                Some(prefix) => command.starts_with(prefix),
+                None => glob_matches(pattern, command),
+            }
+        })
+    }
+}
+
+/// Match `text` against `pattern`, where `*` matches any (possibly empty)
+/// run of characters and every other character matches itself.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*` in the pattern — must match exactly.
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
 impl Default for RecommendedPermissions {
     fn default() -> Self {
         Self {
@@ -275,7 +616,8 @@ fn default_allow() -> Vec<String> {
 
 fn default_deny() -> Vec<String> {
     [
-        "Bash(git push*main*)",
+        "Bash(git push * main)",
+        "Bash(git push *:main)",
         "Bash(git push --force*)",
         "Bash(git push*--force*)",
     ]
@@ -290,7 +632,8 @@ fn default_ask() -> Vec<String> {
 
 /// Describes a pane layout tree for session terminals.
 ///
-/// Each leaf is a terminal pane (`"shell"` or `"claude"`).
+/// Each leaf is a terminal pane: `"shell"`, `"claude"`, the name of a
+/// `[[panes]]` command, or a command given inline with `cmd`.
 /// Splits divide space between two children.
 ///
 /// # Example config.toml
@@ -327,11 +670,25 @@ fn default_ask() -> Vec<String> {
 /// [layout.second.second]
 /// pane = "shell"
 /// ```
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum LayoutConfig {
-    /// A leaf pane: `{ pane = "shell" }` or `{ pane = "claude" }`.
-    Pane { pane: String },
+    /// A leaf pane: `{ pane = "shell" }`, `{ pane = "claude" }`,
+    /// `{ pane = "tests" }` for a `[[panes]]` entry, or an inline command
+    /// such as `{ pane = "logs", cmd = "tail -f app.log" }`.
+    Pane {
+        pane: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cmd: Option<String>,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        env: BTreeMap<String, String>,
+        #[serde(
+            default,
+            alias = "restart-on-exit",
+            skip_serializing_if = "std::ops::Not::not"
+        )]
+        restart_on_exit: bool,
+    },
     /// A split between two children.
     Split {
         /// `"horizontal"` (side by side) or `"vertical"` (stacked).
@@ -343,13 +700,51 @@ pub enum LayoutConfig {
     },
 }
 
+impl LayoutConfig {
+    /// A leaf with no command of its own (`"shell"`, `"claude"` or a
+    /// `[[panes]]` name).
+    pub fn pane(name: &str) -> Self {
+        Self::Pane {
+            pane: name.to_string(),
+            cmd: None,
+            env: BTreeMap::new(),
+            restart_on_exit: false,
+        }
+    }
+}
+
+/// A named command pane.
+///
+/// ```toml
+/// [[panes]]
+/// pane = "tests"
+/// cmd = "cargo watch -x test"
+/// env = { RUST_BACKTRACE = "1" }
+/// restart_on_exit = true
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct PaneCommand {
+    /// Name shown in the pane title, used as a layout leaf and in the picker.
+    pub pane: String,
+    /// Command line, run with `$SHELL -c` in the worktree.
+    pub cmd: String,
+    /// Extra environment variables for the command.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Start the command again when it exits. Default: false
+    #[serde(default, alias = "restart-on-exit")]
+    pub restart_on_exit: bool,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct NotificationConfig {
-    /// Whether voice/sound notifications are enabled. Default: true
+    /// Master switch for all notifications. When true the voice command
+    /// fires alongside the banner and extra backends. Default: true
     #[serde(default = "default_true")]
     pub enabled: bool,
 
-    /// Whether macOS system banner notifications are enabled. Default: true
+    /// Whether system banner notifications are enabled (macOS banner, or
+    /// freedesktop notifications on Linux). Default: true
     #[serde(default = "default_true")]
     pub system: bool,
 
@@ -370,6 +765,16 @@ pub struct NotificationConfig {
     /// Speaking rate for the say command (words per minute). Default: none (system default)
     #[serde(default)]
     pub rate: Option<u32>,
+
+    /// Events that trigger the voice command and system banner.
+    /// Default: `["in_review", "done"]`
+    #[serde(default = "default_notification_events")]
+    pub events: Vec<NotifyEvent>,
+
+    /// Additional notification backends (desktop, webhook, terminal, command).
+    /// Each entry has its own event filter. See [`crate::notify::NotificationBackendConfig`].
+    #[serde(default)]
+    pub backends: Vec<NotificationBackendConfig>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -397,6 +802,134 @@ fn default_review_loop_interval() -> u64 {
     120
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct CiFixConfig {
+    /// Start `claustre ci-fix` in the session tab when a task's CI fails.
+    /// Default: false
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Maximum fix attempts per task before giving up. Default: 3
+    #[serde(default = "default_ci_fix_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Custom instructions placed before the failing logs. When set,
+    /// replaces the built-in prompt.
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+impl Default for CiFixConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_attempts: default_ci_fix_max_attempts(),
+            prompt: None,
+        }
+    }
+}
+
+fn default_ci_fix_max_attempts() -> u32 {
+    3
+}
+
+/// How `claustre resolve-conflicts` brings a conflicting branch up to date.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStrategy {
+    /// Merge the base into the branch and push normally.
+    #[default]
+    Merge,
+    /// Rebase the branch onto the base and push with `--force-with-lease`.
+    Rebase,
+}
+
+impl ConflictStrategy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Rebase => "rebase",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ConflictResolutionConfig {
+    /// Run `claustre resolve-conflicts` when a task's PR starts conflicting.
+    /// Default: false
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// `merge` or `rebase`. Default: merge
+    #[serde(default)]
+    pub strategy: ConflictStrategy,
+
+    /// Maximum resolution attempts per task before giving up. Default: 2
+    #[serde(default = "default_conflict_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Custom instructions placed before the conflicted file list. When set,
+    /// replaces the built-in prompt.
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+impl Default for ConflictResolutionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strategy: ConflictStrategy::default(),
+            max_attempts: default_conflict_max_attempts(),
+            prompt: None,
+        }
+    }
+}
+
+fn default_conflict_max_attempts() -> u32 {
+    2
+}
+
+/// How `gh pr merge` merges an auto-merged PR.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMethod {
+    #[default]
+    Squash,
+    Merge,
+    Rebase,
+}
+
+impl MergeMethod {
+    /// The matching `gh pr merge` flag.
+    pub fn flag(self) -> &'static str {
+        match self {
+            Self::Squash => "--squash",
+            Self::Merge => "--merge",
+            Self::Rebase => "--rebase",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct AutoMergeConfig {
+    /// Policy for projects and tasks without their own
+    /// (`claustre merge-policy`). Default: never
+    #[serde(default)]
+    pub policy: Option<crate::store::MergePolicy>,
+
+    /// `squash`, `merge` or `rebase`. Default: squash
+    #[serde(default)]
+    pub method: MergeMethod,
+
+    /// Only report PRs that would be merged. Default: false
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Treat PRs without any checks as green. Default: false
+    #[serde(default)]
+    pub allow_no_checks: bool,
+}
+
 impl Default for NotificationConfig {
     fn default() -> Self {
         NotificationConfig {
@@ -406,6 +939,8 @@ impl Default for NotificationConfig {
             template: default_notification_template(),
             voice: None,
             rate: None,
+            events: default_notification_events(),
+            backends: Vec::new(),
         }
     }
 }
@@ -422,51 +957,83 @@ fn default_notification_template() -> String {
     "completed {task}".to_string()
 }
 
+fn default_notification_events() -> Vec<NotifyEvent> {
+    vec![NotifyEvent::InReview, NotifyEvent::Done]
+}
+
 /// Embedded notification icon (keyboard-key "C").
 const NOTIFICATION_ICON: &[u8] = include_bytes!("../../assets/claustre-icon.png");
 
 impl NotificationConfig {
-    /// Fire a notification for a completed task.
-    /// Sends both the voice command and a macOS system banner (if enabled).
-    /// If `pr_url` is provided, clicking the system notification opens the PR in a browser.
-    /// Otherwise, clicking brings the terminal app to the foreground.
-    pub fn notify(&self, task_title: &str, pr_url: Option<&str>) {
-        let message = self.template.replace("{task}", task_title);
-
-        if self.enabled {
-            let mut cmd = Command::new(&self.command);
+    /// Fire a notification on every backend that accepts the event.
+    ///
+    /// The voice command (if `enabled`) and the system banner (if `system`)
+    /// only fire for the events listed in `events`; each entry in `backends`
+    /// applies its own filter. If `pr_url` is provided, clicking the macOS
+    /// banner opens the PR in a browser.
+    pub fn notify(&self, notification: &Notification<'_>) {
+        self.notify_where(notification, |_| true);
+    }
 
-            // If using "say", support voice and rate options
-            if self.command == "say" {
-                if let Some(ref voice) = self.voice {
-                    cmd.args(["-v", voice]);
-                }
-                if let Some(rate) = self.rate {
-                    cmd.args(["-r", &rate.to_string()]);
-                }
-            }
+    /// [`notify`](Self::notify) from a process outside the TUI: skips
+    /// backends that write to the host terminal, which the TUI relays.
+    pub fn notify_outside_tui(&self, notification: &Notification<'_>) {
+        self.notify_where(notification, |backend| !backend.needs_host_terminal());
+    }
 
-            cmd.arg(&message);
+    /// Fire only the backends that write to the host terminal.
+    pub fn notify_host_terminal(&self, notification: &Notification<'_>) {
+        self.notify_where(notification, |backend| backend.needs_host_terminal());
+    }
 
-            match cmd.spawn() {
-                Ok(mut child) => {
-                    std::thread::spawn(move || {
-                        let _ = child.wait();
-                    });
-                }
-                Err(e) => {
-                    tracing::warn!("notification command failed: {e}");
-                }
+    fn notify_where(
+        &self,
+        notification: &Notification<'_>,
+        include: impl Fn(&dyn NotificationBackend) -> bool,
+    ) {
+        let message = self.message_for(notification);
+        for backend in self.backends() {
+            if !include(backend.as_ref()) || !backend.accepts(notification.event) {
+                continue;
+            }
+            if let Err(e) = backend.send(notification, &message) {
+                tracing::warn!("{} notification failed: {e:#}", backend.name());
             }
         }
+    }
+
+    /// Render the message for a notification. `template` applies to
+    /// `in_review` and `done`; other events use a built-in message.
+    pub fn message_for(&self, notification: &Notification<'_>) -> String {
+        match notification.event {
+            NotifyEvent::InReview | NotifyEvent::Done => notification.render(&self.template, ""),
+            event => event.default_message(notification.task_title),
+        }
+    }
 
+    /// All active backends: the legacy voice/system pair plus configured
+    /// extras. None at all when `enabled` is off.
+    pub fn backends(&self) -> Vec<Box<dyn NotificationBackend>> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        let mut backends: Vec<Box<dyn NotificationBackend>> = vec![Box::new(VoiceBackend {
+            command: self.command.clone(),
+            voice: self.voice.clone(),
+            rate: self.rate,
+            events: self.events.clone(),
+        })];
         if self.system {
-            Self::system_notify(task_title, &message, pr_url);
+            backends.push(Box::new(SystemBackend {
+                events: self.events.clone(),
+            }));
         }
+        backends.extend(self.backends.iter().map(NotificationBackendConfig::build));
+        backends
     }
 
     /// Ensure the notification icon is written to disk and return its path.
-    fn ensure_icon() -> Option<PathBuf> {
+    pub(crate) fn ensure_icon() -> Option<PathBuf> {
         let path = base_dir().ok()?.join("claustre-icon.png");
         if !path.exists()
             && let Err(e) = fs::write(&path, NOTIFICATION_ICON)
@@ -477,13 +1044,14 @@ impl NotificationConfig {
         Some(path)
     }
 
-    /// Send a macOS system banner notification.
+    /// Send a macOS system banner notification. On other platforms
+    /// `SystemBackend` uses freedesktop notifications instead.
     /// Tries `terminal-notifier` first (supports custom icons and click actions),
     /// falls back to `osascript`.
     ///
     /// When `pr_url` is provided, clicking the notification opens the PR in a browser.
     /// Otherwise, clicking brings the terminal app to the foreground.
-    fn system_notify(task_title: &str, message: &str, pr_url: Option<&str>) {
+    pub(crate) fn system_notify(task_title: &str, message: &str, pr_url: Option<&str>) {
         let icon_path = Self::ensure_icon();
 
         // Try terminal-notifier first (supports custom app icon + click actions)
@@ -839,8 +1407,8 @@ pane = "claude"
             } => {
                 assert_eq!(direction, "horizontal");
                 assert_eq!(ratio, Some(50));
-                assert!(matches!(*first, LayoutConfig::Pane { ref pane } if pane == "shell"));
-                assert!(matches!(*second, LayoutConfig::Pane { ref pane } if pane == "claude"));
+                assert_eq!(*first, LayoutConfig::pane("shell"));
+                assert_eq!(*second, LayoutConfig::pane("claude"));
             }
             LayoutConfig::Pane { .. } => panic!("expected Split"),
         }
@@ -893,6 +1461,42 @@ pane = "shell"
         }
     }
 
+    #[test]
+    fn parse_pane_commands_and_inline_leaves() {
+        let toml_str = r#"
+[[panes]]
+pane = "tests"
+cmd = "cargo watch -x test"
+env = { RUST_BACKTRACE = "1" }
+restart-on-exit = true
+
+[layout]
+direction = "horizontal"
+
+[layout.first]
+pane = "tests"
+
+[layout.second]
+pane = "logs"
+cmd = "tail -f app.log"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.panes.len(), 1);
+        assert_eq!(config.panes[0].pane, "tests");
+        assert_eq!(config.panes[0].env["RUST_BACKTRACE"], "1");
+        assert!(config.panes[0].restart_on_exit);
+
+        let Some(LayoutConfig::Split { first, second, .. }) = config.layout else {
+            panic!("expected Split");
+        };
+        assert_eq!(*first, LayoutConfig::pane("tests"));
+        assert!(matches!(
+            *second,
+            LayoutConfig::Pane { ref cmd, restart_on_exit: false, .. }
+                if cmd.as_deref() == Some("tail -f app.log")
+        ));
+    }
+
     #[test]
     fn parse_layout_default_ratio() {
         let toml_str = r#"
@@ -924,11 +1528,84 @@ pane = "claude"
             template: "task {task} is done".to_string(),
             voice: None,
             rate: None,
+            events: default_notification_events(),
+            backends: Vec::new(),
         };
-        let message = config.template.replace("{task}", "my-task");
+        let message = config.message_for(&Notification {
+            event: NotifyEvent::Done,
+            task_title: "my-task",
+            project: None,
+            pr_url: None,
+        });
         assert_eq!(message, "task my-task is done");
     }
 
+    #[test]
+    fn notification_message_for_non_template_events() {
+        let config = NotificationConfig::default();
+        let message = config.message_for(&Notification {
+            event: NotifyEvent::CiFailed,
+            task_title: "my-task",
+            project: None,
+            pr_url: None,
+        });
+        assert_eq!(message, "CI failed: my-task");
+    }
+
+    #[test]
+    fn default_notification_events_are_review_and_done() {
+        let config = NotificationConfig::default();
+        assert_eq!(
+            config.events,
+            vec![NotifyEvent::InReview, NotifyEvent::Done]
+        );
+        assert!(config.backends.is_empty());
+        // voice + system are the only default backends
+        assert_eq!(config.backends().len(), 2);
+    }
+
+    #[test]
+    fn parse_notification_backends() {
+        let toml_str = r#"
+[notifications]
+system = false
+events = ["ci_failed"]
+
+[[notifications.backends]]
+type = "webhook"
+url = "http://localhost:9999/hook"
+events = ["conflict"]
+
+[[notifications.backends]]
+type = "terminal"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.notifications.events, vec![NotifyEvent::CiFailed]);
+        let backends = config.notifications.backends();
+        assert_eq!(backends.len(), 3);
+        assert_eq!(backends[0].name(), "voice");
+        assert_eq!(backends[1].name(), "webhook");
+        assert!(backends[1].accepts(NotifyEvent::Conflict));
+        assert!(!backends[1].accepts(NotifyEvent::Done));
+        assert_eq!(backends[2].name(), "terminal");
+        assert!(backends[2].accepts(NotifyEvent::NeedsInput));
+    }
+
+    #[test]
+    fn disabled_notifications_have_no_backends() {
+        let config: Config = toml::from_str(
+            r#"
+[notifications]
+enabled = false
+
+[[notifications.backends]]
+type = "terminal"
+"#,
+        )
+        .unwrap();
+        assert!(config.notifications.backends().is_empty());
+    }
+
     #[test]
     fn path_helpers_build_expected_paths() {
         let base = base_dir().unwrap();
@@ -1013,6 +1690,25 @@ prompt = "Check PR comments and fix issues"
         );
     }
 
+    #[test]
+    fn parse_ci_fix_config() {
+        let config: Config = toml::from_str(
+            r"
+[ci_fix]
+enabled = true
+max_attempts = 5
+",
+        )
+        .unwrap();
+        assert!(config.ci_fix.enabled);
+        assert_eq!(config.ci_fix.max_attempts, 5);
+        assert!(config.ci_fix.prompt.is_none());
+
+        let config = Config::default();
+        assert!(!config.ci_fix.enabled);
+        assert_eq!(config.ci_fix.max_attempts, 3);
+    }
+
     #[test]
     fn parse_review_loop_partial_config() {
         let toml_str = r"
@@ -1060,6 +1756,154 @@ rate = 200
 
     // ── Permissions config parsing ──
 
+    #[test]
+    fn parse_conflict_resolution_config() {
+        let config: Config = toml::from_str(
+            r#"
+[conflict_resolution]
+enabled = true
+strategy = "rebase"
+"#,
+        )
+        .unwrap();
+        assert!(config.conflict_resolution.enabled);
+        assert_eq!(
+            config.conflict_resolution.strategy,
+            ConflictStrategy::Rebase
+        );
+        assert_eq!(config.conflict_resolution.max_attempts, 2);
+
+        let config = Config::default();
+        assert!(!config.conflict_resolution.enabled);
+        assert_eq!(config.conflict_resolution.strategy, ConflictStrategy::Merge);
+    }
+
+    #[test]
+    fn parse_watchdog_config() {
+        let config: Config = toml::from_str(
+            r#"
+[watchdog]
+enabled = true
+idle_minutes = 0
+action = "interrupt"
+"#,
+        )
+        .unwrap();
+        assert!(config.watchdog.enabled);
+        assert_eq!(config.watchdog.idle_minutes, 0);
+        assert_eq!(config.watchdog.repeat_threshold, 5);
+        assert_eq!(config.watchdog.action, WatchdogAction::Interrupt);
+
+        let config = Config::default();
+        assert!(!config.watchdog.enabled);
+        assert_eq!(config.watchdog.action, WatchdogAction::Flag);
+        assert!(toml::from_str::<Config>("[watchdog]\naction = \"reboot\"").is_err());
+    }
+
+    #[test]
+    fn parse_gc_config() {
+        let config: Config = toml::from_str("[gc]\narchive_after_days = 0\n").unwrap();
+        assert_eq!(config.gc.archive_after_days, 0);
+        assert_eq!(config.gc.max_log_kb, 1024);
+        assert!(config.gc.archive_cutoff(chrono::Utc::now()).is_none());
+
+        let config = Config::default();
+        let now = chrono::Utc::now();
+        assert_eq!(
+            config.gc.archive_cutoff(now),
+            Some(now - chrono::Duration::days(30))
+        );
+    }
+
+    #[test]
+    fn parse_auto_merge_config() {
+        let config: Config = toml::from_str(
+            r#"
+[auto_merge]
+policy = "approved:2"
+method = "rebase"
+dry_run = true
+allow_no_checks = true
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.auto_merge.policy,
+            Some(crate::store::MergePolicy::Approved(2))
+        );
+        assert_eq!(config.auto_merge.method, MergeMethod::Rebase);
+        assert!(config.auto_merge.dry_run);
+        assert!(config.auto_merge.allow_no_checks);
+
+        let config = Config::default();
+        assert_eq!(config.auto_merge.policy, None);
+        assert_eq!(config.auto_merge.method, MergeMethod::Squash);
+        assert!(!config.auto_merge.allow_no_checks);
+
+        assert!(toml::from_str::<Config>("[auto_merge]\npolicy = \"often\"").is_err());
+    }
+
+    #[test]
+    fn parse_fanout_config() {
+        let config: Config = toml::from_str(
+            r#"
+[fanout]
+test_command = "cargo test"
+
+[[fanout.variants]]
+model = "claude-sonnet-4-6"
+
+[[fanout.variants]]
+effort = "low"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.fanout.test_command.as_deref(), Some("cargo test"));
+        assert_eq!(config.fanout.variants.len(), 2);
+        let claude = config.claude.with_overrides(
+            config.fanout.variants[0].model.as_deref(),
+            config.fanout.variants[0].effort.as_deref(),
+        );
+        assert_eq!(claude.model, "claude-sonnet-4-6");
+        assert_eq!(claude.effort, "max");
+
+        let config = Config::default();
+        assert_eq!(config.fanout.variants.len(), 3);
+        assert_eq!(config.fanout.test_command, None);
+    }
+
+    #[test]
+    fn default_deny_rules_block_force_push_and_main() {
+        let perms = RecommendedPermissions::default();
+        assert!(perms.denies_bash("git push --force-with-lease origin HEAD:feat"));
+        assert!(perms.denies_bash("git push origin main"));
+        assert!(perms.denies_bash("git push -u origin HEAD:main"));
+        assert!(!perms.denies_bash("git push origin HEAD:feat"));
+        // Only the exact ref, not branches that merely contain "main"
+        assert!(!perms.denies_bash("git push origin HEAD:task/maintain-x"));
+        assert!(!perms.denies_bash("git push origin main-fixes"));
+    }
+
+    #[test]
+    fn denies_bash_rule_forms() {
+        let perms = RecommendedPermissions {
+            allow: vec![],
+            deny: vec!["Bash(rm:*)".into(), "Bash(git push * HEAD:release)".into()],
+            ask: vec![],
+        };
+        assert!(perms.denies_bash("rm -rf target"));
+        assert!(perms.denies_bash("git push origin HEAD:release"));
+        assert!(!perms.denies_bash("git push origin HEAD:release-2"));
+        assert!(!perms.denies_bash("ls"));
+
+        let perms = RecommendedPermissions {
+            allow: vec![],
+            deny: vec!["Bash".into()],
+            ask: vec![],
+        };
+        assert!(perms.denies_bash("echo hi"));
+    }
+
     #[test]
     fn default_permissions_have_expected_entries() {
         let perms = RecommendedPermissions::default();
@@ -1068,7 +1912,7 @@ rate = 200
         assert!(perms.allow.contains(&"Edit(*)".to_string()));
         assert!(perms.allow.contains(&"Write(*)".to_string()));
         assert!(perms.deny.iter().any(|d| d.contains("push --force")));
-        assert!(perms.deny.iter().any(|d| d.contains("push*main")));
+        assert!(perms.deny.contains(&"Bash(git push * main)".to_string()));
         assert!(perms.ask.contains(&"Bash(rm:*)".to_string()));
     }
 
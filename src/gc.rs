@@ -0,0 +1,556 @@
+//! Archiving and garbage collection (`claustre gc`).
+//!
+//! [`plan`] works out what can go without touching anything, so `claustre gc`
+//! can print a dry-run report; [`apply`] then carries the plan out:
+//!
+//! - done tasks completed more than `[gc] archive_after_days` ago are archived
+//! - closed sessions no queued task points at are deleted
+//! - worktrees under `~/.claustre/worktrees/` without an open session are removed
+//! - `~/.claustre/tmp/<session>` progress dirs of sessions that aren't open
+//! - trust entries in `~/.claude.json` for worktrees that no longer exist
+//! - `*.log` files in `~/.claustre/` over `[gc] max_log_kb` are cut to their tail
+//!
+//! Worktrees and progress dirs with any file touched within the last
+//! [`GRACE`] are kept, so a session being launched while gc runs doesn't
+//! lose its files. Worktrees with uncommitted changes are never removed.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+
+use crate::config::{self, GcConfig};
+use crate::store::{Session, Store, Task};
+
+/// Files modified more recently than this are never collected.
+pub const GRACE: Duration = Duration::from_secs(60 * 60);
+
+/// Everything one `claustre gc` run would archive or remove.
+#[derive(Debug, Default)]
+pub struct GcPlan {
+    pub archive_cutoff: Option<chrono::DateTime<chrono::Utc>>,
+    pub tasks_to_archive: Vec<Task>,
+    pub sessions: Vec<Session>,
+    pub worktrees: Vec<PathBuf>,
+    pub progress_dirs: Vec<PathBuf>,
+    /// Keys of `projects` entries in `~/.claude.json`.
+    pub trust_entries: Vec<String>,
+    /// Oversized logs with their current size in bytes.
+    pub logs: Vec<(PathBuf, u64)>,
+    /// `[gc] max_log_kb` in bytes.
+    max_log_bytes: u64,
+}
+
+impl GcPlan {
+    pub fn is_empty(&self) -> bool {
+        self.tasks_to_archive.is_empty()
+            && self.sessions.is_empty()
+            && self.worktrees.is_empty()
+            && self.progress_dirs.is_empty()
+            && self.trust_entries.is_empty()
+            && self.logs.is_empty()
+    }
+}
+
+/// Where gc looks: `~/.claustre/` and `~/.claude.json`, or temp dirs in tests.
+struct Paths {
+    base: PathBuf,
+    claude_json: PathBuf,
+}
+
+impl Paths {
+    fn from_env() -> Result<Self> {
+        let home = dirs::home_dir().context("could not determine home directory")?;
+        Ok(Self {
+            base: config::base_dir()?,
+            claude_json: home.join(".claude.json"),
+        })
+    }
+
+    fn worktrees(&self) -> PathBuf {
+        self.base.join("worktrees")
+    }
+
+    fn tmp(&self) -> PathBuf {
+        self.base.join("tmp")
+    }
+}
+
+/// Work out what `claustre gc` would do at `now`, without changing anything.
+pub fn plan(store: &Store, cfg: &GcConfig, now: chrono::DateTime<chrono::Utc>) -> Result<GcPlan> {
+    plan_at(store, cfg, now, &Paths::from_env()?)
+}
+
+/// Carry out a plan from [`plan`]. Filesystem removals are best-effort: a
+/// path that can't be removed is reported on stderr and skipped.
+pub fn apply(store: &Store, plan: &GcPlan) -> Result<()> {
+    apply_at(store, plan, &Paths::from_env()?)
+}
+
+/// Archive done tasks that are due, as configured by `[gc]`. Returns how
+/// many were archived.
+pub fn archive_due(store: &Store, cfg: &GcConfig) -> Result<usize> {
+    match cfg.archive_cutoff(chrono::Utc::now()) {
+        Some(cutoff) => store.archive_done_tasks(cutoff),
+        None => Ok(0),
+    }
+}
+
+fn plan_at(
+    store: &Store,
+    cfg: &GcConfig,
+    now: chrono::DateTime<chrono::Utc>,
+    paths: &Paths,
+) -> Result<GcPlan> {
+    let archive_cutoff = cfg.archive_cutoff(now);
+    let tasks_to_archive = match archive_cutoff {
+        Some(cutoff) => store.list_archivable_tasks(cutoff)?,
+        None => Vec::new(),
+    };
+    let sessions = store.list_purgeable_sessions(archive_cutoff)?;
+
+    let open = store.list_open_sessions()?;
+    let open_ids: HashSet<&str> = open.iter().map(|s| s.id.as_str()).collect();
+    let open_worktrees: HashSet<&Path> = open.iter().map(|s| Path::new(&s.worktree_path)).collect();
+
+    let mut worktrees = Vec::new();
+    find_worktrees(&paths.worktrees(), &mut worktrees)?;
+    worktrees.retain(|path| {
+        !open_worktrees.contains(path.as_path()) && !is_recent(path) && is_clean(path)
+    });
+    worktrees.sort();
+
+    let mut progress_dirs: Vec<PathBuf> = read_dir_paths(&paths.tmp())?
+        .into_iter()
+        .filter(|path| {
+            path.is_dir()
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|id| !open_ids.contains(id))
+                && !is_recent(path)
+        })
+        .collect();
+    progress_dirs.sort();
+
+    let trust_entries = stale_trust_entries(&paths.claude_json, &paths.worktrees(), &worktrees)?;
+
+    let max_log_bytes = cfg.max_log_kb * 1024;
+    let mut logs: Vec<(PathBuf, u64)> = read_dir_paths(&paths.base)?
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|path| {
+            let len = fs::metadata(&path).ok()?.len();
+            (path.is_file() && len > max_log_bytes).then_some((path, len))
+        })
+        .collect();
+    logs.sort();
+
+    Ok(GcPlan {
+        archive_cutoff,
+        tasks_to_archive,
+        sessions,
+        worktrees,
+        progress_dirs,
+        trust_entries,
+        logs,
+        max_log_bytes,
+    })
+}
+
+fn apply_at(store: &Store, plan: &GcPlan, paths: &Paths) -> Result<()> {
+    if let Some(cutoff) = plan.archive_cutoff {
+        store.archive_done_tasks(cutoff)?;
+    }
+    for session in &plan.sessions {
+        store.purge_session(&session.id)?;
+    }
+
+    let projects = store.list_projects()?;
+    let worktree_base = paths.worktrees();
+    for path in &plan.worktrees {
+        // Worktrees live at <base>/<project name>/<branch>; let git drop its
+        // bookkeeping when the owning repo is still known.
+        let project = path
+            .strip_prefix(&worktree_base)
+            .ok()
+            .and_then(|rel| rel.components().next())
+            .and_then(|name| {
+                projects
+                    .iter()
+                    .find(|p| name.as_os_str() == p.name.as_str())
+            });
+        if let Some(project) = project {
+            let _ = crate::session::remove_worktree(Path::new(&project.repo_path), path);
+        }
+        remove_dir(path);
+    }
+    for path in &plan.progress_dirs {
+        remove_dir(path);
+    }
+
+    remove_trust_entries(&paths.claude_json, &plan.trust_entries)?;
+
+    for (path, _) in &plan.logs {
+        if let Err(e) = truncate_log(path, plan.max_log_bytes) {
+            eprintln!("gc: could not trim {}: {e:#}", path.display());
+        }
+    }
+    Ok(())
+}
+
+fn remove_dir(path: &Path) {
+    if path.exists()
+        && let Err(e) = fs::remove_dir_all(path)
+    {
+        eprintln!("gc: could not remove {}: {e}", path.display());
+    }
+}
+
+fn read_dir_paths(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))?;
+    Ok(entries.flatten().map(|e| e.path()).collect())
+}
+
+/// Collect git worktree roots (directories with a `.git` entry) under `dir`.
+/// Branch names may contain `/`, so worktrees can sit at any depth.
+fn find_worktrees(dir: &Path, found: &mut Vec<PathBuf>) -> Result<()> {
+    for path in read_dir_paths(dir)? {
+        if !path.is_dir() || path.is_symlink() {
+            continue;
+        }
+        if path.join(".git").exists() {
+            found.push(path);
+        } else {
+            find_worktrees(&path, found)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether `path` or anything below it was modified within [`GRACE`].
+/// Editing a file doesn't touch its parent directories, so the whole tree
+/// is walked; unreadable entries count as recent.
+fn is_recent(path: &Path) -> bool {
+    let recent = fs::symlink_metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_none_or(|age| age < GRACE);
+    if recent || !path.is_dir() || path.is_symlink() {
+        return recent;
+    }
+    match fs::read_dir(path) {
+        Ok(entries) => entries.flatten().any(|entry| is_recent(&entry.path())),
+        Err(_) => true,
+    }
+}
+
+/// Whether the worktree has no uncommitted or untracked changes. A worktree
+/// git can't inspect counts as dirty, so it is kept.
+fn is_clean(path: &Path) -> bool {
+    std::process::Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["status", "--porcelain"])
+        .output()
+        .is_ok_and(|out| out.status.success() && out.stdout.is_empty())
+}
+
+/// Trust entries for paths under the worktree base that are gone or about
+/// to be removed as orphans.
+fn stale_trust_entries(
+    claude_json: &Path,
+    worktree_base: &Path,
+    orphans: &[PathBuf],
+) -> Result<Vec<String>> {
+    let Some(config) = read_claude_json(claude_json)? else {
+        return Ok(Vec::new());
+    };
+    let mut stale: Vec<String> = config
+        .get("projects")
+        .and_then(|p| p.as_object())
+        .into_iter()
+        .flat_map(|projects| projects.keys())
+        .filter(|key| {
+            let path = Path::new(key.as_str());
+            path.starts_with(worktree_base)
+                && path != worktree_base
+                && (!path.exists() || orphans.iter().any(|o| o == path))
+        })
+        .cloned()
+        .collect();
+    stale.sort();
+    Ok(stale)
+}
+
+fn read_claude_json(path: &Path) -> Result<Option<serde_json::Value>> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    let config = serde_json::from_str(&content)
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+    Ok(Some(config))
+}
+
+fn remove_trust_entries(claude_json: &Path, keys: &[String]) -> Result<()> {
+    if keys.is_empty() {
+        return Ok(());
+    }
+    let Some(mut config) = read_claude_json(claude_json)? else {
+        return Ok(());
+    };
+    let Some(projects) = config.get_mut("projects").and_then(|p| p.as_object_mut()) else {
+        return Ok(());
+    };
+    let before = projects.len();
+    for key in keys {
+        projects.remove(key);
+    }
+    if projects.len() != before {
+        fs::write(claude_json, serde_json::to_string_pretty(&config)?)
+            .with_context(|| format!("failed to write {}", claude_json.display()))?;
+    }
+    Ok(())
+}
+
+/// Keep the newest `max_bytes` of a log, starting at a line boundary.
+fn truncate_log(path: &Path, max_bytes: u64) -> Result<()> {
+    let content = fs::read(path)?;
+    let keep_from = content
+        .len()
+        .saturating_sub(usize::try_from(max_bytes).unwrap_or(usize::MAX));
+    if keep_from == 0 {
+        return Ok(());
+    }
+    let start = content[keep_from..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(content.len(), |i| keep_from + i + 1);
+    fs::write(path, &content[start..])?;
+    Ok(())
+}
+
+/// Print the dry-run report for a plan.
+pub fn print_plan(plan: &GcPlan) {
+    if let Some(cutoff) = plan.archive_cutoff {
+        println!(
+            "Archive {} done task(s) completed before {}",
+            plan.tasks_to_archive.len(),
+            cutoff.format("%Y-%m-%d")
+        );
+        for task in &plan.tasks_to_archive {
+            println!("  {}", task.title);
+        }
+    }
+    println!("Purge {} closed session(s)", plan.sessions.len());
+    for session in &plan.sessions {
+        println!("  {} ({})", session.branch_name, session.id);
+    }
+    let sections: [(&str, Vec<String>); 4] = [
+        (
+            "orphan worktree(s)",
+            plan.worktrees
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect(),
+        ),
+        (
+            "stale progress dir(s)",
+            plan.progress_dirs
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect(),
+        ),
+        (
+            "stale trust entr(ies) in ~/.claude.json",
+            plan.trust_entries.clone(),
+        ),
+        (
+            "oversized log(s)",
+            plan.logs
+                .iter()
+                .map(|(p, len)| format!("{} ({} KiB)", p.display(), len / 1024))
+                .collect(),
+        ),
+    ];
+    for (label, items) in sections {
+        println!("Remove {} {label}", items.len());
+        for item in items {
+            println!("  {item}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{PushMode, TaskMode, TaskStatus};
+
+    /// Backdate `path` and everything below it past the grace period.
+    fn age(path: &Path) {
+        if path.is_dir() {
+            for entry in fs::read_dir(path).unwrap().flatten() {
+                age(&entry.path());
+            }
+        }
+        let old = SystemTime::now() - GRACE * 2;
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .or_else(|_| fs::File::open(path))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+    }
+
+    /// A clean git checkout standing in for a worktree.
+    fn worktree(dir: &Path) -> PathBuf {
+        fs::create_dir_all(dir).unwrap();
+        let status = std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        age(dir);
+        dir.to_path_buf()
+    }
+
+    #[test]
+    fn plan_and_apply_collect_only_stale_state() {
+        let home = tempfile::tempdir().unwrap();
+        let paths = Paths {
+            base: home.path().join(".claustre"),
+            claude_json: home.path().join(".claude.json"),
+        };
+        let store = Store::open_in_memory().unwrap();
+        let project = store
+            .create_project("app", "/tmp/app", "main", true)
+            .unwrap();
+
+        let live = worktree(&paths.worktrees().join("app/task/live"));
+        let orphan = worktree(&paths.worktrees().join("app/task/orphan"));
+        // Edited deep inside, which leaves the root's mtime old.
+        let fresh = worktree(&paths.worktrees().join("app/task/fresh"));
+        fs::create_dir_all(fresh.join("src/deep")).unwrap();
+        fs::write(fresh.join("src/deep/wip.rs"), "fn wip() {}").unwrap();
+        age(&fresh.join("src"));
+        fs::write(fresh.join("src/deep/wip.rs"), "fn wip() { todo!() }").unwrap();
+        // Old uncommitted work.
+        let dirty = worktree(&paths.worktrees().join("app/task/dirty"));
+        fs::write(dirty.join("notes.txt"), "unsaved").unwrap();
+        age(&dirty);
+
+        let session = store
+            .create_session(&project.id, "task/live", live.to_str().unwrap(), "app:live")
+            .unwrap();
+        let closed = store
+            .create_session(
+                &project.id,
+                "task/orphan",
+                orphan.to_str().unwrap(),
+                "app:o",
+            )
+            .unwrap();
+        store.close_session(&closed.id).unwrap();
+
+        let live_tmp = paths.tmp().join(&session.id);
+        let stale_tmp = paths.tmp().join(&closed.id);
+        for dir in [&live_tmp, &stale_tmp] {
+            fs::create_dir_all(dir).unwrap();
+            age(dir);
+        }
+        let stray_file = paths.tmp().join("stray.txt");
+        fs::write(&stray_file, "").unwrap();
+        age(&stray_file);
+
+        let gone = paths.worktrees().join("app/task/gone");
+        let trusted: serde_json::Map<String, serde_json::Value> = [
+            live.to_str().unwrap(),
+            orphan.to_str().unwrap(),
+            gone.to_str().unwrap(),
+            "/home/me/code",
+        ]
+        .into_iter()
+        .map(|key| {
+            (
+                key.to_string(),
+                serde_json::json!({ "hasTrustDialogAccepted": true }),
+            )
+        })
+        .collect();
+        let trust = serde_json::json!({ "projects": trusted });
+        fs::write(&paths.claude_json, trust.to_string()).unwrap();
+
+        let log = paths.base.join("hook-debug.log");
+        fs::write(&log, "old line\n".repeat(300)).unwrap();
+        let small_log = paths.base.join("other.log");
+        fs::write(&small_log, "fine\n").unwrap();
+
+        let task = store
+            .create_task(
+                &project.id,
+                "shipped",
+                "",
+                TaskMode::Supervised,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap();
+        store
+            .update_task_status(&task.id, TaskStatus::Working)
+            .unwrap();
+        store
+            .update_task_status(&task.id, TaskStatus::Done)
+            .unwrap();
+
+        let cfg = GcConfig {
+            archive_after_days: 1,
+            max_log_kb: 1,
+        };
+        let now = chrono::Utc::now() + chrono::Duration::days(2);
+        let plan = plan_at(&store, &cfg, now, &paths).unwrap();
+        assert_eq!(plan.tasks_to_archive.len(), 1);
+        assert_eq!(plan.sessions.len(), 1);
+        assert_eq!(plan.sessions[0].id, closed.id);
+        assert_eq!(plan.worktrees, std::slice::from_ref(&orphan));
+        assert_eq!(plan.progress_dirs, std::slice::from_ref(&stale_tmp));
+        let mut expected_trust = vec![
+            gone.to_str().unwrap().to_string(),
+            orphan.to_str().unwrap().to_string(),
+        ];
+        expected_trust.sort();
+        assert_eq!(plan.trust_entries, expected_trust);
+        assert_eq!(plan.logs.len(), 1);
+        assert_eq!(plan.logs[0].0, log);
+
+        // The plan is a dry run: nothing has changed yet.
+        assert!(orphan.exists());
+        assert!(store.get_task(&task.id).unwrap().archived_at.is_none());
+
+        apply_at(&store, &plan, &paths).unwrap();
+        assert!(store.get_task(&task.id).unwrap().archived_at.is_some());
+        assert!(store.get_session(&closed.id).is_err());
+        assert!(!orphan.exists());
+        assert!(live.exists() && fresh.exists() && dirty.exists());
+        assert!(stray_file.exists());
+        assert!(!stale_tmp.exists() && live_tmp.exists());
+        let trust = read_claude_json(&paths.claude_json).unwrap().unwrap();
+        assert_eq!(trust["projects"].as_object().unwrap().len(), 2);
+        assert!(trust["projects"].get(live.to_str().unwrap()).is_some());
+        let trimmed = fs::read_to_string(&log).unwrap();
+        assert!(trimmed.len() <= 1024 && trimmed.starts_with("old line\n"));
+        assert_eq!(fs::read_to_string(&small_log).unwrap(), "fine\n");
+
+        let again = plan_at(&store, &cfg, now, &paths).unwrap();
+        assert!(again.is_empty(), "{again:?}");
+    }
+}
@@ -1,9 +1,13 @@
-//! GitHub CLI wrapper for sprint board data.
+//! GitHub CLI wrapper for sprint board data and CI logs.
 //!
 //! Uses `gh` via `std::process::Command` to fetch issues and milestones.
 //! The sprint board maps GitHub milestones to sprints and uses issue labels
-//! to assign issues to board columns.
+//! to assign issues to board columns. The CI fix loop uses the workflow run
+//! helpers to pull failing job logs, auto-merge uses `merge_pr`, task
+//! retries use `pr_feedback` to carry reviewer comments forward, and fan-out
+//! promotion opens the winner's PR with `create_pr`.
 
+use std::path::Path;
 use std::process::Command;
 
 use anyhow::{Context, Result};
@@ -124,6 +128,171 @@ pub fn fetch_milestones(repo_path: &str) -> Result<Vec<GitHubMilestone>> {
     Ok(milestones)
 }
 
+/// ID of the most recent failed workflow run on `branch`, if any.
+pub fn latest_failed_run(repo_path: &str, branch: &str) -> Result<Option<String>> {
+    let output = Command::new("gh")
+        .args([
+            "run",
+            "list",
+            "--branch",
+            branch,
+            "--status",
+            "failure",
+            "--limit",
+            "1",
+            "--json",
+            "databaseId",
+            "--jq",
+            ".[0].databaseId // empty",
+        ])
+        .current_dir(repo_path)
+        .output()
+        .context("failed to run `gh run list` — is `gh` installed and authenticated?")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh run list failed: {stderr}");
+    }
+
+    let id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok((!id.is_empty()).then_some(id))
+}
+
+/// Logs of the failed jobs in a workflow run (`gh run view --log-failed`).
+pub fn failed_run_logs(repo_path: &str, run_id: &str) -> Result<String> {
+    let output = Command::new("gh")
+        .args(["run", "view", run_id, "--log-failed"])
+        .current_dir(repo_path)
+        .output()
+        .context("failed to run `gh run view`")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh run view failed: {stderr}");
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Merge a PR with `gh pr merge <url> <method_flag>`.
+pub fn merge_pr(pr_url: &str, method_flag: &str) -> Result<()> {
+    let output = Command::new("gh")
+        .args(["pr", "merge", pr_url, method_flag])
+        .output()
+        .context("failed to run `gh pr merge`")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh pr merge failed: {}", stderr.trim());
+    }
+    Ok(())
+}
+
+/// Open a PR for the branch checked out in `dir` with `gh pr create`,
+/// returning its URL.
+pub fn create_pr(dir: &Path, base: &str, title: &str, body: &str) -> Result<String> {
+    let output = Command::new("gh")
+        .args([
+            "pr", "create", "--base", base, "--title", title, "--body", body,
+        ])
+        .current_dir(dir)
+        .output()
+        .context("failed to run `gh pr create`")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh pr create failed: {}", stderr.trim());
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .rfind(|line| line.starts_with("https://"))
+        .map(str::to_string)
+        .context("gh pr create printed no PR URL")
+}
+
+/// A PR review or conversation comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrFeedback {
+    pub author: String,
+    /// Review state (`CHANGES_REQUESTED`, `COMMENTED`, ...); `None` for plain comments.
+    pub state: Option<String>,
+    pub body: String,
+}
+
+/// Reviews and comments on a PR, oldest first, skipping empty bodies.
+pub fn pr_feedback(pr_url: &str) -> Result<Vec<PrFeedback>> {
+    let output = Command::new("gh")
+        .args(["pr", "view", pr_url, "--json", "reviews,comments"])
+        .output()
+        .context("failed to run `gh pr view`")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh pr view failed: {}", stderr.trim());
+    }
+    parse_pr_feedback(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse `gh pr view --json reviews,comments`.
+fn parse_pr_feedback(json: &str) -> Result<Vec<PrFeedback>> {
+    #[derive(Deserialize)]
+    struct Author {
+        login: String,
+    }
+    #[derive(Deserialize)]
+    struct Entry {
+        author: Option<Author>,
+        #[serde(default)]
+        body: String,
+        #[serde(default)]
+        state: Option<String>,
+        #[serde(rename = "submittedAt", alias = "createdAt", default)]
+        at: Option<String>,
+    }
+    #[derive(Deserialize)]
+    struct View {
+        #[serde(default)]
+        reviews: Vec<Entry>,
+        #[serde(default)]
+        comments: Vec<Entry>,
+    }
+
+    let view: View = serde_json::from_str(json).context("failed to parse gh pr view output")?;
+    let mut entries: Vec<Entry> = view
+        .reviews
+        .into_iter()
+        .chain(view.comments)
+        .filter(|e| !e.body.trim().is_empty())
+        .collect();
+    entries.sort_by(|a, b| a.at.cmp(&b.at));
+    Ok(entries
+        .into_iter()
+        .map(|e| PrFeedback {
+            author: e.author.map_or_else(|| "unknown".into(), |a| a.login),
+            state: e.state,
+            body: e.body.trim().to_string(),
+        })
+        .collect())
+}
+
+/// Keep the last `max_bytes` of a log (errors usually sit at the end),
+/// cutting on a line boundary where possible.
+pub fn tail_log(log: &str, max_bytes: usize) -> &str {
+    if log.len() <= max_bytes {
+        return log;
+    }
+    let mut start = log.len() - max_bytes;
+    while !log.is_char_boundary(start) {
+        start += 1;
+    }
+    let tail = &log[start..];
+    match tail.find('\n') {
+        Some(nl) if nl + 1 < tail.len() => &tail[nl + 1..],
+        _ => tail,
+    }
+}
+
 /// Get the "current" milestone -- the first open milestone with the nearest due date.
 pub fn current_milestone(milestones: &[GitHubMilestone]) -> Option<&GitHubMilestone> {
     milestones.iter().find(|m| m.state == "open")
@@ -179,6 +348,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_pr_feedback_merges_reviews_and_comments_in_order() {
+        let json = r#"{
+            "reviews": [
+                {"author": {"login": "alice"}, "body": "Needs tests", "state": "CHANGES_REQUESTED", "submittedAt": "2026-01-02T00:00:00Z"},
+                {"author": {"login": "bob"}, "body": "", "state": "APPROVED", "submittedAt": "2026-01-03T00:00:00Z"}
+            ],
+            "comments": [
+                {"author": {"login": "carol"}, "body": " Why a new crate? ", "createdAt": "2026-01-01T00:00:00Z"}
+            ]
+        }"#;
+        let feedback = parse_pr_feedback(json).unwrap();
+        assert_eq!(
+            feedback,
+            [
+                PrFeedback {
+                    author: "carol".into(),
+                    state: None,
+                    body: "Why a new crate?".into(),
+                },
+                PrFeedback {
+                    author: "alice".into(),
+                    state: Some("CHANGES_REQUESTED".into()),
+                    body: "Needs tests".into(),
+                },
+            ]
+        );
+    }
+
     #[test]
     fn assign_column_closed_goes_to_last() {
         let columns = vec![
@@ -263,4 +461,22 @@ mod tests {
         }];
         assert!(current_milestone(&milestones).is_none());
     }
+
+    #[test]
+    fn tail_log_keeps_short_logs() {
+        assert_eq!(tail_log("a\nb", 100), "a\nb");
+    }
+
+    #[test]
+    fn tail_log_cuts_on_line_boundary() {
+        let log = "first line\nsecond line\nerror: boom\n";
+        assert_eq!(tail_log(log, 18), "error: boom\n");
+    }
+
+    #[test]
+    fn tail_log_respects_char_boundaries() {
+        let log = "ééééé";
+        let tail = tail_log(log, 3);
+        assert_eq!(tail, "é");
+    }
 }
@@ -5,14 +5,19 @@
 
 pub mod config;
 pub mod configure;
+pub mod gc;
 pub mod github;
+pub mod notify;
 pub mod pty;
 pub mod scanner;
+pub mod scheduler;
 pub mod session;
 pub mod session_host;
 pub mod session_update;
 pub mod skills;
 pub mod store;
 pub mod sync;
+pub mod templates;
 pub mod tui;
 pub mod update;
+pub mod webhook;
@@ -4,7 +4,8 @@
 //! session management, autonomous task chains, or skill operations.
 
 use claustre::{
-    config, configure, session, session_host, session_update, skills, store, sync, tui, update,
+    config, configure, gc, github, notify, scanner, scheduler, session, session_host,
+    session_update, skills, store, sync, templates, tui, update, webhook,
 };
 
 use std::fs;
@@ -52,14 +53,20 @@ enum Commands {
     AddTask {
         /// Project name
         project: String,
-        /// Task title
-        title: String,
-        /// Task description
-        #[arg(short, long, default_value = "")]
-        description: String,
-        /// Task mode: autonomous or supervised
-        #[arg(short, long, default_value = "supervised")]
-        mode: String,
+        /// Task title (optional when the template defines one)
+        title: Option<String>,
+        /// Task description (overrides the template's)
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Task mode: autonomous or supervised (default: template's, else supervised)
+        #[arg(short, long)]
+        mode: Option<String>,
+        /// Template from ~/.claustre/templates/ or <repo>/.claustre/templates/
+        #[arg(short, long)]
+        template: Option<String>,
+        /// Template variable as key=value (repeatable)
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
     },
     /// List projects
     ListProjects,
@@ -67,12 +74,60 @@ enum Commands {
     ListTasks {
         /// Project name
         project: String,
+        /// Filter query, e.g. `status:in_review has:pr ci:failed created:<7d "text"`
+        #[arg(short, long)]
+        query: Option<String>,
     },
     /// Show stats for a project
     Stats {
         /// Project name
         project: String,
     },
+    /// Show or set the auto-merge policy for a project or one of its tasks
+    MergePolicy {
+        /// Project name
+        project: String,
+        /// Task ID or exact title (default: the project itself)
+        #[arg(short, long)]
+        task: Option<String>,
+        /// never, green, approved:<n>, or inherit to clear (omit to show)
+        policy: Option<String>,
+    },
+    /// Show or set a recurring schedule that clones a task when it comes due
+    Schedule {
+        /// Project name
+        project: String,
+        /// Task ID or exact title (omit to list the project's schedules)
+        #[arg(short, long)]
+        task: Option<String>,
+        /// `every 7d`, `@weekly`, a cron expression like `0 9 * * 1`, or off (omit to show)
+        spec: Option<String>,
+        /// Launch each clone immediately in autonomous mode
+        #[arg(long)]
+        auto_launch: bool,
+    },
+    /// Show or set the commands a task must pass before its PR is accepted
+    Verify {
+        /// Project name
+        project: String,
+        /// Commands run in order in the worktree, e.g. "cargo test" (replaces the list; omit to show)
+        commands: Vec<String>,
+        /// Remove all verification commands
+        #[arg(long, conflicts_with = "commands")]
+        clear: bool,
+    },
+    /// Queue a follow-up prompt for a task's session; the TUI delivers it
+    Send {
+        /// Task ID or exact title
+        task: String,
+        /// Prompt typed into the session's Claude pane, followed by Enter
+        message: String,
+    },
+    /// Inspect a single task
+    Task {
+        #[command(subcommand)]
+        action: TaskAction,
+    },
     /// Remove a project from claustre
     RemoveProject {
         /// Project name
@@ -129,6 +184,9 @@ enum Commands {
         /// Force session to idle (used by the Notification hook on `idle_prompt`)
         #[arg(long)]
         set_idle: bool,
+        /// Transcript of the turn that just ended (Stop hook), read for subtask step reports
+        #[arg(long)]
+        transcript: Option<String>,
     },
     /// Run a session host (PTY owner + socket server, detached from TUI)
     SessionHost {
@@ -148,11 +206,38 @@ enum Commands {
         #[arg(long)]
         session_id: String,
     },
+    /// Make one attempt at fixing failing CI checks on a task's PR (bounded by `[ci_fix] max_attempts`)
+    CiFix {
+        /// Session ID whose task's failing CI to fix
+        #[arg(long)]
+        session_id: String,
+    },
+    /// Bring a conflicting task's branch up to date with its base, using Claude for conflicts
+    ResolveConflicts {
+        /// Task whose PR has merge conflicts
+        #[arg(long)]
+        task_id: String,
+        /// Run Claude in print mode (no terminal required)
+        #[arg(long)]
+        headless: bool,
+    },
     /// Sync claustre state across machines via a git repo
     Sync {
         #[command(subcommand)]
         action: SyncAction,
     },
+    /// Inspect and deliver queued outbound webhooks
+    Webhooks {
+        #[command(subcommand)]
+        action: WebhookAction,
+    },
+    /// Archive old done tasks and remove closed sessions, orphan worktrees,
+    /// stale progress dirs and trust entries, and oversized logs
+    Gc {
+        /// Carry out the cleanup (default: only print what would be removed)
+        #[arg(long)]
+        apply: bool,
+    },
     /// Print shell integration script (add `eval "$(claustre shell-init)"` to your .zshrc/.bashrc)
     ShellInit,
     /// Verify the binary is functional (used by auto-update smoke test)
@@ -180,6 +265,24 @@ enum SyncAction {
     Cd,
 }
 
+#[derive(Subcommand)]
+enum TaskAction {
+    /// Print the task's timeline: status changes, PR and CI updates,
+    /// resumes, review-loop runs, messages and user actions
+    Log {
+        /// Task ID or exact title
+        task: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum WebhookAction {
+    /// Deliver every queued webhook whose retry time has come
+    Flush,
+    /// List queued and permanently failed webhook deliveries
+    List,
+}
+
 #[derive(Subcommand)]
 enum SkillsAction {
     /// Search for skills on skills.sh
@@ -207,10 +310,26 @@ enum SkillsAction {
     Update,
 }
 
+impl Commands {
+    /// Who task events recorded by this command are attributed to.
+    fn event_source(&self) -> store::EventSource {
+        match self {
+            Self::Dashboard => store::EventSource::Tui,
+            Self::FeedNext { .. } | Self::SessionUpdate { .. } => store::EventSource::Hook,
+            Self::ReviewLoop { .. } | Self::CiFix { .. } | Self::ResolveConflicts { .. } => {
+                store::EventSource::Poller
+            }
+            _ => store::EventSource::Cli,
+        }
+    }
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    let command = cli.command.unwrap_or(Commands::Dashboard);
+    store::set_default_event_source(command.event_source());
 
-    match cli.command.unwrap_or(Commands::Dashboard) {
+    match command {
         Commands::Init => {
             config::ensure_dirs()?;
             println!("claustre initialized at ~/.claustre/");
@@ -236,23 +355,51 @@ fn main() -> Result<()> {
             title,
             description,
             mode,
+            template,
+            vars,
         } => {
-            anyhow::ensure!(!title.trim().is_empty(), "task title must not be empty");
             let store = open_store()?;
             let proj = find_project_by_name(&store, &project)?;
-            let task_mode: store::TaskMode = mode.parse().map_err(|_| {
-                anyhow::anyhow!("invalid task mode '{mode}': expected 'autonomous' or 'supervised'")
-            })?;
+            let rendered = if let Some(name) = &template {
+                let template = templates::find_template(name, Some(Path::new(&proj.repo_path)))?;
+                Some(template.render(&templates::parse_vars(&vars)?)?)
+            } else {
+                anyhow::ensure!(vars.is_empty(), "--var requires --template");
+                None
+            };
+            let rendered = rendered.as_ref();
+            let title = title
+                .or_else(|| rendered.and_then(|r| r.title.clone()))
+                .unwrap_or_default();
+            anyhow::ensure!(!title.trim().is_empty(), "task title must not be empty");
+            let description = description
+                .or_else(|| rendered.map(|r| r.description.clone()))
+                .unwrap_or_default();
+            let task_mode: store::TaskMode = match mode {
+                Some(mode) => mode.parse().map_err(|_| {
+                    anyhow::anyhow!(
+                        "invalid task mode '{mode}': expected 'autonomous' or 'supervised'"
+                    )
+                })?,
+                None => rendered
+                    .and_then(|r| r.mode)
+                    .unwrap_or(store::TaskMode::Supervised),
+            };
             let task = store.create_task(
                 &proj.id,
                 &title,
                 &description,
                 task_mode,
-                None,
-                None,
-                store::PushMode::Pr,
-                false,
+                rendered.and_then(|r| r.branch.as_deref()),
+                rendered.and_then(|r| r.base.as_deref()),
+                rendered
+                    .and_then(|r| r.push_mode)
+                    .unwrap_or(store::PushMode::Pr),
+                rendered.and_then(|r| r.review_loop).unwrap_or(false),
             )?;
+            for subtask in rendered.map(|r| r.subtasks.as_slice()).unwrap_or_default() {
+                store.create_subtask(&task.id, subtask, subtask)?;
+            }
             println!(
                 "Created task '{}' ({}) for project '{}'",
                 task.title,
@@ -291,12 +438,24 @@ fn main() -> Result<()> {
             }
             Ok(())
         }
-        Commands::ListTasks { project } => {
+        Commands::ListTasks { project, query } => {
             let store = open_store()?;
             let proj = find_project_by_name(&store, &project)?;
-            let tasks = store.list_tasks_for_project(&proj.id)?;
+            let mut tasks = store.list_tasks_for_project(&proj.id)?;
+            if let Some(query) = &query {
+                let query = store::TaskQuery::parse(query).context("invalid --query")?;
+                if query.includes_archived() {
+                    tasks.extend(store.list_archived_tasks_for_project(&proj.id)?);
+                }
+                let now = chrono::Utc::now();
+                tasks.retain(|t| query.matches(t, now));
+            }
             if tasks.is_empty() {
-                println!("No tasks for '{}'.", proj.name);
+                if query.is_some() {
+                    println!("No tasks in '{}' match the query.", proj.name);
+                } else {
+                    println!("No tasks for '{}'.", proj.name);
+                }
             } else {
                 for t in &tasks {
                     println!(
@@ -310,6 +469,197 @@ fn main() -> Result<()> {
             }
             Ok(())
         }
+        Commands::MergePolicy {
+            project,
+            task,
+            policy,
+        } => {
+            let store = open_store()?;
+            let cfg = config::load()?;
+            let proj = find_project_by_name(&store, &project)?;
+            let task = task
+                .map(|t| {
+                    store
+                        .list_tasks_for_project(&proj.id)?
+                        .into_iter()
+                        .find(|candidate| candidate.id == t || candidate.title == t)
+                        .with_context(|| format!("task '{t}' not found in '{}'", proj.name))
+                })
+                .transpose()?;
+            if let Some(policy) = policy {
+                let policy = if policy == "inherit" {
+                    None
+                } else {
+                    Some(policy.parse::<store::MergePolicy>().map_err(|_| {
+                        anyhow::anyhow!(
+                            "invalid merge policy '{policy}': expected 'never', 'green', 'approved:<n>' or 'inherit'"
+                        )
+                    })?)
+                };
+                match &task {
+                    Some(t) => store.set_task_merge_policy(&t.id, policy)?,
+                    None => store.set_project_merge_policy(&proj.id, policy)?,
+                }
+            }
+            let fallback = cfg.auto_merge.policy.unwrap_or(store::MergePolicy::Never);
+            let effective = match &task {
+                Some(t) => store.effective_merge_policy(&t.id)?,
+                None => store.project_merge_policy(&proj.id)?,
+            };
+            let target = task
+                .as_ref()
+                .map_or(proj.name.as_str(), |t| t.title.as_str());
+            match effective {
+                Some(p) => println!("{target}: {p}"),
+                None => println!("{target}: {fallback} (config default)"),
+            }
+            Ok(())
+        }
+        Commands::Schedule {
+            project,
+            task,
+            spec,
+            auto_launch,
+        } => {
+            let store = open_store()?;
+            let proj = find_project_by_name(&store, &project)?;
+            let tasks = store.list_tasks_for_project(&proj.id)?;
+            let print_schedule = |title: &str, schedule: &store::TaskSchedule| {
+                let fmt = |ts: &Option<String>| {
+                    ts.as_deref()
+                        .map_or_else(|| "never".to_string(), scheduler::format_local)
+                };
+                println!(
+                    "{title}: {}{}  (last: {}, next: {})",
+                    schedule.spec,
+                    if schedule.auto_launch {
+                        ", auto-launch"
+                    } else {
+                        ""
+                    },
+                    fmt(&schedule.last_run_at),
+                    fmt(&schedule.next_run_at)
+                );
+            };
+            let Some(t) = task else {
+                anyhow::ensure!(spec.is_none(), "--task is required to set a schedule");
+                let mut any = false;
+                for schedule in store.list_task_schedules()? {
+                    if let Some(t) = tasks.iter().find(|t| t.id == schedule.task_id) {
+                        print_schedule(&t.title, &schedule);
+                        any = true;
+                    }
+                }
+                if !any {
+                    println!("No schedules in '{}'.", proj.name);
+                }
+                return Ok(());
+            };
+            let task = tasks
+                .iter()
+                .find(|candidate| candidate.id == t || candidate.title == t)
+                .with_context(|| format!("task '{t}' not found in '{}'", proj.name))?;
+            match spec.as_deref() {
+                Some("off") => {
+                    store.clear_task_schedule(&task.id)?;
+                    println!("{}: no schedule", task.title);
+                    return Ok(());
+                }
+                Some(spec) => {
+                    let parsed: scheduler::ScheduleSpec = spec.parse()?;
+                    let next = parsed
+                        .next_run(None, chrono::Utc::now())
+                        .with_context(|| format!("schedule '{spec}' never fires"))?;
+                    store.set_task_schedule(&task.id, spec, auto_launch, &next.to_rfc3339())?;
+                }
+                None => {}
+            }
+            match store.get_task_schedule(&task.id)? {
+                Some(schedule) => print_schedule(&task.title, &schedule),
+                None => println!("{}: no schedule", task.title),
+            }
+            Ok(())
+        }
+        Commands::Send { task, message } => {
+            let store = open_store()?;
+            let task = find_task(&store, &task)?;
+            let session_id = task
+                .session_id
+                .as_deref()
+                .with_context(|| format!("task '{}' has no session", task.title))?;
+            store.record_task_message(&task.id, Some(session_id), &message, false)?;
+            println!(
+                "Queued for '{}'. The TUI types it into the session's Claude pane (resuming Claude if it exited) while the session tab is open.",
+                task.title
+            );
+            Ok(())
+        }
+        Commands::Task { action } => {
+            let store = open_store()?;
+            match action {
+                TaskAction::Log { task } => {
+                    let task = find_task(&store, &task)?;
+                    let events = store.list_task_events(&task.id)?;
+                    println!("{} ({}) — {}", task.title, task.id, task.status);
+                    for event in &events {
+                        let at = chrono::DateTime::parse_from_rfc3339(&event.created_at)
+                            .map_or_else(
+                                |_| event.created_at.clone(),
+                                |t| {
+                                    t.with_timezone(&chrono::Local)
+                                        .format("%Y-%m-%d %H:%M:%S")
+                                        .to_string()
+                                },
+                            );
+                        println!(
+                            "{at}  {:<6} {:<11} {}",
+                            event.source.as_str(),
+                            event.kind.as_str(),
+                            event.detail.lines().next().unwrap_or_default()
+                        );
+                    }
+                    // Statuses entered more than once, e.g. CI failing repeatedly
+                    let mut entered = std::collections::BTreeMap::<&str, usize>::new();
+                    for event in &events {
+                        if event.kind == store::TaskEventKind::Status
+                            && let Some((_, to)) = event.detail.split_once(" → ")
+                        {
+                            *entered.entry(to).or_default() += 1;
+                        }
+                    }
+                    let bounces: Vec<String> = entered
+                        .iter()
+                        .filter(|(_, n)| **n > 1)
+                        .map(|(status, n)| format!("{status} ×{n}"))
+                        .collect();
+                    if !bounces.is_empty() {
+                        println!("Repeated: {}", bounces.join(", "));
+                    }
+                }
+            }
+            Ok(())
+        }
+        Commands::Verify {
+            project,
+            commands,
+            clear,
+        } => {
+            let store = open_store()?;
+            let proj = find_project_by_name(&store, &project)?;
+            if clear || !commands.is_empty() {
+                store.set_project_verify_commands(&proj.id, &commands)?;
+            }
+            let commands = store.project_verify_commands(&proj.id)?;
+            if commands.is_empty() {
+                println!("No verification commands for '{}'.", proj.name);
+            } else {
+                println!("Verification commands for '{}':", proj.name);
+                for (i, command) in commands.iter().enumerate() {
+                    println!("  {}. {command}", i + 1);
+                }
+            }
+            Ok(())
+        }
         Commands::Stats { project } => {
             let store = open_store()?;
             let proj = find_project_by_name(&store, &project)?;
@@ -443,6 +793,54 @@ fn main() -> Result<()> {
                 Ok(())
             }
         },
+        Commands::Webhooks { action } => {
+            let store = open_store()?;
+            match action {
+                WebhookAction::Flush => {
+                    let report = webhook::deliver_due(&store)?;
+                    println!(
+                        "Delivered {}, retrying {}, failed {}",
+                        report.delivered, report.retrying, report.failed
+                    );
+                }
+                WebhookAction::List => {
+                    for status in [
+                        store::WebhookDeliveryStatus::Pending,
+                        store::WebhookDeliveryStatus::InFlight,
+                        store::WebhookDeliveryStatus::Failed,
+                    ] {
+                        for d in store.list_webhook_deliveries(status)? {
+                            println!(
+                                "{status:<8} {:<10} attempts={} next={} {}",
+                                d.event, d.attempts, d.next_attempt_at, d.url
+                            );
+                            if let Some(ref err) = d.last_error {
+                                println!("         last error: {err}");
+                            }
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+        Commands::Gc { apply } => {
+            let store = open_store()?;
+            let cfg = config::load()?;
+            let plan = gc::plan(&store, &cfg.gc, chrono::Utc::now())?;
+            if plan.is_empty() {
+                println!("Nothing to clean up.");
+                return Ok(());
+            }
+            gc::print_plan(&plan);
+            if apply {
+                gc::apply(&store, &plan)?;
+                println!("Done.");
+                sync::try_auto_push();
+            } else {
+                println!("\nDry run: re-run with `claustre gc --apply` to clean up.");
+            }
+            Ok(())
+        }
         Commands::FeedNext {
             session_id,
             remote,
@@ -457,6 +855,7 @@ fn main() -> Result<()> {
             resumed,
             claude_session_id,
             set_idle,
+            transcript,
         } => {
             let store = open_store()?;
 
@@ -471,11 +870,35 @@ fn main() -> Result<()> {
                 None
             };
 
+            // Subtask steps: a reported step either hands Claude the next one
+            // or pauses the chain; either way a PR has to wait.
+            let step = match transcript.as_deref() {
+                Some(path) => session::steps::advance(
+                    &store,
+                    &session_id,
+                    scanner::last_assistant_text(Path::new(path)).as_deref(),
+                    pr_url.as_deref(),
+                )?,
+                None => session::steps::Step::Inactive,
+            };
+
+            // Verification gate: a new PR is only accepted once the project's
+            // verification commands pass in the worktree.
+            let verdict = match pr_url.as_deref() {
+                Some(url) if !step.holds_pr() => {
+                    let max_attempts = config::load()?.verify.max_attempts;
+                    session::verify::gate(&store, &session_id, url, max_attempts)?
+                }
+                _ => session::verify::Verdict::NotRequired,
+            };
+
             let outcome = session_update::apply(
                 &store,
                 &session_update::SessionUpdateArgs {
                     session_id: &session_id,
-                    pr_url: pr_url.as_deref(),
+                    pr_url: pr_url
+                        .as_deref()
+                        .filter(|_| !step.holds_pr() && verdict.accepts_pr()),
                     input_tokens,
                     output_tokens,
                     resumed,
@@ -485,6 +908,23 @@ fn main() -> Result<()> {
                 },
             )?;
 
+            // Stdout is read by Claude Code: a `block` decision keeps Claude
+            // working on the next step or the verification failure.
+            if let Some(output) = step.hook_output().or_else(|| verdict.hook_output()) {
+                println!("{output}");
+            }
+            if let session::steps::Step::Paused { ref message } = step {
+                store.update_session_status(&session_id, store::ClaudeStatus::Idle, message)?;
+            }
+            if let session::verify::Verdict::Failed {
+                ref task_id,
+                ref message,
+            } = verdict
+                && let Some(ref url) = pr_url
+            {
+                session::verify::fail_task(&store, &session_id, task_id, url, message)?;
+            }
+
             // Fire notification for new PRs
             if let session_update::SessionUpdateOutcome::PrDetected {
                 ref task_id,
@@ -493,9 +933,27 @@ fn main() -> Result<()> {
                 && let Some(ref url) = pr_url
             {
                 let cfg = config::load()?;
-                if cfg.notifications.enabled {
-                    let task = store.get_task(task_id)?;
-                    cfg.notifications.notify(&task.title, Some(url));
+                let task = store.get_task(task_id)?;
+                let project = store.get_project(&task.project_id)?;
+                notify_outside_tui(
+                    &store,
+                    &cfg.notifications,
+                    &task.id,
+                    &notify::Notification {
+                        event: notify::NotifyEvent::InReview,
+                        task_title: &task.title,
+                        project: Some(&project.name),
+                        pr_url: Some(url),
+                    },
+                );
+                if webhook::enqueue(
+                    &store,
+                    &cfg.webhooks,
+                    notify::NotifyEvent::InReview,
+                    task_id,
+                )? > 0
+                {
+                    webhook::spawn_flush();
                 }
             }
 
@@ -510,6 +968,15 @@ fn main() -> Result<()> {
             cmd,
         } => session_host::run(&session_id, &cmd, &worktree_path),
         Commands::ReviewLoop { session_id } => run_review_loop(&session_id),
+        Commands::CiFix { session_id } => run_ci_fix(&session_id),
+        Commands::ResolveConflicts { task_id, headless } => {
+            let store = open_store()?;
+            let cfg = config::load()?;
+            let outcome =
+                session::conflict::resolve_task_conflicts(&store, &cfg, &task_id, headless)?;
+            eprintln!("resolve-conflicts: {outcome}");
+            Ok(())
+        }
         Commands::ShellInit => {
             print!("{}", include_str!("shell_init.sh"));
             Ok(())
@@ -622,8 +1089,9 @@ fn is_rate_limited_from_cache() -> bool {
 
 /// Blocking loop that feeds autonomous tasks to a Claude session.
 ///
-/// For each task: builds the prompt (including subtasks if any), runs Claude as a
-/// blocking subprocess, then checks whether the Stop hook transitioned the task.
+/// For each task: builds the prompt (the first unfinished subtask if any; the
+/// Stop hook feeds the rest one turn at a time), runs Claude as a blocking
+/// subprocess, then checks whether the Stop hook transitioned the task.
 /// Continues to the next autonomous task until none remain or rate limited.
 fn run_feed_next(session_id: &str, remote: bool, model: &str, effort: &str) -> Result<()> {
     let store = open_store()?;
@@ -636,6 +1104,22 @@ fn run_feed_next(session_id: &str, remote: bool, model: &str, effort: &str) -> R
         // Check rate limits from the shared cache
         if is_rate_limited_from_cache() {
             eprintln!("feed-next: rate limited (>=80% usage), stopping");
+            let stalled = store
+                .working_task_for_session(session_id)?
+                .or(store.next_pending_task_for_session(session_id)?);
+            if let Some(task) = stalled {
+                notify_outside_tui(
+                    &store,
+                    &config::load()?.notifications,
+                    &task.id,
+                    &notify::Notification {
+                        event: notify::NotifyEvent::RateLimited,
+                        task_title: &task.title,
+                        project: Some(&project.name),
+                        pr_url: task.pr_url.as_deref(),
+                    },
+                );
+            }
             break;
         }
 
@@ -678,31 +1162,27 @@ fn run_feed_next(session_id: &str, remote: bool, model: &str, effort: &str) -> R
             )?;
         }
 
-        // Build prompt: if task has subtasks, concatenate them all into an ordered list
-        let subtasks = store.list_subtasks_for_task(&task.id)?;
         // Use the task's base branch for PR targeting if set, otherwise project default
         let effective_base = task
             .base
             .as_deref()
             .filter(|b| !b.is_empty())
             .unwrap_or(&project.default_branch);
-        let instructions = session::completion_instructions(effective_base, task.push_mode);
-        let prompt = if subtasks.is_empty() {
-            format!(
-                "{}{}{}",
+        let instructions = format!(
+            "{}{}",
+            session::verify::prompt_section(&store.project_verify_commands(&project.id)?),
+            session::completion_instructions(effective_base, task.push_mode)
+        );
+        let previous = session::retry::prompt_section(&store, &task.id);
+        let prompt = match session::steps::start(&store, &task)? {
+            Some(step) => format!("{step}{previous}{}", session::AUTONOMOUS_SUFFIX),
+            None => format!(
+                "{}{}{}{}",
                 task.description,
+                previous,
                 session::AUTONOMOUS_SUFFIX,
                 instructions
-            )
-        } else {
-            use std::fmt::Write;
-            let mut p = format!("# {}\n\n{}\n\n## Steps\n\n", task.title, task.description);
-            for (i, st) in subtasks.iter().enumerate() {
-                let _ = writeln!(p, "{}. **{}**: {}", i + 1, st.title, st.description);
-            }
-            p.push_str(session::AUTONOMOUS_SUFFIX);
-            p.push_str(&instructions);
-            p
+            ),
         };
 
         // Run Claude as a blocking subprocess
@@ -717,6 +1197,11 @@ fn run_feed_next(session_id: &str, remote: bool, model: &str, effort: &str) -> R
 
         if use_resume {
             eprintln!("feed-next: resuming task '{}'", task.title);
+            store.record_task_event(
+                &task.id,
+                store::TaskEventKind::Resume,
+                "feed-next: claude --resume",
+            )?;
         } else {
             eprintln!("feed-next: running task '{}'", task.title);
         }
@@ -755,6 +1240,23 @@ fn run_feed_next(session_id: &str, remote: bool, model: &str, effort: &str) -> R
         // After Claude exits, the Stop hook has already fired.
         // Re-read task from DB to check its state.
         let task = store.get_task(&task.id)?;
+        // Claude exited part-way through the steps (typically a failed step):
+        // pause here rather than complete the task. A retry resumes at the step.
+        if task.status == store::TaskStatus::Working
+            && let Some(step) = store.current_subtask(&task.id)?
+        {
+            store.update_task_status(&task.id, store::TaskStatus::Error)?;
+            store.update_session_status(
+                session_id,
+                store::ClaudeStatus::Error,
+                &format!("Stopped at step: {}", step.title),
+            )?;
+            eprintln!(
+                "feed-next: '{}' stopped at step '{}'",
+                task.title, step.title
+            );
+            break;
+        }
         if task.status == store::TaskStatus::Working {
             let cfg = config::load()?;
             if task.push_mode == store::PushMode::Push {
@@ -765,8 +1267,20 @@ fn run_feed_next(session_id: &str, remote: bool, model: &str, effort: &str) -> R
                     store::ClaudeStatus::Done,
                     &format!("Completed: {}", task.title),
                 )?;
-                if cfg.notifications.enabled {
-                    cfg.notifications.notify(&task.title, None);
+                notify_outside_tui(
+                    &store,
+                    &cfg.notifications,
+                    &task.id,
+                    &notify::Notification {
+                        event: notify::NotifyEvent::Done,
+                        task_title: &task.title,
+                        project: Some(&project.name),
+                        pr_url: None,
+                    },
+                );
+                if webhook::enqueue(&store, &cfg.webhooks, notify::NotifyEvent::Done, &task.id)? > 0
+                {
+                    webhook::spawn_flush();
                 }
             } else {
                 // PR-mode: Stop hook didn't find a PR — mark in_review as best-effort fallback
@@ -776,18 +1290,25 @@ fn run_feed_next(session_id: &str, remote: bool, model: &str, effort: &str) -> R
                     store::ClaudeStatus::Done,
                     &format!("Review: {}", task.title),
                 )?;
-                if cfg.notifications.enabled {
-                    cfg.notifications
-                        .notify(&task.title, task.pr_url.as_deref());
-                }
-            }
-        }
-
-        // Mark subtasks done if the task was completed
-        if !subtasks.is_empty() {
-            for st in &subtasks {
-                if st.status != store::TaskStatus::Done {
-                    store.update_subtask_status(&st.id, store::TaskStatus::Done)?;
+                notify_outside_tui(
+                    &store,
+                    &cfg.notifications,
+                    &task.id,
+                    &notify::Notification {
+                        event: notify::NotifyEvent::InReview,
+                        task_title: &task.title,
+                        project: Some(&project.name),
+                        pr_url: task.pr_url.as_deref(),
+                    },
+                );
+                if webhook::enqueue(
+                    &store,
+                    &cfg.webhooks,
+                    notify::NotifyEvent::InReview,
+                    &task.id,
+                )? > 0
+                {
+                    webhook::spawn_flush();
                 }
             }
         }
@@ -861,6 +1382,11 @@ fn run_review_loop(session_id: &str) -> Result<()> {
         };
 
         eprintln!("review-loop: checking PR comments for '{}'", task.title);
+        store.record_task_event(
+            &task.id,
+            store::TaskEventKind::ReviewLoop,
+            "checking PR review comments",
+        )?;
 
         // Run Claude with the review prompt
         let status = std::process::Command::new("claude")
@@ -880,6 +1406,18 @@ fn run_review_loop(session_id: &str) -> Result<()> {
         // Check rate limits
         if is_rate_limited_from_cache() {
             eprintln!("review-loop: rate limited, stopping");
+            let project = store.get_project(&task.project_id)?;
+            notify_outside_tui(
+                &store,
+                &cfg.notifications,
+                &task.id,
+                &notify::Notification {
+                    event: notify::NotifyEvent::RateLimited,
+                    task_title: &task.title,
+                    project: Some(&project.name),
+                    pr_url: task.pr_url.as_deref(),
+                },
+            );
             break;
         }
 
@@ -900,6 +1438,152 @@ fn run_review_loop(session_id: &str) -> Result<()> {
     Ok(())
 }
 
+const CI_FIX_PROMPT: &str = r"CI checks failed on the pull request for this branch. The logs of the failing jobs are included below. Follow these steps:
+
+1. Identify the root cause of every failure in the logs.
+2. Fix the code. Do NOT disable, skip or delete failing tests unless the test itself is clearly wrong — explain why if you do.
+3. Run the relevant checks locally where possible to confirm the fix.
+4. Stage and commit the fix with a message describing what broke and why.
+5. Do NOT push — claustre pushes your commits after you exit.
+
+IMPORTANT: This is an autonomous task. Do NOT ask the user for clarification. Make your best judgment and proceed.";
+
+/// Maximum bytes of failing CI log passed to Claude.
+const CI_FIX_MAX_LOG_BYTES: usize = 30_000;
+
+/// Current `HEAD` commit of a worktree.
+fn git_head(worktree: &str) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["-C", worktree, "rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Make one CI fix attempt on the session's `ci_failed` task: feed the
+/// failing job logs to Claude and push the resulting commits, then exit.
+/// The pushed commit re-runs CI; the PR poller moves the task back to
+/// `in_review`, and if the re-run fails again the TUI spawns a fresh
+/// `ci-fix` until `[ci_fix] max_attempts` is reached.
+fn run_ci_fix(session_id: &str) -> Result<()> {
+    let store = open_store()?;
+    let cfg = config::load()?;
+    let instructions = cfg.ci_fix.prompt.as_deref().unwrap_or(CI_FIX_PROMPT);
+    let session = store.get_session(session_id)?;
+    let worktree = session.worktree_path.as_str();
+
+    let Some(task) = store.in_review_task_for_session(session_id)? else {
+        eprintln!("ci-fix: no task awaiting CI for this session, exiting");
+        return Ok(());
+    };
+    if task.status != store::TaskStatus::CiFailed {
+        eprintln!("ci-fix: task is {}, nothing to fix, exiting", task.status);
+        return Ok(());
+    }
+    if matches!(
+        task.ci_status,
+        Some(store::CiStatus::Running | store::CiStatus::Passed)
+    ) {
+        eprintln!("ci-fix: CI is re-running, nothing to fix, exiting");
+        return Ok(());
+    }
+
+    let attempts = store.count_ci_fix_attempts(&task.id)?;
+    if attempts >= i64::from(cfg.ci_fix.max_attempts) {
+        eprintln!(
+            "ci-fix: reached {attempts} attempts for '{}', giving up",
+            task.title
+        );
+        return Ok(());
+    }
+    if is_rate_limited_from_cache() {
+        eprintln!("ci-fix: rate limited, stopping");
+        let project = store.get_project(&task.project_id)?;
+        notify_outside_tui(
+            &store,
+            &cfg.notifications,
+            &task.id,
+            &notify::Notification {
+                event: notify::NotifyEvent::RateLimited,
+                task_title: &task.title,
+                project: Some(&project.name),
+                pr_url: task.pr_url.as_deref(),
+            },
+        );
+        return Ok(());
+    }
+
+    let run_id = match github::latest_failed_run(worktree, &session.branch_name) {
+        Ok(id) => id,
+        Err(e) => {
+            eprintln!("ci-fix: {e:#}");
+            None
+        }
+    };
+    let attempt = store.start_ci_fix_attempt(&task.id, run_id.as_deref())?;
+    eprintln!(
+        "ci-fix: attempt {}/{} for '{}'",
+        attempt.attempt, cfg.ci_fix.max_attempts, task.title
+    );
+
+    let logs = match run_id
+        .as_deref()
+        .map(|id| github::failed_run_logs(worktree, id))
+    {
+        Some(Ok(logs)) if !logs.trim().is_empty() => logs,
+        Some(Err(e)) => {
+            eprintln!("ci-fix: {e:#}");
+            store.finish_ci_fix_attempt(&attempt.id, store::CiFixOutcome::Failed)?;
+            return Ok(());
+        }
+        _ => {
+            eprintln!("ci-fix: no failed workflow run logs found, exiting");
+            store.finish_ci_fix_attempt(&attempt.id, store::CiFixOutcome::Failed)?;
+            return Ok(());
+        }
+    };
+    let prompt = format!(
+        "{instructions}\n\n## Failing CI logs\n\n```\n{}\n```",
+        github::tail_log(&logs, CI_FIX_MAX_LOG_BYTES)
+    );
+
+    let head_before = git_head(worktree);
+    let status = std::process::Command::new("claude")
+        .args(["--model", &cfg.claude.model, "--effort", &cfg.claude.effort])
+        .arg(&prompt)
+        .current_dir(worktree)
+        .env("CLAUSTRE_SESSION", "1")
+        .status()
+        .context("failed to run claude for ci fix")?;
+
+    let outcome = if !status.success() {
+        store::CiFixOutcome::Failed
+    } else if git_head(worktree) == head_before {
+        store::CiFixOutcome::NoChanges
+    } else {
+        let pushed = std::process::Command::new("git")
+            .args(["-C", worktree, "push"])
+            .status()
+            .is_ok_and(|s| s.success());
+        if pushed {
+            store::CiFixOutcome::Pushed
+        } else {
+            store::CiFixOutcome::PushFailed
+        }
+    };
+    store.finish_ci_fix_attempt(&attempt.id, outcome)?;
+    eprintln!("ci-fix: attempt {} finished: {outcome}", attempt.attempt);
+
+    if outcome == store::CiFixOutcome::Pushed {
+        // CI re-runs on the new commit; the PR poller reports the result.
+        store.update_task_ci_status(&task.id, Some(store::CiStatus::Running))?;
+    }
+    Ok(())
+}
+
 /// Replace the current process with a new invocation of the given executable.
 /// Uses Unix `execv` — only returns on error.
 fn exec_process(exe: &Path, args: &[String]) -> std::io::Error {
@@ -932,6 +1616,57 @@ fn nix_execv(exe: &std::ffi::CStr, args: &[&std::ffi::CStr]) -> std::io::Error {
     std::io::Error::last_os_error()
 }
 
+/// Fire a notification from a hook or session loop. These run inside a
+/// session's PTY, so backends that write to the host terminal are skipped
+/// here; the TUI relays them after picking the `notify` event up from the
+/// task timeline.
+fn notify_outside_tui(
+    store: &store::Store,
+    notifications: &config::NotificationConfig,
+    task_id: &str,
+    notification: &notify::Notification<'_>,
+) {
+    notifications.notify_outside_tui(notification);
+    if let Err(e) = store.record_task_event(
+        task_id,
+        store::TaskEventKind::Notify,
+        notification.event.as_str(),
+    ) {
+        tracing::warn!("failed to record notification for {task_id}: {e:#}");
+    }
+}
+
+/// Find a task by ID, or by exact title across all projects.
+fn find_task(store: &store::Store, reference: &str) -> Result<store::Task> {
+    if let Ok(task) = store.get_task(reference) {
+        return Ok(task);
+    }
+    let mut matches = Vec::new();
+    for project in store.list_projects()? {
+        let archived = store.list_archived_tasks_for_project(&project.id)?;
+        matches.extend(
+            store
+                .list_tasks_for_project(&project.id)?
+                .into_iter()
+                .chain(archived)
+                .filter(|t| t.title == reference),
+        );
+    }
+    match matches.len() {
+        0 => anyhow::bail!("task '{reference}' not found"),
+        1 => Ok(matches.remove(0)),
+        _ => anyhow::bail!(
+            "'{reference}' matches {} tasks; use an ID: {}",
+            matches.len(),
+            matches
+                .iter()
+                .map(|t| t.id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
 fn find_project_by_name(store: &store::Store, name: &str) -> Result<store::Project> {
     let projects = store.list_projects()?;
     projects
@@ -0,0 +1,756 @@
+//! Pluggable notification backends for task lifecycle events.
+//!
+//! A [`Notification`] describes something worth telling the user about
+//! (PR opened, CI failed, conflict, rate limited, Claude waiting for input).
+//! `NotificationConfig::notify` in [`crate::config`] fans it out to every
+//! configured [`NotificationBackend`] whose event filter matches.
+//!
+//! Built-in backends:
+//! - **voice** — the legacy `say`-style command (`[notifications] command`)
+//! - **system** — macOS banner via `terminal-notifier` / `osascript`,
+//!   freedesktop notifications everywhere else
+//! - **desktop** — freedesktop notifications via `notify-send` (D-Bus fallback)
+//! - **webhook** — JSON POST to an arbitrary URL (via `curl`)
+//! - **terminal** — bell or OSC 9 / OSC 777 escape written to the host terminal
+//! - **command** — arbitrary program with templated arguments
+//!
+//! All backends are fire-and-forget: they spawn a process (or write a few
+//! bytes) and return immediately. Failures are logged, never surfaced.
+
+use std::fmt;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// A task lifecycle event that can trigger a notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyEvent {
+    /// A PR was opened (or the task otherwise became ready for review).
+    InReview,
+    /// The task finished (PR merged or push-mode task pushed).
+    Done,
+    /// CI checks failed on the task's PR.
+    CiFailed,
+    /// The task's PR has merge conflicts.
+    Conflict,
+    /// An autonomous chain stopped because of usage limits.
+    RateLimited,
+    /// Claude is blocked on a permission prompt or a question.
+    NeedsInput,
+    /// The watchdog flagged the task's session as stuck.
+    Stuck,
+}
+
+impl NotifyEvent {
+    pub const ALL: [Self; 7] = [
+        Self::InReview,
+        Self::Done,
+        Self::CiFailed,
+        Self::Conflict,
+        Self::RateLimited,
+        Self::NeedsInput,
+        Self::Stuck,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InReview => "in_review",
+            Self::Done => "done",
+            Self::CiFailed => "ci_failed",
+            Self::Conflict => "conflict",
+            Self::RateLimited => "rate_limited",
+            Self::NeedsInput => "needs_input",
+            Self::Stuck => "stuck",
+        }
+    }
+
+    /// Built-in message used when no template applies to this event.
+    pub fn default_message(&self, task_title: &str) -> String {
+        match self {
+            Self::InReview => format!("ready for review: {task_title}"),
+            Self::Done => format!("completed {task_title}"),
+            Self::CiFailed => format!("CI failed: {task_title}"),
+            Self::Conflict => format!("merge conflict: {task_title}"),
+            Self::RateLimited => format!("rate limited, stopped: {task_title}"),
+            Self::NeedsInput => format!("needs input: {task_title}"),
+            Self::Stuck => format!("stuck: {task_title}"),
+        }
+    }
+}
+
+impl fmt::Display for NotifyEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NotifyEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|e| e.as_str() == s)
+            .ok_or_else(|| format!("unknown notification event: {s}"))
+    }
+}
+
+/// What happened, and to which task.
+#[derive(Debug, Clone, Copy)]
+pub struct Notification<'a> {
+    pub event: NotifyEvent,
+    pub task_title: &'a str,
+    pub project: Option<&'a str>,
+    pub pr_url: Option<&'a str>,
+}
+
+impl Notification<'_> {
+    /// Substitute `{task}`, `{event}`, `{project}`, `{pr_url}` and `{message}`
+    /// in a template. Missing optional values render as empty strings.
+    pub fn render(&self, template: &str, message: &str) -> String {
+        template
+            .replace("{task}", self.task_title)
+            .replace("{event}", self.event.as_str())
+            .replace("{project}", self.project.unwrap_or(""))
+            .replace("{pr_url}", self.pr_url.unwrap_or(""))
+            .replace("{message}", message)
+    }
+
+    /// JSON body sent by the webhook backend.
+    pub fn webhook_payload(&self, message: &str) -> serde_json::Value {
+        serde_json::json!({
+            "event": self.event.as_str(),
+            "task": self.task_title,
+            "project": self.project,
+            "pr_url": self.pr_url,
+            "message": message,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        })
+    }
+}
+
+/// A destination for notifications.
+pub trait NotificationBackend {
+    /// Short identifier used in log messages.
+    fn name(&self) -> &'static str;
+
+    /// Whether this backend wants to hear about `event`.
+    fn accepts(&self, event: NotifyEvent) -> bool;
+
+    /// Deliver a notification. Must not block on slow I/O.
+    fn send(&self, notification: &Notification<'_>, message: &str) -> Result<()>;
+
+    /// Whether delivery needs the terminal claustre itself runs in. Hooks
+    /// and session loops run inside a session's PTY, so only the TUI can
+    /// deliver these; other processes hand them over via the task timeline.
+    fn needs_host_terminal(&self) -> bool {
+        false
+    }
+}
+
+/// Returns true if `event` passes an event filter. An empty filter accepts everything.
+pub fn filter_accepts(filter: &[NotifyEvent], event: NotifyEvent) -> bool {
+    filter.is_empty() || filter.contains(&event)
+}
+
+/// Spawn a command and reap it on a background thread so it never becomes a zombie.
+fn spawn_detached(cmd: &mut Command) -> Result<()> {
+    let mut child = cmd
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("failed to spawn {}", cmd.get_program().display()))?;
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+/// Like [`spawn_detached`], but feeds `input` to the child's stdin.
+fn spawn_detached_with_input(cmd: &mut Command, input: String) -> Result<()> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("failed to spawn {}", cmd.get_program().display()))?;
+    let stdin = child.stdin.take();
+    std::thread::spawn(move || {
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+/// A curl config (read with `-K -`) that POSTs `body` as JSON to `url`.
+///
+/// Headers and body travel over stdin rather than argv, so auth tokens and
+/// payloads never show up in `ps` output.
+pub(crate) fn curl_config(headers: &[String], body: &str, url: &str) -> String {
+    let mut config = String::from("header = \"Content-Type: application/json\"\n");
+    for header in headers {
+        let _ = writeln!(config, "header = {}", curl_quote(header));
+    }
+    let _ = writeln!(config, "data-binary = {}", curl_quote(body));
+    let _ = writeln!(config, "url = {}", curl_quote(url));
+    config
+}
+
+/// Quote a value for a curl config file, escaping what curl unescapes.
+fn curl_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// ── Backend configuration ──
+
+/// Style of the escape sequence written by the terminal backend.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminalStyle {
+    /// Plain BEL (`\a`) — most terminals flash or mark the tab.
+    #[default]
+    Bell,
+    /// `OSC 9` — iTerm2, `WezTerm`, Windows Terminal, kitty.
+    Osc9,
+    /// `OSC 777;notify` — rxvt-unicode, foot, Ghostty, VTE-based terminals.
+    Osc777,
+}
+
+/// One entry of `[[notifications.backends]]` in `config.toml`.
+///
+/// ```toml
+/// [[notifications.backends]]
+/// type = "desktop"
+/// events = ["ci_failed", "conflict", "needs_input"]
+///
+/// [[notifications.backends]]
+/// type = "webhook"
+/// url = "https://ntfy.sh/my-claustre"
+/// headers = ["Authorization: Bearer xyz"]
+///
+/// [[notifications.backends]]
+/// type = "terminal"
+/// style = "osc9"
+///
+/// [[notifications.backends]]
+/// type = "command"
+/// command = "notify-me"
+/// args = ["--title", "{project}", "{message}"]
+/// ```
+///
+/// `events` is optional on every backend; when omitted the backend receives
+/// all events.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationBackendConfig {
+    /// Freedesktop notifications (`notify-send`, falling back to `gdbus`).
+    Desktop {
+        #[serde(default)]
+        events: Vec<NotifyEvent>,
+    },
+    /// HTTP POST with a JSON body.
+    Webhook {
+        url: String,
+        /// Extra `Name: value` headers.
+        #[serde(default)]
+        headers: Vec<String>,
+        #[serde(default)]
+        events: Vec<NotifyEvent>,
+    },
+    /// Escape sequence written to the controlling terminal.
+    Terminal {
+        #[serde(default)]
+        style: TerminalStyle,
+        #[serde(default)]
+        events: Vec<NotifyEvent>,
+    },
+    /// Arbitrary program. `args` support the same placeholders as `template`.
+    Command {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        events: Vec<NotifyEvent>,
+    },
+}
+
+impl NotificationBackendConfig {
+    /// Build the runtime backend described by this config entry.
+    pub fn build(&self) -> Box<dyn NotificationBackend> {
+        match self {
+            Self::Desktop { events } => Box::new(DesktopBackend {
+                events: events.clone(),
+            }),
+            Self::Webhook {
+                url,
+                headers,
+                events,
+            } => Box::new(WebhookBackend {
+                url: url.clone(),
+                headers: headers.clone(),
+                events: events.clone(),
+            }),
+            Self::Terminal { style, events } => Box::new(TerminalBackend {
+                style: *style,
+                events: events.clone(),
+            }),
+            Self::Command {
+                command,
+                args,
+                events,
+            } => Box::new(CommandBackend {
+                command: command.clone(),
+                args: args.clone(),
+                events: events.clone(),
+            }),
+        }
+    }
+}
+
+// ── Backends ──
+
+/// Voice/sound command (default: macOS `say`).
+pub struct VoiceBackend {
+    pub command: String,
+    pub voice: Option<String>,
+    pub rate: Option<u32>,
+    pub events: Vec<NotifyEvent>,
+}
+
+impl NotificationBackend for VoiceBackend {
+    fn name(&self) -> &'static str {
+        "voice"
+    }
+
+    fn accepts(&self, event: NotifyEvent) -> bool {
+        filter_accepts(&self.events, event)
+    }
+
+    fn send(&self, _notification: &Notification<'_>, message: &str) -> Result<()> {
+        let mut cmd = Command::new(&self.command);
+
+        // If using "say", support voice and rate options
+        if self.command == "say" {
+            if let Some(ref voice) = self.voice {
+                cmd.args(["-v", voice]);
+            }
+            if let Some(rate) = self.rate {
+                cmd.args(["-r", &rate.to_string()]);
+            }
+        }
+
+        cmd.arg(message);
+        spawn_detached(&mut cmd)
+    }
+}
+
+/// Native system banner: macOS banner on macOS, freedesktop elsewhere.
+pub struct SystemBackend {
+    pub events: Vec<NotifyEvent>,
+}
+
+impl NotificationBackend for SystemBackend {
+    fn name(&self) -> &'static str {
+        "system"
+    }
+
+    fn accepts(&self, event: NotifyEvent) -> bool {
+        filter_accepts(&self.events, event)
+    }
+
+    fn send(&self, notification: &Notification<'_>, message: &str) -> Result<()> {
+        if cfg!(target_os = "macos") {
+            crate::config::NotificationConfig::system_notify(
+                notification.task_title,
+                message,
+                notification.pr_url,
+            );
+            Ok(())
+        } else {
+            desktop_notify(notification, message)
+        }
+    }
+}
+
+/// Freedesktop notifications.
+pub struct DesktopBackend {
+    pub events: Vec<NotifyEvent>,
+}
+
+impl NotificationBackend for DesktopBackend {
+    fn name(&self) -> &'static str {
+        "desktop"
+    }
+
+    fn accepts(&self, event: NotifyEvent) -> bool {
+        filter_accepts(&self.events, event)
+    }
+
+    fn send(&self, notification: &Notification<'_>, message: &str) -> Result<()> {
+        desktop_notify(notification, message)
+    }
+}
+
+/// Urgency hint for freedesktop notifications: failures are `critical`
+/// so they stay on screen until dismissed.
+fn desktop_urgency(event: NotifyEvent) -> &'static str {
+    match event {
+        NotifyEvent::CiFailed
+        | NotifyEvent::Conflict
+        | NotifyEvent::NeedsInput
+        | NotifyEvent::Stuck => "critical",
+        NotifyEvent::RateLimited => "normal",
+        NotifyEvent::InReview | NotifyEvent::Done => "low",
+    }
+}
+
+/// Send a freedesktop notification via `notify-send`, falling back to a
+/// direct D-Bus call through `gdbus` when `notify-send` is not installed.
+fn desktop_notify(notification: &Notification<'_>, message: &str) -> Result<()> {
+    let summary = match notification.project {
+        Some(project) => format!("claustre · {project}"),
+        None => "claustre".to_string(),
+    };
+    let urgency = desktop_urgency(notification.event);
+
+    let mut notify_send = Command::new("notify-send");
+    notify_send.args(["--app-name", "claustre", "--urgency", urgency]);
+    if let Some(icon) = crate::config::NotificationConfig::ensure_icon() {
+        notify_send.args(["--icon", &icon.display().to_string()]);
+    }
+    notify_send.args([summary.as_str(), message]);
+    if spawn_detached(&mut notify_send).is_ok() {
+        return Ok(());
+    }
+
+    // org.freedesktop.Notifications.Notify(app_name, replaces_id, app_icon,
+    // summary, body, actions, hints, expire_timeout)
+    let quote = |s: &str| format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"));
+    let mut gdbus = Command::new("gdbus");
+    gdbus.args([
+        "call",
+        "--session",
+        "--dest",
+        "org.freedesktop.Notifications",
+        "--object-path",
+        "/org/freedesktop/Notifications",
+        "--method",
+        "org.freedesktop.Notifications.Notify",
+        "claustre",
+        "0",
+        "",
+        &quote(&summary),
+        &quote(message),
+        "[]",
+        "{}",
+        "-1",
+    ]);
+    spawn_detached(&mut gdbus).context("neither notify-send nor gdbus is available")
+}
+
+/// JSON POST to a URL.
+pub struct WebhookBackend {
+    pub url: String,
+    pub headers: Vec<String>,
+    pub events: Vec<NotifyEvent>,
+}
+
+impl NotificationBackend for WebhookBackend {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn accepts(&self, event: NotifyEvent) -> bool {
+        filter_accepts(&self.events, event)
+    }
+
+    fn send(&self, notification: &Notification<'_>, message: &str) -> Result<()> {
+        let body = notification.webhook_payload(message).to_string();
+        let mut cmd = Command::new("curl");
+        cmd.args(["-sf", "--max-time", "10", "-X", "POST", "-K", "-"]);
+        spawn_detached_with_input(&mut cmd, curl_config(&self.headers, &body, &self.url))
+    }
+}
+
+/// Bell or desktop-notification escape sequence for the host terminal.
+pub struct TerminalBackend {
+    pub style: TerminalStyle,
+    pub events: Vec<NotifyEvent>,
+}
+
+impl NotificationBackend for TerminalBackend {
+    fn name(&self) -> &'static str {
+        "terminal"
+    }
+
+    fn accepts(&self, event: NotifyEvent) -> bool {
+        filter_accepts(&self.events, event)
+    }
+
+    fn needs_host_terminal(&self) -> bool {
+        true
+    }
+
+    fn send(&self, _notification: &Notification<'_>, message: &str) -> Result<()> {
+        // Write to the controlling terminal rather than stdout so the TUI's
+        // alternate screen buffer doesn't swallow the sequence.
+        let mut tty = std::fs::OpenOptions::new()
+            .write(true)
+            .open("/dev/tty")
+            .context("no controlling terminal")?;
+        tty.write_all(terminal_sequence(self.style, message).as_bytes())?;
+        tty.flush()?;
+        Ok(())
+    }
+}
+
+/// Build the escape sequence for a terminal notification.
+///
+/// Control characters are stripped from the message so it can't terminate
+/// the OSC string early or inject further escapes.
+pub fn terminal_sequence(style: TerminalStyle, message: &str) -> String {
+    let clean: String = message.chars().filter(|c| !c.is_control()).collect();
+    match style {
+        TerminalStyle::Bell => "\x07".to_string(),
+        TerminalStyle::Osc9 => format!("\x1b]9;{clean}\x07"),
+        TerminalStyle::Osc777 => format!("\x1b]777;notify;claustre;{clean}\x07"),
+    }
+}
+
+/// Arbitrary program with templated arguments.
+pub struct CommandBackend {
+    pub command: String,
+    pub args: Vec<String>,
+    pub events: Vec<NotifyEvent>,
+}
+
+impl NotificationBackend for CommandBackend {
+    fn name(&self) -> &'static str {
+        "command"
+    }
+
+    fn accepts(&self, event: NotifyEvent) -> bool {
+        filter_accepts(&self.events, event)
+    }
+
+    fn send(&self, notification: &Notification<'_>, message: &str) -> Result<()> {
+        let mut cmd = Command::new(&self.command);
+        for arg in &self.args {
+            cmd.arg(notification.render(arg, message));
+        }
+        cmd.env("CLAUSTRE_EVENT", notification.event.as_str())
+            .env("CLAUSTRE_TASK", notification.task_title)
+            .env("CLAUSTRE_PROJECT", notification.project.unwrap_or(""))
+            .env("CLAUSTRE_PR_URL", notification.pr_url.unwrap_or(""))
+            .env("CLAUSTRE_MESSAGE", message);
+        spawn_detached(&mut cmd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(event: NotifyEvent) -> Notification<'static> {
+        Notification {
+            event,
+            task_title: "fix login",
+            project: Some("web"),
+            pr_url: Some("https://github.com/o/r/pull/7"),
+        }
+    }
+
+    #[test]
+    fn notify_event_round_trip() {
+        for event in NotifyEvent::ALL {
+            assert_eq!(event.as_str().parse::<NotifyEvent>().unwrap(), event);
+            assert_eq!(event.to_string(), event.as_str());
+        }
+        assert!("nonsense".parse::<NotifyEvent>().is_err());
+    }
+
+    #[test]
+    fn render_substitutes_all_placeholders() {
+        let n = sample(NotifyEvent::CiFailed);
+        let out = n.render("[{event}] {project}/{task} {pr_url} — {message}", "boom");
+        assert_eq!(
+            out,
+            "[ci_failed] web/fix login https://github.com/o/r/pull/7 — boom"
+        );
+    }
+
+    #[test]
+    fn render_missing_optionals_are_empty() {
+        let n = Notification {
+            event: NotifyEvent::Done,
+            task_title: "t",
+            project: None,
+            pr_url: None,
+        };
+        assert_eq!(n.render("{project}|{pr_url}|{task}", ""), "||t");
+    }
+
+    #[test]
+    fn empty_filter_accepts_everything() {
+        for event in NotifyEvent::ALL {
+            assert!(filter_accepts(&[], event));
+        }
+        assert!(filter_accepts(
+            &[NotifyEvent::Conflict],
+            NotifyEvent::Conflict
+        ));
+        assert!(!filter_accepts(&[NotifyEvent::Conflict], NotifyEvent::Done));
+    }
+
+    #[test]
+    fn webhook_payload_shape() {
+        let payload = sample(NotifyEvent::Conflict).webhook_payload("msg");
+        assert_eq!(payload["event"], "conflict");
+        assert_eq!(payload["task"], "fix login");
+        assert_eq!(payload["project"], "web");
+        assert_eq!(payload["pr_url"], "https://github.com/o/r/pull/7");
+        assert_eq!(payload["message"], "msg");
+        assert!(payload["timestamp"].is_string());
+    }
+
+    #[test]
+    fn curl_config_quotes_values() {
+        let config = curl_config(
+            &["Authorization: Bearer s3cr\"t".to_string()],
+            r#"{"text":"a\\b"}"#,
+            "https://example.com/hook",
+        );
+        assert_eq!(
+            config,
+            "header = \"Content-Type: application/json\"\n\
+             header = \"Authorization: Bearer s3cr\\\"t\"\n\
+             data-binary = \"{\\\"text\\\":\\\"a\\\\\\\\b\\\"}\"\n\
+             url = \"https://example.com/hook\"\n"
+        );
+    }
+
+    #[test]
+    fn only_terminal_backend_needs_host_terminal() {
+        let terminal = TerminalBackend {
+            style: TerminalStyle::Bell,
+            events: Vec::new(),
+        };
+        let webhook = WebhookBackend {
+            url: "http://localhost/hook".into(),
+            headers: Vec::new(),
+            events: Vec::new(),
+        };
+        assert!(terminal.needs_host_terminal());
+        assert!(!webhook.needs_host_terminal());
+    }
+
+    #[test]
+    fn terminal_sequences() {
+        assert_eq!(terminal_sequence(TerminalStyle::Bell, "hi"), "\x07");
+        assert_eq!(
+            terminal_sequence(TerminalStyle::Osc9, "hi"),
+            "\x1b]9;hi\x07"
+        );
+        assert_eq!(
+            terminal_sequence(TerminalStyle::Osc777, "hi"),
+            "\x1b]777;notify;claustre;hi\x07"
+        );
+    }
+
+    #[test]
+    fn terminal_sequence_strips_control_chars() {
+        let seq = terminal_sequence(TerminalStyle::Osc9, "a\x07b\x1b]0;pwn\nc");
+        assert_eq!(seq, "\x1b]9;ab]0;pwnc\x07");
+    }
+
+    #[test]
+    fn parse_backend_configs() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            backends: Vec<NotificationBackendConfig>,
+        }
+        let toml_str = r#"
+[[backends]]
+type = "desktop"
+events = ["ci_failed", "needs_input"]
+
+[[backends]]
+type = "webhook"
+url = "https://example.com/hook"
+headers = ["X-Token: abc"]
+
+[[backends]]
+type = "terminal"
+style = "osc777"
+
+[[backends]]
+type = "command"
+command = "echo"
+args = ["{event}", "{task}"]
+"#;
+        let w: Wrapper = toml::from_str(toml_str).unwrap();
+        assert_eq!(w.backends.len(), 4);
+
+        let built: Vec<_> = w
+            .backends
+            .iter()
+            .map(NotificationBackendConfig::build)
+            .collect();
+        assert_eq!(built[0].name(), "desktop");
+        assert!(built[0].accepts(NotifyEvent::CiFailed));
+        assert!(!built[0].accepts(NotifyEvent::Done));
+        assert_eq!(built[1].name(), "webhook");
+        assert!(built[1].accepts(NotifyEvent::Done));
+        assert_eq!(built[2].name(), "terminal");
+        assert_eq!(built[3].name(), "command");
+        assert!(matches!(
+            w.backends[2],
+            NotificationBackendConfig::Terminal {
+                style: TerminalStyle::Osc777,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_backend_rejects_unknown_event() {
+        #[derive(Debug, Deserialize)]
+        #[expect(dead_code, reason = "only parsed for the error")]
+        struct Wrapper {
+            backends: Vec<NotificationBackendConfig>,
+        }
+        let toml_str = r#"
+[[backends]]
+type = "desktop"
+events = ["exploded"]
+"#;
+        assert!(toml::from_str::<Wrapper>(toml_str).is_err());
+    }
+
+    #[test]
+    fn desktop_urgency_marks_failures_critical() {
+        assert_eq!(desktop_urgency(NotifyEvent::CiFailed), "critical");
+        assert_eq!(desktop_urgency(NotifyEvent::Conflict), "critical");
+        assert_eq!(desktop_urgency(NotifyEvent::Done), "low");
+    }
+}
@@ -0,0 +1,301 @@
+//! Keyboard copy mode: a cursor over a pane's screen and scrollback with vi
+//! motions, and char, line or block selections that can span the history.
+//!
+//! Positions use the same `(history line, column)` addressing as
+//! [`Selection`]; the caller keeps the cursor in view with
+//! [`super::EmbeddedTerminal::reveal_line`].
+
+use std::iter;
+
+use super::PaneId;
+use super::search::LineText;
+use super::selection::{Selection, SelectionKind};
+
+/// A motion in copy mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    Left,
+    Right,
+    Up(usize),
+    Down(usize),
+    /// Start of the next word (`w`).
+    WordForward,
+    /// Start of the previous word (`b`).
+    WordBackward,
+    /// End of the current or next word (`e`).
+    WordEnd,
+    /// First column (`0`).
+    LineStart,
+    /// First non-blank character (`^`).
+    FirstNonBlank,
+    /// Last character (`$`).
+    LineEnd,
+    /// Oldest history line (`g`).
+    Top,
+    /// Last line of the live screen (`G`).
+    Bottom,
+    /// A given history line, keeping the column (`H`, `M`, `L`).
+    Line(usize),
+}
+
+/// Copy-mode state for one pane.
+pub struct CopyMode {
+    pub pane: PaneId,
+    /// Cursor position as (history line, column).
+    pub cursor: (usize, u16),
+    /// Selection anchor and shape, once `v`, `V` or `Ctrl+V` is pressed.
+    pub anchor: Option<((usize, u16), SelectionKind)>,
+    /// Column to aim for on vertical moves, as in vi.
+    want_col: u16,
+    /// The pane's history when copy mode was entered; motions and yanks
+    /// read it instead of the live scrollback.
+    history: Vec<LineText>,
+}
+
+impl CopyMode {
+    pub fn new(pane: PaneId, cursor: (usize, u16), history: Vec<LineText>) -> Self {
+        Self {
+            pane,
+            cursor,
+            anchor: None,
+            want_col: cursor.1,
+            history,
+        }
+    }
+
+    pub fn history(&self) -> &[LineText] {
+        &self.history
+    }
+
+    /// Start a selection of `kind` at the cursor; the same kind again
+    /// clears it, another kind reshapes it.
+    pub fn toggle_selection(&mut self, kind: SelectionKind) {
+        self.anchor = match self.anchor {
+            Some((_, current)) if current == kind => None,
+            Some((start, _)) => Some((start, kind)),
+            None => Some((self.cursor, kind)),
+        };
+    }
+
+    /// The current selection, if one is started.
+    pub fn selection(&self) -> Option<Selection> {
+        self.anchor.map(|(start, kind)| Selection {
+            pane: self.pane,
+            start,
+            end: self.cursor,
+            kind,
+        })
+    }
+
+    /// Move the cursor within the history, in a pane `cols` wide.
+    pub fn apply(&mut self, motion: Motion, cols: u16) {
+        let lines = self.history.as_slice();
+        let last_line = lines.len().saturating_sub(1);
+        let last_col = cols.saturating_sub(1);
+        let (line, col) = self.cursor;
+        let line_text = |i: usize| lines.get(i);
+        let vertical = |target: usize| (target.min(last_line), self.want_col.min(last_col));
+
+        let (new, keep_want) = match motion {
+            Motion::Left => ((line, col.saturating_sub(1)), false),
+            Motion::Right => ((line, (col + 1).min(last_col)), false),
+            Motion::Up(n) => (vertical(line.saturating_sub(n)), true),
+            Motion::Down(n) => (vertical(line.saturating_add(n)), true),
+            Motion::Line(target) => (vertical(target), true),
+            Motion::Top => ((0, 0), false),
+            Motion::Bottom => ((last_line, 0), false),
+            Motion::LineStart => ((line, 0), false),
+            Motion::FirstNonBlank => {
+                let first = line_text(line)
+                    .and_then(|l| l.chars().find(|&(_, c)| !c.is_whitespace()))
+                    .map_or(0, |(c, _)| c);
+                ((line, first), false)
+            }
+            Motion::LineEnd => {
+                let end = line_text(line)
+                    .and_then(|l| l.chars().next_back())
+                    .map_or(0, |(c, _)| c);
+                ((line, end), false)
+            }
+            Motion::WordForward => (word_forward(lines, self.cursor), false),
+            Motion::WordBackward => (word_backward(lines, self.cursor), false),
+            Motion::WordEnd => (word_end(lines, self.cursor), false),
+        };
+        self.cursor = new;
+        if !keep_want {
+            self.want_col = new.1;
+        }
+    }
+}
+
+/// Character class for word motions: blank, word character, punctuation.
+fn class(c: char) -> u8 {
+    if c.is_whitespace() {
+        0
+    } else if c.is_alphanumeric() || c == '_' {
+        1
+    } else {
+        2
+    }
+}
+
+/// Every character from `from` (exclusive) to the end of the history, with
+/// a blank at the end of each line.
+fn chars_after(lines: &[LineText], from: (usize, u16)) -> impl Iterator<Item = (usize, u16, char)> {
+    lines
+        .iter()
+        .enumerate()
+        .skip(from.0)
+        .flat_map(|(i, line)| {
+            line.chars()
+                .map(move |(col, c)| (i, col, c))
+                .chain(iter::once((i, u16::MAX, ' ')))
+        })
+        .skip_while(move |&(i, col, _)| i == from.0 && col <= from.1)
+}
+
+/// Every character before `from`, nearest first, with a blank at the end
+/// of each line.
+fn chars_before(
+    lines: &[LineText],
+    from: (usize, u16),
+) -> impl Iterator<Item = (usize, u16, char)> {
+    lines[..lines.len().min(from.0 + 1)]
+        .iter()
+        .enumerate()
+        .rev()
+        .flat_map(|(i, line)| {
+            iter::once((i, u16::MAX, ' '))
+                .chain(line.chars().rev().map(move |(col, c)| (i, col, c)))
+        })
+        .skip_while(move |&(i, col, _)| i == from.0 && col >= from.1)
+}
+
+fn class_at(lines: &[LineText], (line, col): (usize, u16)) -> u8 {
+    lines
+        .get(line)
+        .and_then(|l| l.chars().find(|&(c, _)| c == col))
+        .map_or(0, |(_, c)| class(c))
+}
+
+fn word_forward(lines: &[LineText], from: (usize, u16)) -> (usize, u16) {
+    let start_class = class_at(lines, from);
+    let mut left_word = start_class == 0;
+    for (i, col, c) in chars_after(lines, from) {
+        let k = class(c);
+        if k != start_class || col == u16::MAX {
+            left_word = true;
+        }
+        if left_word && k != 0 {
+            return (i, col);
+        }
+    }
+    from
+}
+
+fn word_end(lines: &[LineText], from: (usize, u16)) -> (usize, u16) {
+    let mut chars = chars_after(lines, from).peekable();
+    while chars.next_if(|&(_, _, c)| class(c) == 0).is_some() {}
+    let Some((mut i, mut col, c)) = chars.next() else {
+        return from;
+    };
+    let k = class(c);
+    while let Some((ni, ncol, _)) =
+        chars.next_if(|&(_, ncol, nc)| ncol != u16::MAX && class(nc) == k)
+    {
+        (i, col) = (ni, ncol);
+    }
+    (i, col)
+}
+
+fn word_backward(lines: &[LineText], from: (usize, u16)) -> (usize, u16) {
+    let mut chars = chars_before(lines, from).peekable();
+    while chars.next_if(|&(_, _, c)| class(c) == 0).is_some() {}
+    let Some((mut i, mut col, c)) = chars.next() else {
+        return from;
+    };
+    let k = class(c);
+    while let Some((ni, ncol, _)) =
+        chars.next_if(|&(_, ncol, nc)| ncol != u16::MAX && class(nc) == k)
+    {
+        (i, col) = (ni, ncol);
+    }
+    (i, col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &[u8]) -> Vec<LineText> {
+        let mut parser = vt100::Parser::new(4, 30, 0);
+        parser.process(text);
+        (0..4)
+            .map(|row| LineText::from_screen(parser.screen(), row))
+            .collect()
+    }
+
+    #[test]
+    fn word_motions_cross_lines() {
+        let mut mode = CopyMode::new(0, (0, 0), lines(b"let x = foo.bar;\r\n  next();"));
+
+        let mut visit = |motion| {
+            mode.apply(motion, 30);
+            mode.cursor
+        };
+        assert_eq!(visit(Motion::WordForward), (0, 4));
+        assert_eq!(visit(Motion::WordForward), (0, 6));
+        assert_eq!(visit(Motion::WordForward), (0, 8));
+        assert_eq!(visit(Motion::WordEnd), (0, 10));
+        assert_eq!(visit(Motion::WordForward), (0, 11));
+        assert_eq!(visit(Motion::WordForward), (0, 12));
+        assert_eq!(visit(Motion::WordForward), (0, 15));
+        assert_eq!(visit(Motion::WordForward), (1, 2));
+        assert_eq!(visit(Motion::WordBackward), (0, 15));
+        assert_eq!(visit(Motion::WordBackward), (0, 12));
+        assert_eq!(visit(Motion::LineEnd), (0, 15));
+        assert_eq!(visit(Motion::LineStart), (0, 0));
+        assert_eq!(visit(Motion::Down(1)), (1, 0));
+        assert_eq!(visit(Motion::FirstNonBlank), (1, 2));
+    }
+
+    #[test]
+    fn vertical_moves_keep_the_wanted_column() {
+        let mut mode = CopyMode::new(
+            0,
+            (0, 12),
+            lines(b"a long first line\r\nshort\r\nanother long line"),
+        );
+
+        mode.apply(Motion::Down(1), 30);
+        assert_eq!(mode.cursor, (1, 12));
+        mode.apply(Motion::Down(5), 30);
+        assert_eq!(mode.cursor, (3, 12));
+        mode.apply(Motion::Top, 30);
+        assert_eq!(mode.cursor, (0, 0));
+        mode.apply(Motion::Line(2), 30);
+        assert_eq!(mode.cursor, (2, 0));
+    }
+
+    #[test]
+    fn selections_follow_the_cursor() {
+        let mut mode = CopyMode::new(0, (0, 6), lines(b"alpha beta\r\ngamma delta"));
+        assert!(mode.selection().is_none());
+
+        mode.toggle_selection(SelectionKind::Char);
+        mode.apply(Motion::Down(1), 30);
+        mode.apply(Motion::WordEnd, 30);
+        assert_eq!(
+            mode.selection().unwrap().extract_text(mode.history()),
+            "beta\ngamma delta"
+        );
+
+        mode.toggle_selection(SelectionKind::Line);
+        assert_eq!(
+            mode.selection().unwrap().extract_text(mode.history()),
+            "alpha beta\ngamma delta"
+        );
+        mode.toggle_selection(SelectionKind::Line);
+        assert!(mode.selection().is_none());
+    }
+}
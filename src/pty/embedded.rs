@@ -3,11 +3,13 @@
 use std::io::Write;
 use std::sync::mpsc;
 use std::thread;
+use std::time::Instant;
 
 use anyhow::{Context, Result};
 use portable_pty::{CommandBuilder, PtySize};
 use vt100::Parser;
 
+use super::search::LineText;
 use super::{PROCESS_BYTE_BUDGET, SCROLL_DOWN_ACCEL_DIVISOR, SCROLLBACK_LINES};
 
 /// The I/O backend for an `EmbeddedTerminal`.
@@ -43,8 +45,11 @@ pub(crate) enum Backend {
 ///
 /// - **Render phase**: `prepare_for_render()` sets the parser to
 ///   `scroll_offset` for the widget to read.  `restore_after_render()`
-///   returns it to 0.  These are the **only** code paths that set
-///   `scrollback > 0` on the parser.
+///   returns it to 0.  These are the **only** code paths that leave
+///   `scrollback > 0` on the parser between calls; `history()` reads
+///   the buffer but restores 0 before returning.  Offsets deeper than one
+///   screen are rendered from a cached `DeepView` copy of the viewport,
+///   since vt100 cannot list those rows directly.
 pub struct EmbeddedTerminal {
     /// I/O backend (local PTY or remote socket).
     pub(crate) backend: Backend,
@@ -54,6 +59,10 @@ pub struct EmbeddedTerminal {
     pub(crate) parser: Parser,
     /// Whether the child process has exited (reader thread ended).
     pub exited: bool,
+    /// When the child last wrote output (or when it was spawned).
+    pub last_output: Instant,
+    /// PID of the process spawned in the PTY, when the OS reported one.
+    pub(crate) child_pid: Option<u32>,
     /// User-controlled scroll position: 0 = live screen, >0 = lines into history.
     /// Pure arithmetic — never touches the parser's scrollback state.
     pub(crate) scroll_offset: usize,
@@ -61,6 +70,20 @@ pub struct EmbeddedTerminal {
     /// Updated after each `process_output()` call. Used to clamp
     /// `scroll_offset` without calling `parser.set_scrollback()`.
     pub(crate) available_scrollback: usize,
+    /// Viewport rendered from scrollback more than one screen back, reused
+    /// until the scroll position or the output changes.
+    pub(crate) deep_view: Option<DeepView>,
+}
+
+/// A copy of the viewport when it sits deeper in the scrollback than vt100
+/// can show directly (see [`EmbeddedTerminal::with_scrollback`]).
+pub(crate) struct DeepView {
+    scroll_offset: usize,
+    available_scrollback: usize,
+    last_output: Instant,
+    parser: Parser,
+    /// Set between `prepare_for_render()` and `restore_after_render()`.
+    active: bool,
 }
 
 impl EmbeddedTerminal {
@@ -84,6 +107,7 @@ impl EmbeddedTerminal {
             .slave
             .spawn_command(cmd)
             .context("failed to spawn child process")?;
+        let child_pid = child.process_id();
         drop(child);
         drop(pair.slave); // Close slave side in parent
 
@@ -125,8 +149,11 @@ impl EmbeddedTerminal {
             output_rx: rx,
             parser: Parser::new(rows, cols, SCROLLBACK_LINES),
             exited: false,
+            last_output: Instant::now(),
+            child_pid,
             scroll_offset: 0,
             available_scrollback: 0,
+            deep_view: None,
         })
     }
 
@@ -182,6 +209,7 @@ impl EmbeddedTerminal {
             match self.output_rx.try_recv() {
                 Ok(bytes) => {
                     bytes_processed += bytes.len();
+                    self.last_output = Instant::now();
                     self.parser.process(&bytes);
                     if let Some(limit) = budget
                         && bytes_processed >= limit
@@ -242,6 +270,47 @@ impl EmbeddedTerminal {
         self.scroll_offset = self.scroll_offset.min(self.available_scrollback);
     }
 
+    /// Whether the PTY's process has a direct child whose command line
+    /// contains `name`, e.g. `claude` under the shell-fallback wrapper.
+    ///
+    /// Reads the process table with `ps`, so it works on macOS and Linux.
+    /// `None` when that is not possible (no PID, or `ps` failed).
+    pub fn has_child_process(&self, name: &str) -> Option<bool> {
+        let pid = self.child_pid?;
+        let output = std::process::Command::new("ps")
+            .args(["-A", "-o", "ppid=,args="])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())?;
+        Some(ps_lists_child(
+            &String::from_utf8_lossy(&output.stdout),
+            pid,
+            name,
+        ))
+    }
+
+    /// Send SIGTERM to the PTY process's direct children whose command line
+    /// contains `name`. Returns how many were signalled, or `None` when the
+    /// process table can't be read.
+    pub fn terminate_child_process(&self, name: &str) -> Option<usize> {
+        let pid = self.child_pid?;
+        let output = std::process::Command::new("ps")
+            .args(["-A", "-o", "pid=,ppid=,args="])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())?;
+        let children = ps_child_pids(&String::from_utf8_lossy(&output.stdout), pid, name);
+        for child in &children {
+            if let Ok(child) = i32::try_from(*child) {
+                // SAFETY: kill() only sends a signal; the PID was just read from ps.
+                unsafe {
+                    libc::kill(child, libc::SIGTERM);
+                }
+            }
+        }
+        Some(children.len())
+    }
+
     /// Send raw bytes (keystrokes) to the child process.
     pub fn send_bytes(&mut self, bytes: &[u8]) -> Result<()> {
         match &mut self.backend {
@@ -302,7 +371,10 @@ impl EmbeddedTerminal {
 
     /// Get the current terminal screen state for rendering.
     pub fn screen(&self) -> &vt100::Screen {
-        self.parser.screen()
+        match &self.deep_view {
+            Some(view) if view.active => view.parser.screen(),
+            _ => self.parser.screen(),
+        }
     }
 
     /// Get the user's current scroll offset (0 = live screen, >0 = lines into history).
@@ -373,15 +445,106 @@ impl EmbeddedTerminal {
         self.scroll_offset = 0;
     }
 
+    /// Absolute line index (0 = oldest scrollback line) of the top row of
+    /// the user's viewport.
+    pub fn viewport_top(&self) -> usize {
+        self.available_scrollback - self.scroll_offset.min(self.available_scrollback)
+    }
+
+    /// Every line of scrollback plus the live screen, oldest first.
+    ///
+    /// Reads the whole buffer in one pass with [`Self::with_scrollback`],
+    /// which restores the live screen before returning, so the render-phase
+    /// invariant holds for callers.
+    pub fn history(&mut self) -> Vec<LineText> {
+        self.with_scrollback(self.available_scrollback, |screen| {
+            (0..screen.size().0)
+                .map(|row| LineText::from_screen(screen, row))
+                .collect()
+        })
+    }
+
+    /// Scroll so the absolute history `line` sits near the middle of the
+    /// viewport (or as close as the buffer allows).
+    ///
+    /// Pure arithmetic — does not touch the parser's scrollback state.
+    pub fn scroll_to_line(&mut self, line: usize) {
+        let rows = usize::from(self.parser.screen().size().0);
+        let top = line.saturating_sub(rows / 2);
+        self.scroll_offset = self.available_scrollback.saturating_sub(top);
+    }
+
+    /// Scroll the least amount that brings the absolute history `line` into
+    /// the viewport.
+    ///
+    /// Pure arithmetic — does not touch the parser's scrollback state.
+    pub fn reveal_line(&mut self, line: usize) {
+        let rows = usize::from(self.parser.screen().size().0);
+        let top = self.viewport_top();
+        let new_top = if line < top {
+            line
+        } else if line >= top + rows {
+            line + 1 - rows
+        } else {
+            return;
+        };
+        self.scroll_offset = self
+            .available_scrollback
+            .saturating_sub(new_top.min(self.available_scrollback));
+    }
+
     /// Set the parser's scrollback to the user's scroll position for rendering.
     ///
     /// This is the **only** code path that sets `scrollback > 0` on the parser.
     /// Must be paired with [`Self::restore_after_render`] immediately after the draw
     /// call to restore the invariant (parser always at scrollback 0).
+    ///
+    /// Positions more than one screen back can't be read from the parser
+    /// directly, so [`Self::screen`] returns a [`DeepView`] until the restore.
     pub fn prepare_for_render(&mut self) {
+        let (rows, cols) = self.parser.screen().size();
+        if self.scroll_offset > usize::from(rows) {
+            self.activate_deep_view(rows, cols);
+        }
         self.parser.set_scrollback(self.scroll_offset);
     }
 
+    /// Bring the [`DeepView`] up to date with the scroll position and output,
+    /// and serve [`Self::screen`] from it.
+    fn activate_deep_view(&mut self, rows: u16, cols: u16) {
+        let fresh = self.deep_view.as_ref().is_some_and(|view| {
+            view.scroll_offset == self.scroll_offset
+                && view.available_scrollback == self.available_scrollback
+                && view.last_output == self.last_output
+        });
+        if !fresh {
+            let parser = self.with_scrollback(self.scroll_offset, |screen| {
+                let mut view = Parser::new(rows, cols, 0);
+                // Scrolled-back views never show the PTY cursor.
+                view.process(b"\x1b[?25l");
+                for (row, bytes) in screen
+                    .rows_formatted(0, cols)
+                    .take(usize::from(rows))
+                    .enumerate()
+                {
+                    view.process(format!("\x1b[{};1H\x1b[m", row + 1).as_bytes());
+                    view.process(&bytes);
+                }
+                view
+            });
+            self.deep_view = Some(DeepView {
+                scroll_offset: self.scroll_offset,
+                available_scrollback: self.available_scrollback,
+                last_output: self.last_output,
+                parser,
+                active: false,
+            });
+        }
+        if let Some(view) = &mut self.deep_view {
+            view.active = true;
+        }
+    }
+
     /// Restore the parser to the live screen (scrollback 0) after rendering.
     ///
     /// Must be called after every [`Self::prepare_for_render`] to maintain the
@@ -389,5 +552,52 @@ impl EmbeddedTerminal {
     /// phase.
     pub fn restore_after_render(&mut self) {
         self.parser.set_scrollback(0);
+        if let Some(view) = &mut self.deep_view {
+            view.active = false;
+        }
+    }
+
+    /// Run `read` on the screen `offset` lines into the scrollback, then
+    /// return the parser to the live screen.
+    ///
+    /// vt100 computes `rows - offset` when listing visible rows, which
+    /// underflows once the offset is more than one screen back. The screen
+    /// is therefore grown by `offset` blank rows at the bottom for the read,
+    /// so `read` sees the scrollback from `offset` lines back followed by
+    /// the whole live screen.
+    fn with_scrollback<T>(&mut self, offset: usize, read: impl FnOnce(&vt100::Screen) -> T) -> T {
+        let (rows, cols) = self.parser.screen().size();
+        let tall = u16::try_from(offset + usize::from(rows)).unwrap_or(u16::MAX);
+        self.parser.set_size(tall, cols);
+        self.parser.set_scrollback(offset);
+        let result = read(self.parser.screen());
+        self.parser.set_scrollback(0);
+        self.parser.set_size(rows, cols);
+        result
     }
 }
+
+/// PIDs in `ps -o pid=,ppid=,args=` output of children of `pid` whose
+/// command line contains `name`.
+pub(crate) fn ps_child_pids(ps_output: &str, pid: u32, name: &str) -> Vec<u32> {
+    ps_output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let child = fields.next()?.parse::<u32>().ok()?;
+            let parent = fields.next()?.parse::<u32>().ok()?;
+            let args: Vec<&str> = fields.collect();
+            (parent == pid && args.join(" ").contains(name)).then_some(child)
+        })
+        .collect()
+}
+
+/// Whether `ps -o ppid=,args=` output lists a child of `pid` whose command
+/// line contains `name`.
+pub(crate) fn ps_lists_child(ps_output: &str, pid: u32, name: &str) -> bool {
+    ps_output.lines().any(|line| {
+        let mut fields = line.trim_start().splitn(2, char::is_whitespace);
+        fields.next().and_then(|ppid| ppid.parse::<u32>().ok()) == Some(pid)
+            && fields.next().is_some_and(|args| args.contains(name))
+    })
+}
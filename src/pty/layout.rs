@@ -5,6 +5,8 @@ use std::collections::HashMap;
 use anyhow::{Context, Result};
 use portable_pty::CommandBuilder;
 
+use crate::config::{LayoutConfig, PaneCommand};
+
 use super::PaneId;
 use super::embedded::EmbeddedTerminal;
 use super::session_terminals::PaneInfo;
@@ -35,8 +37,108 @@ pub enum LayoutNode {
     },
 }
 
+// ── Pane kinds ──
+
+/// What a pane runs, kept so it can be restarted and its layout saved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PaneKind {
+    Shell,
+    Claude,
+    /// A configured command: a `[[panes]]` entry or an inline layout leaf.
+    Command(PaneCommand),
+    /// A pane claustre opened for one of its own loops; never saved.
+    Transient,
+}
+
+impl PaneKind {
+    /// Resolve a layout leaf, looking bare names up in `[[panes]]`.
+    pub(crate) fn from_leaf(leaf: &LayoutConfig, commands: &[PaneCommand]) -> Result<Self> {
+        let LayoutConfig::Pane {
+            pane,
+            cmd,
+            env,
+            restart_on_exit,
+        } = leaf
+        else {
+            anyhow::bail!("expected a pane leaf, found a split");
+        };
+        if pane == "claude" {
+            return Ok(Self::Claude);
+        }
+        if let Some(cmd) = cmd {
+            return Ok(Self::Command(PaneCommand {
+                pane: pane.clone(),
+                cmd: cmd.clone(),
+                env: env.clone(),
+                restart_on_exit: *restart_on_exit,
+            }));
+        }
+        if pane == "shell" {
+            return Ok(Self::Shell);
+        }
+        commands
+            .iter()
+            .find(|c| &c.pane == pane)
+            .map(|c| Self::Command(c.clone()))
+            .with_context(|| {
+                format!("unknown pane \"{pane}\": define it under [[panes]] or give it a cmd")
+            })
+    }
+
+    /// The layout leaf that rebuilds this pane, or `None` if it isn't saved.
+    pub(crate) fn to_leaf(&self) -> Option<LayoutConfig> {
+        match self {
+            Self::Shell => Some(LayoutConfig::pane("shell")),
+            Self::Claude => Some(LayoutConfig::pane("claude")),
+            Self::Command(command) => Some(LayoutConfig::Pane {
+                pane: command.pane.clone(),
+                cmd: Some(command.cmd.clone()),
+                env: command.env.clone(),
+                restart_on_exit: command.restart_on_exit,
+            }),
+            Self::Transient => None,
+        }
+    }
+
+    /// Default pane title.
+    pub(crate) fn label(&self) -> &str {
+        match self {
+            Self::Shell => "Shell",
+            Self::Claude => "Claude",
+            Self::Command(command) => &command.pane,
+            Self::Transient => "",
+        }
+    }
+}
+
+/// A login shell in the worktree.
+pub(crate) fn shell_command(worktree_path: &str) -> CommandBuilder {
+    let shell_path = std::env::var("SHELL").unwrap_or_else(|_| default_shell().into());
+    let mut cmd = CommandBuilder::new(&shell_path);
+    cmd.cwd(worktree_path);
+    cmd
+}
+
+/// A configured command, run through the user's shell in the worktree.
+pub(crate) fn pane_command(command: &PaneCommand, worktree_path: &str) -> CommandBuilder {
+    let shell_path = std::env::var("SHELL").unwrap_or_else(|_| default_shell().into());
+    let mut cmd = CommandBuilder::new(&shell_path);
+    cmd.arg("-c");
+    cmd.arg(&command.cmd);
+    cmd.cwd(worktree_path);
+    for (key, value) in &command.env {
+        cmd.env(key, value);
+    }
+    cmd
+}
+
 // ── Layout tree helpers ──
 
+/// Smallest share a resize leaves either side of a split, in percent.
+pub(crate) const MIN_RATIO: u16 = 10;
+/// Largest share a resize gives the first side of a split, in percent.
+pub(crate) const MAX_RATIO: u16 = 100 - MIN_RATIO;
+
 /// Return the first available shell, trying `/bin/zsh`, `/bin/bash`, then `/bin/sh`.
 pub(crate) fn default_shell() -> &'static str {
     for shell in ["/bin/zsh", "/bin/bash", "/bin/sh"] {
@@ -58,6 +160,70 @@ pub(crate) fn collect_pane_ids(node: &LayoutNode, ids: &mut Vec<PaneId>) {
     }
 }
 
+/// Whether `target` is a leaf anywhere under `node`.
+pub(crate) fn contains_pane(node: &LayoutNode, target: PaneId) -> bool {
+    match node {
+        LayoutNode::Pane(id) => *id == target,
+        LayoutNode::Split { first, second, .. } => {
+            contains_pane(first, target) || contains_pane(second, target)
+        }
+    }
+}
+
+/// Move the divider of the innermost `direction` split around `target` by
+/// `delta` percent (positive moves it right or down). Returns false if the
+/// pane has no such split.
+pub(crate) fn adjust_ratio(
+    node: &mut LayoutNode,
+    target: PaneId,
+    direction: SplitDirection,
+    delta: i16,
+) -> bool {
+    let LayoutNode::Split {
+        direction: split_direction,
+        ratio,
+        first,
+        second,
+    } = node
+    else {
+        return false;
+    };
+    let child = if contains_pane(first, target) {
+        first
+    } else if contains_pane(second, target) {
+        second
+    } else {
+        return false;
+    };
+    if adjust_ratio(child, target, direction, delta) {
+        return true;
+    }
+    if *split_direction != direction {
+        return false;
+    }
+    *ratio = ratio
+        .saturating_add_signed(delta)
+        .clamp(MIN_RATIO, MAX_RATIO);
+    true
+}
+
+/// The split reached by following `path` from `node` (`false` = first
+/// child, `true` = second).
+pub(crate) fn split_at_path<'a>(
+    node: &'a mut LayoutNode,
+    path: &[bool],
+) -> Option<&'a mut LayoutNode> {
+    let Some((&step, rest)) = path.split_first() else {
+        return matches!(node, LayoutNode::Split { .. }).then_some(node);
+    };
+    match node {
+        LayoutNode::Split { first, second, .. } => {
+            split_at_path(if step { second } else { first }, rest)
+        }
+        LayoutNode::Pane(_) => None,
+    }
+}
+
 /// Replace the leaf node matching `target` with `replacement`.
 pub(crate) fn replace_leaf(node: &mut LayoutNode, target: PaneId, replacement: LayoutNode) -> bool {
     match node {
@@ -96,9 +262,15 @@ pub(crate) fn remove_leaf(node: &mut LayoutNode, target: PaneId) -> bool {
     }
 }
 
-/// Build a `LayoutNode` tree from a config, spawning shell terminals as needed.
+/// Build a `LayoutNode` tree from a config, spawning shell and command
+/// terminals as needed.
+#[expect(
+    clippy::too_many_arguments,
+    reason = "recursive builder threads its state"
+)]
 pub(crate) fn build_layout_from_config(
-    config: &crate::config::LayoutConfig,
+    config: &LayoutConfig,
+    commands: &[PaneCommand],
     panes: &mut HashMap<PaneId, PaneInfo>,
     next_id: &mut PaneId,
     claude: &mut Option<EmbeddedTerminal>,
@@ -107,27 +279,27 @@ pub(crate) fn build_layout_from_config(
     cols: u16,
 ) -> Result<LayoutNode> {
     match config {
-        crate::config::LayoutConfig::Pane { pane } => {
+        LayoutConfig::Pane { .. } => {
             let id = *next_id;
             *next_id += 1;
 
-            let (terminal, label) = if pane == "claude" {
-                let t = claude
+            let kind = PaneKind::from_leaf(config, commands)?;
+            let terminal = match &kind {
+                PaneKind::Claude => claude
                     .take()
-                    .context("layout config has multiple 'claude' panes")?;
-                (t, "Claude".to_string())
-            } else {
-                let shell_path = std::env::var("SHELL").unwrap_or_else(|_| default_shell().into());
-                let mut cmd = CommandBuilder::new(&shell_path);
-                cmd.cwd(worktree_path);
-                let t = EmbeddedTerminal::spawn(cmd, rows, cols)?;
-                (t, "Shell".to_string())
+                    .context("layout config has multiple 'claude' panes")?,
+                PaneKind::Command(command) => {
+                    EmbeddedTerminal::spawn(pane_command(command, worktree_path), rows, cols)?
+                }
+                PaneKind::Shell | PaneKind::Transient => {
+                    EmbeddedTerminal::spawn(shell_command(worktree_path), rows, cols)?
+                }
             };
 
-            panes.insert(id, PaneInfo { terminal, label });
+            panes.insert(id, PaneInfo::new(terminal, kind));
             Ok(LayoutNode::Pane(id))
         }
-        crate::config::LayoutConfig::Split {
+        LayoutConfig::Split {
             direction,
             ratio,
             first,
@@ -152,6 +324,7 @@ pub(crate) fn build_layout_from_config(
 
             let first_node = build_layout_from_config(
                 first,
+                commands,
                 panes,
                 next_id,
                 claude,
@@ -161,6 +334,7 @@ pub(crate) fn build_layout_from_config(
             )?;
             let second_node = build_layout_from_config(
                 second,
+                commands,
                 panes,
                 next_id,
                 claude,
@@ -179,6 +353,38 @@ pub(crate) fn build_layout_from_config(
     }
 }
 
+/// Convert a live layout back to config form so it can be saved and rebuilt.
+/// Panes that aren't saved drop out, their sibling taking the split's place.
+pub(crate) fn layout_to_config(
+    node: &LayoutNode,
+    panes: &HashMap<PaneId, PaneInfo>,
+) -> Option<LayoutConfig> {
+    match node {
+        LayoutNode::Pane(id) => panes.get(id)?.kind.to_leaf(),
+        LayoutNode::Split {
+            direction,
+            ratio,
+            first,
+            second,
+        } => match (
+            layout_to_config(first, panes),
+            layout_to_config(second, panes),
+        ) {
+            (Some(first), Some(second)) => Some(LayoutConfig::Split {
+                direction: match direction {
+                    SplitDirection::Horizontal => "horizontal",
+                    SplitDirection::Vertical => "vertical",
+                }
+                .to_string(),
+                ratio: Some(*ratio),
+                first: Box::new(first),
+                second: Box::new(second),
+            }),
+            (only, None) | (None, only) => only,
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -356,4 +562,143 @@ mod tests {
             "default shell should be an absolute path"
         );
     }
+
+    // ── Pane kinds and saved layouts ──
+
+    fn mock_pane(kind: PaneKind) -> PaneInfo {
+        let (_tx, rx) = std::sync::mpsc::channel();
+        let terminal = EmbeddedTerminal {
+            backend: super::super::Backend::Mock,
+            output_rx: rx,
+            parser: vt100::Parser::new(24, 80, 0),
+            exited: false,
+            last_output: std::time::Instant::now(),
+            child_pid: None,
+            scroll_offset: 0,
+            available_scrollback: 0,
+            deep_view: None,
+        };
+        PaneInfo::new(terminal, kind)
+    }
+
+    #[test]
+    fn pane_leaves_resolve_against_configured_commands() {
+        let tests = PaneCommand {
+            pane: "tests".to_string(),
+            cmd: "cargo watch -x test".to_string(),
+            env: std::collections::BTreeMap::new(),
+            restart_on_exit: true,
+        };
+        let commands = [tests.clone()];
+
+        let resolve = |leaf: &LayoutConfig| PaneKind::from_leaf(leaf, &commands);
+        assert_eq!(
+            resolve(&LayoutConfig::pane("claude")).unwrap(),
+            PaneKind::Claude
+        );
+        assert_eq!(
+            resolve(&LayoutConfig::pane("shell")).unwrap(),
+            PaneKind::Shell
+        );
+        assert_eq!(
+            resolve(&LayoutConfig::pane("tests")).unwrap(),
+            PaneKind::Command(tests.clone())
+        );
+        assert!(resolve(&LayoutConfig::pane("logs")).is_err());
+
+        // Saved leaves carry the whole command, so they resolve without config.
+        let saved = PaneKind::Command(tests).to_leaf().unwrap();
+        assert_eq!(
+            PaneKind::from_leaf(&saved, &[]).unwrap(),
+            resolve(&LayoutConfig::pane("tests")).unwrap()
+        );
+    }
+
+    #[test]
+    fn layout_to_config_drops_transient_panes() {
+        let mut panes = HashMap::new();
+        panes.insert(0, mock_pane(PaneKind::Shell));
+        panes.insert(1, mock_pane(PaneKind::Claude));
+        panes.insert(2, mock_pane(PaneKind::Transient));
+        let node = LayoutNode::Split {
+            direction: SplitDirection::Horizontal,
+            ratio: 30,
+            first: Box::new(LayoutNode::Pane(0)),
+            second: Box::new(LayoutNode::Split {
+                direction: SplitDirection::Vertical,
+                ratio: 50,
+                first: Box::new(LayoutNode::Pane(1)),
+                second: Box::new(LayoutNode::Pane(2)),
+            }),
+        };
+
+        assert_eq!(
+            layout_to_config(&node, &panes),
+            Some(LayoutConfig::Split {
+                direction: "horizontal".to_string(),
+                ratio: Some(30),
+                first: Box::new(LayoutConfig::pane("shell")),
+                second: Box::new(LayoutConfig::pane("claude")),
+            })
+        );
+    }
+
+    // ── Resizing ──
+
+    #[test]
+    fn adjust_ratio_moves_innermost_matching_split() {
+        // 0 | (1 / 2)
+        let mut node = LayoutNode::Split {
+            direction: SplitDirection::Horizontal,
+            ratio: 50,
+            first: Box::new(LayoutNode::Pane(0)),
+            second: Box::new(LayoutNode::Split {
+                direction: SplitDirection::Vertical,
+                ratio: 50,
+                first: Box::new(LayoutNode::Pane(1)),
+                second: Box::new(LayoutNode::Pane(2)),
+            }),
+        };
+        let ratios = |node: &LayoutNode| match node {
+            LayoutNode::Split { ratio, second, .. } => match second.as_ref() {
+                LayoutNode::Split { ratio: inner, .. } => (*ratio, *inner),
+                LayoutNode::Pane(_) => panic!("expected nested split"),
+            },
+            LayoutNode::Pane(_) => panic!("expected split"),
+        };
+
+        assert!(adjust_ratio(&mut node, 2, SplitDirection::Vertical, -5));
+        assert_eq!(ratios(&node), (50, 45));
+        assert!(adjust_ratio(&mut node, 2, SplitDirection::Horizontal, 5));
+        assert_eq!(ratios(&node), (55, 45));
+        // Pane 0 has no vertical split around it.
+        assert!(!adjust_ratio(&mut node, 0, SplitDirection::Vertical, 5));
+        // Ratios stay within bounds.
+        assert!(adjust_ratio(&mut node, 0, SplitDirection::Horizontal, 100));
+        assert_eq!(ratios(&node), (MAX_RATIO, 45));
+    }
+
+    #[test]
+    fn split_at_path_finds_nested_splits() {
+        let mut node = LayoutNode::Split {
+            direction: SplitDirection::Horizontal,
+            ratio: 50,
+            first: Box::new(LayoutNode::Pane(0)),
+            second: Box::new(LayoutNode::Split {
+                direction: SplitDirection::Vertical,
+                ratio: 30,
+                first: Box::new(LayoutNode::Pane(1)),
+                second: Box::new(LayoutNode::Pane(2)),
+            }),
+        };
+        assert!(matches!(
+            split_at_path(&mut node, &[true]),
+            Some(LayoutNode::Split { ratio: 30, .. })
+        ));
+        assert!(matches!(
+            split_at_path(&mut node, &[]),
+            Some(LayoutNode::Split { ratio: 50, .. })
+        ));
+        assert!(split_at_path(&mut node, &[false]).is_none());
+    }
 }
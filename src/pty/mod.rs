@@ -5,18 +5,22 @@
 
 pub mod protocol;
 mod widget;
-pub use widget::TerminalWidget;
+pub use widget::{SearchHighlight, TerminalWidget};
 
+mod copy_mode;
 mod embedded;
 mod layout;
+mod search;
 mod selection;
 pub(crate) mod session_terminals;
 
+pub use copy_mode::{CopyMode, Motion};
 #[cfg(test)]
 pub(crate) use embedded::Backend;
 pub use embedded::EmbeddedTerminal;
 pub use layout::{LayoutNode, SplitDirection};
-pub use selection::Selection;
+pub use search::{LineText, Search, SearchMatch};
+pub use selection::{Selection, SelectionKind};
 pub use session_terminals::SessionTerminals;
 
 /// Unique identifier for a pane within a session.
@@ -69,12 +73,40 @@ mod tests {
             output_rx: rx,
             parser: vt100::Parser::new(rows, cols, SCROLLBACK_LINES),
             exited: false,
+            last_output: std::time::Instant::now(),
+            child_pid: None,
             scroll_offset: 0,
             available_scrollback: 0,
+            deep_view: None,
         };
         (term, tx)
     }
 
+    // ── Child process detection ──
+
+    #[test]
+    fn ps_lists_child_matches_parent_and_command() {
+        // `ps -o ppid=,args=`: parent PID, then the child's command line.
+        let ps = "    0 /sbin/init\n    1 /bin/sh -c \"$@\"; exec zsh -l\n  400 claude --resume abc\n  413 zsh -l\n";
+        assert!(embedded::ps_lists_child(ps, 400, "claude"));
+        assert!(!embedded::ps_lists_child(ps, 412, "claude"));
+        assert!(!embedded::ps_lists_child(ps, 413, "claude"));
+        assert!(!embedded::ps_lists_child("", 400, "claude"));
+    }
+
+    #[test]
+    fn ps_child_pids_lists_matching_children() {
+        // `ps -o pid=,ppid=,args=`: PID, parent PID, then the command line.
+        let ps = "  400     1 /bin/sh -c claude; exec zsh -l
+  412   400 claude --resume abc
+  413   400 zsh -l
+  500   412 node claude-helper
+";
+        assert_eq!(embedded::ps_child_pids(ps, 400, "claude"), vec![412]);
+        assert!(embedded::ps_child_pids(ps, 413, "claude").is_empty());
+        assert!(embedded::ps_child_pids("", 400, "claude").is_empty());
+    }
+
     // ── Budget enforcement ──
 
     #[test]
@@ -214,6 +246,84 @@ mod tests {
         assert_eq!(term.scroll_offset, 50);
     }
 
+    #[test]
+    fn history_reads_scrollback_and_restores_live_screen() {
+        let (mut term, tx) = test_terminal(10, 40);
+        for i in 0..95 {
+            tx.send(format!("line {i}\r\n").into_bytes()).unwrap();
+        }
+        term.process_output();
+        term.scroll_up(20);
+
+        let lines = term.history();
+        assert_eq!(lines.len(), term.available_scrollback + 10);
+        assert_eq!(lines[0].text, "line 0");
+        assert_eq!(lines[94].text, "line 94");
+        assert_eq!(term.parser.screen().scrollback(), 0);
+        assert_eq!(term.parser.screen().size(), (10, 40));
+        assert_eq!(term.scroll_offset, 20);
+
+        // Bring line 10 into view: the viewport top lands five rows above it.
+        term.scroll_to_line(10);
+        assert_eq!(term.viewport_top(), 5);
+        term.prepare_for_render();
+        assert_eq!(LineText::from_screen(term.screen(), 5).text, "line 10");
+        term.restore_after_render();
+        assert_eq!(term.parser.screen().size(), (10, 40));
+        assert_eq!(LineText::from_screen(term.screen(), 9).text, "");
+
+        // Lines already on screen don't scroll; others scroll just enough.
+        term.reveal_line(12);
+        assert_eq!(term.viewport_top(), 5);
+        term.reveal_line(30);
+        assert_eq!(term.viewport_top(), 21);
+        term.reveal_line(2);
+        assert_eq!(term.viewport_top(), 2);
+        term.reveal_line(10_000);
+        assert_eq!(term.scroll_offset, 0);
+    }
+
+    #[test]
+    fn deep_scrollback_renders_with_formatting() {
+        let (mut term, tx) = test_terminal(10, 40);
+        tx.send(b"\x1b[31mred\x1b[m plain\r\n".to_vec()).unwrap();
+        for i in 0..50 {
+            tx.send(format!("line {i}\r\n").into_bytes()).unwrap();
+        }
+        term.process_output();
+        term.scroll_up(term.available_scrollback);
+
+        term.prepare_for_render();
+        let screen = term.screen();
+        assert_eq!(screen.size(), (10, 40));
+        assert_eq!(LineText::from_screen(screen, 0).text, "red plain");
+        assert_eq!(LineText::from_screen(screen, 1).text, "line 0");
+        assert_eq!(screen.cell(0, 0).unwrap().fgcolor(), vt100::Color::Idx(1));
+        assert_eq!(screen.cell(0, 4).unwrap().fgcolor(), vt100::Color::Default);
+        assert!(screen.hide_cursor());
+        term.restore_after_render();
+
+        // Outside the render phase the live screen is back
+        assert!(!term.screen().hide_cursor());
+        assert_eq!(LineText::from_screen(term.screen(), 0).text, "line 41");
+    }
+
+    #[test]
+    fn history_with_less_than_a_screen_of_scrollback() {
+        let (mut term, tx) = test_terminal(10, 40);
+        for i in 0..12 {
+            tx.send(format!("line {i}\r\n").into_bytes()).unwrap();
+        }
+        term.process_output();
+
+        let lines = term.history();
+        assert_eq!(term.available_scrollback, 3);
+        assert_eq!(lines.len(), 13);
+        assert_eq!(lines[0].text, "line 0");
+        assert_eq!(lines[11].text, "line 11");
+        assert_eq!(term.parser.screen().size(), (10, 40));
+    }
+
     // ── Regression: scroll-stuck bug ──
 
     #[test]
@@ -0,0 +1,304 @@
+//! Pattern search over a terminal pane's screen and scrollback.
+//!
+//! History is read one line at a time as [`LineText`] (see
+//! [`super::EmbeddedTerminal::history`]); matches are addressed by absolute
+//! line index, 0 being the oldest scrollback line.
+
+use regex::{Regex, RegexBuilder};
+
+use super::PaneId;
+
+/// The text of one terminal row, with the column each character starts at.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LineText {
+    pub text: String,
+    /// `(byte offset in text, column)` for every character.
+    cols: Vec<(usize, u16)>,
+}
+
+impl LineText {
+    /// Read row `row` of the screen's current viewport.
+    pub fn from_screen(screen: &vt100::Screen, row: u16) -> Self {
+        let mut line = Self::default();
+        for col in 0..screen.size().1 {
+            let Some(cell) = screen.cell(row, col) else {
+                continue;
+            };
+            if cell.is_wide_continuation() {
+                continue;
+            }
+            line.cols.push((line.text.len(), col));
+            let contents = cell.contents();
+            if contents.is_empty() {
+                line.text.push(' ');
+            } else {
+                line.text.push_str(&contents);
+            }
+        }
+        let trimmed = line.text.trim_end_matches(' ').len();
+        line.text.truncate(trimmed);
+        line.cols.retain(|&(offset, _)| offset < trimmed);
+        line
+    }
+
+    /// The text between columns `from` and `to` (inclusive).
+    pub fn slice(&self, from: u16, to: u16) -> &str {
+        let offset_at = |pred: &dyn Fn(u16) -> bool| {
+            self.cols
+                .iter()
+                .find(|&&(_, c)| pred(c))
+                .map_or(self.text.len(), |&(o, _)| o)
+        };
+        let start = offset_at(&|c| c >= from);
+        let end = offset_at(&|c| c > to);
+        &self.text[start..end.max(start)]
+    }
+
+    /// Each character with the column it starts at.
+    pub fn chars(&self) -> impl DoubleEndedIterator<Item = (u16, char)> + '_ {
+        self.cols
+            .iter()
+            .filter_map(|&(offset, col)| Some((col, self.text[offset..].chars().next()?)))
+    }
+
+    /// Column range (inclusive) covered by the bytes `start..end`.
+    fn col_range(&self, start: usize, end: usize) -> (u16, u16) {
+        let col_at = |offset: usize| {
+            self.cols
+                .iter()
+                .rev()
+                .find(|&&(o, _)| o <= offset)
+                .map_or(0, |&(_, c)| c)
+        };
+        (col_at(start), col_at(end.saturating_sub(1).max(start)))
+    }
+}
+
+/// One match: absolute history line and inclusive column range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchMatch {
+    pub line: usize,
+    pub start_col: u16,
+    pub end_col: u16,
+}
+
+/// Search state for a session tab's pane.
+pub struct Search {
+    pub pane: PaneId,
+    /// History line the search started from (the bottom of the viewport);
+    /// typing the pattern searches back from here.
+    pub origin: usize,
+    pub query: String,
+    /// Whether the pattern is still being typed.
+    pub editing: bool,
+    regex: Option<Regex>,
+    /// The match the viewport is on.
+    pub current: Option<SearchMatch>,
+    /// Position of `current` among all matches (1-based) and the total.
+    pub position: (usize, usize),
+    /// The pane's history when the search was opened. Matches are looked
+    /// up here rather than re-reading the whole scrollback per keystroke.
+    history: Vec<LineText>,
+}
+
+impl Search {
+    pub fn new(pane: PaneId, origin: usize, history: Vec<LineText>) -> Self {
+        Self {
+            pane,
+            origin,
+            query: String::new(),
+            editing: true,
+            regex: None,
+            current: None,
+            position: (0, 0),
+            history,
+        }
+    }
+
+    /// Replace the pattern. An invalid regex is searched for literally, and
+    /// an all-lowercase pattern matches case-insensitively.
+    pub fn set_query(&mut self, query: String) {
+        let case_insensitive = !query.chars().any(char::is_uppercase);
+        let build = |pattern: &str| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(case_insensitive)
+                .build()
+                .ok()
+        };
+        self.regex = if query.is_empty() {
+            None
+        } else {
+            build(&query).or_else(|| build(&regex::escape(&query)))
+        };
+        self.query = query;
+    }
+
+    pub fn regex(&self) -> Option<&Regex> {
+        self.regex.as_ref()
+    }
+
+    /// Matches in `line`, as inclusive column ranges.
+    pub fn line_matches(&self, line: &LineText) -> Vec<(u16, u16)> {
+        let Some(regex) = &self.regex else {
+            return Vec::new();
+        };
+        regex
+            .find_iter(&line.text)
+            .filter(|m| !m.is_empty())
+            .map(|m| line.col_range(m.start(), m.end()))
+            .collect()
+    }
+
+    /// Every match in the history, oldest first.
+    pub fn all_matches(&self) -> Vec<SearchMatch> {
+        self.history
+            .iter()
+            .enumerate()
+            .flat_map(|(i, line)| {
+                self.line_matches(line)
+                    .into_iter()
+                    .map(move |(start_col, end_col)| SearchMatch {
+                        line: i,
+                        start_col,
+                        end_col,
+                    })
+            })
+            .collect()
+    }
+
+    /// Move to the nearest match before (`older`) or after the current one,
+    /// starting from `anchor` when there is no current match. Wraps around.
+    /// Returns the new current match.
+    pub fn step(&mut self, anchor: usize, older: bool) -> Option<SearchMatch> {
+        let matches = self.all_matches();
+        let key = |m: &SearchMatch| (m.line, m.start_col);
+        let from = self
+            .current
+            .map_or((anchor, if older { u16::MAX } else { 0 }), |m| key(&m));
+        let index = if older {
+            matches
+                .iter()
+                .rposition(|m| key(m) < from)
+                .or_else(|| matches.len().checked_sub(1))
+        } else {
+            matches
+                .iter()
+                .position(|m| key(m) > from)
+                .or(if matches.is_empty() { None } else { Some(0) })
+        };
+        self.current = index.map(|i| matches[i]);
+        self.position = (index.map_or(0, |i| i + 1), matches.len());
+        self.current
+    }
+
+    /// The text of the current match.
+    pub fn match_text(&self) -> Option<String> {
+        let current = self.current?;
+        let line = self.history.get(current.line)?;
+        let regex = self.regex.as_ref()?;
+        regex
+            .find_iter(&line.text)
+            .find(|m| line.col_range(m.start(), m.end()).0 == current.start_col)
+            .map(|m| m.as_str().to_string())
+    }
+
+    /// The block around the current match: its line plus the adjacent
+    /// non-blank lines.
+    pub fn block_text(&self) -> Option<String> {
+        let lines = &self.history;
+        let line = self.current?.line;
+        if line >= lines.len() {
+            return None;
+        }
+        let blank = |i: usize| lines[i].text.trim().is_empty();
+        let mut start = line;
+        while start > 0 && !blank(start - 1) {
+            start -= 1;
+        }
+        let mut end = line;
+        while end + 1 < lines.len() && !blank(end + 1) {
+            end += 1;
+        }
+        Some(
+            lines[start..=end]
+                .iter()
+                .map(|l| l.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &[u8]) -> Vec<LineText> {
+        let mut parser = vt100::Parser::new(6, 40, 0);
+        parser.process(text);
+        (0..6)
+            .map(|row| LineText::from_screen(parser.screen(), row))
+            .collect()
+    }
+
+    #[test]
+    fn smart_case_and_literal_fallback() {
+        let mut search = Search::new(0, 0, lines(b"Error: a\r\nerror: b\r\nfn x(\r\n"));
+
+        search.set_query("error".to_string());
+        assert_eq!(search.all_matches().len(), 2);
+        search.set_query("Error".to_string());
+        assert_eq!(search.all_matches().len(), 1);
+        // Invalid regex: searched literally.
+        search.set_query("x(".to_string());
+        let matches = search.all_matches();
+        assert_eq!(
+            matches,
+            vec![SearchMatch {
+                line: 2,
+                start_col: 3,
+                end_col: 4
+            }]
+        );
+    }
+
+    #[test]
+    fn step_walks_older_and_newer_with_wraparound() {
+        let lines = lines(b"foo 1\r\nbar\r\nfoo 2\r\nfoo 3\r\n");
+        let bottom = lines.len();
+        let mut search = Search::new(0, 0, lines);
+        search.set_query("foo".to_string());
+
+        // From the bottom, older goes to the most recent match first.
+        assert_eq!(search.step(bottom, true).unwrap().line, 3);
+        assert_eq!(search.position, (3, 3));
+        assert_eq!(search.step(0, true).unwrap().line, 2);
+        assert_eq!(search.step(0, true).unwrap().line, 0);
+        // Wraps to the newest.
+        assert_eq!(search.step(0, true).unwrap().line, 3);
+        assert_eq!(search.step(0, false).unwrap().line, 0);
+    }
+
+    #[test]
+    fn copies_match_and_block() {
+        let lines = lines(b"intro\r\n\r\ncargo test\r\ntest result: FAILED\r\n\r\ndone");
+        let bottom = lines.len();
+        let mut search = Search::new(0, 0, lines);
+        search.set_query("res.*:".to_string());
+        search.step(bottom, true);
+
+        assert_eq!(search.match_text().as_deref(), Some("result:"));
+        assert_eq!(
+            search.block_text().as_deref(),
+            Some("cargo test\ntest result: FAILED")
+        );
+    }
+
+    #[test]
+    fn wide_characters_map_to_columns() {
+        let lines = lines("日本 ok".as_bytes());
+        let mut search = Search::new(0, 0, Vec::new());
+        search.set_query("ok".to_string());
+        assert_eq!(search.line_matches(&lines[0]), vec![(5, 6)]);
+    }
+}
@@ -1,20 +1,37 @@
-//! Text selection within a terminal pane (vt100 screen coordinates).
+//! Text selection within a terminal pane.
+//!
+//! Positions are `(history line, column)`, with lines counted from the
+//! oldest scrollback line (see [`super::EmbeddedTerminal::history`]), so a
+//! selection can extend past the visible screen.
 
 use super::PaneId;
+use super::search::LineText;
+
+/// How a selection covers the rows between its ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionKind {
+    /// Running text from start to end, like a mouse drag.
+    Char,
+    /// Whole lines.
+    Line,
+    /// The rectangle spanned by the two ends.
+    Block,
+}
 
-/// A text selection within a terminal pane (vt100 screen coordinates).
+/// A text selection within a terminal pane.
 #[derive(Clone, Copy)]
 pub struct Selection {
     pub pane: PaneId,
-    /// Start position (row, col) where mouse was pressed.
-    pub start: (u16, u16),
-    /// Current end position (row, col) where mouse was dragged/released.
-    pub end: (u16, u16),
+    /// Where the selection started (mouse press or copy-mode anchor).
+    pub start: (usize, u16),
+    /// Where it currently ends (drag position or copy-mode cursor).
+    pub end: (usize, u16),
+    pub kind: SelectionKind,
 }
 
 impl Selection {
     /// Return the selection bounds normalized so that `from` is before `to`.
-    pub fn normalized(&self) -> ((u16, u16), (u16, u16)) {
+    pub fn normalized(&self) -> ((usize, u16), (usize, u16)) {
         let (sr, sc) = self.start;
         let (er, ec) = self.end;
         if sr < er || (sr == er && sc <= ec) {
@@ -24,59 +41,42 @@ impl Selection {
         }
     }
 
-    /// Check if a cell at (row, col) is within this selection.
-    pub fn contains(&self, row: u16, col: u16) -> bool {
-        let ((sr, sc), (er, ec)) = self.normalized();
-        if row < sr || row > er {
-            return false;
-        }
-        if sr == er {
-            return col >= sc && col <= ec;
-        }
-        if row == sr {
-            return col >= sc;
-        }
-        if row == er {
-            return col <= ec;
-        }
-        true // middle row
-    }
-
-    /// Extract the selected text from a vt100 screen.
-    pub fn extract_text(&self, screen: &vt100::Screen) -> String {
+    /// Column range (inclusive) selected on `line`, if any.
+    fn columns(&self, line: usize) -> Option<(u16, u16)> {
         let ((sr, sc), (er, ec)) = self.normalized();
-        let mut text = String::new();
-        let max_cols = screen.size().1;
-
-        for row in sr..=er {
-            let col_start = if row == sr { sc } else { 0 };
-            let col_end = if row == er {
-                ec
-            } else {
-                max_cols.saturating_sub(1)
-            };
-
-            for col in col_start..=col_end {
-                if let Some(cell) = screen.cell(row, col) {
-                    let contents = cell.contents();
-                    if contents.is_empty() {
-                        text.push(' ');
-                    } else {
-                        text.push_str(&contents);
-                    }
-                }
-            }
-            // Trim trailing spaces on each line and add newline between rows
-            if row < er {
-                let trimmed = text.trim_end_matches(' ');
-                text.truncate(trimmed.len());
-                text.push('\n');
-            }
+        if line < sr || line > er {
+            return None;
         }
-        // Trim trailing spaces on the last line
-        let trimmed = text.trim_end_matches(' ');
-        text.truncate(trimmed.len());
-        text
+        Some(match self.kind {
+            SelectionKind::Block => (self.start.1.min(self.end.1), self.start.1.max(self.end.1)),
+            SelectionKind::Char if sr == er => (sc, ec),
+            SelectionKind::Char if line == sr => (sc, u16::MAX),
+            SelectionKind::Char if line == er => (0, ec),
+            SelectionKind::Char | SelectionKind::Line => (0, u16::MAX),
+        })
+    }
+
+    /// Check if the cell at (`line`, `col`) is within this selection.
+    pub fn contains(&self, line: usize, col: u16) -> bool {
+        self.columns(line)
+            .is_some_and(|(from, to)| col >= from && col <= to)
+    }
+
+    /// Extract the selected text from the pane's history lines.
+    pub fn extract_text(&self, lines: &[LineText]) -> String {
+        let ((sr, _), (er, _)) = self.normalized();
+        (sr..=er)
+            .filter_map(|line| {
+                let (from, to) = self.columns(line)?;
+                Some(
+                    lines
+                        .get(line)
+                        .map_or("", |text| text.slice(from, to))
+                        .trim_end_matches(' '),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 }
 
@@ -84,14 +84,23 @@ impl Selection {
 mod tests {
     use super::*;
 
-    fn sel(start: (u16, u16), end: (u16, u16)) -> Selection {
+    fn sel(start: (usize, u16), end: (usize, u16)) -> Selection {
         Selection {
             pane: 0,
             start,
             end,
+            kind: SelectionKind::Char,
         }
     }
 
+    fn lines(text: &[u8]) -> Vec<LineText> {
+        let mut p = vt100::Parser::new(24, 80, 0);
+        p.process(text);
+        (0..24)
+            .map(|row| LineText::from_screen(p.screen(), row))
+            .collect()
+    }
+
     // ── normalized ──
 
     #[test]
@@ -170,52 +179,64 @@ mod tests {
         assert!(!s.contains(4, 0));
     }
 
+    #[test]
+    fn contains_line_and_block_selections() {
+        let line = Selection {
+            kind: SelectionKind::Line,
+            ..sel((4, 6), (2, 9))
+        };
+        assert!(line.contains(2, 0));
+        assert!(line.contains(4, 79));
+        assert!(!line.contains(5, 0));
+
+        let block = Selection {
+            kind: SelectionKind::Block,
+            ..sel((4, 6), (2, 9))
+        };
+        assert!(block.contains(3, 6));
+        assert!(block.contains(2, 9));
+        assert!(!block.contains(3, 5));
+        assert!(!block.contains(4, 10));
+    }
+
     // ── extract_text ──
 
     #[test]
     fn extract_text_single_line() {
-        let parser = vt100::Parser::new(24, 80, 0);
-        // Write "Hello World" to the screen
-        let mut p = parser;
-        p.process(b"Hello World");
-        let screen = p.screen();
-
+        let lines = lines(b"Hello World");
         let s = sel((0, 0), (0, 10));
-        let text = s.extract_text(screen);
-        assert_eq!(text, "Hello World");
+        assert_eq!(s.extract_text(&lines), "Hello World");
     }
 
     #[test]
     fn extract_text_partial_line() {
-        let mut p = vt100::Parser::new(24, 80, 0);
-        p.process(b"ABCDEFGHIJ");
-        let screen = p.screen();
-
+        let lines = lines(b"ABCDEFGHIJ");
         let s = sel((0, 2), (0, 5));
-        let text = s.extract_text(screen);
-        assert_eq!(text, "CDEF");
+        assert_eq!(s.extract_text(&lines), "CDEF");
     }
 
     #[test]
     fn extract_text_multi_line() {
-        let mut p = vt100::Parser::new(24, 80, 0);
-        p.process(b"Line one\r\nLine two\r\nLine three");
-        let screen = p.screen();
-
+        let lines = lines(b"Line one\r\nLine two\r\nLine three");
         let s = sel((0, 5), (2, 3));
-        let text = s.extract_text(screen);
-        assert_eq!(text, "one\nLine two\nLine");
+        assert_eq!(s.extract_text(&lines), "one\nLine two\nLine");
     }
 
     #[test]
     fn extract_text_trims_trailing_spaces() {
-        let mut p = vt100::Parser::new(24, 80, 0);
-        p.process(b"Hi");
-        let screen = p.screen();
-
+        let lines = lines(b"Hi");
         // Select past the end of "Hi" — empty cells become spaces, but trailing should be trimmed
         let s = sel((0, 0), (0, 10));
-        let text = s.extract_text(screen);
-        assert_eq!(text, "Hi");
+        assert_eq!(s.extract_text(&lines), "Hi");
+    }
+
+    #[test]
+    fn extract_text_block_columns() {
+        let lines = lines(b"ab 123 cd\r\nef 456 gh\r\nij 7");
+        let s = Selection {
+            kind: SelectionKind::Block,
+            ..sel((0, 3), (2, 5))
+        };
+        assert_eq!(s.extract_text(&lines), "123\n456\n7");
     }
 }
@@ -1,22 +1,46 @@
 //! Multi-pane terminal management: `PaneInfo` and `SessionTerminals`.
 
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use portable_pty::CommandBuilder;
 
+use crate::config::{LayoutConfig, PaneCommand};
+
 use super::PaneId;
+use super::copy_mode::CopyMode;
 use super::embedded::EmbeddedTerminal;
 use super::layout::{
-    LayoutNode, SplitDirection, build_layout_from_config, collect_pane_ids, default_shell,
-    remove_leaf, replace_leaf,
+    LayoutNode, MAX_RATIO, MIN_RATIO, PaneKind, SplitDirection, adjust_ratio,
+    build_layout_from_config, collect_pane_ids, layout_to_config, pane_command, remove_leaf,
+    replace_leaf, shell_command, split_at_path,
 };
+use super::search::Search;
 use super::selection::Selection;
 
+/// Minimum time between restarts of a `restart_on_exit` pane, so a command
+/// that fails on startup doesn't respawn every tick.
+const RESTART_DELAY: Duration = Duration::from_secs(2);
+
 /// Information about a single terminal pane.
 pub(crate) struct PaneInfo {
     pub(crate) terminal: EmbeddedTerminal,
     pub(crate) label: String,
+    pub(crate) kind: PaneKind,
+    /// When the pane's process was last spawned.
+    pub(crate) started_at: Instant,
+}
+
+impl PaneInfo {
+    pub(crate) fn new(terminal: EmbeddedTerminal, kind: PaneKind) -> Self {
+        Self {
+            terminal,
+            label: kind.label().to_string(),
+            kind,
+            started_at: Instant::now(),
+        }
+    }
 }
 
 /// Terminal panes for a session, arranged in a configurable tree layout.
@@ -31,6 +55,12 @@ pub struct SessionTerminals {
     /// Which pane holds the Claude terminal (for paused detection).
     pub claude_pane_id: PaneId,
     pub selection: Option<Selection>,
+    /// Active scrollback search, if any.
+    pub search: Option<Search>,
+    /// Active keyboard copy mode, if any.
+    pub copy_mode: Option<CopyMode>,
+    /// Whether the focused pane is zoomed to fill the tab.
+    pub zoomed: bool,
     /// Worktree path — needed to spawn new shell panes on split.
     pub worktree_path: String,
 }
@@ -43,20 +73,8 @@ impl SessionTerminals {
         worktree_path: &str,
     ) -> Self {
         let mut panes = HashMap::new();
-        panes.insert(
-            0,
-            PaneInfo {
-                terminal: shell,
-                label: "Shell".to_string(),
-            },
-        );
-        panes.insert(
-            1,
-            PaneInfo {
-                terminal: claude,
-                label: "Claude".to_string(),
-            },
-        );
+        panes.insert(0, PaneInfo::new(shell, PaneKind::Shell));
+        panes.insert(1, PaneInfo::new(claude, PaneKind::Claude));
         Self {
             panes,
             layout: LayoutNode::Split {
@@ -69,18 +87,23 @@ impl SessionTerminals {
             next_id: 2,
             claude_pane_id: 1,
             selection: None,
+            search: None,
+            copy_mode: None,
+            zoomed: false,
             worktree_path: worktree_path.to_string(),
         }
     }
 
-    /// Create from a layout config, spawning shell panes as needed.
+    /// Create from a layout config, spawning shell and command panes as needed.
     ///
     /// The `claude` terminal is placed at the "claude" leaf in the config tree.
-    /// Every "shell" leaf gets a freshly-spawned shell in the worktree.
+    /// Every "shell" leaf gets a freshly-spawned shell in the worktree; other
+    /// leaves run their inline `cmd` or the `[[panes]]` entry they name.
     pub fn from_layout(
         claude: EmbeddedTerminal,
         worktree_path: &str,
-        layout_config: &crate::config::LayoutConfig,
+        layout_config: &LayoutConfig,
+        commands: &[PaneCommand],
         rows: u16,
         cols: u16,
     ) -> Result<Self> {
@@ -90,6 +113,7 @@ impl SessionTerminals {
 
         let layout = build_layout_from_config(
             layout_config,
+            commands,
             &mut panes,
             &mut next_id,
             &mut claude_opt,
@@ -100,7 +124,7 @@ impl SessionTerminals {
 
         let claude_pane_id = panes
             .iter()
-            .find(|(_, info)| info.label == "Claude")
+            .find(|(_, info)| info.kind == PaneKind::Claude)
             .map(|(&id, _)| id)
             .context("layout config must contain exactly one 'claude' pane")?;
 
@@ -111,6 +135,9 @@ impl SessionTerminals {
             next_id,
             claude_pane_id,
             selection: None,
+            search: None,
+            copy_mode: None,
+            zoomed: false,
             worktree_path: worktree_path.to_string(),
         })
     }
@@ -155,8 +182,95 @@ impl SessionTerminals {
         Some(f(info.terminal.screen()))
     }
 
+    /// When the Claude pane last produced output.
+    pub fn claude_last_output(&self) -> Option<Instant> {
+        self.panes
+            .get(&self.claude_pane_id)
+            .map(|info| info.terminal.last_output)
+    }
+
+    /// Send keystrokes to the Claude pane regardless of which pane has focus.
+    pub fn send_to_claude(&mut self, bytes: &[u8]) -> Result<()> {
+        let info = self
+            .panes
+            .get_mut(&self.claude_pane_id)
+            .context("session has no Claude pane")?;
+        info.terminal.reset_scrollback();
+        info.terminal.send_bytes(bytes)
+    }
+
+    /// Stop the Claude process in the Claude pane, leaving the pane and its
+    /// fallback shell in place. Falls back to two Ctrl+C presses when the
+    /// process can't be found in the process table.
+    pub fn stop_claude(&mut self) -> Result<()> {
+        let info = self
+            .panes
+            .get_mut(&self.claude_pane_id)
+            .context("session has no Claude pane")?;
+        if info.terminal.terminate_child_process("claude").unwrap_or(0) > 0 {
+            return Ok(());
+        }
+        info.terminal.send_bytes(b"\x03\x03")
+    }
+
+    /// Whether Claude has quit in this tab: its pane's process is gone, or
+    /// only the fallback shell is left running in it. Unknown counts as
+    /// still running.
+    pub fn claude_exited(&self) -> bool {
+        self.panes.get(&self.claude_pane_id).is_some_and(|info| {
+            info.terminal.exited || info.terminal.has_child_process("claude") == Some(false)
+        })
+    }
+
+    /// Swap in a freshly spawned Claude process, keeping the pane's place in
+    /// the layout.
+    pub fn replace_claude(&mut self, terminal: EmbeddedTerminal) {
+        if let Some(info) = self.panes.get_mut(&self.claude_pane_id) {
+            info.terminal = terminal;
+            info.started_at = Instant::now();
+        }
+    }
+
+    /// Size of the Claude pane as `(rows, cols)`.
+    pub fn claude_size(&self) -> Option<(u16, u16)> {
+        self.panes
+            .get(&self.claude_pane_id)
+            .map(|info| info.terminal.screen().size())
+    }
+
+    /// The layout as drawn: just the focused pane while zoomed.
+    pub fn visible_layout(&self) -> LayoutNode {
+        if self.zoomed {
+            LayoutNode::Pane(self.focused)
+        } else {
+            self.layout.clone()
+        }
+    }
+
+    /// Zoom the focused pane to fill the tab, or restore the layout.
+    pub fn toggle_zoom(&mut self) {
+        self.zoomed = !self.zoomed && self.panes.len() > 1;
+    }
+
+    /// Move the divider nearest the focused pane in `direction` by `delta`
+    /// percent. Returns false if no split of that direction encloses it.
+    pub fn resize_focused(&mut self, direction: SplitDirection, delta: i16) -> bool {
+        self.zoomed = false;
+        adjust_ratio(&mut self.layout, self.focused, direction, delta)
+    }
+
+    /// Set the ratio of the split at `path` (see [`split_at_path`]).
+    pub fn set_ratio(&mut self, path: &[bool], ratio: u16) {
+        if let Some(LayoutNode::Split { ratio: current, .. }) =
+            split_at_path(&mut self.layout, path)
+        {
+            *current = ratio.clamp(MIN_RATIO, MAX_RATIO);
+        }
+    }
+
     /// Cycle focus to the next pane (DFS order).
     pub fn focus_next(&mut self) {
+        self.zoomed = false;
         let ids = self.pane_ids_in_order();
         if !ids.is_empty()
             && let Some(pos) = ids.iter().position(|&id| id == self.focused)
@@ -167,6 +281,7 @@ impl SessionTerminals {
 
     /// Cycle focus to the previous pane (reverse DFS order).
     pub fn focus_prev(&mut self) {
+        self.zoomed = false;
         let ids = self.pane_ids_in_order();
         if !ids.is_empty()
             && let Some(pos) = ids.iter().position(|&id| id == self.focused)
@@ -184,79 +299,69 @@ impl SessionTerminals {
 
     /// Split the focused pane, creating a new shell terminal beside/below it.
     pub fn split_focused(&mut self, direction: SplitDirection, rows: u16, cols: u16) -> Result<()> {
-        let new_id = self.next_id;
-        self.next_id += 1;
-
-        let shell_path = std::env::var("SHELL").unwrap_or_else(|_| default_shell().into());
-        let mut cmd = CommandBuilder::new(&shell_path);
-        cmd.cwd(&self.worktree_path);
-
-        // Approximate size for the new pane (corrected on next resize)
-        let (new_rows, new_cols) = match direction {
-            SplitDirection::Horizontal => (rows, cols / 2),
-            SplitDirection::Vertical => (rows / 2, cols),
-        };
-
-        let terminal = EmbeddedTerminal::spawn(cmd, new_rows, new_cols)?;
-
-        self.panes.insert(
-            new_id,
-            PaneInfo {
-                terminal,
-                label: "Shell".to_string(),
-            },
-        );
+        let cmd = shell_command(&self.worktree_path);
+        self.split_with(direction, rows, cols, cmd, PaneKind::Shell)
+    }
 
-        // Replace the focused pane's leaf with a split containing both the
-        // original pane and the new one.
-        let replaced = replace_leaf(
-            &mut self.layout,
-            self.focused,
-            LayoutNode::Split {
-                direction,
-                ratio: 50,
-                first: Box::new(LayoutNode::Pane(self.focused)),
-                second: Box::new(LayoutNode::Pane(new_id)),
-            },
-        );
+    /// Split the focused pane, running a configured pane command in the new pane.
+    pub fn split_with_pane(
+        &mut self,
+        direction: SplitDirection,
+        rows: u16,
+        cols: u16,
+        command: &PaneCommand,
+    ) -> Result<()> {
+        let cmd = pane_command(command, &self.worktree_path);
+        self.split_with(
+            direction,
+            rows,
+            cols,
+            cmd,
+            PaneKind::Command(command.clone()),
+        )
+    }
 
-        if !replaced {
-            // Layout tree didn't contain the focused pane — roll back
-            self.panes.remove(&new_id);
-            anyhow::bail!("focused pane not found in layout tree during split");
+    /// Split the focused pane, creating a new terminal running the given
+    /// command. These panes belong to claustre's own loops and are not
+    /// part of the saved layout.
+    pub fn split_with_command(
+        &mut self,
+        direction: SplitDirection,
+        rows: u16,
+        cols: u16,
+        cmd: CommandBuilder,
+        label: &str,
+    ) -> Result<()> {
+        self.split_with(direction, rows, cols, cmd, PaneKind::Transient)?;
+        if let Some(info) = self.panes.get_mut(&self.focused) {
+            info.label = label.to_string();
         }
-
-        self.focused = new_id;
         Ok(())
     }
 
-    /// Split the focused pane, creating a new terminal running the given command.
-    pub fn split_with_command(
+    fn split_with(
         &mut self,
         direction: SplitDirection,
         rows: u16,
         cols: u16,
         cmd: CommandBuilder,
-        label: &str,
+        kind: PaneKind,
     ) -> Result<()> {
         let new_id = self.next_id;
         self.next_id += 1;
+        self.zoomed = false;
 
+        // Approximate size for the new pane (corrected on next resize)
         let (new_rows, new_cols) = match direction {
             SplitDirection::Horizontal => (rows, cols / 2),
             SplitDirection::Vertical => (rows / 2, cols),
         };
 
         let terminal = EmbeddedTerminal::spawn(cmd, new_rows, new_cols)?;
+        self.panes.insert(new_id, PaneInfo::new(terminal, kind));
 
-        self.panes.insert(
-            new_id,
-            PaneInfo {
-                terminal,
-                label: label.to_string(),
-            },
-        );
-
+        // Replace the focused pane's leaf with a split containing both the
+        // original pane and the new one.
         let replaced = replace_leaf(
             &mut self.layout,
             self.focused,
@@ -269,6 +374,7 @@ impl SessionTerminals {
         );
 
         if !replaced {
+            // Layout tree didn't contain the focused pane — roll back
             self.panes.remove(&new_id);
             anyhow::bail!("focused pane not found in layout tree during split");
         }
@@ -277,6 +383,12 @@ impl SessionTerminals {
         Ok(())
     }
 
+    /// The layout in config form, for saving and rebuilding the tab later.
+    /// Loop panes opened by claustre are left out.
+    pub fn layout_config(&self) -> Option<LayoutConfig> {
+        layout_to_config(&self.layout, &self.panes)
+    }
+
     /// Close the focused pane. Returns false if it's the last pane.
     pub fn close_focused(&mut self) -> bool {
         if self.panes.len() <= 1 {
@@ -285,6 +397,13 @@ impl SessionTerminals {
 
         let closed_id = self.focused;
         self.panes.remove(&closed_id);
+        self.zoomed = false;
+        if self.search.as_ref().is_some_and(|s| s.pane == closed_id) {
+            self.search = None;
+        }
+        if self.copy_mode.as_ref().is_some_and(|c| c.pane == closed_id) {
+            self.copy_mode = None;
+        }
         remove_leaf(&mut self.layout, closed_id);
 
         // Focus the first remaining pane in layout order.
@@ -301,6 +420,30 @@ impl SessionTerminals {
         for info in self.panes.values_mut() {
             info.terminal.process_output();
         }
+        self.restart_exited_panes();
+    }
+
+    /// Respawn `restart_on_exit` command panes whose process has ended, at
+    /// most once per [`RESTART_DELAY`].
+    fn restart_exited_panes(&mut self) {
+        for info in self.panes.values_mut() {
+            let PaneKind::Command(command) = &info.kind else {
+                continue;
+            };
+            if !command.restart_on_exit
+                || !info.terminal.exited
+                || info.started_at.elapsed() < RESTART_DELAY
+            {
+                continue;
+            }
+            let (rows, cols) = info.terminal.screen().size();
+            info.started_at = Instant::now();
+            if let Ok(terminal) =
+                EmbeddedTerminal::spawn(pane_command(command, &self.worktree_path), rows, cols)
+            {
+                info.terminal = terminal;
+            }
+        }
     }
 
     /// Drain all pending output from every pane without a byte budget.
@@ -3,13 +3,27 @@ use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::widgets::Widget;
 
-use super::Selection;
+use super::search::LineText;
+use super::{Search, Selection};
+
+/// Search matches to highlight in a [`TerminalWidget`].
+pub struct SearchHighlight<'a> {
+    pub search: &'a Search,
+    /// Viewport row and inclusive column range of the current match, when
+    /// it is on screen.
+    pub current: Option<(u16, u16, u16)>,
+}
 
 /// A ratatui widget that renders a vt100 terminal screen.
 pub struct TerminalWidget<'a> {
     screen: &'a vt100::Screen,
     focused: bool,
     selection: Option<&'a Selection>,
+    search: Option<SearchHighlight<'a>>,
+    /// History line shown on the top row; selections are in history lines.
+    viewport_top: usize,
+    /// Copy-mode cursor (viewport row, column), drawn instead of the PTY cursor.
+    copy_cursor: Option<(u16, u16)>,
     /// Number of lines scrolled back from the live screen (0 = live).
     scrollback_offset: usize,
 }
@@ -20,6 +34,9 @@ impl<'a> TerminalWidget<'a> {
             screen,
             focused,
             selection: None,
+            search: None,
+            viewport_top: 0,
+            copy_cursor: None,
             scrollback_offset: 0,
         }
     }
@@ -30,6 +47,24 @@ impl<'a> TerminalWidget<'a> {
         self
     }
 
+    #[must_use]
+    pub fn with_viewport_top(mut self, line: usize) -> Self {
+        self.viewport_top = line;
+        self
+    }
+
+    #[must_use]
+    pub fn with_copy_cursor(mut self, cursor: Option<(u16, u16)>) -> Self {
+        self.copy_cursor = cursor;
+        self
+    }
+
+    #[must_use]
+    pub fn with_search(mut self, search: Option<SearchHighlight<'a>>) -> Self {
+        self.search = search;
+        self
+    }
+
     #[must_use]
     pub fn with_scrollback_offset(mut self, offset: usize) -> Self {
         self.scrollback_offset = offset;
@@ -43,6 +78,15 @@ impl Widget for TerminalWidget<'_> {
         let cols = area.width.min(self.screen.size().1);
 
         for row in 0..rows {
+            let matches = self.search.as_ref().map_or_else(Vec::new, |h| {
+                h.search
+                    .line_matches(&LineText::from_screen(self.screen, row))
+            });
+            let current = self
+                .search
+                .as_ref()
+                .and_then(|h| h.current)
+                .filter(|&(r, _, _)| r == row);
             for col in 0..cols {
                 let Some(vt_cell) = self.screen.cell(row, col) else {
                     continue;
@@ -59,15 +103,28 @@ impl Widget for TerminalWidget<'_> {
                     buf_cell.set_symbol(&contents);
                 }
 
-                let is_selected = self.selection.is_some_and(|sel| sel.contains(row, col));
+                let line = self.viewport_top + usize::from(row);
+                let is_selected = self.selection.is_some_and(|sel| sel.contains(line, col));
 
-                if is_selected {
+                if self.copy_cursor == Some((row, col)) {
+                    buf_cell.set_fg(Color::Black);
+                    buf_cell.set_bg(Color::Cyan);
+                } else if is_selected {
                     // Highlight selected cells: swap fg/bg for visibility
                     let fg = vt100_color_to_ratatui(vt_cell.bgcolor());
                     let bg = vt100_color_to_ratatui(vt_cell.fgcolor());
                     // Use sensible defaults when colors are Reset
                     buf_cell.set_fg(if fg == Color::Reset { Color::Black } else { fg });
                     buf_cell.set_bg(if bg == Color::Reset { Color::White } else { bg });
+                } else if current.is_some_and(|(_, start, end)| (start..=end).contains(&col)) {
+                    buf_cell.set_fg(Color::Black);
+                    buf_cell.set_bg(Color::LightRed);
+                } else if matches
+                    .iter()
+                    .any(|&(start, end)| (start..=end).contains(&col))
+                {
+                    buf_cell.set_fg(Color::Black);
+                    buf_cell.set_bg(Color::Yellow);
                 } else {
                     buf_cell.set_fg(vt100_color_to_ratatui(vt_cell.fgcolor()));
                     buf_cell.set_bg(vt100_color_to_ratatui(vt_cell.bgcolor()));
@@ -99,9 +156,9 @@ impl Widget for TerminalWidget<'_> {
             let cx = area.x.saturating_add(cursor.1);
             let cy = area.y.saturating_add(cursor.0);
             if cx < area.x.saturating_add(area.width) && cy < area.y.saturating_add(area.height) {
-                let cursor_selected = self
-                    .selection
-                    .is_some_and(|sel| sel.contains(cursor.0, cursor.1));
+                let cursor_selected = self.selection.is_some_and(|sel| {
+                    sel.contains(self.viewport_top + usize::from(cursor.0), cursor.1)
+                });
                 if !cursor_selected && let Some(cell) = buf.cell_mut((cx, cy)) {
                     cell.set_style(Style::default().add_modifier(Modifier::REVERSED));
                 }
@@ -142,6 +199,7 @@ fn vt100_color_to_ratatui(color: vt100::Color) -> Color {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pty::SelectionKind;
 
     /// Render a `TerminalWidget` into a `Buffer` and return it for assertions.
     fn render_widget(widget: TerminalWidget, width: u16, height: u16) -> Buffer {
@@ -274,6 +332,7 @@ mod tests {
             pane: 0,
             start: (0, 0),
             end: (0, 2),
+            kind: SelectionKind::Char,
         };
         let widget = TerminalWidget::new(parser.screen(), false).with_selection(Some(&sel));
         let buf = render_widget(widget, 40, 5);
@@ -284,6 +343,44 @@ mod tests {
         assert_eq!(cell.fg, Color::Black);
     }
 
+    #[test]
+    fn selection_uses_history_lines_and_copy_cursor() {
+        let mut parser = vt100::Parser::new(5, 40, 0);
+        parser.process(b"row0\r\nrow1");
+        let sel = Selection {
+            pane: 0,
+            start: (11, 0),
+            end: (11, 3),
+            kind: SelectionKind::Line,
+        };
+        let widget = TerminalWidget::new(parser.screen(), false)
+            .with_viewport_top(10)
+            .with_selection(Some(&sel))
+            .with_copy_cursor(Some((0, 1)));
+        let buf = render_widget(widget, 40, 5);
+        assert_eq!(buf.cell((0, 0)).unwrap().bg, Color::Reset);
+        assert_eq!(buf.cell((1, 0)).unwrap().bg, Color::Cyan);
+        assert_eq!(buf.cell((0, 1)).unwrap().bg, Color::White);
+        assert_eq!(buf.cell((39, 1)).unwrap().bg, Color::White);
+    }
+
+    #[test]
+    fn search_highlights_matches_and_current() {
+        let mut parser = vt100::Parser::new(5, 40, 0);
+        parser.process(b"foo bar foo");
+        let mut search = Search::new(0, 0, Vec::new());
+        search.set_query("foo".to_string());
+        let widget =
+            TerminalWidget::new(parser.screen(), false).with_search(Some(SearchHighlight {
+                search: &search,
+                current: Some((0, 8, 10)),
+            }));
+        let buf = render_widget(widget, 40, 5);
+        assert_eq!(buf.cell((0, 0)).unwrap().bg, Color::Yellow);
+        assert_eq!(buf.cell((4, 0)).unwrap().bg, Color::Reset);
+        assert_eq!(buf.cell((8, 0)).unwrap().bg, Color::LightRed);
+    }
+
     #[test]
     fn smaller_buffer_clips_gracefully() {
         let mut parser = vt100::Parser::new(10, 80, 0);
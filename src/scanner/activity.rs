@@ -0,0 +1,428 @@
+//! Live tool-call feed for a claustre session.
+//!
+//! Tails the session's Claude JSONL transcript and pairs each assistant
+//! `tool_use` block with the `tool_result` that answers it, so the dashboard
+//! can show what a session is doing without attaching to its PTY.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// Tool calls kept per feed; older calls are dropped as new ones arrive.
+pub const MAX_CALLS: usize = 200;
+
+/// Longest summary or error kept for a tool call, in characters.
+const MAX_TEXT_LEN: usize = 120;
+
+/// One tool invocation from the transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolCall {
+    /// `tool_use` id, used to match the result.
+    pub id: String,
+    /// Tool name (`Bash`, `Edit`, `WebFetch`, ...).
+    pub tool: String,
+    /// Short description of the call: the command, file, URL or query.
+    pub summary: String,
+    pub started_at: Option<DateTime<Utc>>,
+    /// Set once the matching `tool_result` has been seen.
+    pub finished_at: Option<DateTime<Utc>>,
+    pub failed: bool,
+    /// First line of the result when the call failed.
+    pub error: Option<String>,
+}
+
+impl ToolCall {
+    /// Wall time between the call and its result, once both are known.
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        Some(self.finished_at? - self.started_at?)
+    }
+
+    /// Whether the result for this call is still outstanding.
+    pub fn is_running(&self) -> bool {
+        self.finished_at.is_none() && !self.failed
+    }
+}
+
+/// Incremental reader over one transcript file.
+///
+/// Each [`poll`](Self::poll) reads only the bytes appended since the previous
+/// call; a trailing line without its newline is held back until it completes.
+#[derive(Debug, Clone)]
+pub struct ActivityFeed {
+    path: PathBuf,
+    worktree: PathBuf,
+    offset: u64,
+    partial: Vec<u8>,
+    calls: VecDeque<ToolCall>,
+}
+
+impl ActivityFeed {
+    /// Feed over `path`. File paths in summaries are shown relative to `worktree`.
+    pub fn new(path: PathBuf, worktree: &Path) -> Self {
+        Self {
+            path,
+            worktree: worktree.to_path_buf(),
+            offset: 0,
+            partial: Vec::new(),
+            calls: VecDeque::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Known tool calls, oldest first.
+    pub fn calls(&self) -> &VecDeque<ToolCall> {
+        &self.calls
+    }
+
+    /// Read anything appended to the transcript since the last poll.
+    ///
+    /// Returns whether any new lines were processed. A transcript that shrank
+    /// (rewritten or replaced) is re-read from the start.
+    pub fn poll(&mut self) -> Result<bool> {
+        let mut file = fs::File::open(&self.path)
+            .with_context(|| format!("failed to open {}", self.path.display()))?;
+        let len = file
+            .metadata()
+            .with_context(|| format!("failed to stat {}", self.path.display()))?
+            .len();
+        if len < self.offset {
+            self.offset = 0;
+            self.partial.clear();
+            self.calls.clear();
+        }
+        if len == self.offset {
+            return Ok(false);
+        }
+
+        file.seek(SeekFrom::Start(self.offset))
+            .with_context(|| format!("failed to seek {}", self.path.display()))?;
+        let mut buf = Vec::new();
+        file.take(len - self.offset)
+            .read_to_end(&mut buf)
+            .with_context(|| format!("failed to read {}", self.path.display()))?;
+        self.offset += buf.len() as u64;
+        self.partial.extend_from_slice(&buf);
+
+        let Some(end) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            return Ok(false);
+        };
+        let complete: Vec<u8> = self.partial.drain(..=end).collect();
+        for line in complete.split(|&b| b == b'\n') {
+            if let Ok(line) = std::str::from_utf8(line) {
+                self.ingest_line(line);
+            }
+        }
+        Ok(true)
+    }
+
+    fn ingest_line(&mut self, line: &str) {
+        if !line.contains("tool_use") && !line.contains("tool_result") {
+            return;
+        }
+        let Ok(entry) = serde_json::from_str::<Value>(line) else {
+            return;
+        };
+        let timestamp = entry
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .map(|ts| ts.with_timezone(&Utc));
+        let Some(content) = entry
+            .get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(Value::as_array)
+        else {
+            return;
+        };
+
+        for block in content {
+            match block.get("type").and_then(Value::as_str) {
+                Some("tool_use") => self.start_call(block, timestamp),
+                Some("tool_result") => self.finish_call(block, timestamp),
+                _ => {}
+            }
+        }
+    }
+
+    fn start_call(&mut self, block: &Value, timestamp: Option<DateTime<Utc>>) {
+        let Some(id) = block.get("id").and_then(Value::as_str) else {
+            return;
+        };
+        let tool = block
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or("tool")
+            .to_string();
+        let input = block.get("input").unwrap_or(&Value::Null);
+        let summary = summarize_input(&tool, input, &self.worktree);
+        if self.calls.len() == MAX_CALLS {
+            self.calls.pop_front();
+        }
+        self.calls.push_back(ToolCall {
+            id: id.to_string(),
+            tool,
+            summary,
+            started_at: timestamp,
+            finished_at: None,
+            failed: false,
+            error: None,
+        });
+    }
+
+    fn finish_call(&mut self, block: &Value, timestamp: Option<DateTime<Utc>>) {
+        let Some(id) = block.get("tool_use_id").and_then(Value::as_str) else {
+            return;
+        };
+        let Some(call) = self.calls.iter_mut().rev().find(|c| c.id == id) else {
+            return;
+        };
+        call.finished_at = timestamp.or(call.started_at);
+        call.failed = block
+            .get("is_error")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        if call.failed {
+            call.error = result_text(block)
+                .lines()
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .map(truncate);
+        }
+    }
+}
+
+/// Locate the transcript Claude is writing for a worktree.
+///
+/// Claude keeps transcripts under `~/.claude/projects/<encoded worktree>/`,
+/// where the worktree path has every non-alphanumeric character replaced by
+/// `-`. The most recently modified `.jsonl` there is the live one, which is
+/// the same rule the Stop hook uses to pick up `claude_session_id`.
+pub fn transcript_path(worktree: &Path) -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    transcript_path_in(&home.join(".claude/projects"), worktree)
+}
+
+fn transcript_path_in(projects_dir: &Path, worktree: &Path) -> Option<PathBuf> {
+    let dir = projects_dir.join(encode_project_dir(worktree));
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .max()
+        .map(|(_, path)| path)
+}
+
+/// Directory name Claude uses for a project path.
+fn encode_project_dir(worktree: &Path) -> String {
+    worktree
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// One-line description of a tool call from its input.
+fn summarize_input(tool: &str, input: &Value, worktree: &Path) -> String {
+    let field = |name: &str| input.get(name).and_then(Value::as_str).unwrap_or("");
+    let text = match tool {
+        "Bash" => field("command").to_string(),
+        "Edit" | "MultiEdit" | "Write" | "Read" => relative(field("file_path"), worktree),
+        "NotebookEdit" => relative(field("notebook_path"), worktree),
+        "WebFetch" => field("url").to_string(),
+        "WebSearch" => field("query").to_string(),
+        "Grep" | "Glob" => field("pattern").to_string(),
+        "Task" => field("description").to_string(),
+        _ => String::new(),
+    };
+    text.lines().next().map(truncate).unwrap_or_default()
+}
+
+fn relative(path: &str, worktree: &Path) -> String {
+    Path::new(path)
+        .strip_prefix(worktree)
+        .map_or_else(|_| path.to_string(), |p| p.display().to_string())
+}
+
+/// Text of a `tool_result` block, whose content is a string or a list of
+/// text blocks.
+fn result_text(block: &Value) -> String {
+    match block.get("content") {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(|p| p.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_TEXT_LEN {
+        return text.to_string();
+    }
+    let mut out: String = text.chars().take(MAX_TEXT_LEN - 1).collect();
+    out.push('\u{2026}');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn tool_use(id: &str, name: &str, input: &Value, ts: &str) -> String {
+        serde_json::json!({
+            "type": "assistant",
+            "timestamp": ts,
+            "message": {"content": [{"type": "tool_use", "id": id, "name": name, "input": input}]},
+        })
+        .to_string()
+    }
+
+    fn tool_result(id: &str, is_error: bool, content: &str, ts: &str) -> String {
+        serde_json::json!({
+            "type": "user",
+            "timestamp": ts,
+            "message": {"content": [{
+                "type": "tool_result",
+                "tool_use_id": id,
+                "is_error": is_error,
+                "content": content,
+            }]},
+        })
+        .to_string()
+    }
+
+    fn append(path: &Path, text: &str) {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn pairs_calls_with_results_and_flags_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("t.jsonl");
+        let worktree = Path::new("/work/tree");
+        append(
+            &path,
+            &format!(
+                "{}\n{}\n{}\n{}\n",
+                tool_use(
+                    "a",
+                    "Edit",
+                    &serde_json::json!({"file_path": "/work/tree/src/main.rs"}),
+                    "2026-01-01T00:00:00Z"
+                ),
+                tool_result("a", false, "ok", "2026-01-01T00:00:02Z"),
+                tool_use(
+                    "b",
+                    "Bash",
+                    &serde_json::json!({"command": "cargo test\n--all"}),
+                    "2026-01-01T00:00:03Z"
+                ),
+                tool_result(
+                    "b",
+                    true,
+                    "\nExit code 101\nfailures:",
+                    "2026-01-01T00:00:13Z"
+                ),
+            ),
+        );
+
+        let mut feed = ActivityFeed::new(path, worktree);
+        assert!(feed.poll().unwrap());
+        let calls = feed.calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].summary, "src/main.rs");
+        assert!(!calls[0].failed);
+        assert_eq!(calls[0].duration(), Some(chrono::Duration::seconds(2)));
+        assert_eq!(calls[1].tool, "Bash");
+        assert_eq!(calls[1].summary, "cargo test");
+        assert!(calls[1].failed);
+        assert_eq!(calls[1].error.as_deref(), Some("Exit code 101"));
+        assert_eq!(calls[1].duration(), Some(chrono::Duration::seconds(10)));
+    }
+
+    #[test]
+    fn poll_tails_appended_lines_and_waits_for_partial_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("t.jsonl");
+        let line = tool_use(
+            "a",
+            "WebFetch",
+            &serde_json::json!({"url": "https://example.com"}),
+            "2026-01-01T00:00:00Z",
+        );
+        let (head, tail) = line.split_at(20);
+        append(&path, head);
+
+        let mut feed = ActivityFeed::new(path.clone(), dir.path());
+        assert!(!feed.poll().unwrap());
+        assert!(feed.calls().is_empty());
+
+        append(&path, &format!("{tail}\n"));
+        assert!(feed.poll().unwrap());
+        assert_eq!(feed.calls().len(), 1);
+        assert!(feed.calls()[0].is_running());
+        assert!(!feed.poll().unwrap());
+
+        append(
+            &path,
+            &format!(
+                "{}\n",
+                tool_result("a", false, "ok", "2026-01-01T00:00:01Z")
+            ),
+        );
+        assert!(feed.poll().unwrap());
+        assert_eq!(feed.calls().len(), 1);
+        assert!(!feed.calls()[0].is_running());
+
+        // A rewritten, shorter transcript is read again from the start.
+        fs::write(&path, "").unwrap();
+        feed.poll().unwrap();
+        assert!(feed.calls().is_empty());
+    }
+
+    #[test]
+    fn transcript_path_picks_newest_jsonl_in_encoded_dir() {
+        let projects = tempfile::tempdir().unwrap();
+        let worktree = Path::new("/home/me/.claustre/worktrees/app/fix_bug");
+        let dir = projects
+            .path()
+            .join("-home-me--claustre-worktrees-app-fix-bug");
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(transcript_path_in(projects.path(), worktree), None);
+
+        fs::write(dir.join("old.jsonl"), "").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+        let old = fs::File::options()
+            .write(true)
+            .open(dir.join("old.jsonl"))
+            .unwrap();
+        old.set_modified(std::time::SystemTime::UNIX_EPOCH).unwrap();
+        fs::write(dir.join("new.jsonl"), "").unwrap();
+
+        assert_eq!(
+            transcript_path_in(projects.path(), worktree),
+            Some(dir.join("new.jsonl"))
+        );
+    }
+}
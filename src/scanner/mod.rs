@@ -5,6 +5,8 @@
 //! Only tracks **active** sessions (JSONL file modified within the last 5 minutes).
 //! Skips claustre-managed sessions and unchanged files for efficiency.
 
+pub mod activity;
+
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{BufRead, BufReader};
@@ -278,6 +280,43 @@ fn parse_jsonl(
     })
 }
 
+/// Text of the last assistant message in a transcript that contains any,
+/// with its text blocks joined by newlines.
+///
+/// Used by the Stop hook to read how Claude ended its turn.
+pub fn last_assistant_text(path: &Path) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
+    let mut last = None;
+    for line in BufReader::new(file).lines() {
+        let Ok(line) = line else { continue };
+        if !line.contains("\"assistant\"") {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if entry.get("type").and_then(Value::as_str) != Some("assistant") {
+            continue;
+        }
+        let Some(content) = entry
+            .get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(Value::as_array)
+        else {
+            continue;
+        };
+        let text: Vec<&str> = content
+            .iter()
+            .filter(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+            .filter_map(|block| block.get("text").and_then(Value::as_str))
+            .collect();
+        if !text.is_empty() {
+            last = Some(text.join("\n"));
+        }
+    }
+    last
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -422,6 +461,25 @@ mod tests {
         assert_eq!(session.project_name, "unknown");
     }
 
+    #[test]
+    fn last_assistant_text_skips_tool_only_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let jsonl_path = dir.path().join("transcript.jsonl");
+        let lines = [
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"first"}]}}"#,
+            r#"{"type":"user","message":{"content":"next"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"done"},{"type":"text","text":"STEP COMPLETE"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Bash"}]}}"#,
+        ];
+        fs::write(&jsonl_path, lines.join("\n")).unwrap();
+
+        assert_eq!(
+            last_assistant_text(&jsonl_path).as_deref(),
+            Some("done\nSTEP COMPLETE")
+        );
+        assert!(last_assistant_text(&dir.path().join("missing.jsonl")).is_none());
+    }
+
     /// Helper: create a project directory with a sessions-index.json and a JSONL session file.
     fn create_project_dir(
         base: &Path,
@@ -0,0 +1,493 @@
+//! Recurring task schedules.
+//!
+//! A schedule is attached to a template task. When it comes due, the task
+//! is cloned into a new pending task (see [`Store::clone_task`]) and,
+//! with `auto_launch`, queued for launch in autonomous mode.
+//!
+//! Supported specs:
+//!
+//! ```text
+//! every 30m | every 12h | every 7d | every 2w
+//! @hourly | @daily | @weekly | @monthly
+//! 0 9 * * 1          (five-field cron: minute hour day-of-month month day-of-week)
+//! ```
+//!
+//! Cron fields accept `*`, numbers, `a-b` ranges, `,` lists and `/n` steps.
+//! Day-of-week runs 0-7 with both 0 and 7 meaning Sunday. As in cron, when
+//! both day fields are restricted a day matching either one fires. Cron
+//! times are evaluated in the local timezone.
+//!
+//! Missed runs (claustre was not running when a schedule came due) are
+//! coalesced: on the next check each overdue schedule fires exactly once,
+//! and its next run is the first occurrence after the current time.
+
+use std::str::FromStr;
+
+use anyhow::{Context, Result, bail, ensure};
+use chrono::{DateTime, Datelike, Days, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+
+use crate::store::{Store, Task, TaskMode};
+
+/// How far ahead to search for the next cron match before giving up
+/// (covers leap-day schedules such as `0 0 29 2 *`).
+const MAX_SEARCH_DAYS: u64 = 366 * 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleSpec {
+    /// A fixed interval, anchored at the previous due time.
+    Every(Duration),
+    Cron(CronSpec),
+}
+
+/// A parsed five-field cron expression. Each field is a bitset of allowed values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSpec {
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    /// Bit 0 is Sunday.
+    days_of_week: u8,
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl FromStr for ScheduleSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Some(interval) = s.strip_prefix("every ") {
+            return parse_interval(interval.trim()).map(Self::Every);
+        }
+        let cron = match s {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        CronSpec::parse(cron)
+            .map(Self::Cron)
+            .with_context(|| format!("invalid schedule '{s}' (try 'every 7d', '@weekly' or a cron expression like '0 9 * * 1')"))
+    }
+}
+
+impl ScheduleSpec {
+    /// The first due time strictly after `now`. `previous` is the due time
+    /// that just fired (or `None` for a new schedule); intervals are anchored
+    /// to it so a schedule keeps its cadence across restarts.
+    pub fn next_run(
+        &self,
+        previous: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        self.next_run_in(previous, now, &Local)
+    }
+
+    fn next_run_in<Tz: TimeZone>(
+        &self,
+        previous: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+        tz: &Tz,
+    ) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Every(interval) => {
+                let Some(anchor) = previous else {
+                    return now.checked_add_signed(*interval);
+                };
+                if anchor > now {
+                    return Some(anchor);
+                }
+                // Skip whole missed periods in one step.
+                let missed = (now - anchor).num_seconds() / interval.num_seconds() + 1;
+                let skip = interval.checked_mul(i32::try_from(missed).ok()?)?;
+                anchor.checked_add_signed(skip)
+            }
+            Self::Cron(cron) => cron.next_after(now, tz),
+        }
+    }
+}
+
+impl CronSpec {
+    fn parse(expr: &str) -> Result<Self> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        ensure!(
+            fields.len() == 5,
+            "expected 5 cron fields, got {}",
+            fields.len()
+        );
+        let minutes = parse_field(fields[0], 0, 59)?;
+        let hours = parse_field(fields[1], 0, 23)?;
+        let days_of_month = parse_field(fields[2], 1, 31)?;
+        let months = parse_field(fields[3], 1, 12)?;
+        let mut days_of_week = parse_field(fields[4], 0, 7)?;
+        // 7 is an alias for Sunday.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week |= 1;
+        }
+        Ok(Self {
+            minutes,
+            hours: u32::try_from(hours)?,
+            days_of_month: u32::try_from(days_of_month)?,
+            months: u16::try_from(months)?,
+            days_of_week: u8::try_from(days_of_week & 0x7f)?,
+            dom_restricted: fields[2] != "*",
+            dow_restricted: fields[4] != "*",
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.dom_restricted, self.dow_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+
+    /// The first matching minute strictly after `now`, evaluated in `tz`.
+    /// Local times skipped by a DST change never match; repeated ones
+    /// fire on their first occurrence.
+    fn next_after<Tz: TimeZone>(&self, now: DateTime<Utc>, tz: &Tz) -> Option<DateTime<Utc>> {
+        let local = now.with_timezone(tz).naive_local();
+        let start_date = local.date();
+        for offset in 0..MAX_SEARCH_DAYS {
+            let date = start_date.checked_add_days(Days::new(offset))?;
+            if !self.day_matches(date) {
+                continue;
+            }
+            for hour in (0..24).filter(|h| self.hours & (1 << h) != 0) {
+                for minute in (0..60).filter(|m| self.minutes & (1 << m) != 0) {
+                    let time = NaiveTime::from_hms_opt(hour, minute, 0)?;
+                    let candidate = date.and_time(time);
+                    if let Some(at) = tz.from_local_datetime(&candidate).earliest() {
+                        let at = at.with_timezone(&Utc);
+                        if at > now {
+                            return Some(at);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Parse one cron field into a bitset of values in `min..=max`.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .with_context(|| format!("invalid step in '{part}'"))?;
+                ensure!(step > 0, "step must be positive in '{part}'");
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((lo, hi)) = range.split_once('-') {
+            (parse_value(lo, min, max)?, parse_value(hi, min, max)?)
+        } else {
+            let value = parse_value(range, min, max)?;
+            // `5/15` means "from 5, every 15" as in most crons.
+            (value, if step > 1 { max } else { value })
+        };
+        ensure!(lo <= hi, "range '{range}' is backwards");
+        for value in (lo..=hi).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_value(s: &str, min: u32, max: u32) -> Result<u32> {
+    let value: u32 = s
+        .parse()
+        .with_context(|| format!("invalid cron value '{s}'"))?;
+    ensure!(
+        (min..=max).contains(&value),
+        "cron value {value} out of range {min}-{max}"
+    );
+    Ok(value)
+}
+
+/// Parse `30m`, `12h`, `7d` or `2w`.
+fn parse_interval(s: &str) -> Result<Duration> {
+    let unit_at = s
+        .find(|c: char| !c.is_ascii_digit())
+        .with_context(|| format!("missing unit in 'every {s}' (m, h, d or w)"))?;
+    let (digits, unit) = s.split_at(unit_at);
+    let n: i64 = digits
+        .parse()
+        .with_context(|| format!("invalid interval 'every {s}'"))?;
+    ensure!(n > 0, "interval must be positive");
+    let interval = match unit {
+        "m" => Duration::try_minutes(n),
+        "h" => Duration::try_hours(n),
+        "d" => Duration::try_days(n),
+        "w" => Duration::try_weeks(n),
+        other => bail!("unknown unit '{other}' in 'every {s}' (m, h, d or w)"),
+    };
+    interval.with_context(|| format!("interval 'every {s}' is too large"))
+}
+
+/// A clone created by a schedule that came due.
+#[derive(Debug, Clone)]
+pub struct ScheduledRun {
+    pub template_id: String,
+    pub clone: Task,
+    pub auto_launch: bool,
+}
+
+/// Fire every schedule due at `now`: clone its task once and advance it to
+/// the next occurrence after `now`. A schedule with an unparseable spec or a
+/// missing template is disabled (next run cleared) rather than retried forever.
+pub fn run_due(store: &Store, now: DateTime<Utc>) -> Result<Vec<ScheduledRun>> {
+    let now_str = now.to_rfc3339();
+    let mut runs = Vec::new();
+    for schedule in store.due_task_schedules(&now_str)? {
+        let previous = schedule
+            .next_run_at
+            .as_deref()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+        let spec = match schedule.spec.parse::<ScheduleSpec>() {
+            Ok(spec) => spec,
+            Err(e) => {
+                tracing::warn!(task_id = %schedule.task_id, "disabling schedule: {e:#}");
+                store.record_schedule_run(
+                    &schedule.task_id,
+                    schedule.last_run_at.as_deref().unwrap_or(&now_str),
+                    None,
+                )?;
+                continue;
+            }
+        };
+        let mode = schedule.auto_launch.then_some(TaskMode::Autonomous);
+        let next = spec.next_run(previous, now).map(|dt| dt.to_rfc3339());
+        match store.clone_task(&schedule.task_id, mode) {
+            Ok(clone) => {
+                store.record_schedule_run(&schedule.task_id, &now_str, next.as_deref())?;
+                runs.push(ScheduledRun {
+                    template_id: schedule.task_id,
+                    clone,
+                    auto_launch: schedule.auto_launch,
+                });
+            }
+            Err(e) => {
+                tracing::warn!(task_id = %schedule.task_id, "scheduled clone failed: {e:#}");
+                store.record_schedule_run(&schedule.task_id, &now_str, next.as_deref())?;
+            }
+        }
+    }
+    Ok(runs)
+}
+
+/// Parse a stored RFC 3339 timestamp and format it in local time for display.
+pub fn format_local(timestamp: &str) -> String {
+    DateTime::parse_from_rfc3339(timestamp).map_or_else(
+        |_| timestamp.to_string(),
+        |dt| {
+            dt.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::PushMode;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(spec: &str, now: &str) -> Option<DateTime<Utc>> {
+        spec.parse::<ScheduleSpec>()
+            .unwrap()
+            .next_run_in(None, at(now), &Utc)
+    }
+
+    #[test]
+    fn parses_intervals_macros_and_cron() {
+        assert_eq!(
+            "every 7d".parse::<ScheduleSpec>().unwrap(),
+            ScheduleSpec::Every(Duration::days(7))
+        );
+        assert_eq!(
+            "every 2w".parse::<ScheduleSpec>().unwrap(),
+            ScheduleSpec::Every(Duration::weeks(2))
+        );
+        assert_eq!(
+            "@weekly".parse::<ScheduleSpec>().unwrap(),
+            "0 0 * * 7".parse::<ScheduleSpec>().unwrap()
+        );
+        assert!("*/15 9-17 * * 1-5".parse::<ScheduleSpec>().is_ok());
+        for bad in [
+            "every 7",
+            "every 0d",
+            "every 3y",
+            "* * * *",
+            "60 * * * *",
+            "5-1 * * * *",
+            "*/0 * * * *",
+            "nonsense",
+        ] {
+            assert!(bad.parse::<ScheduleSpec>().is_err(), "{bad} should fail");
+        }
+    }
+
+    #[test]
+    fn cron_next_run() {
+        // 2026-01-12 is a Monday.
+        assert_eq!(
+            next("0 9 * * 1", "2026-01-12T09:00:00Z"),
+            Some(at("2026-01-19T09:00:00Z"))
+        );
+        assert_eq!(
+            next("0 9 * * 1", "2026-01-12T08:59:30Z"),
+            Some(at("2026-01-12T09:00:00Z"))
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2026-01-12T10:07:00Z"),
+            Some(at("2026-01-12T10:15:00Z"))
+        );
+        assert_eq!(
+            next("@monthly", "2026-01-31T12:00:00Z"),
+            Some(at("2026-02-01T00:00:00Z"))
+        );
+        // Day-of-month OR day-of-week when both are restricted.
+        assert_eq!(
+            next("0 0 15 * 5", "2026-01-12T00:00:00Z"),
+            Some(at("2026-01-15T00:00:00Z"))
+        );
+        assert_eq!(
+            next("0 0 29 2 *", "2026-03-01T00:00:00Z"),
+            Some(at("2028-02-29T00:00:00Z"))
+        );
+        assert_eq!(next("0 0 30 2 *", "2026-01-01T00:00:00Z"), None);
+    }
+
+    #[test]
+    fn intervals_keep_cadence_across_missed_runs() {
+        let spec: ScheduleSpec = "every 7d".parse().unwrap();
+        let now = at("2026-01-20T12:00:00Z");
+        assert_eq!(spec.next_run(None, now), Some(at("2026-01-27T12:00:00Z")));
+        // Due on the 1st, missed the 8th and 15th: next is the 22nd.
+        assert_eq!(
+            spec.next_run(Some(at("2026-01-01T09:00:00Z")), now),
+            Some(at("2026-01-22T09:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn huge_intervals_error_instead_of_panicking() {
+        assert!(
+            "every 9223372036854775807w"
+                .parse::<ScheduleSpec>()
+                .is_err()
+        );
+        assert!("every 999999999999999d".parse::<ScheduleSpec>().is_err());
+
+        // Parses, but the next run is past the end of representable time
+        let spec: ScheduleSpec = "every 1000000000w".parse().unwrap();
+        let now = at("2026-01-20T12:00:00Z");
+        assert_eq!(spec.next_run(None, now), None);
+        assert_eq!(spec.next_run(Some(at("2026-01-01T09:00:00Z")), now), None);
+    }
+
+    #[test]
+    fn run_due_clones_once_and_advances() {
+        let store = Store::open_in_memory().unwrap();
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        let make = |title: &str| {
+            store
+                .create_task(
+                    &project.id,
+                    title,
+                    "weekly chore",
+                    TaskMode::Supervised,
+                    None,
+                    None,
+                    PushMode::Pr,
+                    false,
+                )
+                .unwrap()
+                .id
+        };
+        let audit = make("Audit deps");
+        let triage = make("Triage flakes");
+        let later = make("Changelog");
+        store
+            .set_task_schedule(&audit, "every 7d", true, "2026-01-01T09:00:00+00:00")
+            .unwrap();
+        store
+            .set_task_schedule(&triage, "every 1d", false, "2026-01-19T09:00:00+00:00")
+            .unwrap();
+        store
+            .set_task_schedule(&later, "every 1d", false, "2026-01-21T09:00:00+00:00")
+            .unwrap();
+
+        let now = at("2026-01-20T12:00:00Z");
+        let runs = run_due(&store, now).unwrap();
+        let titles: Vec<&str> = runs.iter().map(|r| r.clone.title.as_str()).collect();
+        assert_eq!(titles, ["Audit deps", "Triage flakes"]);
+        assert!(runs[0].auto_launch);
+        assert_eq!(runs[0].clone.mode, TaskMode::Autonomous);
+        assert_eq!(runs[1].clone.mode, TaskMode::Supervised);
+
+        let audit_schedule = store.get_task_schedule(&audit).unwrap().unwrap();
+        assert_eq!(
+            audit_schedule.next_run_at.as_deref(),
+            Some(at("2026-01-22T09:00:00Z").to_rfc3339().as_str())
+        );
+        assert_eq!(
+            audit_schedule.last_run_at.as_deref(),
+            Some(now.to_rfc3339().as_str())
+        );
+
+        // Running again at the same instant does nothing.
+        assert!(run_due(&store, now).unwrap().is_empty());
+        assert_eq!(store.list_tasks_for_project(&project.id).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn run_due_disables_invalid_specs() {
+        let store = Store::open_in_memory().unwrap();
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        let task = store
+            .create_task(
+                &project.id,
+                "t",
+                "",
+                TaskMode::Supervised,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap();
+        store
+            .set_task_schedule(&task.id, "bogus", false, "2026-01-01T00:00:00+00:00")
+            .unwrap();
+        assert!(
+            run_due(&store, at("2026-01-02T00:00:00Z"))
+                .unwrap()
+                .is_empty()
+        );
+        let schedule = store.get_task_schedule(&task.id).unwrap().unwrap();
+        assert!(schedule.next_run_at.is_none());
+    }
+}
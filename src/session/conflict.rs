@@ -0,0 +1,385 @@
+//! Automatic merge-conflict resolution for tasks whose PR conflicts with its base.
+//!
+//! Reuses the session worktree when it still exists, otherwise reopens one for
+//! the task branch. The base is merged (or rebased) in; if git cannot apply it
+//! cleanly, Claude resolves the conflicted files. The branch is then pushed and
+//! the task returns to `in_review`.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, bail};
+
+use crate::config::{self, ConflictStrategy};
+use crate::store::{ConflictOutcome, Store, TaskStatus};
+
+/// Default instructions given to Claude before the conflicted file list.
+const CONFLICT_PROMPT: &str = r"The base branch was applied to this branch and git stopped with merge conflicts. Follow these steps:
+
+1. Open every conflicted file listed below and resolve the conflict markers, keeping the intent of both sides.
+2. Build and run the relevant tests where possible to confirm the result.
+3. Stage the resolved files with `git add`.
+4. Finish the operation with the command given below. If further conflicts appear, repeat from step 1.
+5. Do NOT push — claustre pushes the branch after you exit.
+
+IMPORTANT: This is an autonomous task. Do NOT ask the user for clarification. Make your best judgment and proceed.";
+
+/// Resolve conflicts for a `conflict` task, recording the attempt.
+///
+/// When `headless` is true Claude runs in print mode so no terminal is
+/// needed (used when the task has no open session tab).
+pub fn resolve_task_conflicts(
+    store: &Store,
+    cfg: &config::Config,
+    task_id: &str,
+    headless: bool,
+) -> Result<ConflictOutcome> {
+    let task = store.get_task(task_id)?;
+    if task.status != TaskStatus::Conflict {
+        bail!("task '{}' is {}, not conflict", task.title, task.status);
+    }
+    let settings = &cfg.conflict_resolution;
+    let attempts = store.count_conflict_attempts(task_id)?;
+    if attempts >= i64::from(settings.max_attempts) {
+        bail!(
+            "reached {attempts} conflict resolution attempts for '{}'",
+            task.title
+        );
+    }
+    let session_id = task
+        .session_id
+        .as_deref()
+        .context("task has no session to resolve conflicts in")?;
+    let session = store.get_session(session_id)?;
+    let project = store.get_project(&task.project_id)?;
+    let base = task.base.as_deref().unwrap_or(&project.default_branch);
+    let branch = session.branch_name.as_str();
+    let strategy = settings.strategy;
+    let push_cmd = push_command(strategy, branch);
+    let denied = push_denied(cfg, branch, &[base, &project.default_branch]);
+
+    let attempt = store.start_conflict_attempt(task_id, strategy.as_str())?;
+    let finish = |outcome: ConflictOutcome, files: &[String]| -> Result<ConflictOutcome> {
+        store.finish_conflict_attempt(&attempt.id, outcome, files)?;
+        Ok(outcome)
+    };
+    if denied {
+        eprintln!("resolve-conflicts: `{push_cmd}` is blocked by the push deny-list");
+        return finish(ConflictOutcome::PushDenied, &[]);
+    }
+
+    let repo_path = Path::new(&project.repo_path);
+    let existing = Path::new(&session.worktree_path);
+    let (worktree, reopened) = if existing.exists() {
+        (existing.to_path_buf(), false)
+    } else {
+        eprintln!("resolve-conflicts: reopening worktree for {branch}");
+        let reopen = super::create_worktree(repo_path, &project.name, branch, base)
+            .and_then(|path| super::write_merged_config(repo_path, &path).map(|()| path));
+        match reopen {
+            Ok(path) => {
+                super::configure_git_excludes(&path);
+                (path, true)
+            }
+            Err(e) => {
+                eprintln!("resolve-conflicts: {e:#}");
+                return finish(ConflictOutcome::Failed, &[]);
+            }
+        }
+    };
+
+    let result = resolve_in_worktree(
+        cfg,
+        &worktree,
+        strategy,
+        base,
+        &push_cmd,
+        settings.prompt.as_deref().unwrap_or(CONFLICT_PROMPT),
+        headless,
+    );
+    if reopened {
+        let _ = super::remove_worktree(repo_path, &worktree);
+    }
+    let (outcome, files) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("resolve-conflicts: {e:#}");
+            (ConflictOutcome::Failed, Vec::new())
+        }
+    };
+    finish(outcome, &files)?;
+
+    if matches!(outcome, ConflictOutcome::Clean | ConflictOutcome::Resolved) {
+        store.try_update_task_status(task_id, TaskStatus::InReview)?;
+    }
+    Ok(outcome)
+}
+
+/// The push that publishes the updated branch. A rebase rewrites history,
+/// so it needs a lease; a merge is a fast-forward of the remote branch.
+fn push_command(strategy: ConflictStrategy, branch: &str) -> String {
+    match strategy {
+        ConflictStrategy::Merge => format!("git push origin HEAD:{branch}"),
+        ConflictStrategy::Rebase => format!("git push --force-with-lease origin HEAD:{branch}"),
+    }
+}
+
+/// Whether pushing the resolved `branch` is refused: it is one of the
+/// `protected` branches (base or default), or a `[permissions] deny` rule
+/// blocks a plain push of it. The lease a rebase needs is not checked
+/// against the deny list, since it only ever rewrites the task's own branch.
+fn push_denied(cfg: &config::Config, branch: &str, protected: &[&str]) -> bool {
+    protected.contains(&branch)
+        || cfg
+            .permissions
+            .denies_bash(&push_command(ConflictStrategy::Merge, branch))
+}
+
+fn resolve_in_worktree(
+    cfg: &config::Config,
+    worktree: &Path,
+    strategy: ConflictStrategy,
+    base: &str,
+    push_cmd: &str,
+    instructions: &str,
+    headless: bool,
+) -> Result<(ConflictOutcome, Vec<String>)> {
+    let status = git_output(worktree, &["status", "--porcelain"])?;
+    if !status.trim().is_empty() {
+        bail!("worktree has uncommitted changes, not touching it");
+    }
+
+    let applied = apply_base(worktree, strategy, base)?;
+    let mut files = Vec::new();
+    let outcome = if applied {
+        ConflictOutcome::Clean
+    } else {
+        files = conflicted_files(worktree)?;
+        if files.is_empty() {
+            abort(worktree, strategy);
+            bail!("git {} failed without conflicts", strategy.as_str());
+        }
+        eprintln!(
+            "resolve-conflicts: {} conflicted file(s), running claude",
+            files.len()
+        );
+        let prompt = conflict_prompt(instructions, strategy, &files);
+        let mut cmd = Command::new("claude");
+        if headless {
+            cmd.arg("-p").stdout(Stdio::null());
+        }
+        let claude_ok = cmd
+            .args(["--model", &cfg.claude.model, "--effort", &cfg.claude.effort])
+            .arg(&prompt)
+            .current_dir(worktree)
+            .env("CLAUSTRE_SESSION", "1")
+            .status()
+            .context("failed to run claude for conflict resolution")?
+            .success();
+
+        if !claude_ok || operation_in_progress(worktree, strategy)? {
+            abort(worktree, strategy);
+            return Ok((ConflictOutcome::Unresolved, files));
+        }
+        ConflictOutcome::Resolved
+    };
+
+    let mut args: Vec<&str> = push_cmd.split_whitespace().skip(1).collect();
+    args.splice(0..0, ["-C", path_str(worktree)?]);
+    let pushed = Command::new("git")
+        .args(&args)
+        .status()
+        .is_ok_and(|s| s.success());
+    if !pushed {
+        return Ok((ConflictOutcome::PushFailed, files));
+    }
+    Ok((outcome, files))
+}
+
+/// Fetch the base and merge/rebase it in. Returns false if git stopped
+/// (normally because of conflicts).
+fn apply_base(worktree: &Path, strategy: ConflictStrategy, base: &str) -> Result<bool> {
+    git_output(worktree, &["fetch", "origin", base])?;
+    let upstream = format!("origin/{base}");
+    let args: &[&str] = match strategy {
+        ConflictStrategy::Merge => &["merge", "--no-edit", &upstream],
+        ConflictStrategy::Rebase => &["rebase", &upstream],
+    };
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(worktree)
+        .args(args)
+        .env("GIT_EDITOR", "true")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .with_context(|| format!("failed to run git {}", strategy.as_str()))?;
+    Ok(status.success())
+}
+
+fn conflicted_files(worktree: &Path) -> Result<Vec<String>> {
+    let output = git_output(worktree, &["diff", "--name-only", "--diff-filter=U"])?;
+    Ok(output.lines().map(str::to_string).collect())
+}
+
+/// Whether the merge/rebase is still unfinished or files are still unmerged.
+fn operation_in_progress(worktree: &Path, strategy: ConflictStrategy) -> Result<bool> {
+    if !conflicted_files(worktree)?.is_empty() {
+        return Ok(true);
+    }
+    let markers: &[&str] = match strategy {
+        ConflictStrategy::Merge => &["MERGE_HEAD"],
+        ConflictStrategy::Rebase => &["rebase-merge", "rebase-apply"],
+    };
+    for marker in markers {
+        let path = git_output(worktree, &["rev-parse", "--git-path", marker])?;
+        if worktree.join(path.trim()).exists() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn abort(worktree: &Path, strategy: ConflictStrategy) {
+    let _ = Command::new("git")
+        .arg("-C")
+        .arg(worktree)
+        .args([strategy.as_str(), "--abort"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+fn conflict_prompt(instructions: &str, strategy: ConflictStrategy, files: &[String]) -> String {
+    let finish = match strategy {
+        ConflictStrategy::Merge => "git commit --no-edit",
+        ConflictStrategy::Rebase => "GIT_EDITOR=true git rebase --continue",
+    };
+    let list: Vec<String> = files.iter().map(|f| format!("- {f}")).collect();
+    format!(
+        "{instructions}\n\nFinish with: `{finish}`\n\n## Conflicted files\n\n{}",
+        list.join("\n")
+    )
+}
+
+fn git_output(worktree: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(worktree)
+        .args(args)
+        .output()
+        .with_context(|| format!("failed to run git {}", args.join(" ")))?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn path_str(path: &Path) -> Result<&str> {
+    path.to_str()
+        .context("worktree path contains invalid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    /// Clone of a bare origin where `feature` and `main` both edited
+    /// `file.txt` (conflicting) and `main` also added `other.txt`.
+    fn diverged_repo(conflicting: bool) -> (tempfile::TempDir, tempfile::TempDir) {
+        let origin = tempfile::tempdir().unwrap();
+        git(origin.path(), &["init", "--bare", "-b", "main"]);
+        let repo = tempfile::tempdir().unwrap();
+        git(
+            repo.path(),
+            &["clone", origin.path().to_str().unwrap(), "."],
+        );
+        git(repo.path(), &["config", "user.name", "test"]);
+        git(repo.path(), &["config", "user.email", "test@example.com"]);
+        git(repo.path(), &["checkout", "-b", "main"]);
+        std::fs::write(repo.path().join("file.txt"), "base\n").unwrap();
+        git(repo.path(), &["add", "."]);
+        git(repo.path(), &["commit", "-m", "base"]);
+        git(repo.path(), &["push", "origin", "main"]);
+
+        git(repo.path(), &["checkout", "-b", "feature"]);
+        std::fs::write(repo.path().join("file.txt"), "feature\n").unwrap();
+        git(repo.path(), &["commit", "-am", "feature"]);
+
+        git(repo.path(), &["checkout", "main"]);
+        let target = if conflicting { "file.txt" } else { "other.txt" };
+        std::fs::write(repo.path().join(target), "main\n").unwrap();
+        git(repo.path(), &["add", "."]);
+        git(repo.path(), &["commit", "-m", "main"]);
+        git(repo.path(), &["push", "origin", "main"]);
+        git(repo.path(), &["checkout", "feature"]);
+        (repo, origin)
+    }
+
+    #[test]
+    fn clean_merge_applies_base() {
+        let (repo, _origin) = diverged_repo(false);
+        assert!(apply_base(repo.path(), ConflictStrategy::Merge, "main").unwrap());
+        assert!(repo.path().join("other.txt").exists());
+        assert!(!operation_in_progress(repo.path(), ConflictStrategy::Merge).unwrap());
+    }
+
+    #[test]
+    fn conflicting_rebase_lists_files_and_aborts() {
+        let (repo, _origin) = diverged_repo(true);
+        assert!(!apply_base(repo.path(), ConflictStrategy::Rebase, "main").unwrap());
+        assert_eq!(conflicted_files(repo.path()).unwrap(), vec!["file.txt"]);
+        assert!(operation_in_progress(repo.path(), ConflictStrategy::Rebase).unwrap());
+
+        abort(repo.path(), ConflictStrategy::Rebase);
+        assert!(!operation_in_progress(repo.path(), ConflictStrategy::Rebase).unwrap());
+        let content = std::fs::read_to_string(repo.path().join("file.txt")).unwrap();
+        assert_eq!(content, "feature\n");
+    }
+
+    #[test]
+    fn prompt_lists_files_and_finish_command() {
+        let files = vec!["a.rs".to_string(), "b.rs".to_string()];
+        let prompt = conflict_prompt("Fix it.", ConflictStrategy::Rebase, &files);
+        assert!(prompt.starts_with("Fix it."));
+        assert!(prompt.contains("git rebase --continue"));
+        assert!(prompt.contains("- a.rs\n- b.rs"));
+    }
+
+    #[test]
+    fn push_command_uses_lease_only_for_rebase() {
+        assert_eq!(
+            push_command(ConflictStrategy::Merge, "task/x"),
+            "git push origin HEAD:task/x"
+        );
+        assert!(push_command(ConflictStrategy::Rebase, "task/x").contains("--force-with-lease"));
+    }
+
+    #[test]
+    fn default_rules_allow_pushing_own_branch() {
+        let cfg = config::Config::default();
+        assert!(!push_denied(&cfg, "task/maintain-x", &["main"]));
+        assert!(push_denied(&cfg, "main", &["main"]));
+        assert!(push_denied(&cfg, "develop", &["develop", "main"]));
+
+        let mut cfg = config::Config::default();
+        cfg.permissions.deny = vec!["Bash(git push * HEAD:task/*)".into()];
+        assert!(push_denied(&cfg, "task/x", &["main"]));
+    }
+}
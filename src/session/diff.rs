@@ -0,0 +1,540 @@
+//! Diff loading and file-level review actions for a task's changes.
+//!
+//! Backs the TUI diff viewer: parses `git diff` output into files and hunks,
+//! stages or discards files in a live worktree, and turns review comments
+//! into a follow-up prompt. Closed sessions are diffed read-only from the
+//! main repo using their pushed branch.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+
+/// Where a task's changes live.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffSource {
+    /// A live worktree, diffed (uncommitted edits included) against its
+    /// merge base with `origin/<base>`.
+    Worktree { path: String, base: String },
+    /// A pushed branch of a closed session, diffed from the main repo.
+    Branch {
+        repo_path: String,
+        base: String,
+        branch: String,
+    },
+}
+
+impl DiffSource {
+    /// Branch diffs have no worktree to stage into or discard from.
+    pub fn is_read_only(&self) -> bool {
+        matches!(self, Self::Branch { .. })
+    }
+
+    /// Short description for the viewer title, e.g. `worktree vs origin/main`.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Worktree { base, .. } => format!("worktree vs origin/{base}"),
+            Self::Branch { base, branch, .. } => format!("{branch} vs origin/{base}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    Modified,
+    Added,
+    Deleted,
+    Renamed,
+}
+
+impl FileStatus {
+    pub fn symbol(self) -> char {
+        match self {
+            Self::Modified => 'M',
+            Self::Added => 'A',
+            Self::Deleted => 'D',
+            Self::Renamed => 'R',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: LineKind,
+    pub text: String,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// The full `@@ -a,b +c,d @@ context` line.
+    pub header: String,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    pub path: String,
+    /// Previous path for renames.
+    pub old_path: Option<String>,
+    pub status: FileStatus,
+    pub binary: bool,
+    pub hunks: Vec<Hunk>,
+}
+
+/// One displayable row of a file's diff: a hunk header or a diff line.
+#[derive(Debug, Clone, Copy)]
+pub enum DiffRow<'a> {
+    Header(&'a str),
+    Line(&'a DiffLine),
+}
+
+impl FileDiff {
+    fn new(path: String) -> Self {
+        Self {
+            path,
+            old_path: None,
+            status: FileStatus::Modified,
+            binary: false,
+            hunks: Vec::new(),
+        }
+    }
+
+    pub fn added(&self) -> usize {
+        self.count(LineKind::Added)
+    }
+
+    pub fn removed(&self) -> usize {
+        self.count(LineKind::Removed)
+    }
+
+    fn count(&self, kind: LineKind) -> usize {
+        self.hunks
+            .iter()
+            .flat_map(|h| &h.lines)
+            .filter(|l| l.kind == kind)
+            .count()
+    }
+
+    /// Hunk headers and lines flattened in display order.
+    pub fn rows(&self) -> Vec<DiffRow<'_>> {
+        let mut rows = Vec::new();
+        for hunk in &self.hunks {
+            rows.push(DiffRow::Header(&hunk.header));
+            rows.extend(hunk.lines.iter().map(DiffRow::Line));
+        }
+        rows
+    }
+}
+
+/// A reviewer note attached to a file (and optionally a line) in the diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewComment {
+    pub path: String,
+    pub line: Option<u32>,
+    pub text: String,
+}
+
+/// Build the follow-up prompt sent to Claude for a batch of review comments.
+pub fn review_prompt(comments: &[ReviewComment]) -> String {
+    let mut prompt = String::from("Review comments on your changes. Please address each one:\n");
+    for comment in comments {
+        let _ = match comment.line {
+            Some(line) => write!(prompt, "\n- {}:{line}: {}", comment.path, comment.text),
+            None => write!(prompt, "\n- {}: {}", comment.path, comment.text),
+        };
+    }
+    prompt
+}
+
+/// Load the files and hunks that differ for `source`.
+///
+/// Worktree diffs include untracked files (shown as added) since Claude
+/// often creates files without committing them.
+pub fn load_diff(source: &DiffSource) -> Result<Vec<FileDiff>> {
+    match source {
+        DiffSource::Worktree { path, base } => {
+            let dir = Path::new(path);
+            let origin_base = format!("origin/{base}");
+            let out = git(
+                dir,
+                &[
+                    "diff",
+                    "--no-color",
+                    "--no-ext-diff",
+                    "--merge-base",
+                    &origin_base,
+                ],
+            )?;
+            let mut files = parse_unified_diff(&out);
+
+            let untracked = git(dir, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+            for file in untracked.split('\0').filter(|f| !f.is_empty()) {
+                // `--no-index` exits 1 when the files differ, so only the
+                // output matters here.
+                let output = Command::new("git")
+                    .args(["diff", "--no-color", "--no-index", "--", "/dev/null", file])
+                    .current_dir(dir)
+                    .output()
+                    .context("failed to run git diff --no-index")?;
+                files.extend(parse_unified_diff(&String::from_utf8_lossy(&output.stdout)));
+            }
+            Ok(files)
+        }
+        DiffSource::Branch {
+            repo_path,
+            base,
+            branch,
+        } => {
+            let dir = Path::new(repo_path);
+            let remote = format!("origin/{branch}");
+            let head = if git(dir, &["rev-parse", "--verify", "--quiet", &remote]).is_ok() {
+                remote
+            } else if git(dir, &["rev-parse", "--verify", "--quiet", branch]).is_ok() {
+                branch.clone()
+            } else {
+                bail!("branch '{branch}' not found locally or on origin");
+            };
+            let range = format!("origin/{base}...{head}");
+            let out = git(dir, &["diff", "--no-color", "--no-ext-diff", &range])?;
+            Ok(parse_unified_diff(&out))
+        }
+    }
+}
+
+/// Paths currently staged in a worktree's index.
+pub fn staged_paths(worktree: &str) -> Result<HashSet<String>> {
+    let out = git(Path::new(worktree), &["diff", "--cached", "--name-only"])?;
+    Ok(out.lines().map(String::from).collect())
+}
+
+/// Stage a file (including deletions) in the worktree.
+pub fn stage_file(worktree: &str, path: &str) -> Result<()> {
+    git(Path::new(worktree), &["add", "-A", "--", path]).map(|_| ())
+}
+
+/// Throw away a file's changes, restoring it to the merge base with `origin/<base>`.
+///
+/// Added files are deleted; renames restore the old path and drop the new one.
+pub fn discard_file(worktree: &str, base: &str, file: &FileDiff) -> Result<()> {
+    let dir = Path::new(worktree);
+    let merge_base = git(dir, &["merge-base", &format!("origin/{base}"), "HEAD"])?;
+    let merge_base = merge_base.trim();
+
+    if matches!(file.status, FileStatus::Added | FileStatus::Renamed) {
+        git(
+            dir,
+            &["rm", "-q", "--cached", "--ignore-unmatch", "--", &file.path],
+        )?;
+        let on_disk = dir.join(&file.path);
+        if on_disk.exists() {
+            std::fs::remove_file(&on_disk)
+                .with_context(|| format!("failed to remove {}", on_disk.display()))?;
+        }
+    }
+    let restore = match file.status {
+        FileStatus::Added => None,
+        FileStatus::Renamed => file.old_path.as_deref(),
+        FileStatus::Modified | FileStatus::Deleted => Some(file.path.as_str()),
+    };
+    if let Some(path) = restore {
+        git(dir, &["checkout", merge_base, "--", path])?;
+    }
+    Ok(())
+}
+
+/// Parse `git diff` unified output into per-file hunks.
+pub fn parse_unified_diff(text: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    let mut old_no = 0u32;
+    let mut new_no = 0u32;
+    // Lines still expected in the open hunk; `---`/`+++` are file headers
+    // only once both reach zero.
+    let mut old_left = 0u32;
+    let mut new_left = 0u32;
+
+    for line in text.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            // Fallback path from the header; `+++`/`rename to` refine it.
+            let path = rest
+                .rfind(" b/")
+                .map_or(rest, |i| &rest[i + 3..])
+                .to_string();
+            files.push(FileDiff::new(path));
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if let Some(hunk) = file.hunks.last_mut()
+            && (old_left > 0 || new_left > 0)
+        {
+            let parsed = match line.as_bytes().first() {
+                Some(b'+') => Some((LineKind::Added, None, Some(new_no))),
+                Some(b'-') => Some((LineKind::Removed, Some(old_no), None)),
+                Some(b' ') => Some((LineKind::Context, Some(old_no), Some(new_no))),
+                _ => None,
+            };
+            if let Some((kind, old_line, new_line)) = parsed {
+                if old_line.is_some() {
+                    old_no += 1;
+                    old_left = old_left.saturating_sub(1);
+                }
+                if new_line.is_some() {
+                    new_no += 1;
+                    new_left = new_left.saturating_sub(1);
+                }
+                hunk.lines.push(DiffLine {
+                    kind,
+                    text: line[1..].to_string(),
+                    old_line,
+                    new_line,
+                });
+                continue;
+            }
+        }
+
+        if line.starts_with("@@") {
+            let ((old_start, old_count), (new_start, new_count)) = parse_hunk_header(line);
+            old_no = old_start;
+            new_no = new_start;
+            old_left = old_count;
+            new_left = new_count;
+            file.hunks.push(Hunk {
+                header: line.to_string(),
+                lines: Vec::new(),
+            });
+        } else if line.starts_with("new file mode") {
+            file.status = FileStatus::Added;
+        } else if line.starts_with("deleted file mode") {
+            file.status = FileStatus::Deleted;
+        } else if let Some(from) = line.strip_prefix("rename from ") {
+            file.status = FileStatus::Renamed;
+            file.old_path = Some(from.to_string());
+        } else if let Some(to) = line.strip_prefix("rename to ") {
+            file.path = to.to_string();
+        } else if let Some(to) = line.strip_prefix("+++ ") {
+            if let Some(path) = to.strip_prefix("b/") {
+                file.path = path.to_string();
+            }
+        } else if line.starts_with("Binary files ") {
+            file.binary = true;
+        }
+    }
+    files
+}
+
+/// Old and new `(start, count)` ranges from `@@ -a,b +c,d @@`. An omitted
+/// count is 1.
+fn parse_hunk_header(line: &str) -> ((u32, u32), (u32, u32)) {
+    let mut old = (0, 0);
+    let mut new = (0, 0);
+    for part in line.split_whitespace().skip(1).take(2) {
+        let (sign, range) = part.split_at(1);
+        let mut bounds = range.split(',').map(|n| n.parse().unwrap_or(0));
+        let start = bounds.next().unwrap_or(0);
+        let count = bounds.next().unwrap_or(1);
+        match sign {
+            "-" => old = (start, count),
+            "+" => new = (start, count),
+            _ => {}
+        }
+    }
+    (old, new)
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .with_context(|| format!("failed to run git {}", args.join(" ")))?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,4 @@ mod a;
+ use std::fs;
+-fn old() {}
++fn new() {}
++fn extra() {}
+ fn tail() {}
+diff --git a/notes.md b/notes.md
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/notes.md
+@@ -0,0 +1 @@
++hello
+diff --git a/old name.txt b/new name.txt
+similarity index 90%
+rename from old name.txt
+rename to new name.txt
+diff --git a/logo.png b/logo.png
+deleted file mode 100644
+index 4444444..0000000
+Binary files a/logo.png and /dev/null differ
+";
+
+    #[test]
+    fn parses_files_hunks_and_line_numbers() {
+        let files = parse_unified_diff(SAMPLE);
+        assert_eq!(files.len(), 4);
+
+        let lib = &files[0];
+        assert_eq!(lib.path, "src/lib.rs");
+        assert_eq!(lib.status, FileStatus::Modified);
+        assert_eq!((lib.added(), lib.removed()), (2, 1));
+        let lines = &lib.hunks[0].lines;
+        assert_eq!(lines[1].kind, LineKind::Removed);
+        assert_eq!(lines[1].old_line, Some(2));
+        assert_eq!(lines[2].text, "fn new() {}");
+        assert_eq!(lines[2].new_line, Some(2));
+        assert_eq!(lines[4].old_line, Some(3));
+        assert_eq!(lines[4].new_line, Some(4));
+        assert_eq!(lib.rows().len(), 6);
+
+        assert_eq!(files[1].path, "notes.md");
+        assert_eq!(files[1].status, FileStatus::Added);
+
+        assert_eq!(files[2].path, "new name.txt");
+        assert_eq!(files[2].old_path.as_deref(), Some("old name.txt"));
+        assert_eq!(files[2].status, FileStatus::Renamed);
+
+        assert_eq!(files[3].status, FileStatus::Deleted);
+        assert!(files[3].binary);
+    }
+
+    #[test]
+    fn dash_and_plus_content_inside_hunk_is_not_a_header() {
+        let diff = "\
+diff --git a/schema.sql b/schema.sql
+--- a/schema.sql
++++ b/schema.sql
+@@ -1,2 +1,2 @@
+--- old comment
++++ b/x
+ SELECT 1;
+diff --git a/next.sql b/next.sql
+--- a/next.sql
++++ b/next.sql
+@@ -1 +1 @@
+-a
++b
+";
+        let files = parse_unified_diff(diff);
+        assert_eq!(files.len(), 2);
+        let schema = &files[0];
+        assert_eq!(schema.path, "schema.sql");
+        let lines = &schema.hunks[0].lines;
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].kind, LineKind::Removed);
+        assert_eq!(lines[0].text, "-- old comment");
+        assert_eq!(lines[1].kind, LineKind::Added);
+        assert_eq!(lines[1].text, "++ b/x");
+        assert_eq!(lines[2].old_line, Some(2));
+        assert_eq!(lines[2].new_line, Some(2));
+        assert_eq!(files[1].path, "next.sql");
+        assert_eq!((files[1].added(), files[1].removed()), (1, 1));
+    }
+
+    #[test]
+    fn review_prompt_lists_comments() {
+        let prompt = review_prompt(&[
+            ReviewComment {
+                path: "src/lib.rs".into(),
+                line: Some(12),
+                text: "handle the error".into(),
+            },
+            ReviewComment {
+                path: "README.md".into(),
+                line: None,
+                text: "document this".into(),
+            },
+        ]);
+        assert!(prompt.contains("\n- src/lib.rs:12: handle the error"));
+        assert!(prompt.ends_with("\n- README.md: document this"));
+    }
+
+    fn run(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(
+            status.status.success(),
+            "git {args:?}: {}",
+            String::from_utf8_lossy(&status.stderr)
+        );
+    }
+
+    #[test]
+    fn worktree_diff_stage_and_discard() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin.git");
+        let clone = tmp.path().join("clone");
+        run(
+            tmp.path(),
+            &["init", "-q", "--bare", "-b", "main", "origin.git"],
+        );
+        run(
+            tmp.path(),
+            &["clone", "-q", origin.to_str().unwrap(), "clone"],
+        );
+        run(&clone, &["config", "user.email", "t@t"]);
+        run(&clone, &["config", "user.name", "t"]);
+        run(&clone, &["checkout", "-q", "-b", "main"]);
+        std::fs::write(clone.join("a.txt"), "one\n").unwrap();
+        run(&clone, &["add", "."]);
+        run(&clone, &["commit", "-qm", "init"]);
+        run(&clone, &["push", "-q", "origin", "main"]);
+
+        std::fs::write(clone.join("a.txt"), "one\ntwo\n").unwrap();
+        std::fs::write(clone.join("new.txt"), "fresh\n").unwrap();
+
+        let worktree = clone.to_str().unwrap();
+        let source = DiffSource::Worktree {
+            path: worktree.into(),
+            base: "main".into(),
+        };
+        let files = load_diff(&source).unwrap();
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["a.txt", "new.txt"]);
+        assert_eq!(files[1].status, FileStatus::Added);
+
+        stage_file(worktree, "a.txt").unwrap();
+        assert!(staged_paths(worktree).unwrap().contains("a.txt"));
+
+        discard_file(worktree, "main", &files[0]).unwrap();
+        discard_file(worktree, "main", &files[1]).unwrap();
+        assert!(load_diff(&source).unwrap().is_empty());
+        assert!(!clone.join("new.txt").exists());
+    }
+}
@@ -0,0 +1,196 @@
+//! Best-of-N fan-out: run one prompt as several variants side by side,
+//! compare them, then publish the winner's branch and tear down the rest.
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+
+use crate::github;
+use crate::store::{ClaudeStatus, PushMode, Store, Task, VariantTestStatus};
+
+/// Replaces the completion instructions for fan-out variants: only the
+/// promoted variant is pushed, and claustre does that itself.
+pub const VARIANT_INSTRUCTIONS: &str = "\n\nWhen you finish your task, commit all changes \
+    with a descriptive commit message.\n\n\
+    IMPORTANT: Do NOT push the branch or open a pull request. This is one of several \
+    parallel attempts at the same task; claustre publishes the one that is chosen.";
+
+/// How much test output to keep for the compare view.
+const TEST_OUTPUT_MAX_BYTES: usize = 4_000;
+
+/// Run `command` with `sh -c` in the variant's worktree and record the result.
+pub fn run_variant_tests(store: &Store, task_id: &str, command: &str) -> Result<VariantTestStatus> {
+    let worktree = variant_worktree(store, task_id)?;
+    store.set_variant_test_result(task_id, VariantTestStatus::Running, None)?;
+    let output = match Command::new("sh")
+        .args(["-c", command])
+        .current_dir(&worktree)
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => {
+            let message = format!("failed to run test command: {e}");
+            store.set_variant_test_result(task_id, VariantTestStatus::Failed, Some(&message))?;
+            return Ok(VariantTestStatus::Failed);
+        }
+    };
+    let status = if output.status.success() {
+        VariantTestStatus::Passed
+    } else {
+        VariantTestStatus::Failed
+    };
+    let mut log = String::from_utf8_lossy(&output.stdout).into_owned();
+    log.push_str(&String::from_utf8_lossy(&output.stderr));
+    let tail = github::tail_log(&log, TEST_OUTPUT_MAX_BYTES).trim();
+    store.set_variant_test_result(task_id, status, Some(tail))?;
+    Ok(status)
+}
+
+/// Outcome of `promote_variant`.
+pub struct Promotion {
+    /// The parent task, now owning the winner's session.
+    pub parent: Task,
+    /// Sessions of the other variants, already torn down; the TUI closes their tabs.
+    pub closed_sessions: Vec<String>,
+}
+
+/// Publish the winning variant and hand it to the parent task: uncommitted
+/// work is committed, the branch is pushed, a PR is opened for PR-mode
+/// tasks, and every other variant's session is torn down.
+pub fn promote_variant(store: &Store, winner_task_id: &str) -> Result<Promotion> {
+    let variant = store
+        .get_task_variant(winner_task_id)?
+        .with_context(|| format!("task '{winner_task_id}' is not a fan-out variant"))?;
+    let parent = store.get_task(&variant.parent_task_id)?;
+    let project = store.get_project(&parent.project_id)?;
+    let winner = store.get_task(winner_task_id)?;
+    let session_id = winner
+        .session_id
+        .context("the variant has no session to promote")?;
+    let worktree = variant_worktree(store, winner_task_id)?;
+
+    let losers: Vec<String> = store
+        .list_task_variants(&parent.id)?
+        .into_iter()
+        .filter(|v| v.task_id != winner_task_id)
+        .filter_map(|v| store.get_task(&v.task_id).ok()?.session_id)
+        .collect();
+
+    if !git(&worktree, &["status", "--porcelain"])?
+        .trim()
+        .is_empty()
+    {
+        git(&worktree, &["add", "-A"])?;
+        git(&worktree, &["commit", "-q", "-m", &parent.title])?;
+    }
+    git(&worktree, &["push", "-u", "origin", "HEAD"])?;
+
+    let pr_url = match parent.push_mode {
+        PushMode::Pr => {
+            let base = parent.base.as_deref().unwrap_or(&project.default_branch);
+            Some(github::create_pr(
+                &worktree,
+                base,
+                &parent.title,
+                &parent.description,
+            )?)
+        }
+        PushMode::Push => None,
+    };
+
+    let parent = store.promote_variant(winner_task_id, pr_url.as_deref())?;
+    store.update_session_status(&session_id, ClaudeStatus::Done, "")?;
+    for session_id in &losers {
+        super::teardown_session(store, session_id)?;
+    }
+    Ok(Promotion {
+        parent,
+        closed_sessions: losers,
+    })
+}
+
+/// Tear down a variant's session and delete its task.
+pub fn discard_variant(store: &Store, task_id: &str) -> Result<()> {
+    if let Some(session_id) = store.get_task(task_id)?.session_id {
+        super::teardown_session(store, &session_id)?;
+    }
+    store.discard_variant(task_id)
+}
+
+fn variant_worktree(store: &Store, task_id: &str) -> Result<std::path::PathBuf> {
+    let session_id = store
+        .get_task(task_id)?
+        .session_id
+        .context("the variant has not been launched")?;
+    let session = store.get_session(&session_id)?;
+    if session.closed_at.is_some() || !Path::new(&session.worktree_path).exists() {
+        bail!("the variant's worktree is gone");
+    }
+    Ok(session.worktree_path.into())
+}
+
+fn git(worktree: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(worktree)
+        .args(args)
+        .output()
+        .with_context(|| format!("failed to run git {}", args.join(" ")))?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{PushMode, TaskMode};
+
+    #[test]
+    fn variant_tests_record_status_and_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open_in_memory().unwrap();
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        let parent = store
+            .create_task(
+                &project.id,
+                "task",
+                "desc",
+                TaskMode::Supervised,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap();
+        let children = store
+            .fan_out_task(&parent.id, &[(None, None), (None, Some("low"))])
+            .unwrap();
+        let child = &children[0].id;
+        assert!(run_variant_tests(&store, child, "true").is_err());
+
+        let session = store
+            .create_session(
+                &project.id,
+                "task/a",
+                dir.path().to_str().unwrap(),
+                "p:task/a",
+            )
+            .unwrap();
+        store.assign_task_to_session(child, &session.id).unwrap();
+
+        let status = run_variant_tests(&store, child, "echo 3 passed").unwrap();
+        assert_eq!(status, VariantTestStatus::Passed);
+        let status = run_variant_tests(&store, child, "echo boom >&2; exit 1").unwrap();
+        assert_eq!(status, VariantTestStatus::Failed);
+        let variant = store.get_task_variant(child).unwrap().unwrap();
+        assert_eq!(variant.test_status, Some(VariantTestStatus::Failed));
+        assert_eq!(variant.test_output.as_deref(), Some("boom"));
+    }
+}
@@ -3,6 +3,13 @@
 //! Creates worktrees, writes merged config and hooks, and cleans up on
 //! session completion.
 
+pub mod conflict;
+pub mod diff;
+pub mod fanout;
+pub mod retry;
+pub mod steps;
+pub mod verify;
+
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -172,7 +179,13 @@ pub fn create_session(
 
     // 5. Write session ID file and hooks
     fs::write(worktree_path.join(".claustre_session_id"), &session.id)?;
-    write_hooks(&worktree_path, &session.id)?;
+    let verify_commands = store.project_verify_commands(project_id)?;
+    let stop_timeout_secs = if verify_commands.is_empty() {
+        30
+    } else {
+        verify::VERIFY_HOOK_TIMEOUT_SECS
+    };
+    write_hooks(&worktree_path, &session.id, stop_timeout_secs)?;
 
     // 6. Hide claustre-managed files from git status
     configure_git_excludes(&worktree_path);
@@ -183,6 +196,11 @@ pub fn create_session(
     // 8. Build the Claude command (spawned by the TUI as a local PTY)
     let mut claude_cmd = None;
     if let Some(task) = task {
+        let variant = store.get_task_variant(&task.id)?;
+        let claude_config = &match &variant {
+            Some(v) => claude_config.with_overrides(v.model.as_deref(), v.effort.as_deref()),
+            None => claude_config.clone(),
+        };
         store.assign_task_to_session(&task.id, &session.id)?;
         store.update_task_status(&task.id, TaskStatus::Working)?;
 
@@ -221,13 +239,23 @@ pub fn create_session(
                 cmd
             }
             TaskMode::Supervised => {
-                // Supervised: launch Claude directly with the prompt
-                let instructions = completion_instructions(effective_base, task.push_mode);
-                let prompt = if let Some(subtask) = store.next_pending_subtask(&task.id)? {
-                    store.update_subtask_status(&subtask.id, TaskStatus::Working)?;
-                    format!("{}{instructions}", subtask.description)
+                // Supervised: launch Claude directly with the prompt. Fan-out
+                // variants commit only; the promoted one is pushed later.
+                let instructions = if variant.is_some() {
+                    fanout::VARIANT_INSTRUCTIONS.to_string()
                 } else {
-                    format!("{}{instructions}", task.description)
+                    format!(
+                        "{}{}",
+                        verify::prompt_section(&verify_commands),
+                        completion_instructions(effective_base, task.push_mode)
+                    )
+                };
+                let previous = retry::prompt_section(store, &task.id);
+                // Subtasks run one per turn; the completion instructions
+                // follow the last step.
+                let prompt = match steps::start(store, task)? {
+                    Some(step) => format!("{step}{previous}"),
+                    None => format!("{}{previous}{instructions}", task.description),
                 };
                 let mut cmd = vec!["claude".to_string()];
                 if remote_enabled {
@@ -412,7 +440,7 @@ fn create_worktree(
     Ok(worktree_path)
 }
 
-fn remove_worktree(repo_path: &Path, worktree_path: &Path) -> Result<()> {
+pub(crate) fn remove_worktree(repo_path: &Path, worktree_path: &Path) -> Result<()> {
     let repo_str = repo_path
         .to_str()
         .context("repo path contains invalid UTF-8")?;
@@ -511,8 +539,9 @@ fn copy_dir_contents(src: &Path, dst: &Path) -> Result<()> {
 ///   and token usage to claustre. Fires each time Claude marks a task completed.
 /// - **`Stop`**: final validation + PR detection. Ensures progress and usage are
 ///   up to date after the full turn, and transitions the task to `in_review`
-///   when a PR is detected.
-fn write_hooks(worktree_path: &Path, session_id: &str) -> Result<()> {
+///   when a PR is detected (after the verification gate, which is why its
+///   timeout is `stop_timeout_secs`).
+fn write_hooks(worktree_path: &Path, session_id: &str, stop_timeout_secs: u64) -> Result<()> {
     let hooks_dir = worktree_path.join(".claude").join("hooks");
     fs::create_dir_all(&hooks_dir)?;
 
@@ -622,6 +651,10 @@ if [ -z "$SESSION_ID" ]; then
     exit 0
 fi
 
+# The hook input names the transcript of the turn that just ended; claustre
+# reads Claude's last reply from it to advance subtask steps.
+TRANSCRIPT=$(jq -r '.transcript_path // empty' 2>/dev/null)
+
 sync_progress
 extract_usage
 
@@ -630,6 +663,10 @@ CSID_ARGS=""
 if [ -n "$CLAUDE_SID" ]; then
     CSID_ARGS="--claude-session-id $CLAUDE_SID"
 fi
+TRANSCRIPT_ARGS=()
+if [ -n "$TRANSCRIPT" ]; then
+    TRANSCRIPT_ARGS=(--transcript "$TRANSCRIPT")
+fi
 
 # Check for open PR on current branch only (no fallback to other branches —
 # gh pr list would pick up PRs from unrelated sessions and cause cross-session spam)
@@ -637,10 +674,10 @@ PR_URL=$(cd "$WORKTREE_ROOT" && gh pr view --json url --jq '.url' 2>/dev/null)
 
 if [ -n "$PR_URL" ]; then
     echo "$(date -u +%FT%TZ) stop sid=$SESSION_ID pr=$PR_URL usage='$USAGE_ARGS' csid=$CLAUDE_SID" >> "$LOG"
-    claustre session-update --session-id "$SESSION_ID" --pr-url "$PR_URL" $USAGE_ARGS $CSID_ARGS 2>> "$LOG"
+    claustre session-update --session-id "$SESSION_ID" --pr-url "$PR_URL" $USAGE_ARGS $CSID_ARGS "${TRANSCRIPT_ARGS[@]}" 2>> "$LOG"
 else
     echo "$(date -u +%FT%TZ) stop sid=$SESSION_ID no-pr usage='$USAGE_ARGS' csid=$CLAUDE_SID" >> "$LOG"
-    claustre session-update --session-id "$SESSION_ID" $USAGE_ARGS $CSID_ARGS 2>> "$LOG"
+    claustre session-update --session-id "$SESSION_ID" $USAGE_ARGS $CSID_ARGS "${TRANSCRIPT_ARGS[@]}" 2>> "$LOG"
 fi
 echo "$(date -u +%FT%TZ) stop sid=$SESSION_ID exit=$?" >> "$LOG"
 exit 0
@@ -756,7 +793,7 @@ exit 0
                 "hooks": [{
                     "type": "command",
                     "command": stop_cmd,
-                    "timeout": 30
+                    "timeout": stop_timeout_secs
                 }]
             }],
             "Notification": [{
@@ -0,0 +1,202 @@
+//! Task retries: close out a failed run as an attempt and carry a summary of
+//! why it failed (CI logs, PR review feedback, a typed note) into the next
+//! attempt's prompt.
+
+use std::fmt::Write;
+
+use anyhow::Result;
+use tracing::warn;
+
+use crate::github::{self, PrFeedback};
+use crate::store::{CiStatus, Store, TaskAttempt, TaskStatus, TaskVerification};
+
+/// How much of the failed CI log to carry into the retry prompt.
+const RETRY_MAX_LOG_BYTES: usize = 8_000;
+
+/// Record the task's current run as an attempt and reset it to `pending`.
+///
+/// With `include_failure`, the failing CI logs and PR review feedback are
+/// fetched with `gh` (best effort) and stored with `note` as the attempt's
+/// failure context. Does not tear down the session; callers do that.
+pub fn retry_task(
+    store: &Store,
+    task_id: &str,
+    note: Option<&str>,
+    include_failure: bool,
+) -> Result<TaskAttempt> {
+    let task = store.get_task(task_id)?;
+    let session = task
+        .session_id
+        .as_deref()
+        .and_then(|id| store.get_session(id).ok());
+    let branch = session
+        .as_ref()
+        .map(|s| s.branch_name.clone())
+        .or_else(|| task.branch.clone());
+
+    let mut ci_log = None;
+    let mut feedback = Vec::new();
+    let mut error_message = None;
+    let mut verification = None;
+    if include_failure {
+        let project = store.get_project(&task.project_id)?;
+        let ci_failed =
+            task.status == TaskStatus::CiFailed || task.ci_status == Some(CiStatus::Failed);
+        if ci_failed && let Some(branch) = branch.as_deref() {
+            match github::latest_failed_run(&project.repo_path, branch).and_then(|run| {
+                run.map(|id| github::failed_run_logs(&project.repo_path, &id))
+                    .transpose()
+            }) {
+                Ok(log) => ci_log = log,
+                Err(e) => warn!(task_id, "retry: could not fetch CI logs: {e:#}"),
+            }
+        }
+        if let Some(pr_url) = task.pr_url.as_deref() {
+            match github::pr_feedback(pr_url) {
+                Ok(items) => feedback = items,
+                Err(e) => warn!(task_id, "retry: could not fetch PR feedback: {e:#}"),
+            }
+        }
+        if task.status == TaskStatus::Error {
+            error_message = session
+                .as_ref()
+                .map(|s| s.status_message.clone())
+                .filter(|m| !m.trim().is_empty());
+        }
+        verification = store
+            .latest_task_verification(task_id)?
+            .filter(|v| !v.passed && v.session_id == task.session_id);
+    }
+
+    let context = failure_context(
+        note,
+        error_message.as_deref(),
+        verification.as_ref(),
+        ci_log.as_deref(),
+        &feedback,
+    );
+    store.retry_task(task_id, branch.as_deref(), context.as_deref())
+}
+
+/// Assemble the stored failure summary; `None` when there is nothing to say.
+fn failure_context(
+    note: Option<&str>,
+    error_message: Option<&str>,
+    verification: Option<&TaskVerification>,
+    ci_log: Option<&str>,
+    feedback: &[PrFeedback],
+) -> Option<String> {
+    let mut out = String::new();
+    if let Some(note) = note.map(str::trim).filter(|n| !n.is_empty()) {
+        let _ = writeln!(out, "Note from the user:\n{note}\n");
+    }
+    if let Some(message) = error_message {
+        let _ = writeln!(out, "The session ended in error: {message}\n");
+    }
+    if let Some(verification) = verification
+        && let Some(command) = verification.failed_command.as_deref()
+    {
+        let _ = writeln!(
+            out,
+            "Verification command `{command}` failed:\n```\n{}\n```\n",
+            verification.output.as_deref().unwrap_or_default()
+        );
+    }
+    if !feedback.is_empty() {
+        out.push_str("Review feedback on the PR:\n");
+        for item in feedback {
+            let state = item
+                .state
+                .as_deref()
+                .map(|s| format!(" ({})", s.to_lowercase().replace('_', " ")))
+                .unwrap_or_default();
+            let _ = writeln!(out, "- @{}{state}: {}", item.author, item.body);
+        }
+        out.push('\n');
+    }
+    if let Some(log) = ci_log {
+        let _ = writeln!(
+            out,
+            "Failing CI output (tail):\n```\n{}\n```",
+            github::tail_log(log, RETRY_MAX_LOG_BYTES).trim_end()
+        );
+    }
+    let out = out.trim_end();
+    (!out.is_empty()).then(|| out.to_string())
+}
+
+/// The section appended to a task prompt after a retry, or an empty string
+/// if the task has no attempts or the last one carried no context.
+pub fn prompt_section(store: &Store, task_id: &str) -> String {
+    match store.latest_task_attempt(task_id) {
+        Ok(Some(attempt)) => attempt_section(&attempt),
+        Ok(None) => String::new(),
+        Err(e) => {
+            warn!(task_id, "retry: could not load attempts: {e:#}");
+            String::new()
+        }
+    }
+}
+
+fn attempt_section(attempt: &TaskAttempt) -> String {
+    let Some(context) = attempt.failure_context.as_deref() else {
+        return String::new();
+    };
+    format!(
+        "\n\n## Previous attempt\n\nThis is a retry. Attempt {} ended as {}. \
+         Start fresh from the base branch and avoid repeating what went wrong:\n\n{context}",
+        attempt.attempt, attempt.outcome
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::AttemptOutcome;
+
+    #[test]
+    fn failure_context_combines_sources() {
+        let feedback = [PrFeedback {
+            author: "alice".into(),
+            state: Some("CHANGES_REQUESTED".into()),
+            body: "Needs tests".into(),
+        }];
+        let context = failure_context(
+            Some("  use the existing cache  "),
+            None,
+            None,
+            Some("line 1\nerror: assertion failed\n"),
+            &feedback,
+        )
+        .unwrap();
+        assert!(context.starts_with("Note from the user:\nuse the existing cache"));
+        assert!(context.contains("- @alice (changes requested): Needs tests"));
+        assert!(context.contains("error: assertion failed\n```"));
+
+        assert_eq!(failure_context(Some("   "), None, None, None, &[]), None);
+    }
+
+    #[test]
+    fn prompt_section_only_when_context_recorded() {
+        let mut attempt = TaskAttempt {
+            id: "a".into(),
+            task_id: "t".into(),
+            attempt: 2,
+            session_id: None,
+            branch: None,
+            pr_url: None,
+            input_tokens: 0,
+            output_tokens: 0,
+            started_at: None,
+            finished_at: "2026-01-01T00:00:00+00:00".into(),
+            outcome: AttemptOutcome::Rejected,
+            failure_context: None,
+        };
+        assert_eq!(attempt_section(&attempt), "");
+        attempt.failure_context = Some("Needs tests".into());
+        let section = attempt_section(&attempt);
+        assert!(section.starts_with("\n\n## Previous attempt"));
+        assert!(section.contains("Attempt 2 ended as rejected"));
+        assert!(section.ends_with("Needs tests"));
+    }
+}
@@ -0,0 +1,350 @@
+//! Step-by-step execution of a task's subtasks.
+//!
+//! A task with subtasks runs one subtask per turn. The launch prompt carries
+//! the first step; each time the Stop hook fires, `advance` reads how Claude
+//! ended its turn. A completed step hands Claude the next one through the
+//! Stop hook's `block` decision, and after the last step Claude gets the
+//! task's completion instructions. A failed step pauses the chain until the
+//! user steps in; a later `STEP COMPLETE` picks it up again.
+
+use std::fmt::Write;
+
+use anyhow::Result;
+
+use crate::store::{Store, Subtask, Task, TaskStatus};
+
+use super::{completion_instructions, fanout, verify};
+
+/// Line Claude ends its reply with once the current step is done.
+pub const STEP_DONE_MARKER: &str = "STEP COMPLETE";
+
+/// Prefix of the line Claude ends its reply with when it cannot finish a step.
+pub const STEP_FAILED_MARKER: &str = "STEP FAILED";
+
+/// What the Stop hook found about the session's current step.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Step {
+    /// No active task with a step in progress.
+    Inactive,
+    /// Claude ended its turn without reporting on the step (e.g. it asked a
+    /// question); the chain waits.
+    Waiting,
+    /// The step is done and the next one has started.
+    Next { prompt: String },
+    /// The last step is done. `prompt` holds the completion instructions, or
+    /// is `None` when Claude already opened the PR.
+    Finished { prompt: Option<String> },
+    /// The step failed; the chain waits for the user.
+    Paused { message: String },
+}
+
+impl Step {
+    /// Whether a PR reported alongside this step must wait: the chain is
+    /// still running or paused, so the task is not finished.
+    pub fn holds_pr(&self) -> bool {
+        matches!(self, Self::Next { .. } | Self::Paused { .. })
+    }
+
+    /// JSON for the Stop hook's stdout that sends Claude its next
+    /// instruction; `None` when the turn should end normally.
+    pub fn hook_output(&self) -> Option<String> {
+        match self {
+            Self::Next { prompt }
+            | Self::Finished {
+                prompt: Some(prompt),
+            } => Some(serde_json::json!({ "decision": "block", "reason": prompt }).to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// How Claude reported on a step.
+#[derive(Debug, PartialEq, Eq)]
+enum Report {
+    Done,
+    Failed(String),
+}
+
+/// Start the task's current step and build the launch prompt for it: the
+/// task as context, then the step. `None` when no step is left.
+pub fn start(store: &Store, task: &Task) -> Result<Option<String>> {
+    let Some(current) = store.current_subtask(&task.id)? else {
+        return Ok(None);
+    };
+    store.update_subtask_status(&current.id, TaskStatus::Working)?;
+    let subtasks = store.list_subtasks_for_task(&task.id)?;
+    Ok(Some(format!(
+        "# {}\n\n{}\n\n{}",
+        task.title,
+        task.description,
+        step_prompt(&subtasks, &current)
+    )))
+}
+
+/// Record Claude's report on the current step of the session's task and
+/// decide what happens next. `pr_url` is the PR the Stop hook found, if any.
+pub fn advance(
+    store: &Store,
+    session_id: &str,
+    last_message: Option<&str>,
+    pr_url: Option<&str>,
+) -> Result<Step> {
+    let Some(task) = store
+        .working_task_for_session(session_id)?
+        .or(store.interrupted_task_for_session(session_id)?)
+    else {
+        return Ok(Step::Inactive);
+    };
+    let Some(current) = store.current_subtask(&task.id)? else {
+        return Ok(Step::Inactive);
+    };
+    if current.status == TaskStatus::Pending {
+        // Added after the chain finished, or the task predates it.
+        return Ok(Step::Inactive);
+    }
+    let Some(report) = last_message.and_then(parse_report) else {
+        return Ok(Step::Waiting);
+    };
+
+    let subtasks = store.list_subtasks_for_task(&task.id)?;
+    match report {
+        Report::Failed(reason) => {
+            store.update_subtask_status(&current.id, TaskStatus::Error)?;
+            Ok(Step::Paused {
+                message: format!("Step {} failed: {reason}", step_number(&subtasks, &current)),
+            })
+        }
+        Report::Done => {
+            store.update_subtask_status(&current.id, TaskStatus::Done)?;
+            if let Some(next) = store.current_subtask(&task.id)? {
+                store.update_subtask_status(&next.id, TaskStatus::Working)?;
+                return Ok(Step::Next {
+                    prompt: step_prompt(&subtasks, &next),
+                });
+            }
+            let prompt = match pr_url {
+                Some(_) => None,
+                None => Some(finish_prompt(store, &task, subtasks.len())?),
+            };
+            Ok(Step::Finished { prompt })
+        }
+    }
+}
+
+/// The report on the last non-empty line of Claude's reply, ignoring
+/// Markdown emphasis and code ticks around it.
+fn parse_report(message: &str) -> Option<Report> {
+    let line = message
+        .lines()
+        .rev()
+        .map(|line| line.trim().trim_matches(['*', '`', '_']).trim())
+        .find(|line| !line.is_empty())?;
+    if line.starts_with(STEP_DONE_MARKER) {
+        return Some(Report::Done);
+    }
+    let reason = line
+        .strip_prefix(STEP_FAILED_MARKER)?
+        .trim_start_matches(':')
+        .trim();
+    Some(Report::Failed(if reason.is_empty() {
+        "no reason given".to_string()
+    } else {
+        reason.to_string()
+    }))
+}
+
+fn step_number(subtasks: &[Subtask], step: &Subtask) -> usize {
+    subtasks
+        .iter()
+        .position(|s| s.id == step.id)
+        .map_or(subtasks.len(), |i| i + 1)
+}
+
+fn step_prompt(subtasks: &[Subtask], step: &Subtask) -> String {
+    let mut prompt = format!(
+        "## Step {} of {}: {}",
+        step_number(subtasks, step),
+        subtasks.len(),
+        step.title
+    );
+    // Template subtasks repeat the title as their description.
+    if !step.description.is_empty() && step.description != step.title {
+        prompt.push_str("\n\n");
+        prompt.push_str(&step.description);
+    }
+    let _ = write!(
+        prompt,
+        "\n\nWork on this step only. The remaining steps follow one at a time, so do \
+         not push or open a pull request yet. When the step is done, end your reply \
+         with a line reading `{STEP_DONE_MARKER}`. If you cannot finish it, end your \
+         reply with `{STEP_FAILED_MARKER}: <reason>` instead."
+    );
+    prompt
+}
+
+/// Completion instructions sent once every step is done.
+fn finish_prompt(store: &Store, task: &Task, steps: usize) -> Result<String> {
+    let instructions = if store.get_task_variant(&task.id)?.is_some() {
+        fanout::VARIANT_INSTRUCTIONS.to_string()
+    } else {
+        let project = store.get_project(&task.project_id)?;
+        let base = task
+            .base
+            .as_deref()
+            .filter(|b| !b.is_empty())
+            .unwrap_or(&project.default_branch);
+        format!(
+            "{}{}",
+            verify::prompt_section(&store.project_verify_commands(&project.id)?),
+            completion_instructions(base, task.push_mode)
+        )
+    };
+    Ok(format!("All {steps} steps are complete.{instructions}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{PushMode, TaskMode};
+
+    fn setup(store: &Store) -> (String, Task, Vec<Subtask>) {
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        let session = store
+            .create_session(&project.id, "feat", "/tmp/wt", "tab")
+            .unwrap();
+        let task = store
+            .create_task(
+                &project.id,
+                "Refactor",
+                "Split the parser",
+                TaskMode::Supervised,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap();
+        store.assign_task_to_session(&task.id, &session.id).unwrap();
+        store
+            .update_task_status(&task.id, TaskStatus::Working)
+            .unwrap();
+        let subtasks = vec![
+            store
+                .create_subtask(&task.id, "Extract lexer", "Move the lexer out")
+                .unwrap(),
+            store
+                .create_subtask(&task.id, "Add tests", "Add tests")
+                .unwrap(),
+        ];
+        (session.id, task, subtasks)
+    }
+
+    #[test]
+    fn parse_report_reads_last_line() {
+        assert_eq!(
+            parse_report("Moved it.\n\n**STEP COMPLETE**\n"),
+            Some(Report::Done)
+        );
+        assert_eq!(
+            parse_report("Tried.\n`STEP FAILED: tests need a database`"),
+            Some(Report::Failed("tests need a database".to_string()))
+        );
+        assert_eq!(
+            parse_report("STEP FAILED"),
+            Some(Report::Failed("no reason given".to_string()))
+        );
+        assert_eq!(parse_report("STEP COMPLETE\nShould I continue?"), None);
+    }
+
+    #[test]
+    fn steps_advance_one_turn_at_a_time() {
+        let store = Store::open_in_memory().unwrap();
+        let (session_id, task, subtasks) = setup(&store);
+
+        let prompt = start(&store, &task).unwrap().unwrap();
+        assert!(prompt.contains("Split the parser"));
+        assert!(prompt.contains("Step 1 of 2: Extract lexer"));
+        assert!(!prompt.contains("Add tests"));
+        let first = store.get_subtask(&subtasks[0].id).unwrap();
+        assert_eq!(first.status, TaskStatus::Working);
+        assert!(first.started_at.is_some());
+
+        // No report: the chain waits.
+        assert_eq!(
+            advance(&store, &session_id, Some("Which lexer?"), None).unwrap(),
+            Step::Waiting
+        );
+
+        let Step::Next { prompt } =
+            advance(&store, &session_id, Some("STEP COMPLETE"), None).unwrap()
+        else {
+            panic!("expected the next step");
+        };
+        assert!(prompt.starts_with("## Step 2 of 2: Add tests\n\nWork on this step"));
+        let first = store.get_subtask(&subtasks[0].id).unwrap();
+        assert_eq!(first.status, TaskStatus::Done);
+        assert!(first.completed_at.is_some());
+        assert_eq!(
+            store.get_subtask(&subtasks[1].id).unwrap().status,
+            TaskStatus::Working
+        );
+
+        let step = advance(&store, &session_id, Some("STEP COMPLETE"), None).unwrap();
+        let Step::Finished {
+            prompt: Some(ref prompt),
+        } = step
+        else {
+            panic!("expected the chain to finish");
+        };
+        assert!(prompt.contains("gh pr create"));
+        assert!(step.hook_output().unwrap().contains("\"block\""));
+        assert_eq!(
+            advance(&store, &session_id, Some("STEP COMPLETE"), None).unwrap(),
+            Step::Inactive
+        );
+    }
+
+    #[test]
+    fn failed_step_pauses_until_reported_done() {
+        let store = Store::open_in_memory().unwrap();
+        let (session_id, task, subtasks) = setup(&store);
+        start(&store, &task).unwrap();
+
+        let step = advance(
+            &store,
+            &session_id,
+            Some("STEP FAILED: lexer is generated code"),
+            Some("https://github.com/o/r/pull/1"),
+        )
+        .unwrap();
+        assert_eq!(
+            step,
+            Step::Paused {
+                message: "Step 1 failed: lexer is generated code".to_string()
+            }
+        );
+        assert!(step.holds_pr());
+        assert!(step.hook_output().is_none());
+        assert_eq!(
+            store.get_subtask(&subtasks[0].id).unwrap().status,
+            TaskStatus::Error
+        );
+
+        // After the user helps, a completion report resumes the chain.
+        assert!(matches!(
+            advance(&store, &session_id, Some("Fixed.\nSTEP COMPLETE"), None).unwrap(),
+            Step::Next { .. }
+        ));
+        // Finishing with a PR already open needs no further instructions.
+        assert_eq!(
+            advance(
+                &store,
+                &session_id,
+                Some("STEP COMPLETE"),
+                Some("https://github.com/o/r/pull/1")
+            )
+            .unwrap(),
+            Step::Finished { prompt: None }
+        );
+    }
+}
@@ -0,0 +1,295 @@
+//! Verification gate: run a project's verification commands in the session
+//! worktree before a detected PR moves the task to `in_review`.
+//!
+//! Called from `claustre session-update` when the Stop hook reports a new PR.
+//! A failure is fed back to Claude through the Stop hook's `block` decision
+//! until `[verify] max_attempts` is reached; then the task moves to `error`.
+
+use std::fmt::Write;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+
+use crate::github;
+use crate::store::{ClaudeStatus, Store, TaskStatus};
+
+/// How much of a failing command's output to keep and send back to Claude.
+const VERIFY_MAX_OUTPUT_BYTES: usize = 6_000;
+
+/// Stop hook timeout for sessions whose project has verification commands;
+/// test suites can take far longer than the default 30 seconds.
+pub const VERIFY_HOOK_TIMEOUT_SECS: u64 = 900;
+
+/// What the gate decided about a reported PR.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// No verification applies (no commands, no active task, or a PR that
+    /// was already accepted); proceed as usual.
+    NotRequired,
+    /// Every command passed; accept the PR.
+    Passed,
+    /// A command failed; keep Claude working with this feedback.
+    Retry { reason: String },
+    /// A command failed and attempts are exhausted; the caller moves the
+    /// task to `error` with `fail_task` once the hook update is applied.
+    Failed { task_id: String, message: String },
+}
+
+impl Verdict {
+    /// Whether the PR transition may go ahead.
+    pub fn accepts_pr(&self) -> bool {
+        matches!(self, Self::NotRequired | Self::Passed)
+    }
+
+    /// JSON for the Stop hook's stdout that keeps Claude working with the
+    /// failure as its next instruction; `None` unless this is a retry.
+    pub fn hook_output(&self) -> Option<String> {
+        match self {
+            Self::Retry { reason } => {
+                Some(serde_json::json!({ "decision": "block", "reason": reason }).to_string())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A failed command and the tail of its output.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CommandFailure {
+    pub command: String,
+    pub output: String,
+}
+
+/// Run `commands` in order with `sh -c` in `dir`, stopping at the first failure.
+pub fn run_commands(dir: &Path, commands: &[String]) -> Option<CommandFailure> {
+    commands.iter().find_map(|command| {
+        let output = match Command::new("sh")
+            .args(["-c", command])
+            .current_dir(dir)
+            .output()
+        {
+            Ok(output) if output.status.success() => return None,
+            Ok(output) => {
+                let mut log = String::from_utf8_lossy(&output.stdout).into_owned();
+                log.push_str(&String::from_utf8_lossy(&output.stderr));
+                let _ = write!(log, "\n[{}]", output.status);
+                log
+            }
+            Err(e) => format!("failed to run command: {e}"),
+        };
+        Some(CommandFailure {
+            command: command.clone(),
+            output: github::tail_log(&output, VERIFY_MAX_OUTPUT_BYTES)
+                .trim()
+                .to_string(),
+        })
+    })
+}
+
+/// Verify the session's active task before accepting `pr_url`, recording
+/// the result.
+pub fn gate(store: &Store, session_id: &str, pr_url: &str, max_attempts: u32) -> Result<Verdict> {
+    let Some(task) = store
+        .working_task_for_session(session_id)?
+        .or(store.interrupted_task_for_session(session_id)?)
+    else {
+        return Ok(Verdict::NotRequired);
+    };
+    if task.pr_url.as_deref() == Some(pr_url) {
+        return Ok(Verdict::NotRequired);
+    }
+    let commands = store.project_verify_commands(&task.project_id)?;
+    if commands.is_empty() {
+        return Ok(Verdict::NotRequired);
+    }
+    let session = store.get_session(session_id)?;
+
+    let Some(failure) = run_commands(Path::new(&session.worktree_path), &commands) else {
+        store.record_task_verification(&task.id, Some(session_id), None, None)?;
+        return Ok(Verdict::Passed);
+    };
+    store.record_task_verification(
+        &task.id,
+        Some(session_id),
+        Some(&failure.command),
+        Some(&failure.output),
+    )?;
+    let failures = store.consecutive_verification_failures(&task.id, session_id)?;
+    if failures < max_attempts {
+        return Ok(Verdict::Retry {
+            reason: retry_reason(&failure, failures, max_attempts),
+        });
+    }
+
+    Ok(Verdict::Failed {
+        task_id: task.id,
+        message: format!("Verification failed: {}", failure.command),
+    })
+}
+
+/// Move a task that exhausted its verification attempts to `error`, with
+/// the PR attached so a retry (`R`) can pick up its review feedback.
+pub fn fail_task(
+    store: &Store,
+    session_id: &str,
+    task_id: &str,
+    pr_url: &str,
+    message: &str,
+) -> Result<()> {
+    let task = store.get_task(task_id)?;
+    store.update_task_pr_url(task_id, pr_url)?;
+    if task.status == TaskStatus::Interrupted {
+        store.update_task_status(task_id, TaskStatus::Working)?;
+    }
+    store.update_task_status(task_id, TaskStatus::Error)?;
+    store.update_session_status(session_id, ClaudeStatus::Error, message)?;
+    Ok(())
+}
+
+/// Prompt section listing the verification commands Claude should run
+/// before pushing; empty when the project has none.
+pub fn prompt_section(commands: &[String]) -> String {
+    if commands.is_empty() {
+        return String::new();
+    }
+    let list: Vec<String> = commands.iter().map(|c| format!("- `{c}`")).collect();
+    format!(
+        "\n\nBefore you push, run these verification commands and fix any failures. \
+         claustre runs them again before accepting the PR:\n{}",
+        list.join("\n")
+    )
+}
+
+fn retry_reason(failure: &CommandFailure, attempt: u32, max_attempts: u32) -> String {
+    format!(
+        "claustre verification failed before accepting the PR (attempt {attempt} of \
+         {max_attempts}): `{}` did not pass. Fix the problem, commit and push to the same \
+         branch, then finish.\n\nOutput (tail):\n```\n{}\n```",
+        failure.command, failure.output
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{PushMode, TaskMode};
+
+    #[test]
+    fn run_commands_stops_at_first_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let commands = vec![
+            "echo ok".to_string(),
+            "echo 'test foo failed' >&2; exit 3".to_string(),
+            "touch never-ran".to_string(),
+        ];
+        let failure = run_commands(dir.path(), &commands).unwrap();
+        assert_eq!(failure.command, commands[1]);
+        assert!(failure.output.starts_with("test foo failed"));
+        assert!(!dir.path().join("never-ran").exists());
+        assert_eq!(run_commands(dir.path(), &commands[..1]), None);
+    }
+
+    #[test]
+    fn gate_retries_then_fails_the_task() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open_in_memory().unwrap();
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        let task = store
+            .create_task(
+                &project.id,
+                "task",
+                "desc",
+                TaskMode::Supervised,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap();
+        let session = store
+            .create_session(
+                &project.id,
+                "task/a",
+                dir.path().to_str().unwrap(),
+                "p:task/a",
+            )
+            .unwrap();
+        store.assign_task_to_session(&task.id, &session.id).unwrap();
+        store
+            .update_task_status(&task.id, TaskStatus::Working)
+            .unwrap();
+        let url = "https://github.com/o/r/pull/1";
+
+        assert_eq!(
+            gate(&store, &session.id, url, 2).unwrap(),
+            Verdict::NotRequired
+        );
+
+        store
+            .set_project_verify_commands(&project.id, &["test -f ok".to_string()])
+            .unwrap();
+        let verdict = gate(&store, &session.id, url, 2).unwrap();
+        assert!(!verdict.accepts_pr());
+        let output = verdict.hook_output().unwrap();
+        assert!(output.contains(r#""decision":"block""#));
+        assert!(output.contains("attempt 1 of 2"));
+
+        let verdict = gate(&store, &session.id, url, 2).unwrap();
+        let Verdict::Failed { task_id, message } = verdict else {
+            panic!("expected the second failure to exhaust attempts, got {verdict:?}");
+        };
+        assert_eq!(message, "Verification failed: test -f ok");
+        fail_task(&store, &session.id, &task_id, url, &message).unwrap();
+        let task_after = store.get_task(&task.id).unwrap();
+        assert_eq!(task_after.status, TaskStatus::Error);
+        assert_eq!(task_after.pr_url.as_deref(), Some(url));
+    }
+
+    #[test]
+    fn gate_passes_and_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open_in_memory().unwrap();
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        let task = store
+            .create_task(
+                &project.id,
+                "task",
+                "desc",
+                TaskMode::Supervised,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap();
+        let session = store
+            .create_session(
+                &project.id,
+                "task/a",
+                dir.path().to_str().unwrap(),
+                "p:task/a",
+            )
+            .unwrap();
+        store.assign_task_to_session(&task.id, &session.id).unwrap();
+        store
+            .update_task_status(&task.id, TaskStatus::Working)
+            .unwrap();
+        store
+            .set_project_verify_commands(&project.id, &["true".to_string()])
+            .unwrap();
+
+        let verdict = gate(&store, &session.id, "https://github.com/o/r/pull/2", 3).unwrap();
+        assert_eq!(verdict, Verdict::Passed);
+        assert!(verdict.accepts_pr());
+        assert!(
+            store
+                .latest_task_verification(&task.id)
+                .unwrap()
+                .unwrap()
+                .passed
+        );
+        assert!(prompt_section(&[]).is_empty());
+        assert!(prompt_section(&["true".to_string()]).contains("- `true`"));
+    }
+}
@@ -131,6 +131,11 @@ pub fn apply(store: &Store, args: &SessionUpdateArgs<'_>) -> Result<SessionUpdat
             .or(store.interrupted_task_for_session(args.session_id)?)
         {
             // Resume from in_review/conflict/ci_failed/interrupted task
+            store.record_task_event(
+                &task.id,
+                store::TaskEventKind::Resume,
+                &format!("prompted again from {}", task.status),
+            )?;
             store.update_task_status(&task.id, store::TaskStatus::Working)?;
             // Clear stale ci_status so the dashboard doesn't show "CI failed"
             // from a previous run while the user is actively working on fixes.
@@ -5,12 +5,20 @@
 
 mod models;
 mod queries;
+mod task_query;
 
 pub use models::{
-    CiStatus, ClaudeProgressItem, ClaudeStatus, ExternalSession, Project, PushMode, RateLimitState,
-    Session, Subtask, Task, TaskMode, TaskStatus, TaskStatusCounts,
+    AttemptOutcome, CiFixAttempt, CiFixOutcome, CiStatus, ClaudeProgressItem, ClaudeStatus,
+    ConflictAttempt, ConflictOutcome, EventSource, ExternalSession, IllegalTransition, MergePolicy,
+    Project, PushMode, RateLimitState, Session, Subtask, Task, TaskAttempt, TaskEvent,
+    TaskEventKind, TaskMessage, TaskMode, TaskSchedule, TaskStatus, TaskStatusCounts, TaskVariant,
+    TaskVerification, VariantTestStatus, WebhookDelivery, WebhookDeliveryStatus,
 };
 pub use queries::ProjectStats;
+pub use task_query::TaskQuery;
+
+use std::cell::Cell;
+use std::sync::OnceLock;
 
 use anyhow::{Context, Result};
 use rusqlite::Connection;
@@ -159,10 +167,199 @@ static MIGRATIONS: &[Migration] = &[
             ALTER TABLE projects ADD COLUMN is_git_linked INTEGER NOT NULL DEFAULT 1;
         ",
     },
+    Migration {
+        version: 9,
+        sql: "
+            CREATE TABLE webhook_deliveries (
+                id TEXT PRIMARY KEY,
+                url TEXT NOT NULL,
+                headers TEXT NOT NULL DEFAULT '[]',
+                event TEXT NOT NULL,
+                task_id TEXT,
+                payload TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_attempt_at TEXT NOT NULL,
+                last_error TEXT,
+                created_at TEXT NOT NULL
+            );
+            CREATE INDEX idx_webhook_deliveries_due ON webhook_deliveries(status, next_attempt_at);
+        ",
+    },
+    Migration {
+        version: 10,
+        sql: "
+            CREATE TABLE ci_fix_attempts (
+                id TEXT PRIMARY KEY,
+                task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                attempt INTEGER NOT NULL,
+                run_id TEXT,
+                outcome TEXT NOT NULL DEFAULT 'running',
+                started_at TEXT NOT NULL,
+                finished_at TEXT
+            );
+            CREATE INDEX idx_ci_fix_attempts_task ON ci_fix_attempts(task_id);
+        ",
+    },
+    Migration {
+        version: 11,
+        sql: "
+            CREATE TABLE conflict_attempts (
+                id TEXT PRIMARY KEY,
+                task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                attempt INTEGER NOT NULL,
+                strategy TEXT NOT NULL,
+                outcome TEXT NOT NULL DEFAULT 'running',
+                conflicted_files TEXT NOT NULL DEFAULT '[]',
+                started_at TEXT NOT NULL,
+                finished_at TEXT
+            );
+            CREATE INDEX idx_conflict_attempts_task ON conflict_attempts(task_id);
+        ",
+    },
+    Migration {
+        version: 12,
+        sql: "
+            ALTER TABLE projects ADD COLUMN merge_policy TEXT;
+            ALTER TABLE tasks ADD COLUMN merge_policy TEXT;
+        ",
+    },
+    Migration {
+        version: 13,
+        sql: "
+            CREATE TABLE task_schedules (
+                task_id TEXT PRIMARY KEY REFERENCES tasks(id) ON DELETE CASCADE,
+                spec TEXT NOT NULL,
+                auto_launch INTEGER NOT NULL DEFAULT 0,
+                last_run_at TEXT,
+                next_run_at TEXT,
+                created_at TEXT NOT NULL
+            );
+            CREATE INDEX idx_task_schedules_next_run ON task_schedules(next_run_at);
+        ",
+    },
+    Migration {
+        version: 14,
+        sql: "
+            CREATE TABLE task_attempts (
+                id TEXT PRIMARY KEY,
+                task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                attempt INTEGER NOT NULL,
+                session_id TEXT,
+                branch TEXT,
+                pr_url TEXT,
+                input_tokens INTEGER NOT NULL DEFAULT 0,
+                output_tokens INTEGER NOT NULL DEFAULT 0,
+                started_at TEXT,
+                finished_at TEXT NOT NULL,
+                outcome TEXT NOT NULL,
+                failure_context TEXT
+            );
+            CREATE INDEX idx_task_attempts_task ON task_attempts(task_id);
+        ",
+    },
+    Migration {
+        version: 15,
+        sql: "
+            CREATE TABLE task_variants (
+                task_id TEXT PRIMARY KEY REFERENCES tasks(id) ON DELETE CASCADE,
+                parent_task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                variant INTEGER NOT NULL,
+                model TEXT,
+                effort TEXT,
+                test_status TEXT,
+                test_output TEXT,
+                created_at TEXT NOT NULL
+            );
+            CREATE INDEX idx_task_variants_parent ON task_variants(parent_task_id);
+        ",
+    },
+    Migration {
+        version: 16,
+        sql: "
+            ALTER TABLE projects ADD COLUMN verify_commands TEXT;
+            CREATE TABLE task_verifications (
+                id TEXT PRIMARY KEY,
+                task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                session_id TEXT,
+                passed INTEGER NOT NULL,
+                failed_command TEXT,
+                output TEXT,
+                created_at TEXT NOT NULL
+            );
+            CREATE INDEX idx_task_verifications_task ON task_verifications(task_id);
+        ",
+    },
+    Migration {
+        version: 17,
+        sql: "
+            ALTER TABLE sessions ADD COLUMN layout TEXT;
+        ",
+    },
+    Migration {
+        version: 18,
+        sql: "
+            CREATE TABLE task_messages (
+                id TEXT PRIMARY KEY,
+                task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                session_id TEXT,
+                body TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                delivered_at TEXT
+            );
+            CREATE INDEX idx_task_messages_task ON task_messages(task_id);
+        ",
+    },
+    Migration {
+        version: 19,
+        sql: "
+            CREATE TABLE task_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                kind TEXT NOT NULL,
+                detail TEXT NOT NULL DEFAULT '',
+                source TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            );
+            CREATE INDEX idx_task_events_task ON task_events(task_id, id);
+            CREATE TRIGGER task_events_append_only BEFORE UPDATE ON task_events
+            BEGIN
+                SELECT RAISE(ABORT, 'task_events is append-only');
+            END;
+        ",
+    },
+    Migration {
+        version: 20,
+        sql: "
+            ALTER TABLE tasks ADD COLUMN archived_at TEXT;
+            CREATE INDEX idx_tasks_project_archived ON tasks(project_id, archived_at);
+        ",
+    },
 ];
 
+/// Event source for stores opened without an explicit one; set once at
+/// startup by entry points that aren't the CLI (the TUI, hooks, loops).
+static DEFAULT_EVENT_SOURCE: OnceLock<EventSource> = OnceLock::new();
+
+/// Attribute task events recorded by every store this process opens to
+/// `source`. Only the first call takes effect.
+pub fn set_default_event_source(source: EventSource) {
+    let _ = DEFAULT_EVENT_SOURCE.set(source);
+}
+
+fn default_event_source() -> Cell<EventSource> {
+    Cell::new(
+        DEFAULT_EVENT_SOURCE
+            .get()
+            .copied()
+            .unwrap_or(EventSource::Cli),
+    )
+}
+
 pub struct Store {
     conn: Connection,
+    /// Recorded as the `source` of task events written through this store.
+    event_source: Cell<EventSource>,
 }
 
 #[cfg(test)]
@@ -172,7 +369,10 @@ impl Store {
     fn open_unmigrated() -> Result<Self> {
         let conn = Connection::open_in_memory()?;
         conn.execute_batch("PRAGMA foreign_keys=ON;")?;
-        Ok(Store { conn })
+        Ok(Store {
+            conn,
+            event_source: default_event_source(),
+        })
     }
 }
 
@@ -187,14 +387,20 @@ impl Store {
         let conn = Connection::open(path)
             .with_context(|| format!("failed to open database at {}", path.display()))?;
         conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;")?;
-        Ok(Store { conn })
+        Ok(Store {
+            conn,
+            event_source: default_event_source(),
+        })
     }
 
     #[cfg(test)]
     pub fn open_in_memory() -> Result<Self> {
         let conn = Connection::open_in_memory()?;
         conn.execute_batch("PRAGMA foreign_keys=ON;")?;
-        let store = Store { conn };
+        let store = Store {
+            conn,
+            event_source: default_event_source(),
+        };
         store.migrate()?;
         Ok(store)
     }
@@ -371,6 +577,8 @@ mod tests {
         };
 
         let expected_tables = [
+            "ci_fix_attempts",
+            "conflict_attempts",
             "external_sessions",
             "projects",
             "rate_limit_state",
@@ -378,6 +586,7 @@ mod tests {
             "sessions",
             "subtasks",
             "tasks",
+            "webhook_deliveries",
         ];
         for table in &expected_tables {
             assert!(
@@ -417,6 +626,10 @@ mod tests {
             "ci_status",
             "review_loop",
             "base",
+            // Added by migration v12:
+            "merge_policy",
+            // Added by migration v20:
+            "archived_at",
         ];
         for col in &expected_task_columns {
             assert!(
@@ -425,7 +638,7 @@ mod tests {
             );
         }
 
-        // Check projects table has the v8 column
+        // Check projects table has the v8 and v12 columns
         let project_columns: Vec<String> = {
             let mut stmt = store.conn.prepare("PRAGMA table_info(projects)").unwrap();
             stmt.query_map([], |row| row.get(1))
@@ -433,10 +646,12 @@ mod tests {
                 .collect::<std::result::Result<Vec<_>, _>>()
                 .unwrap()
         };
-        assert!(
-            project_columns.contains(&"is_git_linked".to_string()),
-            "projects table missing column: is_git_linked"
-        );
+        for col in ["is_git_linked", "merge_policy"] {
+            assert!(
+                project_columns.contains(&col.to_string()),
+                "projects table missing column: {col}"
+            );
+        }
 
         // Check sessions table has the v7 column
         let session_columns: Vec<String> = {
@@ -523,6 +738,7 @@ mod tests {
         assert!(fetched.review_loop);
         assert_eq!(fetched.status, super::TaskStatus::Pending);
         assert!(fetched.ci_status.is_none());
+        assert!(fetched.archived_at.is_none());
 
         // Also verify the task columns match by checking the column count in the schema
         let col_count: i64 = store
@@ -533,9 +749,9 @@ mod tests {
                 |row| row.get(0),
             )
             .unwrap();
-        // tasks table should have 20 columns after all migrations
+        // tasks table should have 22 columns after all migrations
         assert_eq!(
-            col_count, 20,
+            col_count, 22,
             "tasks table column count changed — update TASK_COLUMNS and row_to_task"
         );
     }
@@ -33,6 +33,18 @@ pub enum TaskStatus {
 }
 
 impl TaskStatus {
+    pub const ALL: [Self; 9] = [
+        Self::Draft,
+        Self::Pending,
+        Self::Working,
+        Self::Interrupted,
+        Self::InReview,
+        Self::Conflict,
+        Self::CiFailed,
+        Self::Done,
+        Self::Error,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Draft => "draft",
@@ -107,6 +119,27 @@ impl TaskStatus {
         )
     }
 
+    /// Whether a subtask (a step of a task) may move from `self` to
+    /// `new_status`. Steps are simpler than tasks:
+    ///
+    /// ```text
+    /// Pending → Working, Done
+    /// Working → Done, Error, Pending
+    /// Error   → Working, Pending, Done
+    /// Done    → (terminal)
+    /// ```
+    pub fn can_subtask_transition_to(self, new_status: Self) -> bool {
+        if self == new_status {
+            return true;
+        }
+        matches!(
+            (self, new_status),
+            (Self::Pending, Self::Working | Self::Done)
+                | (Self::Working, Self::Done | Self::Error | Self::Pending)
+                | (Self::Error, Self::Working | Self::Pending | Self::Done)
+        )
+    }
+
     /// Sort priority for the task queue panel display.
     /// Lower values appear first: `in_review` → `ci_failed` → `conflict` → `interrupted` → `error` → `pending` → `working` → `done`.
     pub fn sort_priority(&self) -> u8 {
@@ -124,6 +157,28 @@ impl TaskStatus {
     }
 }
 
+/// A status write rejected by the task or subtask state machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IllegalTransition {
+    /// `"task"` or `"subtask"`.
+    pub entity: &'static str,
+    pub id: String,
+    pub from: TaskStatus,
+    pub to: TaskStatus,
+}
+
+impl fmt::Display for IllegalTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid {} status transition: {} -> {} ({} {})",
+            self.entity, self.from, self.to, self.entity, self.id
+        )
+    }
+}
+
+impl std::error::Error for IllegalTransition {}
+
 impl fmt::Display for TaskStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(self.as_str())
@@ -233,6 +288,73 @@ impl FromStr for PushMode {
     }
 }
 
+/// When claustre merges a task's PR on its own.
+///
+/// Stored as `never`, `green` or `approved:<n>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum MergePolicy {
+    /// Leave merging to a human.
+    Never,
+    /// Merge once the PR is mergeable and all checks pass.
+    Green,
+    /// Merge once green and approved by at least this many reviewers.
+    Approved(u32),
+}
+
+impl MergePolicy {
+    /// Whether a PR in this state may be merged under this policy.
+    /// Approvals are ignored while changes are requested.
+    pub fn allows(self, green: bool, approvals: u32) -> bool {
+        match self {
+            Self::Never => false,
+            Self::Green => green,
+            Self::Approved(n) => green && approvals >= n,
+        }
+    }
+}
+
+impl fmt::Display for MergePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Never => f.write_str("never"),
+            Self::Green => f.write_str("green"),
+            Self::Approved(n) => write!(f, "approved:{n}"),
+        }
+    }
+}
+
+impl FromStr for MergePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "never" => Ok(Self::Never),
+            "green" => Ok(Self::Green),
+            _ => s
+                .strip_prefix("approved:")
+                .and_then(|n| n.parse().ok())
+                .filter(|n| *n > 0)
+                .map(Self::Approved)
+                .ok_or_else(|| format!("unknown merge policy: {s}")),
+        }
+    }
+}
+
+impl TryFrom<String> for MergePolicy {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<MergePolicy> for String {
+    fn from(policy: MergePolicy) -> Self {
+        policy.to_string()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum CiStatus {
@@ -300,6 +422,11 @@ pub struct Task {
     pub push_mode: PushMode,
     pub ci_status: Option<CiStatus>,
     pub review_loop: bool,
+    /// Auto-merge override; `None` inherits the project's policy.
+    pub merge_policy: Option<MergePolicy>,
+    /// Set when a long-done task is archived: hidden from the queue, still
+    /// found by `is:archived` queries.
+    pub archived_at: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -425,6 +552,509 @@ pub struct ExternalSession {
     pub jsonl_path: String,
 }
 
+/// Result of one CI fix loop attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CiFixOutcome {
+    /// Claude is still working on the fix.
+    Running,
+    /// A fix was committed and pushed.
+    Pushed,
+    /// Claude finished without committing anything.
+    NoChanges,
+    /// A fix was committed but `git push` failed.
+    PushFailed,
+    /// Claude exited with an error, or the failing logs could not be fetched.
+    Failed,
+}
+
+impl CiFixOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Pushed => "pushed",
+            Self::NoChanges => "no_changes",
+            Self::PushFailed => "push_failed",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for CiFixOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CiFixOutcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "running" => Ok(Self::Running),
+            "pushed" => Ok(Self::Pushed),
+            "no_changes" => Ok(Self::NoChanges),
+            "push_failed" => Ok(Self::PushFailed),
+            "failed" => Ok(Self::Failed),
+            _ => Err(format!("unknown CI fix outcome: {s}")),
+        }
+    }
+}
+
+/// One run of the CI fix loop against a task's failing checks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CiFixAttempt {
+    pub id: String,
+    pub task_id: String,
+    /// 1-based attempt number within the task.
+    pub attempt: i64,
+    /// GitHub Actions run whose failed logs were used.
+    pub run_id: Option<String>,
+    pub outcome: CiFixOutcome,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+/// Result of one automatic merge-conflict resolution attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictOutcome {
+    /// The rebase/merge is still in progress.
+    Running,
+    /// The base applied without conflicts and the branch was pushed.
+    Clean,
+    /// Claude resolved the conflicts and the branch was pushed.
+    Resolved,
+    /// Conflicts remained after Claude finished; the rebase/merge was aborted.
+    Unresolved,
+    /// The push was rejected (e.g. the lease was stale).
+    PushFailed,
+    /// The push target is protected or matches a `[permissions] deny` rule.
+    PushDenied,
+    /// Setup failed (worktree, fetch, dirty tree, Claude error).
+    Failed,
+}
+
+impl ConflictOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Clean => "clean",
+            Self::Resolved => "resolved",
+            Self::Unresolved => "unresolved",
+            Self::PushFailed => "push_failed",
+            Self::PushDenied => "push_denied",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for ConflictOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ConflictOutcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "running" => Ok(Self::Running),
+            "clean" => Ok(Self::Clean),
+            "resolved" => Ok(Self::Resolved),
+            "unresolved" => Ok(Self::Unresolved),
+            "push_failed" => Ok(Self::PushFailed),
+            "push_denied" => Ok(Self::PushDenied),
+            "failed" => Ok(Self::Failed),
+            _ => Err(format!("unknown conflict outcome: {s}")),
+        }
+    }
+}
+
+/// One automatic attempt to resolve a task's merge conflicts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictAttempt {
+    pub id: String,
+    pub task_id: String,
+    /// 1-based attempt number within the task.
+    pub attempt: i64,
+    /// `rebase` or `merge`.
+    pub strategy: String,
+    pub outcome: ConflictOutcome,
+    /// Files that conflicted when the base was applied.
+    pub conflicted_files: Vec<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+/// A recurring schedule attached to a template task. When it comes due the
+/// scheduler clones the task into a new pending task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskSchedule {
+    pub task_id: String,
+    /// `every 7d`, `@weekly`, or a five-field cron expression.
+    pub spec: String,
+    /// Launch each clone immediately in autonomous mode.
+    pub auto_launch: bool,
+    pub last_run_at: Option<String>,
+    pub next_run_at: Option<String>,
+    pub created_at: String,
+}
+
+/// How a task attempt ended, recorded when the task is retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptOutcome {
+    /// The task was in `error`.
+    Error,
+    /// The PR's checks failed.
+    CiFailed,
+    /// The PR conflicted with its base.
+    Conflict,
+    /// The work reached review and was sent back.
+    Rejected,
+    /// The session was interrupted before finishing.
+    Abandoned,
+}
+
+impl AttemptOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::CiFailed => "ci_failed",
+            Self::Conflict => "conflict",
+            Self::Rejected => "rejected",
+            Self::Abandoned => "abandoned",
+        }
+    }
+
+    /// The outcome for a task retried from `status`, or `None` if a task in
+    /// that status cannot be retried.
+    pub fn for_status(status: TaskStatus) -> Option<Self> {
+        match status {
+            TaskStatus::Error => Some(Self::Error),
+            TaskStatus::CiFailed => Some(Self::CiFailed),
+            TaskStatus::Conflict => Some(Self::Conflict),
+            TaskStatus::InReview => Some(Self::Rejected),
+            TaskStatus::Interrupted => Some(Self::Abandoned),
+            TaskStatus::Draft | TaskStatus::Pending | TaskStatus::Working | TaskStatus::Done => {
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Display for AttemptOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AttemptOutcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Self::Error),
+            "ci_failed" => Ok(Self::CiFailed),
+            "conflict" => Ok(Self::Conflict),
+            "rejected" => Ok(Self::Rejected),
+            "abandoned" => Ok(Self::Abandoned),
+            _ => Err(format!("unknown attempt outcome: {s}")),
+        }
+    }
+}
+
+/// One finished run of a task, recorded when it is retried.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskAttempt {
+    pub id: String,
+    pub task_id: String,
+    /// 1-based attempt number within the task.
+    pub attempt: i64,
+    pub session_id: Option<String>,
+    pub branch: Option<String>,
+    pub pr_url: Option<String>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub started_at: Option<String>,
+    pub finished_at: String,
+    pub outcome: AttemptOutcome,
+    /// Why the attempt failed, carried into the next attempt's prompt.
+    pub failure_context: Option<String>,
+}
+
+/// Result of running the fan-out test command in a variant's worktree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VariantTestStatus {
+    Running,
+    Passed,
+    Failed,
+}
+
+impl VariantTestStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Passed => "passed",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for VariantTestStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for VariantTestStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "running" => Ok(Self::Running),
+            "passed" => Ok(Self::Passed),
+            "failed" => Ok(Self::Failed),
+            _ => Err(format!("unknown variant test status: {s}")),
+        }
+    }
+}
+
+/// One fan-out variant: a child task running the parent's prompt with its
+/// own model and effort, in its own worktree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskVariant {
+    pub task_id: String,
+    pub parent_task_id: String,
+    /// 1-based position within the fan-out.
+    pub variant: i64,
+    /// Overrides `[claude] model` when set.
+    pub model: Option<String>,
+    /// Overrides `[claude] effort` when set.
+    pub effort: Option<String>,
+    pub test_status: Option<VariantTestStatus>,
+    /// Tail of the test command's output.
+    pub test_output: Option<String>,
+    pub created_at: String,
+}
+
+impl TaskVariant {
+    /// Short label such as `opus-4-6/high`, used in titles and the compare view.
+    pub fn label(&self, default_model: &str, default_effort: &str) -> String {
+        let model = self.model.as_deref().unwrap_or(default_model);
+        let effort = self.effort.as_deref().unwrap_or(default_effort);
+        format!(
+            "{}/{effort}",
+            model.strip_prefix("claude-").unwrap_or(model)
+        )
+    }
+}
+
+/// One run of a project's verification commands for a task, recorded when
+/// a PR is about to be accepted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskVerification {
+    pub id: String,
+    pub task_id: String,
+    pub session_id: Option<String>,
+    pub passed: bool,
+    /// The first command that failed; `None` when every command passed.
+    pub failed_command: Option<String>,
+    /// Tail of the failing command's output.
+    pub output: Option<String>,
+    pub created_at: String,
+}
+
+/// A follow-up prompt sent to a task's session from the dashboard or
+/// `claustre send`. Undelivered rows are the queue the TUI drains into the
+/// session's Claude pane.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskMessage {
+    pub id: String,
+    pub task_id: String,
+    pub session_id: Option<String>,
+    pub body: String,
+    pub created_at: String,
+    /// When the text reached the Claude pane; `None` while queued.
+    pub delivered_at: Option<String>,
+}
+
+/// What a `TaskEvent` records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskEventKind {
+    Created,
+    Status,
+    Pr,
+    Ci,
+    Resume,
+    ReviewLoop,
+    Message,
+    Subtask,
+    Action,
+    /// A notification fired outside the TUI; the detail is the event name.
+    /// The TUI relays it to backends that need the host terminal.
+    Notify,
+}
+
+impl TaskEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Status => "status",
+            Self::Pr => "pr",
+            Self::Ci => "ci",
+            Self::Resume => "resume",
+            Self::ReviewLoop => "review_loop",
+            Self::Message => "message",
+            Self::Subtask => "subtask",
+            Self::Action => "action",
+            Self::Notify => "notify",
+        }
+    }
+}
+
+impl fmt::Display for TaskEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TaskEventKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "created" => Ok(Self::Created),
+            "status" => Ok(Self::Status),
+            "pr" => Ok(Self::Pr),
+            "ci" => Ok(Self::Ci),
+            "resume" => Ok(Self::Resume),
+            "review_loop" => Ok(Self::ReviewLoop),
+            "message" => Ok(Self::Message),
+            "subtask" => Ok(Self::Subtask),
+            "action" => Ok(Self::Action),
+            "notify" => Ok(Self::Notify),
+            _ => Err(format!("unknown task event kind: {s}")),
+        }
+    }
+}
+
+/// The part of claustre that caused a task event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSource {
+    /// Claude Code hooks and the `feed-next` chain they drive.
+    Hook,
+    /// Background loops watching GitHub: PR/CI polling, review and CI-fix loops.
+    Poller,
+    Tui,
+    Cli,
+}
+
+impl EventSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hook => "hook",
+            Self::Poller => "poller",
+            Self::Tui => "tui",
+            Self::Cli => "cli",
+        }
+    }
+}
+
+impl fmt::Display for EventSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EventSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hook" => Ok(Self::Hook),
+            "poller" => Ok(Self::Poller),
+            "tui" => Ok(Self::Tui),
+            "cli" => Ok(Self::Cli),
+            _ => Err(format!("unknown event source: {s}")),
+        }
+    }
+}
+
+/// One entry in a task's append-only timeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskEvent {
+    pub id: i64,
+    pub task_id: String,
+    pub kind: TaskEventKind,
+    /// Human-readable specifics, e.g. `working → in_review` or the PR URL.
+    pub detail: String,
+    pub source: EventSource,
+    pub created_at: String,
+}
+
+/// Delivery state of a queued outbound webhook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its next attempt.
+    Pending,
+    /// Claimed by a flusher; returns to the due set when the lease expires.
+    InFlight,
+    /// Gave up after exhausting all retries.
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::InFlight => "in_flight",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for WebhookDeliveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebhookDeliveryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "in_flight" => Ok(Self::InFlight),
+            "failed" => Ok(Self::Failed),
+            _ => Err(format!("unknown webhook delivery status: {s}")),
+        }
+    }
+}
+
+/// An outbound webhook request persisted until it is delivered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub url: String,
+    pub headers: Vec<String>,
+    pub event: String,
+    pub task_id: Option<String>,
+    pub payload: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i64,
+    pub next_attempt_at: String,
+    pub last_error: Option<String>,
+    pub created_at: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -608,6 +1238,25 @@ mod tests {
         assert!(!TaskStatus::Error.can_transition_to(TaskStatus::Working));
     }
 
+    #[test]
+    fn merge_policy_round_trip_and_allows() {
+        for policy in [
+            MergePolicy::Never,
+            MergePolicy::Green,
+            MergePolicy::Approved(2),
+        ] {
+            assert_eq!(policy.to_string().parse::<MergePolicy>(), Ok(policy));
+        }
+        assert!("approved:0".parse::<MergePolicy>().is_err());
+        assert!("sometimes".parse::<MergePolicy>().is_err());
+
+        assert!(!MergePolicy::Never.allows(true, 5));
+        assert!(MergePolicy::Green.allows(true, 0));
+        assert!(!MergePolicy::Green.allows(false, 3));
+        assert!(!MergePolicy::Approved(2).allows(true, 1));
+        assert!(MergePolicy::Approved(2).allows(true, 2));
+    }
+
     #[test]
     fn task_status_conflict_ci_failed_cross_transitions() {
         // Conflict ↔ CiFailed
@@ -0,0 +1,230 @@
+//! Task attempt history and the retry transition.
+
+use anyhow::{Context, Result};
+use rusqlite::{OptionalExtension, params};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::store::Store;
+use crate::store::models::{AttemptOutcome, TaskAttempt, TaskEventKind, TaskStatus};
+
+/// Column list for all queries that use `row_to_task_attempt`.
+const ATTEMPT_COLUMNS: &str = "id, task_id, attempt, session_id, branch, pr_url, \
+    input_tokens, output_tokens, started_at, finished_at, outcome, failure_context";
+
+impl Store {
+    /// Close out the task's current run as an attempt and reset the task to
+    /// `pending` for a fresh one: the session, PR, CI status, start time and
+    /// token counts are cleared. Fails if the task's status can't be retried.
+    pub fn retry_task(
+        &self,
+        task_id: &str,
+        branch: Option<&str>,
+        failure_context: Option<&str>,
+    ) -> Result<TaskAttempt> {
+        let task = self.get_task(task_id)?;
+        let outcome = AttemptOutcome::for_status(task.status)
+            .with_context(|| format!("a {} task cannot be retried", task.status))?;
+        let id = Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        self.in_transaction(|| {
+            let attempt = self.count_task_attempts(task_id)? + 1;
+            self.conn.execute(
+                "INSERT INTO task_attempts (id, task_id, attempt, session_id, branch, pr_url,
+                     input_tokens, output_tokens, started_at, finished_at, outcome, failure_context)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    id,
+                    task_id,
+                    attempt,
+                    task.session_id,
+                    branch,
+                    task.pr_url,
+                    task.input_tokens,
+                    task.output_tokens,
+                    task.started_at,
+                    now,
+                    outcome.as_str(),
+                    failure_context,
+                ],
+            )?;
+            self.record_task_event(
+                task_id,
+                TaskEventKind::Action,
+                &format!("retry: attempt {attempt} closed as {outcome}"),
+            )?;
+            self.update_task_status(task_id, TaskStatus::Pending)?;
+            self.conn.execute(
+                "UPDATE tasks SET session_id = NULL, pr_url = NULL, ci_status = NULL,
+                     started_at = NULL, input_tokens = 0, output_tokens = 0
+                 WHERE id = ?1",
+                params![task_id],
+            )?;
+            Ok(())
+        })
+        .with_context(|| format!("failed to retry task '{task_id}'"))?;
+        self.get_task_attempt(&id)
+    }
+
+    pub fn get_task_attempt(&self, id: &str) -> Result<TaskAttempt> {
+        let sql = format!("SELECT {ATTEMPT_COLUMNS} FROM task_attempts WHERE id = ?1");
+        let attempt = self
+            .conn
+            .query_row(&sql, params![id], Self::row_to_task_attempt)
+            .with_context(|| format!("failed to fetch task attempt '{id}'"))?;
+        Ok(attempt)
+    }
+
+    pub fn count_task_attempts(&self, task_id: &str) -> Result<i64> {
+        let count = self.conn.query_row(
+            "SELECT COUNT(*) FROM task_attempts WHERE task_id = ?1",
+            params![task_id],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// All attempts for a task, oldest first.
+    pub fn list_task_attempts(&self, task_id: &str) -> Result<Vec<TaskAttempt>> {
+        let sql = format!(
+            "SELECT {ATTEMPT_COLUMNS} FROM task_attempts \
+             WHERE task_id = ?1 \
+             ORDER BY attempt"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let attempts = stmt
+            .query_map(params![task_id], Self::row_to_task_attempt)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(attempts)
+    }
+
+    /// The most recent attempt, whose failure context feeds the next prompt.
+    pub fn latest_task_attempt(&self, task_id: &str) -> Result<Option<TaskAttempt>> {
+        let sql = format!(
+            "SELECT {ATTEMPT_COLUMNS} FROM task_attempts \
+             WHERE task_id = ?1 \
+             ORDER BY attempt DESC LIMIT 1"
+        );
+        let attempt = self
+            .conn
+            .query_row(&sql, params![task_id], Self::row_to_task_attempt)
+            .optional()?;
+        Ok(attempt)
+    }
+
+    fn row_to_task_attempt(row: &rusqlite::Row<'_>) -> rusqlite::Result<TaskAttempt> {
+        let id: String = row.get(0)?;
+        let outcome_str: String = row.get(10)?;
+        Ok(TaskAttempt {
+            outcome: outcome_str.parse().unwrap_or_else(|_| {
+                warn!(attempt_id = %id, raw = %outcome_str, "unknown attempt outcome in DB, defaulting to Error");
+                AttemptOutcome::Error
+            }),
+            id,
+            task_id: row.get(1)?,
+            attempt: row.get(2)?,
+            session_id: row.get(3)?,
+            branch: row.get(4)?,
+            pr_url: row.get(5)?,
+            input_tokens: row.get(6)?,
+            output_tokens: row.get(7)?,
+            started_at: row.get(8)?,
+            finished_at: row.get(9)?,
+            failure_context: row.get(11)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::{AttemptOutcome, CiStatus, PushMode, Store, TaskMode, TaskStatus};
+
+    fn make_task(store: &Store) -> String {
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        store
+            .create_task(
+                &project.id,
+                "task",
+                "desc",
+                TaskMode::Supervised,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap()
+            .id
+    }
+
+    #[test]
+    fn retry_records_attempt_and_resets_task() {
+        let store = Store::open_in_memory().unwrap();
+        let task_id = make_task(&store);
+        let project_id = store.get_task(&task_id).unwrap().project_id;
+        let session = store
+            .create_session(&project_id, "task/a-1", "/tmp/wt", "p:task/a-1")
+            .unwrap();
+        store.assign_task_to_session(&task_id, &session.id).unwrap();
+        store
+            .update_task_status(&task_id, TaskStatus::Working)
+            .unwrap();
+        store.set_task_usage(&task_id, 1200, 300).unwrap();
+        store
+            .update_task_pr_url(&task_id, "https://github.com/o/r/pull/1")
+            .unwrap();
+        store
+            .update_task_status(&task_id, TaskStatus::InReview)
+            .unwrap();
+        store
+            .update_task_ci_status(&task_id, Some(CiStatus::Failed))
+            .unwrap();
+        store
+            .update_task_status(&task_id, TaskStatus::CiFailed)
+            .unwrap();
+
+        let first = store
+            .retry_task(&task_id, Some("task/a-1"), Some("tests failed"))
+            .unwrap();
+        assert_eq!(first.attempt, 1);
+        assert_eq!(first.outcome, AttemptOutcome::CiFailed);
+        assert_eq!(first.session_id.as_deref(), Some(session.id.as_str()));
+        assert_eq!(first.branch.as_deref(), Some("task/a-1"));
+        assert_eq!(
+            first.pr_url.as_deref(),
+            Some("https://github.com/o/r/pull/1")
+        );
+        assert_eq!((first.input_tokens, first.output_tokens), (1200, 300));
+        assert!(first.started_at.is_some());
+
+        let task = store.get_task(&task_id).unwrap();
+        assert_eq!(task.status, TaskStatus::Pending);
+        assert!(task.session_id.is_none());
+        assert!(task.pr_url.is_none());
+        assert!(task.ci_status.is_none());
+        assert!(task.started_at.is_none());
+        assert_eq!(task.input_tokens, 0);
+
+        // Pending tasks can't be retried.
+        assert!(store.retry_task(&task_id, None, None).is_err());
+
+        store
+            .update_task_status(&task_id, TaskStatus::Working)
+            .unwrap();
+        store
+            .update_task_status(&task_id, TaskStatus::Error)
+            .unwrap();
+        let second = store.retry_task(&task_id, None, None).unwrap();
+        assert_eq!(second.attempt, 2);
+        assert_eq!(second.outcome, AttemptOutcome::Error);
+
+        let attempts = store.list_task_attempts(&task_id).unwrap();
+        assert_eq!(attempts.len(), 2);
+        assert_eq!(
+            store
+                .latest_task_attempt(&task_id)
+                .unwrap()
+                .map(|a| a.attempt),
+            Some(2)
+        );
+    }
+}
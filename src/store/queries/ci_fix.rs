@@ -0,0 +1,148 @@
+//! CI fix loop attempt history.
+
+use anyhow::{Context, Result};
+use rusqlite::params;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::store::Store;
+use crate::store::models::{CiFixAttempt, CiFixOutcome};
+
+/// Column list for all queries that use `row_to_ci_fix_attempt`.
+const CI_FIX_COLUMNS: &str = "id, task_id, attempt, run_id, outcome, started_at, finished_at";
+
+impl Store {
+    /// Record the start of a new CI fix attempt. The attempt number is one
+    /// past the task's previous attempts.
+    pub fn start_ci_fix_attempt(
+        &self,
+        task_id: &str,
+        run_id: Option<&str>,
+    ) -> Result<CiFixAttempt> {
+        let id = Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        let attempt = self.count_ci_fix_attempts(task_id)? + 1;
+        self.conn
+            .execute(
+                "INSERT INTO ci_fix_attempts (id, task_id, attempt, run_id, outcome, started_at)
+                 VALUES (?1, ?2, ?3, ?4, 'running', ?5)",
+                params![id, task_id, attempt, run_id, now],
+            )
+            .with_context(|| format!("failed to record CI fix attempt for task '{task_id}'"))?;
+        self.get_ci_fix_attempt(&id)
+    }
+
+    pub fn get_ci_fix_attempt(&self, id: &str) -> Result<CiFixAttempt> {
+        let sql = format!("SELECT {CI_FIX_COLUMNS} FROM ci_fix_attempts WHERE id = ?1");
+        let attempt = self
+            .conn
+            .query_row(&sql, params![id], Self::row_to_ci_fix_attempt)
+            .with_context(|| format!("failed to fetch CI fix attempt '{id}'"))?;
+        Ok(attempt)
+    }
+
+    pub fn finish_ci_fix_attempt(&self, id: &str, outcome: CiFixOutcome) -> Result<()> {
+        let now = chrono::Utc::now().to_rfc3339();
+        self.conn.execute(
+            "UPDATE ci_fix_attempts SET outcome = ?1, finished_at = ?2 WHERE id = ?3",
+            params![outcome.as_str(), now, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn count_ci_fix_attempts(&self, task_id: &str) -> Result<i64> {
+        let count = self.conn.query_row(
+            "SELECT COUNT(*) FROM ci_fix_attempts WHERE task_id = ?1",
+            params![task_id],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// All attempts for a task, oldest first.
+    pub fn list_ci_fix_attempts(&self, task_id: &str) -> Result<Vec<CiFixAttempt>> {
+        let sql = format!(
+            "SELECT {CI_FIX_COLUMNS} FROM ci_fix_attempts \
+             WHERE task_id = ?1 \
+             ORDER BY attempt"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let attempts = stmt
+            .query_map(params![task_id], Self::row_to_ci_fix_attempt)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(attempts)
+    }
+
+    fn row_to_ci_fix_attempt(row: &rusqlite::Row<'_>) -> rusqlite::Result<CiFixAttempt> {
+        let id: String = row.get(0)?;
+        let outcome_str: String = row.get(4)?;
+        Ok(CiFixAttempt {
+            outcome: outcome_str.parse().unwrap_or_else(|_| {
+                warn!(attempt_id = %id, raw = %outcome_str, "unknown CI fix outcome in DB, defaulting to Failed");
+                CiFixOutcome::Failed
+            }),
+            id,
+            task_id: row.get(1)?,
+            attempt: row.get(2)?,
+            run_id: row.get(3)?,
+            started_at: row.get(5)?,
+            finished_at: row.get(6)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::{CiFixOutcome, PushMode, Store, TaskMode};
+
+    fn make_task(store: &Store) -> String {
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        store
+            .create_task(
+                &project.id,
+                "task",
+                "desc",
+                TaskMode::Autonomous,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap()
+            .id
+    }
+
+    #[test]
+    fn ci_fix_attempts_are_numbered_per_task() {
+        let store = Store::open_in_memory().unwrap();
+        let task_id = make_task(&store);
+
+        let first = store.start_ci_fix_attempt(&task_id, Some("123")).unwrap();
+        assert_eq!(first.attempt, 1);
+        assert_eq!(first.outcome, CiFixOutcome::Running);
+        assert!(first.finished_at.is_none());
+        store
+            .finish_ci_fix_attempt(&first.id, CiFixOutcome::Pushed)
+            .unwrap();
+
+        let second = store.start_ci_fix_attempt(&task_id, None).unwrap();
+        assert_eq!(second.attempt, 2);
+
+        let attempts = store.list_ci_fix_attempts(&task_id).unwrap();
+        assert_eq!(attempts.len(), 2);
+        assert_eq!(attempts[0].outcome, CiFixOutcome::Pushed);
+        assert_eq!(attempts[0].run_id.as_deref(), Some("123"));
+        assert!(attempts[0].finished_at.is_some());
+        assert_eq!(store.count_ci_fix_attempts(&task_id).unwrap(), 2);
+    }
+
+    #[test]
+    fn ci_fix_attempts_cascade_on_task_delete() {
+        let store = Store::open_in_memory().unwrap();
+        let task_id = make_task(&store);
+        store.start_ci_fix_attempt(&task_id, None).unwrap();
+
+        store.delete_task(&task_id).unwrap();
+        assert_eq!(store.count_ci_fix_attempts(&task_id).unwrap(), 0);
+    }
+}
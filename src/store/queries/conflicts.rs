@@ -0,0 +1,141 @@
+//! Automatic merge-conflict resolution attempt history.
+
+use anyhow::{Context, Result};
+use rusqlite::params;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::store::Store;
+use crate::store::models::{ConflictAttempt, ConflictOutcome};
+
+/// Column list for all queries that use `row_to_conflict_attempt`.
+const CONFLICT_COLUMNS: &str =
+    "id, task_id, attempt, strategy, outcome, conflicted_files, started_at, finished_at";
+
+impl Store {
+    /// Record the start of a new conflict resolution attempt. The attempt
+    /// number is one past the task's previous attempts.
+    pub fn start_conflict_attempt(&self, task_id: &str, strategy: &str) -> Result<ConflictAttempt> {
+        let id = Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        let attempt = self.count_conflict_attempts(task_id)? + 1;
+        self.conn
+            .execute(
+                "INSERT INTO conflict_attempts (id, task_id, attempt, strategy, outcome, started_at)
+                 VALUES (?1, ?2, ?3, ?4, 'running', ?5)",
+                params![id, task_id, attempt, strategy, now],
+            )
+            .with_context(|| format!("failed to record conflict attempt for task '{task_id}'"))?;
+        self.get_conflict_attempt(&id)
+    }
+
+    pub fn get_conflict_attempt(&self, id: &str) -> Result<ConflictAttempt> {
+        let sql = format!("SELECT {CONFLICT_COLUMNS} FROM conflict_attempts WHERE id = ?1");
+        let attempt = self
+            .conn
+            .query_row(&sql, params![id], Self::row_to_conflict_attempt)
+            .with_context(|| format!("failed to fetch conflict attempt '{id}'"))?;
+        Ok(attempt)
+    }
+
+    pub fn finish_conflict_attempt(
+        &self,
+        id: &str,
+        outcome: ConflictOutcome,
+        conflicted_files: &[String],
+    ) -> Result<()> {
+        let now = chrono::Utc::now().to_rfc3339();
+        let files_json = serde_json::to_string(conflicted_files)?;
+        self.conn.execute(
+            "UPDATE conflict_attempts SET outcome = ?1, conflicted_files = ?2, finished_at = ?3
+             WHERE id = ?4",
+            params![outcome.as_str(), files_json, now, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn count_conflict_attempts(&self, task_id: &str) -> Result<i64> {
+        let count = self.conn.query_row(
+            "SELECT COUNT(*) FROM conflict_attempts WHERE task_id = ?1",
+            params![task_id],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// All attempts for a task, oldest first.
+    pub fn list_conflict_attempts(&self, task_id: &str) -> Result<Vec<ConflictAttempt>> {
+        let sql = format!(
+            "SELECT {CONFLICT_COLUMNS} FROM conflict_attempts \
+             WHERE task_id = ?1 \
+             ORDER BY attempt"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let attempts = stmt
+            .query_map(params![task_id], Self::row_to_conflict_attempt)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(attempts)
+    }
+
+    fn row_to_conflict_attempt(row: &rusqlite::Row<'_>) -> rusqlite::Result<ConflictAttempt> {
+        let id: String = row.get(0)?;
+        let outcome_str: String = row.get(4)?;
+        let files_json: String = row.get(5)?;
+        Ok(ConflictAttempt {
+            outcome: outcome_str.parse().unwrap_or_else(|_| {
+                warn!(attempt_id = %id, raw = %outcome_str, "unknown conflict outcome in DB, defaulting to Failed");
+                ConflictOutcome::Failed
+            }),
+            conflicted_files: serde_json::from_str(&files_json).unwrap_or_default(),
+            id,
+            task_id: row.get(1)?,
+            attempt: row.get(2)?,
+            strategy: row.get(3)?,
+            started_at: row.get(6)?,
+            finished_at: row.get(7)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::{ConflictOutcome, PushMode, Store, TaskMode};
+
+    #[test]
+    fn conflict_attempts_record_outcome_and_files() {
+        let store = Store::open_in_memory().unwrap();
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        let task = store
+            .create_task(
+                &project.id,
+                "task",
+                "",
+                TaskMode::Supervised,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap();
+
+        let first = store.start_conflict_attempt(&task.id, "rebase").unwrap();
+        assert_eq!(first.attempt, 1);
+        assert_eq!(first.outcome, ConflictOutcome::Running);
+        assert!(first.conflicted_files.is_empty());
+
+        let files = vec!["src/lib.rs".to_string(), "Cargo.toml".to_string()];
+        store
+            .finish_conflict_attempt(&first.id, ConflictOutcome::Resolved, &files)
+            .unwrap();
+        let second = store.start_conflict_attempt(&task.id, "merge").unwrap();
+        assert_eq!(second.attempt, 2);
+
+        let attempts = store.list_conflict_attempts(&task.id).unwrap();
+        assert_eq!(attempts.len(), 2);
+        assert_eq!(attempts[0].outcome, ConflictOutcome::Resolved);
+        assert_eq!(attempts[0].conflicted_files, files);
+        assert!(attempts[0].finished_at.is_some());
+        assert_eq!(attempts[1].strategy, "merge");
+        assert_eq!(store.count_conflict_attempts(&task.id).unwrap(), 2);
+    }
+}
@@ -0,0 +1,269 @@
+//! The append-only task timeline: status changes, PR and CI updates,
+//! resumes, review-loop runs, messages and user actions, each tagged with
+//! the part of claustre that caused it.
+
+use anyhow::{Context, Result};
+use rusqlite::params;
+
+use crate::store::Store;
+use crate::store::models::{EventSource, TaskEvent, TaskEventKind};
+
+/// Column list for all queries that use `row_to_task_event`.
+const EVENT_COLUMNS: &str = "id, task_id, kind, detail, source, created_at";
+
+impl Store {
+    /// Source recorded on events written through this store.
+    pub fn event_source(&self) -> EventSource {
+        self.event_source.get()
+    }
+
+    /// Attribute subsequent events to `source`, returning the previous one
+    /// so callers can restore it.
+    pub fn set_event_source(&self, source: EventSource) -> EventSource {
+        self.event_source.replace(source)
+    }
+
+    pub fn record_task_event(
+        &self,
+        task_id: &str,
+        kind: TaskEventKind,
+        detail: &str,
+    ) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO task_events (task_id, kind, detail, source, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    task_id,
+                    kind.as_str(),
+                    detail,
+                    self.event_source().as_str(),
+                    chrono::Utc::now().to_rfc3339(),
+                ],
+            )
+            .with_context(|| format!("failed to record {kind} event for task '{task_id}'"))?;
+        Ok(())
+    }
+
+    /// Record an event on every task assigned to a session.
+    pub fn record_session_event(
+        &self,
+        session_id: &str,
+        kind: TaskEventKind,
+        detail: &str,
+    ) -> Result<()> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id FROM tasks WHERE session_id = ?1")?;
+        let task_ids = stmt
+            .query_map(params![session_id], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        for task_id in task_ids {
+            self.record_task_event(&task_id, kind, detail)?;
+        }
+        Ok(())
+    }
+
+    /// A task's timeline, oldest first.
+    pub fn list_task_events(&self, task_id: &str) -> Result<Vec<TaskEvent>> {
+        let sql = format!("SELECT {EVENT_COLUMNS} FROM task_events WHERE task_id = ?1 ORDER BY id");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![task_id], Self::row_to_task_event)?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Id of the newest timeline event, or 0 when there is none.
+    pub fn last_task_event_id(&self) -> Result<i64> {
+        let id =
+            self.conn
+                .query_row("SELECT COALESCE(MAX(id), 0) FROM task_events", [], |row| {
+                    row.get(0)
+                })?;
+        Ok(id)
+    }
+
+    /// `notify` events recorded after `after_id`, oldest first.
+    pub fn list_notify_events_since(&self, after_id: i64) -> Result<Vec<TaskEvent>> {
+        let sql = format!(
+            "SELECT {EVENT_COLUMNS} FROM task_events \
+             WHERE id > ?1 AND kind = 'notify' ORDER BY id"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![after_id], Self::row_to_task_event)?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    fn row_to_task_event(row: &rusqlite::Row<'_>) -> rusqlite::Result<TaskEvent> {
+        let kind: String = row.get(2)?;
+        let source: String = row.get(4)?;
+        Ok(TaskEvent {
+            id: row.get(0)?,
+            task_id: row.get(1)?,
+            kind: kind.parse().unwrap_or(TaskEventKind::Action),
+            detail: row.get(3)?,
+            source: source.parse().unwrap_or(EventSource::Cli),
+            created_at: row.get(5)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::{
+        CiStatus, EventSource, PushMode, Store, TaskEventKind, TaskMode, TaskStatus,
+    };
+
+    fn store_with_task() -> (Store, String) {
+        let store = Store::open_in_memory().unwrap();
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        let task = store
+            .create_task(
+                &project.id,
+                "task",
+                "desc",
+                TaskMode::Autonomous,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap();
+        (store, task.id)
+    }
+
+    #[test]
+    fn notify_events_since_cursor() {
+        let (store, task_id) = store_with_task();
+        let cursor = store.last_task_event_id().unwrap();
+        assert!(cursor > 0);
+        assert!(store.list_notify_events_since(cursor).unwrap().is_empty());
+
+        store
+            .record_task_event(&task_id, TaskEventKind::Notify, "in_review")
+            .unwrap();
+        store
+            .update_task_status(&task_id, TaskStatus::Working)
+            .unwrap();
+        store
+            .record_task_event(&task_id, TaskEventKind::Notify, "done")
+            .unwrap();
+
+        let events = store.list_notify_events_since(cursor).unwrap();
+        let details: Vec<&str> = events.iter().map(|e| e.detail.as_str()).collect();
+        assert_eq!(details, ["in_review", "done"]);
+        assert!(
+            store
+                .list_notify_events_since(events[1].id)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn timeline_records_changes_with_their_source() {
+        let (store, task_id) = store_with_task();
+
+        store.set_event_source(EventSource::Hook);
+        store
+            .update_task_status(&task_id, TaskStatus::Working)
+            .unwrap();
+        store
+            .update_task_pr_url(&task_id, "https://github.com/o/r/pull/1")
+            .unwrap();
+        // Unchanged values add nothing
+        store
+            .update_task_pr_url(&task_id, "https://github.com/o/r/pull/1")
+            .unwrap();
+        store
+            .update_task_status(&task_id, TaskStatus::InReview)
+            .unwrap();
+
+        let previous = store.set_event_source(EventSource::Poller);
+        assert_eq!(previous, EventSource::Hook);
+        for _ in 0..2 {
+            store
+                .update_task_status(&task_id, TaskStatus::CiFailed)
+                .unwrap();
+            store
+                .update_task_ci_status(&task_id, Some(CiStatus::Failed))
+                .unwrap();
+            store
+                .update_task_status(&task_id, TaskStatus::InReview)
+                .unwrap();
+        }
+        // Rejected transitions are not recorded
+        assert!(
+            !store
+                .try_update_task_status(&task_id, TaskStatus::Draft)
+                .unwrap()
+        );
+
+        store.set_event_source(EventSource::Tui);
+        store
+            .record_task_message(&task_id, None, "add a test", false)
+            .unwrap();
+
+        let events = store.list_task_events(&task_id).unwrap();
+        let summary: Vec<(TaskEventKind, &str, EventSource)> = events
+            .iter()
+            .map(|e| (e.kind, e.detail.as_str(), e.source))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (TaskEventKind::Created, "autonomous", EventSource::Cli),
+                (
+                    TaskEventKind::Status,
+                    "pending → working",
+                    EventSource::Hook
+                ),
+                (
+                    TaskEventKind::Pr,
+                    "https://github.com/o/r/pull/1",
+                    EventSource::Hook
+                ),
+                (
+                    TaskEventKind::Status,
+                    "working → in_review",
+                    EventSource::Hook
+                ),
+                (
+                    TaskEventKind::Status,
+                    "in_review → ci_failed",
+                    EventSource::Poller
+                ),
+                (TaskEventKind::Ci, "failed", EventSource::Poller),
+                (
+                    TaskEventKind::Status,
+                    "ci_failed → in_review",
+                    EventSource::Poller
+                ),
+                (
+                    TaskEventKind::Status,
+                    "in_review → ci_failed",
+                    EventSource::Poller
+                ),
+                (
+                    TaskEventKind::Status,
+                    "ci_failed → in_review",
+                    EventSource::Poller
+                ),
+                (TaskEventKind::Message, "add a test", EventSource::Tui),
+            ]
+        );
+    }
+
+    #[test]
+    fn timeline_is_append_only() {
+        let (store, task_id) = store_with_task();
+        let result = store.conn.execute(
+            "UPDATE task_events SET detail = 'rewritten' WHERE task_id = ?1",
+            rusqlite::params![task_id],
+        );
+        assert!(result.is_err());
+
+        // Deleting the task still takes its timeline with it
+        store.delete_task(&task_id).unwrap();
+        assert!(store.list_task_events(&task_id).unwrap().is_empty());
+    }
+}
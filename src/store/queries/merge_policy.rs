@@ -0,0 +1,129 @@
+//! Per-project and per-task auto-merge policy overrides.
+
+use anyhow::{Context, Result};
+use rusqlite::{OptionalExtension, params};
+use tracing::warn;
+
+use crate::store::Store;
+use crate::store::models::MergePolicy;
+
+/// Parse a stored policy, treating unknown values as unset.
+fn parse_policy(raw: Option<String>) -> Option<MergePolicy> {
+    let raw = raw?;
+    raw.parse()
+        .inspect_err(|_| warn!(raw = %raw, "unknown merge policy in DB, ignoring"))
+        .ok()
+}
+
+impl Store {
+    /// Set (or clear with `None`) a project's merge policy.
+    pub fn set_project_merge_policy(
+        &self,
+        project_id: &str,
+        policy: Option<MergePolicy>,
+    ) -> Result<()> {
+        self.conn
+            .execute(
+                "UPDATE projects SET merge_policy = ?1 WHERE id = ?2",
+                params![policy.map(|p| p.to_string()), project_id],
+            )
+            .with_context(|| format!("failed to set merge policy for project '{project_id}'"))?;
+        Ok(())
+    }
+
+    /// Set (or clear with `None`) a task's merge policy override.
+    pub fn set_task_merge_policy(&self, task_id: &str, policy: Option<MergePolicy>) -> Result<()> {
+        let now = chrono::Utc::now().to_rfc3339();
+        self.conn
+            .execute(
+                "UPDATE tasks SET merge_policy = ?1, updated_at = ?2 WHERE id = ?3",
+                params![policy.map(|p| p.to_string()), now, task_id],
+            )
+            .with_context(|| format!("failed to set merge policy for task '{task_id}'"))?;
+        Ok(())
+    }
+
+    pub fn project_merge_policy(&self, project_id: &str) -> Result<Option<MergePolicy>> {
+        let raw: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT merge_policy FROM projects WHERE id = ?1",
+                params![project_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(parse_policy(raw))
+    }
+
+    /// The task's own policy, else its project's, else `None` (caller
+    /// falls back to the configured default).
+    pub fn effective_merge_policy(&self, task_id: &str) -> Result<Option<MergePolicy>> {
+        let (task_raw, project_raw): (Option<String>, Option<String>) = self
+            .conn
+            .query_row(
+                "SELECT t.merge_policy, p.merge_policy FROM tasks t \
+                 JOIN projects p ON p.id = t.project_id \
+                 WHERE t.id = ?1",
+                params![task_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .with_context(|| format!("failed to fetch merge policy for task '{task_id}'"))?;
+        Ok(parse_policy(task_raw).or_else(|| parse_policy(project_raw)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::{MergePolicy, PushMode, Store, TaskMode};
+
+    #[test]
+    fn task_policy_overrides_project_policy() {
+        let store = Store::open_in_memory().unwrap();
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        let task = store
+            .create_task(
+                &project.id,
+                "task",
+                "",
+                TaskMode::Autonomous,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap();
+        assert_eq!(store.effective_merge_policy(&task.id).unwrap(), None);
+
+        store
+            .set_project_merge_policy(&project.id, Some(MergePolicy::Green))
+            .unwrap();
+        assert_eq!(
+            store.effective_merge_policy(&task.id).unwrap(),
+            Some(MergePolicy::Green)
+        );
+
+        store
+            .set_task_merge_policy(&task.id, Some(MergePolicy::Approved(2)))
+            .unwrap();
+        assert_eq!(
+            store.effective_merge_policy(&task.id).unwrap(),
+            Some(MergePolicy::Approved(2))
+        );
+        assert_eq!(
+            store.get_task(&task.id).unwrap().merge_policy,
+            Some(MergePolicy::Approved(2))
+        );
+        assert_eq!(
+            store.project_merge_policy(&project.id).unwrap(),
+            Some(MergePolicy::Green)
+        );
+
+        store.set_task_merge_policy(&task.id, None).unwrap();
+        assert_eq!(store.get_task(&task.id).unwrap().merge_policy, None);
+        assert_eq!(
+            store.effective_merge_policy(&task.id).unwrap(),
+            Some(MergePolicy::Green)
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Follow-up prompts sent to task sessions, and the queue of those the TUI
+//! has yet to type into a Claude pane.
+
+use anyhow::{Context, Result};
+use rusqlite::params;
+use uuid::Uuid;
+
+use crate::store::Store;
+use crate::store::models::{TaskEventKind, TaskMessage};
+
+/// Column list for all queries that use `row_to_task_message`.
+const MESSAGE_COLUMNS: &str = "id, task_id, session_id, body, created_at, delivered_at";
+
+impl Store {
+    /// Record a message for a task's session, already delivered or queued.
+    pub fn record_task_message(
+        &self,
+        task_id: &str,
+        session_id: Option<&str>,
+        body: &str,
+        delivered: bool,
+    ) -> Result<TaskMessage> {
+        let id = Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        let delivered_at = delivered.then(|| now.clone());
+        self.conn
+            .execute(
+                "INSERT INTO task_messages (id, task_id, session_id, body, created_at, delivered_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![id, task_id, session_id, body, now, delivered_at],
+            )
+            .with_context(|| format!("failed to record message for task '{task_id}'"))?;
+        self.record_task_event(task_id, TaskEventKind::Message, body)?;
+        let sql = format!("SELECT {MESSAGE_COLUMNS} FROM task_messages WHERE id = ?1");
+        let message = self
+            .conn
+            .query_row(&sql, params![id], Self::row_to_task_message)?;
+        Ok(message)
+    }
+
+    pub fn mark_task_message_delivered(&self, id: &str) -> Result<()> {
+        self.conn
+            .execute(
+                "UPDATE task_messages SET delivered_at = ?1 WHERE id = ?2",
+                params![chrono::Utc::now().to_rfc3339(), id],
+            )
+            .with_context(|| format!("failed to mark message '{id}' delivered"))?;
+        Ok(())
+    }
+
+    /// Queued messages, oldest first.
+    pub fn undelivered_task_messages(&self) -> Result<Vec<TaskMessage>> {
+        let sql = format!(
+            "SELECT {MESSAGE_COLUMNS} FROM task_messages \
+             WHERE delivered_at IS NULL ORDER BY created_at, rowid"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], Self::row_to_task_message)?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Every message sent to a task, oldest first.
+    pub fn list_task_messages(&self, task_id: &str) -> Result<Vec<TaskMessage>> {
+        let sql = format!(
+            "SELECT {MESSAGE_COLUMNS} FROM task_messages \
+             WHERE task_id = ?1 ORDER BY created_at, rowid"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![task_id], Self::row_to_task_message)?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    fn row_to_task_message(row: &rusqlite::Row<'_>) -> rusqlite::Result<TaskMessage> {
+        Ok(TaskMessage {
+            id: row.get(0)?,
+            task_id: row.get(1)?,
+            session_id: row.get(2)?,
+            body: row.get(3)?,
+            created_at: row.get(4)?,
+            delivered_at: row.get(5)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::{PushMode, Store, TaskMode};
+
+    #[test]
+    fn queued_messages_drain_once_delivered() {
+        let store = Store::open_in_memory().unwrap();
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        let task = store
+            .create_task(
+                &project.id,
+                "task",
+                "desc",
+                TaskMode::Supervised,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap();
+
+        let sent = store
+            .record_task_message(&task.id, Some("s1"), "use the cache", true)
+            .unwrap();
+        assert!(sent.delivered_at.is_some());
+        let queued = store
+            .record_task_message(&task.id, Some("s1"), "and add a test", false)
+            .unwrap();
+        assert!(queued.delivered_at.is_none());
+
+        let pending = store.undelivered_task_messages().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].body, "and add a test");
+
+        store.mark_task_message_delivered(&queued.id).unwrap();
+        assert!(store.undelivered_task_messages().unwrap().is_empty());
+
+        let all = store.list_task_messages(&task.id).unwrap();
+        assert_eq!(
+            all.iter().map(|m| m.body.as_str()).collect::<Vec<_>>(),
+            vec!["use the cache", "and add a test"]
+        );
+        assert!(all.iter().all(|m| m.delivered_at.is_some()));
+    }
+}
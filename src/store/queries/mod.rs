@@ -3,14 +3,25 @@
 //! All database access goes through `impl Store` methods defined here.
 //! Uses `anyhow::Context` for actionable error messages on key operations.
 
+mod attempts;
+mod ci_fix;
+mod conflicts;
+mod events;
 mod external_sessions;
+mod merge_policy;
+mod messages;
 mod projects;
 mod rate_limits;
+mod schedules;
 mod sessions;
 mod stats;
 mod subtasks;
 mod sync;
 mod tasks;
+mod transitions;
+mod variants;
+mod verification;
+mod webhooks;
 
 pub use stats::ProjectStats;
 
@@ -51,6 +62,7 @@ mod tests {
     use super::super::Store;
     use super::super::models::{
         ClaudeProgressItem, ClaudeStatus, ExternalSession, PushMode, TaskMode, TaskStatus,
+        WebhookDeliveryStatus,
     };
 
     #[test]
@@ -2555,4 +2567,80 @@ mod tests {
         assert_eq!(sessions[0].output_tokens, 100);
         assert!(sessions[0].ended_at.is_some());
     }
+
+    // ── Webhook delivery queue ──
+
+    #[test]
+    fn test_webhook_delivery_enqueue_and_due() {
+        let store = Store::open_in_memory().unwrap();
+        let headers = vec!["X-Token: abc".to_string()];
+        let delivery = store
+            .enqueue_webhook_delivery("http://hook", &headers, "done", Some("t1"), "{}")
+            .unwrap();
+        assert_eq!(delivery.status, WebhookDeliveryStatus::Pending);
+        assert_eq!(delivery.attempts, 0);
+        assert_eq!(delivery.headers, headers);
+
+        let due = store
+            .list_due_webhook_deliveries("9999-01-01T00:00:00Z", 10)
+            .unwrap();
+        assert_eq!(due.len(), 1);
+        assert!(
+            store
+                .list_due_webhook_deliveries("2000-01-01T00:00:00Z", 10)
+                .unwrap()
+                .is_empty()
+        );
+
+        store.delete_webhook_delivery(&delivery.id).unwrap();
+        assert!(
+            store
+                .list_due_webhook_deliveries("9999-01-01T00:00:00Z", 10)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_webhook_delivery_failure_reschedules_then_gives_up() {
+        let store = Store::open_in_memory().unwrap();
+        let delivery = store
+            .enqueue_webhook_delivery("http://hook", &[], "ci_failed", None, "{}")
+            .unwrap();
+
+        store
+            .record_webhook_failure(&delivery.id, "HTTP 500", Some("9000-01-01T00:00:00Z"))
+            .unwrap();
+        let fetched = store.get_webhook_delivery(&delivery.id).unwrap();
+        assert_eq!(fetched.attempts, 1);
+        assert_eq!(fetched.status, WebhookDeliveryStatus::Pending);
+        assert_eq!(fetched.next_attempt_at, "9000-01-01T00:00:00Z");
+        assert_eq!(fetched.last_error.as_deref(), Some("HTTP 500"));
+        assert!(
+            store
+                .list_due_webhook_deliveries("8999-01-01T00:00:00Z", 10)
+                .unwrap()
+                .is_empty()
+        );
+
+        store
+            .record_webhook_failure(&delivery.id, "HTTP 502", None)
+            .unwrap();
+        let fetched = store.get_webhook_delivery(&delivery.id).unwrap();
+        assert_eq!(fetched.attempts, 2);
+        assert_eq!(fetched.status, WebhookDeliveryStatus::Failed);
+        assert!(
+            store
+                .list_due_webhook_deliveries("9999-01-01T00:00:00Z", 10)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            store
+                .list_webhook_deliveries(WebhookDeliveryStatus::Failed)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
 }
@@ -0,0 +1,251 @@
+//! Recurring task schedules and the task cloning they drive.
+
+use anyhow::{Context, Result};
+use rusqlite::{OptionalExtension, params};
+
+use crate::store::Store;
+use crate::store::models::{Subtask, Task, TaskMode, TaskSchedule};
+
+/// Column list for all queries that use `row_to_task_schedule`.
+const SCHEDULE_COLUMNS: &str = "task_id, spec, auto_launch, last_run_at, next_run_at, created_at";
+
+impl Store {
+    /// Attach (or replace) a task's schedule. The last run is kept when an
+    /// existing schedule is edited so the details overlay stays accurate.
+    pub fn set_task_schedule(
+        &self,
+        task_id: &str,
+        spec: &str,
+        auto_launch: bool,
+        next_run_at: &str,
+    ) -> Result<TaskSchedule> {
+        let now = chrono::Utc::now().to_rfc3339();
+        self.conn
+            .execute(
+                "INSERT INTO task_schedules (task_id, spec, auto_launch, next_run_at, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(task_id) DO UPDATE SET
+                     spec = excluded.spec,
+                     auto_launch = excluded.auto_launch,
+                     next_run_at = excluded.next_run_at",
+                params![task_id, spec, auto_launch, next_run_at, now],
+            )
+            .with_context(|| format!("failed to set schedule for task '{task_id}'"))?;
+        self.get_task_schedule(task_id)?
+            .with_context(|| format!("schedule for task '{task_id}' vanished after insert"))
+    }
+
+    pub fn clear_task_schedule(&self, task_id: &str) -> Result<()> {
+        self.conn
+            .execute(
+                "DELETE FROM task_schedules WHERE task_id = ?1",
+                params![task_id],
+            )
+            .with_context(|| format!("failed to clear schedule for task '{task_id}'"))?;
+        Ok(())
+    }
+
+    pub fn get_task_schedule(&self, task_id: &str) -> Result<Option<TaskSchedule>> {
+        let sql = format!("SELECT {SCHEDULE_COLUMNS} FROM task_schedules WHERE task_id = ?1");
+        let schedule = self
+            .conn
+            .query_row(&sql, params![task_id], Self::row_to_task_schedule)
+            .optional()?;
+        Ok(schedule)
+    }
+
+    /// All schedules, soonest next run first.
+    pub fn list_task_schedules(&self) -> Result<Vec<TaskSchedule>> {
+        let sql = format!(
+            "SELECT {SCHEDULE_COLUMNS} FROM task_schedules \
+             ORDER BY next_run_at IS NULL, next_run_at, task_id"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let schedules = stmt
+            .query_map([], Self::row_to_task_schedule)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(schedules)
+    }
+
+    /// Schedules whose next run is at or before `now` (RFC 3339, UTC).
+    /// Ordered by due time, then task id, so catch-up is deterministic.
+    pub fn due_task_schedules(&self, now: &str) -> Result<Vec<TaskSchedule>> {
+        let sql = format!(
+            "SELECT {SCHEDULE_COLUMNS} FROM task_schedules \
+             WHERE next_run_at IS NOT NULL AND next_run_at <= ?1 \
+             ORDER BY next_run_at, task_id"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let schedules = stmt
+            .query_map(params![now], Self::row_to_task_schedule)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(schedules)
+    }
+
+    /// Record a completed run and the next due time (`None` if the schedule
+    /// can never fire again).
+    pub fn record_schedule_run(
+        &self,
+        task_id: &str,
+        last_run_at: &str,
+        next_run_at: Option<&str>,
+    ) -> Result<()> {
+        self.conn
+            .execute(
+                "UPDATE task_schedules SET last_run_at = ?1, next_run_at = ?2 WHERE task_id = ?3",
+                params![last_run_at, next_run_at, task_id],
+            )
+            .with_context(|| format!("failed to record schedule run for task '{task_id}'"))?;
+        Ok(())
+    }
+
+    /// Copy a task (prompt, settings, merge policy and subtasks) into a new
+    /// pending task at the end of its project's queue. The branch is not
+    /// copied so each clone gets its own. `mode` overrides the source mode.
+    pub fn clone_task(&self, task_id: &str, mode: Option<TaskMode>) -> Result<Task> {
+        let source = self.get_task(task_id)?;
+        let subtasks = self.list_subtasks_for_task(task_id)?;
+        let mut clone_id = None;
+        self.in_transaction(|| {
+            clone_id = Some(self.insert_task_copy(&source, &subtasks, mode)?.id);
+            Ok(())
+        })
+        .with_context(|| format!("failed to clone task '{task_id}'"))?;
+        let clone_id = clone_id.context("clone transaction produced no task")?;
+        self.get_task(&clone_id)
+    }
+
+    /// Insert the copy for `clone_task`; the caller owns the transaction.
+    pub(super) fn insert_task_copy(
+        &self,
+        source: &Task,
+        subtasks: &[Subtask],
+        mode: Option<TaskMode>,
+    ) -> Result<Task> {
+        let clone = self.create_task(
+            &source.project_id,
+            &source.title,
+            &source.description,
+            mode.unwrap_or(source.mode),
+            None,
+            source.base.as_deref(),
+            source.push_mode,
+            source.review_loop,
+        )?;
+        if source.merge_policy.is_some() {
+            self.set_task_merge_policy(&clone.id, source.merge_policy)?;
+        }
+        for subtask in subtasks {
+            self.create_subtask(&clone.id, &subtask.title, &subtask.description)?;
+        }
+        Ok(clone)
+    }
+
+    fn row_to_task_schedule(row: &rusqlite::Row<'_>) -> rusqlite::Result<TaskSchedule> {
+        Ok(TaskSchedule {
+            task_id: row.get(0)?,
+            spec: row.get(1)?,
+            auto_launch: row.get(2)?,
+            last_run_at: row.get(3)?,
+            next_run_at: row.get(4)?,
+            created_at: row.get(5)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::{MergePolicy, PushMode, Store, TaskMode, TaskStatus};
+
+    fn make_task(store: &Store) -> String {
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        store
+            .create_task(
+                &project.id,
+                "Dependency audit",
+                "Audit dependencies",
+                TaskMode::Supervised,
+                Some("chore/audit"),
+                Some("develop"),
+                PushMode::Push,
+                true,
+            )
+            .unwrap()
+            .id
+    }
+
+    #[test]
+    fn schedules_upsert_and_report_due() {
+        let store = Store::open_in_memory().unwrap();
+        let task_id = make_task(&store);
+
+        store
+            .set_task_schedule(&task_id, "every 7d", false, "2026-01-08T00:00:00+00:00")
+            .unwrap();
+        store
+            .record_schedule_run(
+                &task_id,
+                "2026-01-08T00:00:05+00:00",
+                Some("2026-01-15T00:00:00+00:00"),
+            )
+            .unwrap();
+        let edited = store
+            .set_task_schedule(&task_id, "@daily", true, "2026-01-09T00:00:00+00:00")
+            .unwrap();
+        assert_eq!(edited.spec, "@daily");
+        assert!(edited.auto_launch);
+        assert_eq!(
+            edited.last_run_at.as_deref(),
+            Some("2026-01-08T00:00:05+00:00")
+        );
+
+        assert!(
+            store
+                .due_task_schedules("2026-01-08T23:59:59+00:00")
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            store
+                .due_task_schedules("2026-01-09T00:00:00+00:00")
+                .unwrap()
+                .len(),
+            1
+        );
+
+        store.clear_task_schedule(&task_id).unwrap();
+        assert!(store.get_task_schedule(&task_id).unwrap().is_none());
+        assert!(store.list_task_schedules().unwrap().is_empty());
+    }
+
+    #[test]
+    fn clone_task_copies_settings_and_subtasks() {
+        let store = Store::open_in_memory().unwrap();
+        let task_id = make_task(&store);
+        store
+            .set_task_merge_policy(&task_id, Some(MergePolicy::Green))
+            .unwrap();
+        store.create_subtask(&task_id, "Step 1", "Step 1").unwrap();
+        store.create_subtask(&task_id, "Step 2", "Step 2").unwrap();
+
+        let clone = store
+            .clone_task(&task_id, Some(TaskMode::Autonomous))
+            .unwrap();
+        assert_ne!(clone.id, task_id);
+        assert_eq!(clone.title, "Dependency audit");
+        assert_eq!(clone.status, TaskStatus::Pending);
+        assert_eq!(clone.mode, TaskMode::Autonomous);
+        assert_eq!(clone.branch, None);
+        assert_eq!(clone.base.as_deref(), Some("develop"));
+        assert_eq!(clone.push_mode, PushMode::Push);
+        assert!(clone.review_loop);
+        assert_eq!(clone.merge_policy, Some(MergePolicy::Green));
+        let titles: Vec<String> = store
+            .list_subtasks_for_task(&clone.id)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.title)
+            .collect();
+        assert_eq!(titles, ["Step 1", "Step 2"]);
+    }
+}
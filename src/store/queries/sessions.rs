@@ -5,6 +5,7 @@ use rusqlite::params;
 use tracing::warn;
 use uuid::Uuid;
 
+use crate::config::LayoutConfig;
 use crate::store::Store;
 use crate::store::models::{ClaudeProgressItem, ClaudeStatus, Session};
 
@@ -159,6 +160,27 @@ impl Store {
         Ok(())
     }
 
+    /// Save the session tab's pane layout so a restored tab gets the same panes.
+    pub fn set_session_layout(&self, id: &str, layout: &LayoutConfig) -> Result<()> {
+        let json = serde_json::to_string(layout)?;
+        self.conn.execute(
+            "UPDATE sessions SET layout = ?1 WHERE id = ?2",
+            params![json, id],
+        )?;
+        Ok(())
+    }
+
+    /// The session tab's saved pane layout, if one was saved.
+    pub fn session_layout(&self, id: &str) -> Result<Option<LayoutConfig>> {
+        let json: Option<String> = self.conn.query_row(
+            "SELECT layout FROM sessions WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        json.map(|json| serde_json::from_str(&json).context("invalid saved session layout"))
+            .transpose()
+    }
+
     pub fn update_session_progress(&self, id: &str, progress: &[ClaudeProgressItem]) -> Result<()> {
         let json = serde_json::to_string(progress)?;
         self.conn.execute(
@@ -167,11 +189,67 @@ impl Store {
         )?;
         Ok(())
     }
+
+    /// Open sessions across every project.
+    pub fn list_open_sessions(&self) -> Result<Vec<Session>> {
+        let sql = format!(
+            "SELECT {SESSION_COLUMNS} FROM sessions \
+             WHERE closed_at IS NULL \
+             ORDER BY created_at"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let sessions = stmt
+            .query_map([], Self::row_to_session)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(sessions)
+    }
+
+    /// Closed sessions that no task in the queue points at any more. Sessions
+    /// of archived tasks count, as do those of done tasks completed before
+    /// `archive_cutoff` (archived in the same run); their tasks just lose the
+    /// link when purged.
+    pub fn list_purgeable_sessions(
+        &self,
+        archive_cutoff: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Vec<Session>> {
+        let sql = format!(
+            "SELECT {SESSION_COLUMNS} FROM sessions \
+             WHERE closed_at IS NOT NULL \
+               AND NOT EXISTS (SELECT 1 FROM tasks \
+                   WHERE tasks.session_id = sessions.id AND tasks.archived_at IS NULL \
+                     AND NOT (tasks.status = 'done' AND IFNULL(tasks.completed_at < ?1, 0))) \
+             ORDER BY closed_at"
+        );
+        let cutoff = archive_cutoff.map(|c| c.to_rfc3339());
+        let mut stmt = self.conn.prepare(&sql)?;
+        let sessions = stmt
+            .query_map(params![cutoff], Self::row_to_session)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(sessions)
+    }
+
+    /// Delete a closed session, unlinking any (archived) tasks that still
+    /// reference it.
+    pub fn purge_session(&self, id: &str) -> Result<()> {
+        self.in_transaction(|| {
+            self.conn.execute(
+                "UPDATE tasks SET session_id = NULL WHERE session_id = ?1",
+                params![id],
+            )?;
+            self.conn.execute(
+                "DELETE FROM sessions WHERE id = ?1 AND closed_at IS NOT NULL",
+                params![id],
+            )?;
+            Ok(())
+        })
+        .with_context(|| format!("failed to purge session '{id}'"))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::store::{ClaudeProgressItem, ClaudeStatus, Store};
+    use crate::config::LayoutConfig;
+    use crate::store::{ClaudeProgressItem, ClaudeStatus, PushMode, Store, TaskMode, TaskStatus};
 
     fn setup(store: &Store) -> (String, String) {
         let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
@@ -197,6 +275,27 @@ mod tests {
         assert!(session.claude_session_id.is_none());
     }
 
+    #[test]
+    fn session_layout_round_trips() {
+        let store = Store::open_in_memory().unwrap();
+        let (_, sid) = setup(&store);
+        assert!(store.session_layout(&sid).unwrap().is_none());
+
+        let layout = LayoutConfig::Split {
+            direction: "vertical".to_string(),
+            ratio: Some(70),
+            first: Box::new(LayoutConfig::pane("claude")),
+            second: Box::new(LayoutConfig::Pane {
+                pane: "tests".to_string(),
+                cmd: Some("cargo watch -x test".to_string()),
+                env: [("RUST_LOG".to_string(), "debug".to_string())].into(),
+                restart_on_exit: true,
+            }),
+        };
+        store.set_session_layout(&sid, &layout).unwrap();
+        assert_eq!(store.session_layout(&sid).unwrap(), Some(layout));
+    }
+
     #[test]
     fn update_session_status_working_does_not_update_activity() {
         let store = Store::open_in_memory().unwrap();
@@ -327,4 +426,74 @@ mod tests {
         let session = store.get_session(&sid).unwrap();
         assert!(session.claude_progress.is_empty());
     }
+
+    #[test]
+    fn purgeable_sessions_exclude_open_and_queued_ones() {
+        let store = Store::open_in_memory().unwrap();
+        let (pid, open) = setup(&store);
+        let closed = |branch: &str| {
+            let id = store
+                .create_session(&pid, branch, "/tmp/wt2", branch)
+                .unwrap()
+                .id;
+            store.close_session(&id).unwrap();
+            id
+        };
+        let orphan = closed("orphan");
+        let queued = closed("queued");
+        let archived = closed("archived");
+        let task_on = |sid: &str| {
+            let task = store
+                .create_task(
+                    &pid,
+                    sid,
+                    "",
+                    TaskMode::Supervised,
+                    None,
+                    None,
+                    PushMode::Pr,
+                    false,
+                )
+                .unwrap();
+            store.assign_task_to_session(&task.id, sid).unwrap();
+            task.id
+        };
+        task_on(&queued);
+        let archived_task = task_on(&archived);
+        store
+            .update_task_status(&archived_task, TaskStatus::Working)
+            .unwrap();
+        store
+            .update_task_status(&archived_task, TaskStatus::Done)
+            .unwrap();
+        let purgeable = |cutoff| -> Vec<String> {
+            store
+                .list_purgeable_sessions(cutoff)
+                .unwrap()
+                .into_iter()
+                .map(|s| s.id)
+                .collect()
+        };
+        let cutoff = chrono::Utc::now() + chrono::Duration::seconds(1);
+        assert_eq!(purgeable(None), std::slice::from_ref(&orphan));
+        assert_eq!(purgeable(Some(cutoff)), [orphan.clone(), archived.clone()]);
+
+        store.archive_done_tasks(cutoff).unwrap();
+        let ids = purgeable(None);
+        assert_eq!(ids, [orphan.clone(), archived]);
+
+        for id in &ids {
+            store.purge_session(id).unwrap();
+        }
+        assert!(store.get_session(&orphan).is_err());
+        assert!(store.get_task(&archived_task).unwrap().session_id.is_none());
+        let open_ids: Vec<String> = store
+            .list_open_sessions()
+            .unwrap()
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(open_ids, [open]);
+        assert!(store.get_session(&queued).is_ok());
+    }
 }
@@ -343,13 +343,7 @@ mod tests {
             )
             .unwrap();
         // Default status is pending, move to draft
-        store
-            .conn
-            .execute(
-                "UPDATE tasks SET status = 'draft' WHERE id = ?1",
-                rusqlite::params![t3.id],
-            )
-            .unwrap();
+        store.update_task_status(&t3.id, TaskStatus::Draft).unwrap();
 
         let counts = store.count_tasks_by_status(&project.id).unwrap();
         // pending: t1, done: t2 (excluded), draft: t3
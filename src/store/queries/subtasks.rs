@@ -55,31 +55,6 @@ impl Store {
         Ok(subtasks)
     }
 
-    pub fn update_subtask_status(&self, id: &str, status: TaskStatus) -> Result<()> {
-        self.conn.execute(
-            "UPDATE subtasks SET status = ?1 WHERE id = ?2",
-            params![status.as_str(), id],
-        )?;
-
-        let now = chrono::Utc::now().to_rfc3339();
-        match status {
-            TaskStatus::Working => {
-                self.conn.execute(
-                    "UPDATE subtasks SET started_at = ?1 WHERE id = ?2 AND started_at IS NULL",
-                    params![now, id],
-                )?;
-            }
-            TaskStatus::Done => {
-                self.conn.execute(
-                    "UPDATE subtasks SET completed_at = ?1 WHERE id = ?2",
-                    params![now, id],
-                )?;
-            }
-            _ => {}
-        }
-        Ok(())
-    }
-
     pub fn delete_subtask(&self, id: &str) -> Result<()> {
         self.conn
             .execute("DELETE FROM subtasks WHERE id = ?1", params![id])?;
@@ -99,6 +74,21 @@ impl Store {
         )
     }
 
+    /// The first subtask that is not done: the step a task is on, or will
+    /// resume at after a failure.
+    pub fn current_subtask(&self, task_id: &str) -> Result<Option<Subtask>> {
+        let sql = format!(
+            "SELECT {SUBTASK_COLUMNS} FROM subtasks \
+             WHERE task_id = ?1 AND status != 'done' \
+             ORDER BY sort_order, created_at \
+             LIMIT 1"
+        );
+        optional(
+            self.conn
+                .query_row(&sql, params![task_id], Self::row_to_subtask),
+        )
+    }
+
     pub fn subtask_count(&self, task_id: &str) -> Result<(i64, i64)> {
         let (total, done) = self.conn.query_row(
             "SELECT COUNT(*),
@@ -229,6 +219,33 @@ mod tests {
         assert!(store.next_pending_subtask(&task_id).unwrap().is_none());
     }
 
+    #[test]
+    fn current_subtask_includes_failed_steps() {
+        let store = Store::open_in_memory().unwrap();
+        let (_, task_id) = make_task(&store);
+
+        let s1 = store.create_subtask(&task_id, "step 1", "").unwrap();
+        let s2 = store.create_subtask(&task_id, "step 2", "").unwrap();
+        store
+            .update_subtask_status(&s1.id, TaskStatus::Done)
+            .unwrap();
+        store
+            .update_subtask_status(&s2.id, TaskStatus::Working)
+            .unwrap();
+        store
+            .update_subtask_status(&s2.id, TaskStatus::Error)
+            .unwrap();
+
+        let current = store.current_subtask(&task_id).unwrap().unwrap();
+        assert_eq!(current.id, s2.id);
+        assert!(store.next_pending_subtask(&task_id).unwrap().is_none());
+
+        store
+            .update_subtask_status(&s2.id, TaskStatus::Done)
+            .unwrap();
+        assert!(store.current_subtask(&task_id).unwrap().is_none());
+    }
+
     #[test]
     fn subtask_sort_order_auto_increments() {
         let store = Store::open_in_memory().unwrap();
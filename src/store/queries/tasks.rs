@@ -1,12 +1,14 @@
 //! Task CRUD operations and queries.
 
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result};
 use rusqlite::params;
 use tracing::warn;
 use uuid::Uuid;
 
 use crate::store::Store;
-use crate::store::models::{CiStatus, PushMode, Task, TaskMode, TaskStatus};
+use crate::store::models::{
+    CiStatus, MergePolicy, PushMode, Task, TaskEventKind, TaskMode, TaskStatus,
+};
 
 use super::optional;
 
@@ -17,7 +19,7 @@ pub(super) const TASK_COLUMNS: &str = "\
     id, project_id, title, description, status, mode, session_id, \
     created_at, updated_at, started_at, completed_at, \
     input_tokens, output_tokens, sort_order, pr_url, \
-    branch, push_mode, ci_status, review_loop, base";
+    branch, push_mode, ci_status, review_loop, base, merge_policy, archived_at";
 
 impl Store {
     #[expect(
@@ -47,6 +49,7 @@ impl Store {
                 params![id, project_id, title, description, mode.as_str(), max_order + 1, branch, base, push_mode.as_str(), review_loop],
             )
             .with_context(|| format!("failed to create task '{title}'"))?;
+        self.record_task_event(&id, TaskEventKind::Created, mode.as_str())?;
         self.get_task(&id)
     }
 
@@ -59,10 +62,12 @@ impl Store {
         Ok(task)
     }
 
+    /// The project's task queue. Archived tasks are left out; see
+    /// `list_archived_tasks_for_project`.
     pub fn list_tasks_for_project(&self, project_id: &str) -> Result<Vec<Task>> {
         let sql = format!(
             "SELECT {TASK_COLUMNS} FROM tasks \
-             WHERE project_id = ?1 \
+             WHERE project_id = ?1 AND archived_at IS NULL \
              ORDER BY sort_order, created_at"
         );
         let mut stmt = self.conn.prepare(&sql)?;
@@ -72,6 +77,28 @@ impl Store {
         Ok(tasks)
     }
 
+    /// List tasks across every project for the "all projects" dashboard.
+    /// Tasks needing attention (in review, CI failed, conflict) come first,
+    /// then by project name and queue order.
+    pub fn list_tasks_all_projects(&self) -> Result<Vec<Task>> {
+        let sql = format!(
+            "SELECT {TASK_COLUMNS} FROM tasks \
+             WHERE archived_at IS NULL \
+             ORDER BY CASE status \
+                 WHEN 'in_review' THEN 0 \
+                 WHEN 'ci_failed' THEN 1 \
+                 WHEN 'conflict' THEN 2 \
+                 ELSE 3 END, \
+             (SELECT name FROM projects WHERE projects.id = tasks.project_id), \
+             sort_order, created_at"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let tasks = stmt
+            .query_map([], Self::row_to_task)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(tasks)
+    }
+
     #[expect(clippy::too_many_arguments, reason = "task update requires all fields")]
     pub fn update_task(
         &self,
@@ -129,6 +156,7 @@ impl Store {
         let mode_str: String = row.get(5)?;
         let push_mode_str: String = row.get(16)?;
         let ci_status_str: Option<String> = row.get(17)?;
+        let merge_policy_str: Option<String> = row.get(20)?;
         let id: String = row.get(0)?;
         Ok(Task {
             status: status_str.parse().unwrap_or_else(|_| {
@@ -148,6 +176,11 @@ impl Store {
                     warn!(task_id = %id, raw = %s, "unknown CI status in DB, defaulting to None");
                 }).ok()
             }),
+            merge_policy: merge_policy_str.and_then(|s| {
+                s.parse::<MergePolicy>().map_err(|_| {
+                    warn!(task_id = %id, raw = %s, "unknown merge policy in DB, defaulting to None");
+                }).ok()
+            }),
             id,
             project_id: row.get(1)?,
             title: row.get(2)?,
@@ -164,6 +197,7 @@ impl Store {
             branch: row.get(15)?,
             review_loop: row.get::<_, i64>(18).unwrap_or(0) != 0,
             base: row.get(19)?,
+            archived_at: row.get(21)?,
         })
     }
 
@@ -173,84 +207,35 @@ impl Store {
             "UPDATE tasks SET title = ?1, updated_at = ?2 WHERE id = ?3",
             params![title, now, id],
         )?;
+        self.record_task_event(id, TaskEventKind::Action, &format!("renamed to '{title}'"))?;
         Ok(())
     }
 
+    /// Set the task's PR URL, recording a timeline event when it changes.
     pub fn update_task_pr_url(&self, id: &str, pr_url: &str) -> Result<()> {
-        self.conn.execute(
-            "UPDATE tasks SET pr_url = ?1 WHERE id = ?2",
+        let changed = self.conn.execute(
+            "UPDATE tasks SET pr_url = ?1 WHERE id = ?2 AND pr_url IS NOT ?1",
             params![pr_url, id],
         )?;
+        if changed > 0 {
+            self.record_task_event(id, TaskEventKind::Pr, pr_url)?;
+        }
         Ok(())
     }
 
+    /// Set the task's CI status, recording a timeline event when it changes.
     pub fn update_task_ci_status(&self, id: &str, ci_status: Option<CiStatus>) -> Result<()> {
-        let val = ci_status.map(|s| s.as_str().to_string());
-        self.conn.execute(
-            "UPDATE tasks SET ci_status = ?1 WHERE id = ?2",
+        let val = ci_status.map(|s| s.as_str());
+        let changed = self.conn.execute(
+            "UPDATE tasks SET ci_status = ?1 WHERE id = ?2 AND ci_status IS NOT ?1",
             params![val, id],
         )?;
-        Ok(())
-    }
-
-    pub fn update_task_status(&self, id: &str, status: TaskStatus) -> Result<()> {
-        if !self.try_update_task_status(id, status)? {
-            let current_status_str: String = self
-                .conn
-                .query_row(
-                    "SELECT status FROM tasks WHERE id = ?1",
-                    params![id],
-                    |row| row.get(0),
-                )
-                .context("task not found for status update")?;
-            bail!("invalid task status transition: {current_status_str} -> {status} (task {id})");
+        if changed > 0 {
+            self.record_task_event(id, TaskEventKind::Ci, val.unwrap_or("cleared"))?;
         }
         Ok(())
     }
 
-    /// Attempt a task status transition, returning `Ok(true)` if the transition
-    /// was applied or `Ok(false)` if it was invalid. Returns `Err` only on
-    /// database errors. Use this for background/polling code where stale state
-    /// makes invalid transitions expected rather than exceptional.
-    pub fn try_update_task_status(&self, id: &str, status: TaskStatus) -> Result<bool> {
-        let current_status_str: String = self
-            .conn
-            .query_row(
-                "SELECT status FROM tasks WHERE id = ?1",
-                params![id],
-                |row| row.get(0),
-            )
-            .context("task not found for status update")?;
-        let current_status: TaskStatus = current_status_str.parse().unwrap_or(TaskStatus::Pending);
-
-        if !current_status.can_transition_to(status) {
-            return Ok(false);
-        }
-
-        let now = chrono::Utc::now().to_rfc3339();
-        self.conn.execute(
-            "UPDATE tasks SET status = ?1, updated_at = ?2 WHERE id = ?3",
-            params![status.as_str(), now, id],
-        )?;
-
-        match status {
-            TaskStatus::Working => {
-                self.conn.execute(
-                    "UPDATE tasks SET started_at = ?1 WHERE id = ?2 AND started_at IS NULL",
-                    params![now, id],
-                )?;
-            }
-            TaskStatus::Done => {
-                self.conn.execute(
-                    "UPDATE tasks SET completed_at = ?1 WHERE id = ?2",
-                    params![now, id],
-                )?;
-            }
-            _ => {}
-        }
-        Ok(true)
-    }
-
     pub fn assign_task_to_session(&self, task_id: &str, session_id: &str) -> Result<()> {
         self.conn.execute(
             "UPDATE tasks SET session_id = ?1 WHERE id = ?2",
@@ -337,10 +322,12 @@ impl Store {
 
     /// Find all pending autonomous tasks not assigned to any session.
     /// Used on startup to auto-launch tasks that were pending when claustre was closed.
+    /// Scheduled tasks are templates for their clones and never launch themselves.
     pub fn pending_autonomous_tasks_unassigned(&self) -> Result<Vec<Task>> {
         let sql = format!(
             "SELECT {TASK_COLUMNS} FROM tasks \
              WHERE status = 'pending' AND mode = 'autonomous' AND session_id IS NULL \
+               AND id NOT IN (SELECT task_id FROM task_schedules) \
              ORDER BY sort_order, created_at"
         );
         let mut stmt = self.conn.prepare(&sql)?;
@@ -349,6 +336,59 @@ impl Store {
             .collect::<std::result::Result<Vec<_>, _>>()?;
         Ok(tasks)
     }
+
+    /// Archived tasks of a project, most recently archived first.
+    pub fn list_archived_tasks_for_project(&self, project_id: &str) -> Result<Vec<Task>> {
+        let sql = format!(
+            "SELECT {TASK_COLUMNS} FROM tasks \
+             WHERE project_id = ?1 AND archived_at IS NOT NULL \
+             ORDER BY archived_at DESC, sort_order"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let tasks = stmt
+            .query_map(params![project_id], Self::row_to_task)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(tasks)
+    }
+
+    /// Done tasks, across all projects, that were completed before `cutoff`
+    /// and aren't archived yet. Schedule templates are kept.
+    pub fn list_archivable_tasks(
+        &self,
+        cutoff: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Task>> {
+        let sql = format!(
+            "SELECT {TASK_COLUMNS} FROM tasks \
+             WHERE status = 'done' AND archived_at IS NULL \
+               AND completed_at IS NOT NULL AND completed_at < ?1 \
+               AND id NOT IN (SELECT task_id FROM task_schedules) \
+             ORDER BY completed_at"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let tasks = stmt
+            .query_map(params![cutoff.to_rfc3339()], Self::row_to_task)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(tasks)
+    }
+
+    /// Archive every task `list_archivable_tasks` returns for `cutoff`.
+    /// Returns how many were archived.
+    pub fn archive_done_tasks(&self, cutoff: chrono::DateTime<chrono::Utc>) -> Result<usize> {
+        let tasks = self.list_archivable_tasks(cutoff)?;
+        let now = chrono::Utc::now().to_rfc3339();
+        self.in_transaction(|| {
+            for task in &tasks {
+                self.conn.execute(
+                    "UPDATE tasks SET archived_at = ?1 WHERE id = ?2",
+                    params![now, task.id],
+                )?;
+                self.record_task_event(&task.id, TaskEventKind::Action, "archived")?;
+            }
+            Ok(())
+        })
+        .context("failed to archive done tasks")?;
+        Ok(tasks.len())
+    }
 }
 
 #[cfg(test)]
@@ -771,6 +811,40 @@ mod tests {
         assert_eq!(results[0].title, "auto");
     }
 
+    #[test]
+    fn pending_autonomous_unassigned_skips_schedule_templates() {
+        let store = Store::open_in_memory().unwrap();
+        let pid = setup(&store);
+        let template = store
+            .create_task(
+                &pid,
+                "nightly audit",
+                "",
+                TaskMode::Autonomous,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap();
+        store
+            .set_task_schedule(&template.id, "@daily", true, "2026-01-09T00:00:00+00:00")
+            .unwrap();
+
+        assert!(
+            store
+                .pending_autonomous_tasks_unassigned()
+                .unwrap()
+                .is_empty()
+        );
+
+        store.clear_task_schedule(&template.id).unwrap();
+        assert_eq!(
+            store.pending_autonomous_tasks_unassigned().unwrap().len(),
+            1
+        );
+    }
+
     // ── Next pending for session ──
 
     #[test]
@@ -996,4 +1070,106 @@ mod tests {
         assert_eq!(t.push_mode, PushMode::Push);
         assert!(t.review_loop);
     }
+
+    #[test]
+    fn list_tasks_all_projects_puts_attention_first() {
+        let store = Store::open_in_memory().unwrap();
+        let beta = store
+            .create_project("beta", "/tmp/beta", "main", true)
+            .unwrap()
+            .id;
+        let alpha = store
+            .create_project("alpha", "/tmp/alpha", "main", true)
+            .unwrap()
+            .id;
+        let create = |pid: &str, title: &str| {
+            store
+                .create_task(
+                    pid,
+                    title,
+                    "",
+                    TaskMode::Supervised,
+                    None,
+                    None,
+                    PushMode::Pr,
+                    false,
+                )
+                .unwrap()
+                .id
+        };
+        create(&beta, "beta pending");
+        create(&alpha, "alpha pending");
+        let review = create(&beta, "beta review");
+        store
+            .update_task_status(&review, TaskStatus::Working)
+            .unwrap();
+        store
+            .update_task_status(&review, TaskStatus::InReview)
+            .unwrap();
+
+        let titles: Vec<String> = store
+            .list_tasks_all_projects()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.title)
+            .collect();
+        assert_eq!(titles, ["beta review", "alpha pending", "beta pending"]);
+    }
+
+    #[test]
+    fn archive_hides_old_done_tasks_from_the_queue() {
+        let store = Store::open_in_memory().unwrap();
+        let pid = setup(&store);
+        let create = |title: &str| {
+            store
+                .create_task(
+                    &pid,
+                    title,
+                    "",
+                    TaskMode::Supervised,
+                    None,
+                    None,
+                    PushMode::Pr,
+                    false,
+                )
+                .unwrap()
+                .id
+        };
+        let old = create("old");
+        let recent = create("recent");
+        let open = create("open");
+        for id in [&old, &recent] {
+            store.update_task_status(id, TaskStatus::Working).unwrap();
+            store.update_task_status(id, TaskStatus::Done).unwrap();
+        }
+        store
+            .conn
+            .execute(
+                "UPDATE tasks SET completed_at = '2020-01-01T00:00:00+00:00' WHERE id = ?1",
+                rusqlite::params![old],
+            )
+            .unwrap();
+
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(30);
+        let archivable = store.list_archivable_tasks(cutoff).unwrap();
+        assert_eq!(archivable.len(), 1);
+        assert_eq!(archivable[0].id, old);
+        assert_eq!(store.archive_done_tasks(cutoff).unwrap(), 1);
+        assert_eq!(store.archive_done_tasks(cutoff).unwrap(), 0);
+
+        let queue: Vec<String> = store
+            .list_tasks_for_project(&pid)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(queue, [recent, open]);
+        assert_eq!(store.list_tasks_all_projects().unwrap().len(), 2);
+
+        let archived = store.list_archived_tasks_for_project(&pid).unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].id, old);
+        assert!(archived[0].archived_at.is_some());
+        assert!(store.get_task(&old).unwrap().archived_at.is_some());
+    }
 }
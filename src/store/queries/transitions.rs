@@ -0,0 +1,272 @@
+//! The task and subtask state machines. Every status write goes through
+//! `Store::transition`, which rejects moves the machine doesn't allow with
+//! an `IllegalTransition`, stamps `started_at`/`completed_at`, and records
+//! the change on the task timeline.
+//!
+//! Timestamps follow the status: `started_at` is set on the first move to
+//! `working` and kept from then on, and `completed_at` is set exactly when
+//! the status is `done`.
+
+use anyhow::{Context, Result};
+use rusqlite::params;
+
+use crate::store::Store;
+use crate::store::models::{IllegalTransition, TaskEventKind, TaskStatus};
+
+/// Which state machine a status write belongs to.
+#[derive(Debug, Clone, Copy)]
+enum Machine {
+    Task,
+    Subtask,
+}
+
+impl Machine {
+    fn entity(self) -> &'static str {
+        match self {
+            Self::Task => "task",
+            Self::Subtask => "subtask",
+        }
+    }
+
+    fn allows(self, from: TaskStatus, to: TaskStatus) -> bool {
+        match self {
+            Self::Task => from.can_transition_to(to),
+            Self::Subtask => from.can_subtask_transition_to(to),
+        }
+    }
+
+    /// Current status, owning task ID and title of the row.
+    fn select_sql(self) -> &'static str {
+        match self {
+            Self::Task => "SELECT status, id, title FROM tasks WHERE id = ?1",
+            Self::Subtask => "SELECT status, task_id, title FROM subtasks WHERE id = ?1",
+        }
+    }
+
+    /// Compare-and-set update: `?1` now, `?2` new status, `?3` id, `?4`
+    /// the status read before checking the move.
+    fn update_sql(self, to: TaskStatus) -> String {
+        let (table, touch) = match self {
+            Self::Task => ("tasks", ", updated_at = ?1"),
+            Self::Subtask => ("subtasks", ""),
+        };
+        let stamps = match to {
+            TaskStatus::Working => ", started_at = COALESCE(started_at, ?1)",
+            TaskStatus::Done => ", completed_at = ?1",
+            _ => "",
+        };
+        format!("UPDATE {table} SET status = ?2{touch}{stamps} WHERE id = ?3 AND status = ?4")
+    }
+}
+
+/// How often a write is retried when another process changed the status
+/// between the read and the compare-and-set.
+const MAX_RACE_RETRIES: usize = 3;
+
+impl Store {
+    /// Move a task to `status`. Fails with an `IllegalTransition` (check
+    /// with `err.is::<IllegalTransition>()`) when the move isn't allowed.
+    pub fn update_task_status(&self, id: &str, status: TaskStatus) -> Result<()> {
+        self.transition(Machine::Task, id, status)
+    }
+
+    /// Attempt a task status transition, returning `Ok(true)` if the transition
+    /// was applied or `Ok(false)` if it was invalid. Returns `Err` only on
+    /// database errors. Use this for background/polling code where stale state
+    /// makes invalid transitions expected rather than exceptional.
+    pub fn try_update_task_status(&self, id: &str, status: TaskStatus) -> Result<bool> {
+        match self.update_task_status(id, status) {
+            Ok(()) => Ok(true),
+            Err(e) if e.is::<IllegalTransition>() => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Move a subtask to `status` under the step state machine.
+    pub fn update_subtask_status(&self, id: &str, status: TaskStatus) -> Result<()> {
+        self.transition(Machine::Subtask, id, status)
+    }
+
+    fn transition(&self, machine: Machine, id: &str, to: TaskStatus) -> Result<()> {
+        let entity = machine.entity();
+        for _ in 0..MAX_RACE_RETRIES {
+            let (from_str, task_id, title): (String, String, String) = self
+                .conn
+                .query_row(machine.select_sql(), params![id], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                })
+                .with_context(|| format!("{entity} not found for status update"))?;
+            let from: TaskStatus = from_str.parse().unwrap_or(TaskStatus::Pending);
+            if from == to {
+                return Ok(());
+            }
+            if !machine.allows(from, to) {
+                return Err(IllegalTransition {
+                    entity,
+                    id: id.to_string(),
+                    from,
+                    to,
+                }
+                .into());
+            }
+
+            let now = chrono::Utc::now().to_rfc3339();
+            let changed = self
+                .conn
+                .execute(
+                    &machine.update_sql(to),
+                    params![now, to.as_str(), id, from_str],
+                )
+                .with_context(|| format!("failed to move {entity} '{id}' to {to}"))?;
+            if changed == 0 {
+                // Someone else moved it first; judge the move from the new status
+                continue;
+            }
+
+            let (kind, detail) = match machine {
+                Machine::Task => (TaskEventKind::Status, format!("{from} → {to}")),
+                Machine::Subtask => (TaskEventKind::Subtask, format!("{title}: {from} → {to}")),
+            };
+            return self.record_task_event(&task_id, kind, &detail);
+        }
+        anyhow::bail!("{entity} '{id}' kept changing status while moving it to {to}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::store::{
+        IllegalTransition, PushMode, Store, TaskEvent, TaskEventKind, TaskMode, TaskStatus,
+    };
+
+    fn status() -> impl Strategy<Value = TaskStatus> {
+        prop::sample::select(TaskStatus::ALL.to_vec())
+    }
+
+    fn store_with_task() -> (Store, String) {
+        let store = Store::open_in_memory().unwrap();
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        let task = store
+            .create_task(
+                &project.id,
+                "task",
+                "",
+                TaskMode::Supervised,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap();
+        (store, task.id)
+    }
+
+    /// The `from → to` pairs recorded for events of `kind`.
+    fn recorded_moves(events: &[TaskEvent], kind: TaskEventKind) -> Vec<(TaskStatus, TaskStatus)> {
+        events
+            .iter()
+            .filter(|e| e.kind == kind)
+            .map(|e| {
+                let moved = e.detail.rsplit(": ").next().unwrap();
+                let (from, to) = moved.split_once(" → ").unwrap();
+                (from.parse().unwrap(), to.parse().unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_status_is_reachable_and_done_is_terminal() {
+        let mut reached = vec![TaskStatus::Pending];
+        let mut i = 0;
+        while let Some(&from) = reached.get(i) {
+            for to in TaskStatus::ALL {
+                if from.can_transition_to(to) && !reached.contains(&to) {
+                    reached.push(to);
+                }
+            }
+            i += 1;
+        }
+        assert_eq!(reached.len(), TaskStatus::ALL.len());
+        for to in TaskStatus::ALL {
+            assert_eq!(
+                TaskStatus::Done.can_transition_to(to),
+                to == TaskStatus::Done
+            );
+            assert_eq!(
+                TaskStatus::Done.can_subtask_transition_to(to),
+                to == TaskStatus::Done
+            );
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn task_writes_never_leave_the_state_machine(
+            targets in prop::collection::vec(status(), 0..40),
+        ) {
+            let (store, task_id) = store_with_task();
+            let mut model = TaskStatus::Pending;
+            let mut moves = Vec::new();
+            for to in targets {
+                let legal = model.can_transition_to(to);
+                match store.update_task_status(&task_id, to) {
+                    Ok(()) => {
+                        prop_assert!(legal, "{model} -> {to} was accepted");
+                        if model != to {
+                            moves.push((model, to));
+                        }
+                        model = to;
+                    }
+                    Err(e) => {
+                        prop_assert!(!legal, "{model} -> {to} was rejected: {e:#}");
+                        let illegal = e.downcast_ref::<IllegalTransition>().unwrap();
+                        prop_assert_eq!((illegal.from, illegal.to), (model, to));
+                    }
+                }
+
+                let task = store.get_task(&task_id).unwrap();
+                prop_assert_eq!(task.status, model);
+                prop_assert_eq!(task.completed_at.is_some(), model == TaskStatus::Done);
+                let worked = moves.iter().any(|&(_, to)| to == TaskStatus::Working);
+                prop_assert_eq!(task.started_at.is_some(), worked);
+            }
+
+            let events = store.list_task_events(&task_id).unwrap();
+            prop_assert_eq!(recorded_moves(&events, TaskEventKind::Status), moves);
+        }
+
+        #[test]
+        fn subtask_writes_never_leave_the_state_machine(
+            targets in prop::collection::vec(status(), 0..40),
+        ) {
+            let (store, task_id) = store_with_task();
+            let subtask = store.create_subtask(&task_id, "step", "").unwrap();
+            let mut model = TaskStatus::Pending;
+            let mut moves = Vec::new();
+            for to in targets {
+                let legal = model.can_subtask_transition_to(to);
+                let result = store.update_subtask_status(&subtask.id, to);
+                prop_assert_eq!(result.is_ok(), legal, "{} -> {}", model, to);
+                if let Err(e) = result {
+                    prop_assert!(e.is::<IllegalTransition>());
+                } else {
+                    if model != to {
+                        moves.push((model, to));
+                    }
+                    model = to;
+                }
+
+                let current = store.get_subtask(&subtask.id).unwrap();
+                prop_assert_eq!(current.status, model);
+                prop_assert_eq!(current.completed_at.is_some(), model == TaskStatus::Done);
+            }
+
+            let events = store.list_task_events(&task_id).unwrap();
+            prop_assert_eq!(recorded_moves(&events, TaskEventKind::Subtask), moves);
+        }
+    }
+}
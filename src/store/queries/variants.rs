@@ -0,0 +1,300 @@
+//! Fan-out variants: child tasks that run the same prompt side by side, and
+//! the promotion of a winner back onto the parent.
+
+use anyhow::{Context, Result, bail};
+use rusqlite::{OptionalExtension, params};
+use tracing::warn;
+
+use crate::store::Store;
+use crate::store::models::{PushMode, Task, TaskMode, TaskStatus, TaskVariant, VariantTestStatus};
+
+/// Column list for all queries that use `row_to_task_variant`.
+const VARIANT_COLUMNS: &str =
+    "task_id, parent_task_id, variant, model, effort, test_status, test_output, created_at";
+
+impl Store {
+    /// Fan a pending task out into one supervised child task per
+    /// `(model, effort)` pair and mark the parent `working` until a winner is
+    /// promoted. Children are titled `<title> [#n]`.
+    pub fn fan_out_task(
+        &self,
+        parent_task_id: &str,
+        variants: &[(Option<&str>, Option<&str>)],
+    ) -> Result<Vec<Task>> {
+        let parent = self.get_task(parent_task_id)?;
+        if variants.len() < 2 {
+            bail!("a fan-out needs at least two variants");
+        }
+        if parent.status != TaskStatus::Pending {
+            bail!(
+                "only pending tasks can be fanned out (task is {})",
+                parent.status
+            );
+        }
+        if parent.mode == TaskMode::Exploration {
+            bail!("exploration tasks have no prompt to fan out");
+        }
+        if self.get_task_variant(parent_task_id)?.is_some()
+            || !self.list_task_variants(parent_task_id)?.is_empty()
+        {
+            bail!("task '{}' is already part of a fan-out", parent.title);
+        }
+
+        let subtasks = self.list_subtasks_for_task(parent_task_id)?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let mut child_ids = Vec::with_capacity(variants.len());
+        self.in_transaction(|| {
+            for (n, (model, effort)) in (1_i64..).zip(variants) {
+                let child = self.insert_task_copy(&parent, &subtasks, Some(TaskMode::Supervised))?;
+                self.update_task_title(&child.id, &format!("{} [#{n}]", parent.title))?;
+                self.conn.execute(
+                    "INSERT INTO task_variants (task_id, parent_task_id, variant, model, effort, created_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![child.id, parent_task_id, n, model, effort, now],
+                )?;
+                child_ids.push(child.id);
+            }
+            self.update_task_status(parent_task_id, TaskStatus::Working)?;
+            Ok(())
+        })
+        .with_context(|| format!("failed to fan out task '{parent_task_id}'"))?;
+        child_ids.iter().map(|id| self.get_task(id)).collect()
+    }
+
+    /// The variant record if `task_id` is a fan-out child.
+    pub fn get_task_variant(&self, task_id: &str) -> Result<Option<TaskVariant>> {
+        let sql = format!("SELECT {VARIANT_COLUMNS} FROM task_variants WHERE task_id = ?1");
+        let variant = self
+            .conn
+            .query_row(&sql, params![task_id], Self::row_to_task_variant)
+            .optional()?;
+        Ok(variant)
+    }
+
+    /// A parent's variants in fan-out order.
+    pub fn list_task_variants(&self, parent_task_id: &str) -> Result<Vec<TaskVariant>> {
+        let sql = format!(
+            "SELECT {VARIANT_COLUMNS} FROM task_variants \
+             WHERE parent_task_id = ?1 \
+             ORDER BY variant"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let variants = stmt
+            .query_map(params![parent_task_id], Self::row_to_task_variant)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(variants)
+    }
+
+    pub fn set_variant_test_result(
+        &self,
+        task_id: &str,
+        status: VariantTestStatus,
+        output: Option<&str>,
+    ) -> Result<()> {
+        self.conn
+            .execute(
+                "UPDATE task_variants SET test_status = ?1, test_output = ?2 WHERE task_id = ?3",
+                params![status.as_str(), output, task_id],
+            )
+            .with_context(|| format!("failed to record test result for variant '{task_id}'"))?;
+        Ok(())
+    }
+
+    /// Hand the winning variant's session, branch and usage to the parent and
+    /// delete every child task of the fan-out. A PR-mode parent moves to
+    /// `in_review` with `pr_url`; a push-mode parent is `done`. Returns the
+    /// updated parent.
+    pub fn promote_variant(&self, winner_task_id: &str, pr_url: Option<&str>) -> Result<Task> {
+        let variant = self
+            .get_task_variant(winner_task_id)?
+            .with_context(|| format!("task '{winner_task_id}' is not a fan-out variant"))?;
+        let winner = self.get_task(winner_task_id)?;
+        let parent = self.get_task(&variant.parent_task_id)?;
+        let branch = match winner.session_id.as_deref() {
+            Some(id) => Some(self.get_session(id)?.branch_name),
+            None => winner.branch.clone(),
+        };
+        let status = match (parent.push_mode, pr_url) {
+            (PushMode::Pr, Some(_)) => TaskStatus::InReview,
+            (PushMode::Pr, None) => bail!("a PR-mode task needs a PR URL to be promoted"),
+            (PushMode::Push, _) => TaskStatus::Done,
+        };
+        self.in_transaction(|| {
+            self.conn.execute(
+                "DELETE FROM tasks WHERE id IN
+                     (SELECT task_id FROM task_variants WHERE parent_task_id = ?1)",
+                params![parent.id],
+            )?;
+            self.conn.execute(
+                "UPDATE tasks SET session_id = ?1, branch = ?2, started_at = ?3,
+                     input_tokens = ?4, output_tokens = ?5
+                 WHERE id = ?6",
+                params![
+                    winner.session_id,
+                    branch,
+                    winner.started_at,
+                    winner.input_tokens,
+                    winner.output_tokens,
+                    parent.id,
+                ],
+            )?;
+            if let Some(url) = pr_url {
+                self.update_task_pr_url(&parent.id, url)?;
+            }
+            self.update_task_status(&parent.id, status)?;
+            Ok(())
+        })
+        .with_context(|| format!("failed to promote variant '{winner_task_id}'"))?;
+        self.get_task(&parent.id)
+    }
+
+    /// Delete a variant's child task. When the last variant goes, the parent
+    /// returns to `pending` so it can be launched or fanned out again.
+    pub fn discard_variant(&self, task_id: &str) -> Result<()> {
+        let variant = self
+            .get_task_variant(task_id)?
+            .with_context(|| format!("task '{task_id}' is not a fan-out variant"))?;
+        self.in_transaction(|| {
+            self.delete_task(task_id)?;
+            let parent = self.get_task(&variant.parent_task_id)?;
+            if self.list_task_variants(&parent.id)?.is_empty()
+                && parent.status == TaskStatus::Working
+                && parent.session_id.is_none()
+            {
+                self.update_task_status(&parent.id, TaskStatus::Pending)?;
+            }
+            Ok(())
+        })
+        .with_context(|| format!("failed to discard variant '{task_id}'"))
+    }
+
+    fn row_to_task_variant(row: &rusqlite::Row<'_>) -> rusqlite::Result<TaskVariant> {
+        let task_id: String = row.get(0)?;
+        let test_status: Option<String> = row.get(5)?;
+        Ok(TaskVariant {
+            test_status: test_status.and_then(|raw| {
+                raw.parse()
+                    .inspect_err(|_| warn!(%task_id, %raw, "unknown variant test status in DB"))
+                    .ok()
+            }),
+            task_id,
+            parent_task_id: row.get(1)?,
+            variant: row.get(2)?,
+            model: row.get(3)?,
+            effort: row.get(4)?,
+            test_output: row.get(6)?,
+            created_at: row.get(7)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::{PushMode, Store, TaskMode, TaskStatus, VariantTestStatus};
+
+    fn make_task(store: &Store, push_mode: PushMode) -> String {
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        store
+            .create_task(
+                &project.id,
+                "Speed up parser",
+                "Make the parser faster",
+                TaskMode::Autonomous,
+                None,
+                None,
+                push_mode,
+                false,
+            )
+            .unwrap()
+            .id
+    }
+
+    #[test]
+    fn fan_out_creates_linked_supervised_children() {
+        let store = Store::open_in_memory().unwrap();
+        let parent_id = make_task(&store, PushMode::Pr);
+        store.create_subtask(&parent_id, "Step", "Step").unwrap();
+
+        assert!(
+            store
+                .fan_out_task(&parent_id, &[(Some("claude-opus-4-6"), None)])
+                .is_err()
+        );
+        let children = store
+            .fan_out_task(
+                &parent_id,
+                &[
+                    (Some("claude-opus-4-6"), Some("max")),
+                    (None, Some("medium")),
+                ],
+            )
+            .unwrap();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[1].title, "Speed up parser [#2]");
+        assert_eq!(children[0].mode, TaskMode::Supervised);
+        assert_eq!(
+            store.list_subtasks_for_task(&children[0].id).unwrap().len(),
+            1
+        );
+        assert_eq!(
+            store.get_task(&parent_id).unwrap().status,
+            TaskStatus::Working
+        );
+
+        let variants = store.list_task_variants(&parent_id).unwrap();
+        assert_eq!(variants.len(), 2);
+        assert_eq!(variants[1].effort.as_deref(), Some("medium"));
+        assert_eq!(
+            variants[1].label("claude-opus-4-6", "max"),
+            "opus-4-6/medium"
+        );
+        assert!(
+            store
+                .fan_out_task(&children[0].id, &[(None, None), (None, None)])
+                .is_err()
+        );
+
+        store
+            .set_variant_test_result(&children[0].id, VariantTestStatus::Passed, Some("ok"))
+            .unwrap();
+        let variant = store.get_task_variant(&children[0].id).unwrap().unwrap();
+        assert_eq!(variant.test_status, Some(VariantTestStatus::Passed));
+
+        // Discarding every variant returns the parent to the queue.
+        for child in &children {
+            store.discard_variant(&child.id).unwrap();
+        }
+        assert_eq!(
+            store.get_task(&parent_id).unwrap().status,
+            TaskStatus::Pending
+        );
+    }
+
+    #[test]
+    fn promote_hands_winner_to_parent() {
+        let store = Store::open_in_memory().unwrap();
+        let parent_id = make_task(&store, PushMode::Pr);
+        let children = store
+            .fan_out_task(&parent_id, &[(None, Some("max")), (None, Some("low"))])
+            .unwrap();
+        let project_id = children[1].project_id.clone();
+        let session = store
+            .create_session(&project_id, "task/b-2", "/tmp/wt", "p:task/b-2")
+            .unwrap();
+        store
+            .assign_task_to_session(&children[1].id, &session.id)
+            .unwrap();
+        store.set_task_usage(&children[1].id, 500, 50).unwrap();
+
+        assert!(store.promote_variant(&children[1].id, None).is_err());
+        let parent = store
+            .promote_variant(&children[1].id, Some("https://github.com/o/r/pull/9"))
+            .unwrap();
+        assert_eq!(parent.status, TaskStatus::InReview);
+        assert_eq!(parent.session_id.as_deref(), Some(session.id.as_str()));
+        assert_eq!(parent.branch.as_deref(), Some("task/b-2"));
+        assert_eq!(parent.input_tokens, 500);
+        assert!(store.list_task_variants(&parent_id).unwrap().is_empty());
+        assert!(children.iter().all(|c| store.get_task(&c.id).is_err()));
+    }
+}
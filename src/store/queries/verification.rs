@@ -0,0 +1,213 @@
+//! Per-project verification commands and the pass/fail record of each run.
+
+use anyhow::{Context, Result};
+use rusqlite::{OptionalExtension, params};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::store::Store;
+use crate::store::models::TaskVerification;
+
+/// Column list for all queries that use `row_to_task_verification`.
+const VERIFICATION_COLUMNS: &str =
+    "id, task_id, session_id, passed, failed_command, output, created_at";
+
+impl Store {
+    /// Replace a project's verification commands; an empty list clears them.
+    pub fn set_project_verify_commands(&self, project_id: &str, commands: &[String]) -> Result<()> {
+        let raw = if commands.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(commands)?)
+        };
+        self.conn
+            .execute(
+                "UPDATE projects SET verify_commands = ?1 WHERE id = ?2",
+                params![raw, project_id],
+            )
+            .with_context(|| {
+                format!("failed to set verification commands for project '{project_id}'")
+            })?;
+        Ok(())
+    }
+
+    /// The project's verification commands, in the order they run.
+    pub fn project_verify_commands(&self, project_id: &str) -> Result<Vec<String>> {
+        let raw: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT verify_commands FROM projects WHERE id = ?1",
+                params![project_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(raw
+            .and_then(|raw| {
+                serde_json::from_str(&raw)
+                    .inspect_err(|e| warn!(project_id, "invalid verify_commands in DB: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default())
+    }
+
+    pub fn record_task_verification(
+        &self,
+        task_id: &str,
+        session_id: Option<&str>,
+        failed_command: Option<&str>,
+        output: Option<&str>,
+    ) -> Result<TaskVerification> {
+        let id = Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        self.conn
+            .execute(
+                "INSERT INTO task_verifications
+                     (id, task_id, session_id, passed, failed_command, output, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    id,
+                    task_id,
+                    session_id,
+                    failed_command.is_none(),
+                    failed_command,
+                    output,
+                    now
+                ],
+            )
+            .with_context(|| format!("failed to record verification for task '{task_id}'"))?;
+        let sql = format!("SELECT {VERIFICATION_COLUMNS} FROM task_verifications WHERE id = ?1");
+        let verification =
+            self.conn
+                .query_row(&sql, params![id], Self::row_to_task_verification)?;
+        Ok(verification)
+    }
+
+    /// The most recent verification run for a task.
+    pub fn latest_task_verification(&self, task_id: &str) -> Result<Option<TaskVerification>> {
+        let sql = format!(
+            "SELECT {VERIFICATION_COLUMNS} FROM task_verifications \
+             WHERE task_id = ?1 \
+             ORDER BY created_at DESC, rowid DESC LIMIT 1"
+        );
+        let verification = self
+            .conn
+            .query_row(&sql, params![task_id], Self::row_to_task_verification)
+            .optional()?;
+        Ok(verification)
+    }
+
+    /// Failed runs in `session_id` since its last pass; drives the
+    /// give-up threshold for the verification gate.
+    pub fn consecutive_verification_failures(
+        &self,
+        task_id: &str,
+        session_id: &str,
+    ) -> Result<u32> {
+        let count = self.conn.query_row(
+            "SELECT COUNT(*) FROM task_verifications
+             WHERE task_id = ?1 AND session_id = ?2 AND passed = 0
+               AND rowid > COALESCE(
+                   (SELECT MAX(rowid) FROM task_verifications
+                    WHERE task_id = ?1 AND session_id = ?2 AND passed = 1), 0)",
+            params![task_id, session_id],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    fn row_to_task_verification(row: &rusqlite::Row<'_>) -> rusqlite::Result<TaskVerification> {
+        Ok(TaskVerification {
+            id: row.get(0)?,
+            task_id: row.get(1)?,
+            session_id: row.get(2)?,
+            passed: row.get(3)?,
+            failed_command: row.get(4)?,
+            output: row.get(5)?,
+            created_at: row.get(6)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::{PushMode, Store, TaskMode};
+
+    #[test]
+    fn verification_commands_and_results() {
+        let store = Store::open_in_memory().unwrap();
+        let project = store.create_project("p", "/tmp/p", "main", true).unwrap();
+        let task = store
+            .create_task(
+                &project.id,
+                "task",
+                "desc",
+                TaskMode::Supervised,
+                None,
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap();
+
+        assert!(
+            store
+                .project_verify_commands(&project.id)
+                .unwrap()
+                .is_empty()
+        );
+        let commands = vec!["cargo test".to_string(), "cargo clippy".to_string()];
+        store
+            .set_project_verify_commands(&project.id, &commands)
+            .unwrap();
+        assert_eq!(
+            store.project_verify_commands(&project.id).unwrap(),
+            commands
+        );
+        store.set_project_verify_commands(&project.id, &[]).unwrap();
+        assert!(
+            store
+                .project_verify_commands(&project.id)
+                .unwrap()
+                .is_empty()
+        );
+
+        assert!(store.latest_task_verification(&task.id).unwrap().is_none());
+        store
+            .record_task_verification(&task.id, Some("s1"), Some("cargo test"), Some("boom"))
+            .unwrap();
+        store
+            .record_task_verification(&task.id, Some("s1"), Some("cargo test"), Some("boom"))
+            .unwrap();
+        assert_eq!(
+            store
+                .consecutive_verification_failures(&task.id, "s1")
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            store
+                .consecutive_verification_failures(&task.id, "s2")
+                .unwrap(),
+            0
+        );
+
+        let passed = store
+            .record_task_verification(&task.id, Some("s1"), None, None)
+            .unwrap();
+        assert!(passed.passed);
+        assert_eq!(
+            store
+                .consecutive_verification_failures(&task.id, "s1")
+                .unwrap(),
+            0
+        );
+        assert!(
+            store
+                .latest_task_verification(&task.id)
+                .unwrap()
+                .unwrap()
+                .passed
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Outbound webhook delivery queue.
+//!
+//! Deliveries are deleted once they succeed; rows that exhaust their
+//! retries stay behind with status `failed` for inspection.
+
+use anyhow::{Context, Result};
+use rusqlite::params;
+use tracing::warn;
+
+use crate::store::Store;
+use crate::store::models::{WebhookDelivery, WebhookDeliveryStatus};
+
+const WEBHOOK_COLUMNS: &str = "id, url, headers, event, task_id, payload, status, attempts,
+    next_attempt_at, last_error, created_at";
+
+impl Store {
+    /// Queue a webhook for delivery. The first attempt is due immediately.
+    pub fn enqueue_webhook_delivery(
+        &self,
+        url: &str,
+        headers: &[String],
+        event: &str,
+        task_id: Option<&str>,
+        payload: &str,
+    ) -> Result<WebhookDelivery> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        let headers_json = serde_json::to_string(headers)?;
+        self.conn
+            .execute(
+                "INSERT INTO webhook_deliveries (id, url, headers, event, task_id, payload,
+                    status, attempts, next_attempt_at, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'pending', 0, ?7, ?7)",
+                params![id, url, headers_json, event, task_id, payload, now],
+            )
+            .context("failed to enqueue webhook delivery")?;
+        self.get_webhook_delivery(&id)
+    }
+
+    pub fn get_webhook_delivery(&self, id: &str) -> Result<WebhookDelivery> {
+        let delivery = self
+            .conn
+            .query_row(
+                &format!("SELECT {WEBHOOK_COLUMNS} FROM webhook_deliveries WHERE id = ?1"),
+                params![id],
+                Self::row_to_webhook_delivery,
+            )
+            .with_context(|| format!("webhook delivery not found: {id}"))?;
+        Ok(delivery)
+    }
+
+    /// Pending deliveries whose next attempt is at or before `now`
+    /// (an RFC 3339 UTC timestamp), oldest first. In-flight deliveries
+    /// whose claim lease has expired are due again.
+    pub fn list_due_webhook_deliveries(
+        &self,
+        now: &str,
+        limit: usize,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM webhook_deliveries
+             WHERE status IN ('pending', 'in_flight') AND next_attempt_at <= ?1
+             ORDER BY next_attempt_at, created_at
+             LIMIT ?2"
+        ))?;
+        let rows = stmt.query_map(
+            params![now, i64::try_from(limit).unwrap_or(i64::MAX)],
+            Self::row_to_webhook_delivery,
+        )?;
+        let mut deliveries = Vec::new();
+        for row in rows {
+            deliveries.push(row?);
+        }
+        Ok(deliveries)
+    }
+
+    /// Claim a due delivery for this flusher, leasing it until `lease_until`.
+    /// Returns `false` when another flusher claimed it first, in which case
+    /// the caller must not post it.
+    pub fn claim_webhook_delivery(&self, id: &str, now: &str, lease_until: &str) -> Result<bool> {
+        let changed = self.conn.execute(
+            "UPDATE webhook_deliveries
+             SET status = 'in_flight', next_attempt_at = ?3
+             WHERE id = ?1 AND status IN ('pending', 'in_flight') AND next_attempt_at <= ?2",
+            params![id, now, lease_until],
+        )?;
+        Ok(changed == 1)
+    }
+
+    /// All deliveries with the given status, oldest first.
+    pub fn list_webhook_deliveries(
+        &self,
+        status: WebhookDeliveryStatus,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM webhook_deliveries
+             WHERE status = ?1
+             ORDER BY created_at"
+        ))?;
+        let rows = stmt.query_map(params![status.as_str()], Self::row_to_webhook_delivery)?;
+        let mut deliveries = Vec::new();
+        for row in rows {
+            deliveries.push(row?);
+        }
+        Ok(deliveries)
+    }
+
+    /// Remove a delivery after it succeeded.
+    pub fn delete_webhook_delivery(&self, id: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM webhook_deliveries WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Record a failed attempt. With `retry_at` the delivery stays pending
+    /// until then; without it the delivery is marked `failed` for good.
+    pub fn record_webhook_failure(
+        &self,
+        id: &str,
+        error: &str,
+        retry_at: Option<&str>,
+    ) -> Result<()> {
+        let status = if retry_at.is_some() {
+            WebhookDeliveryStatus::Pending
+        } else {
+            WebhookDeliveryStatus::Failed
+        };
+        self.conn.execute(
+            "UPDATE webhook_deliveries
+             SET attempts = attempts + 1, last_error = ?2, status = ?3,
+                 next_attempt_at = COALESCE(?4, next_attempt_at)
+             WHERE id = ?1",
+            params![id, error, status.as_str(), retry_at],
+        )?;
+        Ok(())
+    }
+
+    fn row_to_webhook_delivery(row: &rusqlite::Row<'_>) -> rusqlite::Result<WebhookDelivery> {
+        let id: String = row.get(0)?;
+        let headers_json: String = row.get(2)?;
+        let status_str: String = row.get(6)?;
+        Ok(WebhookDelivery {
+            headers: serde_json::from_str(&headers_json).unwrap_or_else(|_| {
+                warn!(delivery_id = %id, "invalid webhook headers in DB, ignoring");
+                Vec::new()
+            }),
+            status: status_str.parse().unwrap_or_else(|_| {
+                warn!(delivery_id = %id, raw = %status_str, "unknown webhook status in DB, defaulting to Failed");
+                WebhookDeliveryStatus::Failed
+            }),
+            id,
+            url: row.get(1)?,
+            event: row.get(3)?,
+            task_id: row.get(4)?,
+            payload: row.get(5)?,
+            attempts: row.get(7)?,
+            next_attempt_at: row.get(8)?,
+            last_error: row.get(9)?,
+            created_at: row.get(10)?,
+        })
+    }
+}
@@ -0,0 +1,366 @@
+//! Task filter query language.
+//!
+//! Shared by the TUI task filter (`/`), saved views, and
+//! `claustre list-tasks --query`. A query is a whitespace-separated list of
+//! terms that must all match:
+//!
+//! ```text
+//! status:in_review,ci_failed  mode:autonomous  has:pr  ci:failed
+//! created:<7d  updated:>2h  -status:done  is:archived  "exact phrase"  word
+//! ```
+//!
+//! Bare words and quoted phrases match case-insensitively against the title,
+//! description, branch and PR URL. A leading `-` negates any term.
+//!
+//! Archived tasks aren't in the task lists a query usually runs over; callers
+//! load them too when `includes_archived` is true.
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+
+use super::{CiStatus, Task, TaskMode, TaskStatus};
+
+/// A parsed filter query. The empty query matches every task.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskQuery {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    negate: bool,
+    kind: TermKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TermKind {
+    Status(Vec<TaskStatus>),
+    Mode(Vec<TaskMode>),
+    Has(HasField),
+    /// `None` in the list matches tasks without a CI status (`ci:none`).
+    Ci(Vec<Option<CiStatus>>),
+    Created(Age),
+    Updated(Age),
+    /// `is:archived`.
+    Archived,
+    /// Lowercased substring.
+    Text(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HasField {
+    Pr,
+    Branch,
+    Session,
+    Description,
+}
+
+/// `<7d` (newer than) or `>7d` (older than).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Age {
+    newer: bool,
+    span: Duration,
+}
+
+impl TaskQuery {
+    pub fn parse(input: &str) -> Result<Self> {
+        let terms = tokenize(input)?
+            .into_iter()
+            .map(|(token, quoted)| parse_term(&token, quoted))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { terms })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Whether the query asks for archived tasks (`is:archived`, not negated).
+    pub fn includes_archived(&self) -> bool {
+        self.terms
+            .iter()
+            .any(|term| term.kind == TermKind::Archived && !term.negate)
+    }
+
+    /// Whether `task` satisfies every term. `now` anchors `created:`/`updated:`.
+    pub fn matches(&self, task: &Task, now: DateTime<Utc>) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.kind.matches(task, now) != term.negate)
+    }
+}
+
+impl TermKind {
+    fn matches(&self, task: &Task, now: DateTime<Utc>) -> bool {
+        match self {
+            Self::Status(statuses) => statuses.contains(&task.status),
+            Self::Mode(modes) => modes.contains(&task.mode),
+            Self::Has(HasField::Pr) => task.pr_url.is_some(),
+            Self::Has(HasField::Branch) => task.branch.is_some(),
+            Self::Has(HasField::Session) => task.session_id.is_some(),
+            Self::Has(HasField::Description) => !task.description.trim().is_empty(),
+            Self::Ci(statuses) => statuses.contains(&task.ci_status),
+            Self::Created(age) => age.matches(&task.created_at, now),
+            Self::Updated(age) => age.matches(&task.updated_at, now),
+            Self::Archived => task.archived_at.is_some(),
+            Self::Text(needle) => [
+                Some(task.title.as_str()),
+                Some(task.description.as_str()),
+                task.branch.as_deref(),
+                task.pr_url.as_deref(),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(needle)),
+        }
+    }
+}
+
+impl Age {
+    fn matches(self, timestamp: &str, now: DateTime<Utc>) -> bool {
+        let Some(at) = parse_timestamp(timestamp) else {
+            return false;
+        };
+        let elapsed = now - at;
+        if self.newer {
+            elapsed < self.span
+        } else {
+            elapsed > self.span
+        }
+    }
+}
+
+/// Timestamps are RFC 3339 when written by claustre, or `SQLite`'s
+/// `datetime('now')` format for rows created by migration defaults.
+fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").map(|dt| dt.and_utc()))
+        .ok()
+}
+
+/// Split on whitespace, keeping double-quoted phrases together.
+/// Returns each token with whether it was (at least partly) quoted.
+fn tokenize(input: &str) -> Result<Vec<(String, bool)>> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    for c in input.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                quoted = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() || quoted {
+                    tokens.push((std::mem::take(&mut current), quoted));
+                }
+                quoted = false;
+            }
+            c => current.push(c),
+        }
+    }
+    if in_quotes {
+        bail!("unterminated quote");
+    }
+    if !current.is_empty() || quoted {
+        tokens.push((current, quoted));
+    }
+    Ok(tokens)
+}
+
+fn parse_term(token: &str, quoted: bool) -> Result<Term> {
+    let (negate, body) = match token.strip_prefix('-') {
+        Some(rest) if !rest.is_empty() => (true, rest),
+        _ => (false, token),
+    };
+    let text = || TermKind::Text(body.to_lowercase());
+    let kind = match body.split_once(':') {
+        _ if quoted => text(),
+        Some((key, value)) => match key.to_lowercase().as_str() {
+            "status" => TermKind::Status(parse_list(value, str::parse)?),
+            "mode" => TermKind::Mode(parse_list(value, str::parse)?),
+            "ci" => TermKind::Ci(parse_list(value, |v| match v {
+                "none" => Ok(None),
+                v => v.parse().map(Some),
+            })?),
+            "has" => TermKind::Has(match value.to_lowercase().as_str() {
+                "pr" => HasField::Pr,
+                "branch" => HasField::Branch,
+                "session" => HasField::Session,
+                "description" | "desc" => HasField::Description,
+                other => bail!("unknown has: field '{other}' (pr, branch, session, description)"),
+            }),
+            "is" => match value.to_lowercase().as_str() {
+                "archived" => TermKind::Archived,
+                other => bail!("unknown is: value '{other}' (archived)"),
+            },
+            "created" => TermKind::Created(parse_age(value)?),
+            "updated" => TermKind::Updated(parse_age(value)?),
+            // Not a known key: treat `foo:bar` as plain text (e.g. a URL fragment).
+            _ => text(),
+        },
+        None => text(),
+    };
+    Ok(Term { negate, kind })
+}
+
+/// Parse a comma-separated list of values (`status:working,in_review`).
+fn parse_list<T>(value: &str, parse: impl Fn(&str) -> Result<T, String>) -> Result<Vec<T>> {
+    let items = value
+        .split(',')
+        .filter(|v| !v.is_empty())
+        .map(|v| parse(&v.to_lowercase()).map_err(anyhow::Error::msg))
+        .collect::<Result<Vec<_>>>()?;
+    if items.is_empty() {
+        bail!("missing value");
+    }
+    Ok(items)
+}
+
+/// Parse `<7d`, `>12h`, or a bare `7d` (same as `<7d`). Units: m, h, d, w.
+fn parse_age(value: &str) -> Result<Age> {
+    let (newer, span) = match value.chars().next() {
+        Some('<') => (true, &value[1..]),
+        Some('>') => (false, &value[1..]),
+        _ => (true, value),
+    };
+    let unit_at = span
+        .find(|c: char| !c.is_ascii_digit())
+        .with_context(|| format!("missing unit in '{value}' (m, h, d or w)"))?;
+    let (digits, unit) = span.split_at(unit_at);
+    let n: i64 = digits
+        .parse()
+        .with_context(|| format!("invalid age '{value}'"))?;
+    let span = match unit {
+        "m" => Duration::minutes(n),
+        "h" => Duration::hours(n),
+        "d" => Duration::days(n),
+        "w" => Duration::weeks(n),
+        other => bail!("unknown unit '{other}' in '{value}' (m, h, d or w)"),
+    };
+    Ok(Age { newer, span })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::PushMode;
+
+    fn task(title: &str) -> Task {
+        Task {
+            id: "t1".into(),
+            project_id: "p1".into(),
+            title: title.into(),
+            description: String::new(),
+            status: TaskStatus::Pending,
+            mode: TaskMode::Supervised,
+            session_id: None,
+            created_at: "2026-01-10T12:00:00+00:00".into(),
+            updated_at: "2026-01-10 12:00:00".into(),
+            started_at: None,
+            completed_at: None,
+            input_tokens: 0,
+            output_tokens: 0,
+            sort_order: 0,
+            pr_url: None,
+            branch: None,
+            base: None,
+            push_mode: PushMode::Pr,
+            ci_status: None,
+            review_loop: false,
+            merge_policy: None,
+            archived_at: None,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        parse_timestamp("2026-01-12T12:00:00Z").unwrap()
+    }
+
+    fn matches(query: &str, task: &Task) -> bool {
+        TaskQuery::parse(query).unwrap().matches(task, now())
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        let q = TaskQuery::parse("   ").unwrap();
+        assert!(q.is_empty());
+        assert!(q.matches(&task("anything"), now()));
+    }
+
+    #[test]
+    fn text_matches_title_description_branch_and_pr_url() {
+        let mut t = task("Fix login");
+        t.description = "Session cookie expires early".into();
+        t.branch = Some("claustre/fix-login-42".into());
+        t.pr_url = Some("https://github.com/acme/app/pull/917".into());
+
+        assert!(matches("LOGIN", &t));
+        assert!(matches("\"cookie expires\"", &t));
+        assert!(matches("login-42", &t));
+        assert!(matches("pull/917", &t));
+        assert!(!matches("\"expires cookie\"", &t));
+        assert!(!matches("login -cookie", &t));
+    }
+
+    #[test]
+    fn field_terms() {
+        let mut t = task("Add caching");
+        t.status = TaskStatus::InReview;
+        t.mode = TaskMode::Autonomous;
+        t.pr_url = Some("https://github.com/acme/app/pull/1".into());
+        t.ci_status = Some(CiStatus::Failed);
+
+        assert!(matches(
+            "status:in_review mode:autonomous has:pr ci:failed",
+            &t
+        ));
+        assert!(matches("status:working,in_review", &t));
+        assert!(matches("-status:done -has:session", &t));
+        assert!(!matches("ci:passed,none", &t));
+        assert!(!matches("has:branch", &t));
+        assert!(matches("ci:none", &task("no ci")));
+    }
+
+    #[test]
+    fn archived_term() {
+        let mut t = task("Old cleanup");
+        assert!(!matches("is:archived", &t));
+        t.archived_at = Some("2026-01-11T00:00:00+00:00".into());
+        assert!(matches("is:archived cleanup", &t));
+        assert!(!matches("-is:archived", &t));
+
+        assert!(TaskQuery::parse("is:archived").unwrap().includes_archived());
+        assert!(
+            !TaskQuery::parse("-is:archived")
+                .unwrap()
+                .includes_archived()
+        );
+        assert!(!TaskQuery::parse("cleanup").unwrap().includes_archived());
+    }
+
+    #[test]
+    fn age_terms_accept_both_timestamp_formats() {
+        let t = task("old");
+        // created two days before `now()`, updated (SQLite format) likewise.
+        assert!(matches("created:<7d", &t));
+        assert!(matches("created:3d", &t));
+        assert!(!matches("created:<1d", &t));
+        assert!(matches("created:>36h updated:>1d", &t));
+        assert!(matches("updated:<1w", &t));
+    }
+
+    #[test]
+    fn invalid_queries_are_errors() {
+        assert!(TaskQuery::parse("status:bogus").is_err());
+        assert!(TaskQuery::parse("created:<7").is_err());
+        assert!(TaskQuery::parse("created:<7y").is_err());
+        assert!(TaskQuery::parse("has:nothing").is_err());
+        assert!(TaskQuery::parse("is:done").is_err());
+        assert!(TaskQuery::parse("\"unterminated").is_err());
+        // Unknown keys fall back to text.
+        assert!(TaskQuery::parse("foo:bar").is_ok());
+    }
+}
@@ -0,0 +1,342 @@
+//! Task templates: reusable task prompts with variables, default field
+//! values and predefined subtasks.
+//!
+//! Templates are TOML files in `~/.claustre/templates/` (global) and
+//! `<repo>/.claustre/templates/` (per project; wins over a global template
+//! with the same name). The file stem is the template name:
+//!
+//! ```toml
+//! # ~/.claustre/templates/bugfix.toml
+//! about = "Reproduce, fix and test a bug"
+//! title = "Fix {{area}} bug"
+//! description = """
+//! Fix this bug in {{area}}: {{details}}
+//! Write a failing test first, then make it pass.
+//! """
+//! mode = "autonomous"
+//! push_mode = "pr"
+//! review_loop = true
+//! branch = "fix/{{area}}"
+//! subtasks = ["Reproduce with a failing test", "Fix and run the full suite"]
+//!
+//! [[vars]]
+//! name = "area"
+//! default = "api"
+//!
+//! [[vars]]
+//! name = "details"
+//! ```
+//!
+//! `{{name}}` placeholders may appear in the title, description, base,
+//! branch and subtasks.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+use crate::store::{PushMode, TaskMode};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TaskTemplate {
+    /// File stem; filled in when loading.
+    #[serde(skip)]
+    pub name: String,
+    /// One-line summary shown in the template picker.
+    #[serde(default)]
+    pub about: String,
+    /// Task title (CLI only; the TUI generates titles from the description).
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub mode: Option<TaskMode>,
+    #[serde(default)]
+    pub push_mode: Option<PushMode>,
+    #[serde(default)]
+    pub review_loop: Option<bool>,
+    #[serde(default)]
+    pub base: Option<String>,
+    #[serde(default)]
+    pub branch: Option<String>,
+    #[serde(default)]
+    pub subtasks: Vec<String>,
+    /// Variables in prompt order.
+    #[serde(default)]
+    pub vars: Vec<TemplateVar>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TemplateVar {
+    pub name: String,
+    /// Used when no value is given; a variable without a default is required.
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+/// A template with every variable substituted, ready to fill a task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedTemplate {
+    pub title: Option<String>,
+    pub description: String,
+    pub mode: Option<TaskMode>,
+    pub push_mode: Option<PushMode>,
+    pub review_loop: Option<bool>,
+    pub base: Option<String>,
+    pub branch: Option<String>,
+    pub subtasks: Vec<String>,
+}
+
+impl TaskTemplate {
+    /// Substitute `values` (falling back to each variable's default).
+    /// Errors on a missing required value, an unknown variable name, or a
+    /// placeholder that no variable declares.
+    pub fn render(&self, values: &HashMap<String, String>) -> Result<RenderedTemplate> {
+        if let Some(unknown) = values
+            .keys()
+            .find(|k| !self.vars.iter().any(|v| &v.name == *k))
+        {
+            bail!("template '{}' has no variable '{unknown}'", self.name);
+        }
+        let mut resolved = HashMap::new();
+        for var in &self.vars {
+            let value = values
+                .get(&var.name)
+                .or(var.default.as_ref())
+                .with_context(|| {
+                    format!(
+                        "template '{}' needs a value for '{}' (--var {}=...)",
+                        self.name, var.name, var.name
+                    )
+                })?;
+            resolved.insert(var.name.as_str(), value.as_str());
+        }
+        let fill = |text: &str| substitute(text, &resolved);
+        let fill_opt = |text: &Option<String>| text.as_deref().map(fill).transpose();
+        Ok(RenderedTemplate {
+            title: fill_opt(&self.title)?,
+            description: fill(&self.description)?,
+            mode: self.mode,
+            push_mode: self.push_mode,
+            review_loop: self.review_loop,
+            base: fill_opt(&self.base)?,
+            branch: fill_opt(&self.branch)?,
+            subtasks: self
+                .subtasks
+                .iter()
+                .map(|s| fill(s))
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+/// Replace every `{{ name }}` in `text`.
+fn substitute(text: &str, values: &HashMap<&str, &str>) -> Result<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .with_context(|| format!("unclosed '{{{{' in '{text}'"))?;
+        let name = after[..end].trim();
+        let value = values.get(name).with_context(|| {
+            format!("placeholder '{{{{{name}}}}}' has no matching [[vars]] entry")
+        })?;
+        out.push_str(value);
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Parse `key=value` pairs from `--var` flags.
+pub fn parse_vars(pairs: &[String]) -> Result<HashMap<String, String>> {
+    pairs
+        .iter()
+        .map(|pair| {
+            let (key, value) = pair
+                .split_once('=')
+                .with_context(|| format!("expected key=value, got '{pair}'"))?;
+            Ok((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Returns the global template directory: ~/.claustre/templates/
+pub fn global_templates_dir() -> Result<PathBuf> {
+    Ok(crate::config::base_dir()?.join("templates"))
+}
+
+/// Templates available to a project, sorted by name. Repo templates
+/// override global ones with the same name.
+pub fn load_templates(repo_path: Option<&Path>) -> Result<Vec<TaskTemplate>> {
+    let mut dirs = vec![global_templates_dir()?];
+    if let Some(repo) = repo_path {
+        dirs.push(repo.join(".claustre").join("templates"));
+    }
+    Ok(load_templates_from(&dirs))
+}
+
+/// Find a template by name for a project.
+pub fn find_template(name: &str, repo_path: Option<&Path>) -> Result<TaskTemplate> {
+    let templates = load_templates(repo_path)?;
+    let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+    let available = if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    };
+    templates
+        .iter()
+        .find(|t| t.name == name)
+        .cloned()
+        .with_context(|| format!("no template named '{name}' (available: {available})"))
+}
+
+/// Load `*.toml` templates from `dirs` in order; later directories win.
+///
+/// A file that can't be read or parsed is logged and skipped so one broken
+/// template doesn't hide the rest.
+fn load_templates_from(dirs: &[PathBuf]) -> Vec<TaskTemplate> {
+    let mut by_name: HashMap<String, TaskTemplate> = HashMap::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "toml") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    tracing::warn!("skipping template {}: {e}", path.display());
+                    continue;
+                }
+            };
+            let mut template: TaskTemplate = match toml::from_str(&content) {
+                Ok(template) => template,
+                Err(e) => {
+                    tracing::warn!("skipping template {}: {e}", path.display());
+                    continue;
+                }
+            };
+            template.name = name.to_string();
+            by_name.insert(template.name.clone(), template);
+        }
+    }
+    let mut templates: Vec<TaskTemplate> = by_name.into_values().collect();
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    templates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUGFIX: &str = r#"
+about = "Fix a bug"
+title = "Fix {{area}} bug"
+description = "Fix this bug in {{ area }}: {{details}}"
+mode = "autonomous"
+push_mode = "push"
+review_loop = true
+branch = "fix/{{area}}"
+subtasks = ["Reproduce in {{area}}", "Fix"]
+
+[[vars]]
+name = "area"
+default = "api"
+
+[[vars]]
+name = "details"
+"#;
+
+    fn bugfix() -> TaskTemplate {
+        let mut t: TaskTemplate = toml::from_str(BUGFIX).unwrap();
+        t.name = "bugfix".into();
+        t
+    }
+
+    #[test]
+    fn render_substitutes_values_and_defaults() {
+        let values = parse_vars(&["details=login loops".to_string()]).unwrap();
+        let r = bugfix().render(&values).unwrap();
+        assert_eq!(r.title.as_deref(), Some("Fix api bug"));
+        assert_eq!(r.description, "Fix this bug in api: login loops");
+        assert_eq!(r.mode, Some(TaskMode::Autonomous));
+        assert_eq!(r.push_mode, Some(PushMode::Push));
+        assert_eq!(r.review_loop, Some(true));
+        assert_eq!(r.branch.as_deref(), Some("fix/api"));
+        assert_eq!(r.subtasks, ["Reproduce in api", "Fix"]);
+    }
+
+    #[test]
+    fn render_rejects_missing_unknown_and_undeclared() {
+        let err = bugfix().render(&HashMap::new()).unwrap_err();
+        assert!(err.to_string().contains("'details'"));
+
+        let values = parse_vars(&["details=x".into(), "nope=y".into()]).unwrap();
+        assert!(bugfix().render(&values).is_err());
+
+        let mut t = bugfix();
+        t.description = "uses {{missing}}".into();
+        let values = parse_vars(&["details=x".into()]).unwrap();
+        assert!(t.render(&values).is_err());
+
+        assert!(parse_vars(&["no-equals".into()]).is_err());
+    }
+
+    #[test]
+    fn repo_templates_override_global() {
+        let global = tempfile::tempdir().unwrap();
+        let repo = tempfile::tempdir().unwrap();
+        fs::write(global.path().join("bugfix.toml"), BUGFIX).unwrap();
+        fs::write(
+            global.path().join("chore.toml"),
+            "description = \"global chore\"",
+        )
+        .unwrap();
+        fs::write(
+            repo.path().join("chore.toml"),
+            "description = \"repo chore\"",
+        )
+        .unwrap();
+        fs::write(repo.path().join("notes.md"), "ignored").unwrap();
+
+        let templates =
+            load_templates_from(&[global.path().to_path_buf(), repo.path().to_path_buf()]);
+        let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["bugfix", "chore"]);
+        assert_eq!(templates[1].description, "repo chore");
+    }
+
+    #[test]
+    fn malformed_templates_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("bugfix.toml"), BUGFIX).unwrap();
+        fs::write(dir.path().join("broken.toml"), "mode = [").unwrap();
+
+        let templates = load_templates_from(&[dir.path().to_path_buf()]);
+        let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["bugfix"]);
+    }
+
+    #[test]
+    fn substitution_keeps_trailing_whitespace() {
+        let values = HashMap::from([("area", "auth")]);
+        assert_eq!(
+            substitute("Fix {{area}}\n\n", &values).unwrap(),
+            "Fix auth\n\n"
+        );
+    }
+}
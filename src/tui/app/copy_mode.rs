@@ -0,0 +1,145 @@
+//! Keyboard copy mode in session panes: `Ctrl+S` puts a cursor on the
+//! focused pane that moves through the screen and scrollback with vi keys;
+//! `v`/`V`/`Ctrl+V` select characters, lines or a block and `y` yanks.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use crate::pty::{CopyMode, Motion, Selection, SelectionKind};
+use crate::tui::clipboard;
+
+use super::{App, Tab, ToastStyle};
+
+impl App {
+    /// Enter copy mode on the focused pane, with the cursor where the
+    /// terminal's cursor is on the visible screen.
+    pub(super) fn open_copy_mode(&mut self) {
+        if let Some(Tab::Session { terminals, .. }) = self.tabs.get_mut(self.active_tab) {
+            let pane = terminals.focused;
+            let Some(term) = terminals.terminal_mut(pane) else {
+                return;
+            };
+            let (row, col) = term.screen().cursor_position();
+            let cursor = (term.viewport_top() + usize::from(row), col);
+            let history = term.history();
+            terminals.selection = None;
+            terminals.search = None;
+            terminals.copy_mode = Some(CopyMode::new(pane, cursor, history));
+        }
+    }
+
+    /// Handle a key while copy mode is active on the active session tab.
+    /// Returns `false` when copy mode is off. Every key is consumed while
+    /// it is on, so nothing reaches the pane until it is left.
+    pub(super) fn handle_copy_mode_key(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Result<bool> {
+        let Some(Tab::Session { terminals, .. }) = self.tabs.get_mut(self.active_tab) else {
+            return Ok(false);
+        };
+        let Some(mut mode) = terminals.copy_mode.take() else {
+            return Ok(false);
+        };
+        let Some(term) = terminals.terminal_mut(mode.pane) else {
+            return Ok(true);
+        };
+
+        let (rows, cols) = term.screen().size();
+        let rows = usize::from(rows);
+        let top = term.viewport_top();
+        let half = (rows / 2).max(1);
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+
+        let motion = match code {
+            KeyCode::Char('u') if ctrl => Some(Motion::Up(half)),
+            KeyCode::Char('d') if ctrl => Some(Motion::Down(half)),
+            KeyCode::Char('b') if ctrl => Some(Motion::Up(rows)),
+            KeyCode::Char('f') if ctrl => Some(Motion::Down(rows)),
+            KeyCode::Char('v') if ctrl => {
+                mode.toggle_selection(SelectionKind::Block);
+                None
+            }
+            _ if ctrl => None,
+            KeyCode::Char('h') | KeyCode::Left => Some(Motion::Left),
+            KeyCode::Char('l') | KeyCode::Right => Some(Motion::Right),
+            KeyCode::Char('k') | KeyCode::Up => Some(Motion::Up(1)),
+            KeyCode::Char('j') | KeyCode::Down => Some(Motion::Down(1)),
+            KeyCode::PageUp => Some(Motion::Up(rows)),
+            KeyCode::PageDown => Some(Motion::Down(rows)),
+            KeyCode::Char('w') => Some(Motion::WordForward),
+            KeyCode::Char('b') => Some(Motion::WordBackward),
+            KeyCode::Char('e') => Some(Motion::WordEnd),
+            KeyCode::Char('0') | KeyCode::Home => Some(Motion::LineStart),
+            KeyCode::Char('^') => Some(Motion::FirstNonBlank),
+            KeyCode::Char('$') | KeyCode::End => Some(Motion::LineEnd),
+            KeyCode::Char('g') => Some(Motion::Top),
+            KeyCode::Char('G') => Some(Motion::Bottom),
+            KeyCode::Char('H') => Some(Motion::Line(top)),
+            KeyCode::Char('M') => Some(Motion::Line(top + rows / 2)),
+            KeyCode::Char('L') => Some(Motion::Line(top + rows.saturating_sub(1))),
+            KeyCode::Char('v') => {
+                mode.toggle_selection(SelectionKind::Char);
+                None
+            }
+            KeyCode::Char('V') => {
+                mode.toggle_selection(SelectionKind::Line);
+                None
+            }
+            KeyCode::Char('y') | KeyCode::Enter => {
+                let text = mode.selection().map(|sel| sel.extract_text(mode.history()));
+                term.reset_scrollback();
+                self.copy_selection(text);
+                return Ok(true);
+            }
+            KeyCode::Char('Y') => {
+                let line = Selection {
+                    pane: mode.pane,
+                    start: mode.cursor,
+                    end: mode.cursor,
+                    kind: SelectionKind::Line,
+                };
+                let text = line.extract_text(mode.history());
+                term.reset_scrollback();
+                self.copy_selection(Some(text));
+                return Ok(true);
+            }
+            KeyCode::Esc if mode.anchor.is_some() => {
+                mode.anchor = None;
+                None
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                term.reset_scrollback();
+                return Ok(true);
+            }
+            _ => None,
+        };
+
+        if let Some(motion) = motion {
+            mode.apply(motion, cols);
+            term.reveal_line(mode.cursor.0);
+        }
+        terminals.copy_mode = Some(mode);
+        Ok(true)
+    }
+
+    /// Put a yanked selection on the clipboard and report it.
+    fn copy_selection(&mut self, text: Option<String>) {
+        let Some(text) = text.filter(|t| !t.is_empty()) else {
+            self.show_toast("Nothing selected", ToastStyle::Info);
+            return;
+        };
+        let lines = text.lines().count();
+        if clipboard::copy(&text) {
+            let what = if lines > 1 {
+                format!("{lines} lines")
+            } else {
+                "selection".to_string()
+            };
+            self.show_toast(format!("Copied {what}"), ToastStyle::Success);
+        } else {
+            self.show_toast("Clipboard unavailable", ToastStyle::Error);
+        }
+    }
+}
@@ -2,9 +2,9 @@ use std::sync::atomic::Ordering;
 
 use anyhow::Result;
 
-use crate::store::{Project, Session, Task, TaskStatus};
+use crate::store::{Project, Session, Task, TaskQuery, TaskStatus};
 
-use super::{App, ToastStyle, build_project_summaries};
+use super::{App, Focus, ToastStyle, build_project_summaries};
 
 impl App {
     /// Auto-teardown sessions for completed push-mode tasks.
@@ -31,13 +31,32 @@ impl App {
     pub fn refresh_data(&mut self) -> Result<()> {
         self.projects = self.store.list_projects()?;
 
-        if let Some(project) = self.projects.get(self.project_index) {
+        if self.all_projects {
+            self.sessions.clear();
+            for project in &self.projects {
+                self.sessions
+                    .extend(self.store.list_sessions_for_project(&project.id)?);
+            }
+            self.tasks = self.store.list_tasks_all_projects()?;
+        } else if let Some(project) = self.projects.get(self.project_index) {
             self.sessions = self.store.list_sessions_for_project(&project.id)?;
             self.tasks = self.store.list_tasks_for_project(&project.id)?;
         } else {
             self.sessions.clear();
             self.tasks.clear();
         }
+        // Archived tasks stay out of the queue unless the filter asks for them
+        if TaskQuery::parse(&self.task_filter).is_ok_and(|q| q.includes_archived()) {
+            let projects: Vec<&Project> = if self.all_projects {
+                self.projects.iter().collect()
+            } else {
+                self.projects.get(self.project_index).into_iter().collect()
+            };
+            for project in projects {
+                self.tasks
+                    .extend(self.store.list_archived_tasks_for_project(&project.id)?);
+            }
+        }
 
         // Detect Working → InReview transitions and show a toast (once per task)
         let new_review_title = self.tasks.iter().find_map(|t| {
@@ -72,6 +91,24 @@ impl App {
                 .any(|t| t.id == *id && t.status == TaskStatus::InReview)
         });
 
+        // Allow a new CI fix loop once a task leaves ci_failed
+        let store = &self.store;
+        self.ci_fix_spawned.retain(|id| {
+            store
+                .get_task(id)
+                .is_ok_and(|t| t.status == TaskStatus::CiFailed)
+        });
+        self.conflict_resolution_spawned.retain(|id| {
+            store
+                .get_task(id)
+                .is_ok_and(|t| t.status == TaskStatus::Conflict)
+        });
+        self.auto_merge_reported.retain(|id| {
+            store
+                .get_task(id)
+                .is_ok_and(|t| t.status == TaskStatus::InReview)
+        });
+
         // Pre-fetch sidebar summaries for all projects
         self.project_summaries = build_project_summaries(&self.store, &self.projects);
 
@@ -231,28 +268,73 @@ impl App {
     /// Recompute the cached visible task indices. Must be called after any change
     /// to `self.tasks`, `self.task_filter`, or task sort order.
     pub fn recompute_visible_tasks(&mut self) {
-        let filter_lower = self.task_filter.to_lowercase();
+        let query = match TaskQuery::parse(&self.task_filter) {
+            Ok(query) => {
+                self.task_filter_error = None;
+                Some(query)
+            }
+            Err(e) => {
+                self.task_filter_error = Some(format!("{e:#}"));
+                None
+            }
+        };
+        let now = chrono::Utc::now();
         let mut indices: Vec<usize> = self
             .tasks
             .iter()
             .enumerate()
-            .filter(|(_, t)| {
-                filter_lower.is_empty() || t.title.to_lowercase().contains(&filter_lower)
-            })
+            .filter(|(_, t)| query.as_ref().is_some_and(|q| q.matches(t, now)))
             .map(|(i, _)| i)
             .collect();
-        indices.sort_by(|&a, &b| {
-            self.tasks[a]
-                .status
-                .sort_priority()
-                .cmp(&self.tasks[b].status.sort_priority())
-                .then_with(|| self.tasks[a].sort_order.cmp(&self.tasks[b].sort_order))
-        });
+        if self.all_projects {
+            // Stable: keeps the store's project-name / queue order within a group.
+            indices.sort_by_key(|&i| self.attention_rank(&self.tasks[i]));
+        } else {
+            indices.sort_by(|&a, &b| {
+                self.tasks[a]
+                    .status
+                    .sort_priority()
+                    .cmp(&self.tasks[b].status.sort_priority())
+                    .then_with(|| self.tasks[a].sort_order.cmp(&self.tasks[b].sort_order))
+            });
+        }
         self.cached_visible_indices = indices;
     }
 
+    /// Ordering for the all-projects view: in review, CI failed, conflict and
+    /// paused/waiting sessions first, then the usual status order.
+    fn attention_rank(&self, task: &Task) -> u8 {
+        let blocked = task.status == TaskStatus::Working
+            && task.session_id.as_deref().is_some_and(|sid| {
+                self.paused_sessions.contains(sid) || self.waiting_sessions.contains(sid)
+            });
+        match task.status {
+            TaskStatus::InReview => 0,
+            TaskStatus::CiFailed => 1,
+            TaskStatus::Conflict => 2,
+            _ if blocked => 3,
+            status => 4 + status.sort_priority(),
+        }
+    }
+
+    /// Switch the task queue between the selected project and all projects.
+    pub(super) fn toggle_all_projects(&mut self) -> Result<()> {
+        self.all_projects = !self.all_projects;
+        self.task_index = 0;
+        self.focus = Focus::Tasks;
+        self.refresh_data()
+    }
+
+    /// Project name for a task (shown as a column in the all-projects view).
+    pub fn project_name(&self, project_id: &str) -> &str {
+        self.projects
+            .iter()
+            .find(|p| p.id == project_id)
+            .map_or("", |p| p.name.as_str())
+    }
+
     /// Returns all tasks for the selected project, optionally filtered
-    /// by the current search term (`task_filter`). Uses case-insensitive title matching.
+    /// by the current query (`task_filter`, see [`TaskQuery`]).
     /// Tasks are sorted by status priority, then by `sort_order` within each status group.
     /// Done tasks appear last.
     pub fn visible_tasks(&self) -> Vec<&Task> {
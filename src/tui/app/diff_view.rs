@@ -0,0 +1,304 @@
+//! Diff/review viewer: browse a task's changes against its base, stage or
+//! discard files, and send review comments back to the Claude pane.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use crate::session::diff::{self, DiffRow, DiffSource, ReviewComment};
+use crate::store::Task;
+
+use super::super::form::apply_text_edit;
+use super::{App, DiffViewState, InputMode, Tab, ToastStyle};
+
+impl App {
+    /// Open the diff viewer for the selected task.
+    pub(super) fn open_diff_view(&mut self) {
+        let Some(task) = self
+            .visible_tasks()
+            .get(self.task_index)
+            .map(|t| (*t).clone())
+        else {
+            return;
+        };
+        self.open_diff_view_for(&task);
+    }
+
+    /// Open the diff viewer for `task` (also used by the fan-out compare view).
+    pub(super) fn open_diff_view_for(&mut self, task: &Task) {
+        let view = self
+            .diff_source_for_task(task)
+            .and_then(|(source, session_id)| {
+                let files = diff::load_diff(&source)?;
+                let staged = match &source {
+                    DiffSource::Worktree { path, .. } => diff::staged_paths(path)?,
+                    DiffSource::Branch { .. } => HashSet::default(),
+                };
+                Ok(DiffViewState {
+                    task_title: task.title.clone(),
+                    session_id,
+                    source,
+                    files,
+                    staged,
+                    file_index: 0,
+                    row_index: 0,
+                    files_focused: true,
+                    comments: Vec::new(),
+                    confirm_discard: false,
+                })
+            });
+        match view {
+            Ok(view) => {
+                self.diff_view = Some(view);
+                self.input_mode = InputMode::DiffView;
+            }
+            Err(e) => self.show_toast(format!("Diff failed: {e}"), ToastStyle::Error),
+        }
+    }
+
+    /// Where a task's changes live: the session worktree while it is open,
+    /// otherwise the session's pushed branch in the main repo. Also returns
+    /// the session that should receive review comments (live sessions only).
+    fn diff_source_for_task(&self, task: &Task) -> Result<(DiffSource, Option<String>)> {
+        let project = self.store.get_project(&task.project_id)?;
+        let base = task
+            .base
+            .clone()
+            .unwrap_or_else(|| project.default_branch.clone());
+        let session = task
+            .session_id
+            .as_deref()
+            .and_then(|id| self.store.get_session(id).ok());
+
+        if let Some(session) = &session
+            && session.closed_at.is_none()
+            && Path::new(&session.worktree_path).exists()
+        {
+            let source = DiffSource::Worktree {
+                path: session.worktree_path.clone(),
+                base,
+            };
+            return Ok((source, Some(session.id.clone())));
+        }
+
+        let branch = session
+            .map(|s| s.branch_name)
+            .or_else(|| task.branch.clone())
+            .context("task has no worktree or pushed branch to diff")?;
+        let source = DiffSource::Branch {
+            repo_path: project.repo_path,
+            base,
+            branch,
+        };
+        Ok((source, None))
+    }
+
+    /// Re-read the diff after a stage or discard, keeping the selection in range.
+    fn reload_diff_view(&mut self) -> Result<()> {
+        let Some(view) = self.diff_view.as_mut() else {
+            return Ok(());
+        };
+        view.files = diff::load_diff(&view.source)?;
+        if let DiffSource::Worktree { path, .. } = &view.source {
+            view.staged = diff::staged_paths(path)?;
+        }
+        view.file_index = view.file_index.min(view.files.len().saturating_sub(1));
+        let rows = view
+            .files
+            .get(view.file_index)
+            .map_or(0, |f| f.rows().len());
+        view.row_index = view.row_index.min(rows.saturating_sub(1));
+        Ok(())
+    }
+
+    pub(super) fn handle_diff_view_key(&mut self, code: KeyCode) -> Result<()> {
+        let Some(view) = self.diff_view.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        let confirm_discard = std::mem::take(&mut view.confirm_discard);
+        let file_count = view.files.len();
+        let row_count = view
+            .files
+            .get(view.file_index)
+            .map_or(0, |f| f.rows().len());
+
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.diff_view = None;
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Tab => view.files_focused = !view.files_focused,
+            KeyCode::Char('h') | KeyCode::Left => view.files_focused = true,
+            KeyCode::Char('l') | KeyCode::Right => view.files_focused = false,
+            KeyCode::Char('j') | KeyCode::Down => {
+                if view.files_focused {
+                    if view.file_index + 1 < file_count {
+                        view.file_index += 1;
+                        view.row_index = 0;
+                    }
+                } else if view.row_index + 1 < row_count {
+                    view.row_index += 1;
+                }
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                if view.files_focused {
+                    if view.file_index > 0 {
+                        view.file_index -= 1;
+                        view.row_index = 0;
+                    }
+                } else {
+                    view.row_index = view.row_index.saturating_sub(1);
+                }
+            }
+            KeyCode::Char(']') if view.file_index + 1 < file_count => {
+                view.file_index += 1;
+                view.row_index = 0;
+            }
+            KeyCode::Char('[') if view.file_index > 0 => {
+                view.file_index -= 1;
+                view.row_index = 0;
+            }
+            KeyCode::Char('c') if file_count > 0 => {
+                self.input_buffer.clear();
+                self.input_cursor = 0;
+                self.input_mode = InputMode::DiffComment;
+            }
+            KeyCode::Char('S') => self.send_review_comments(),
+            KeyCode::Char('r') => {
+                if let Err(e) = self.reload_diff_view() {
+                    self.show_toast(format!("Reload failed: {e}"), ToastStyle::Error);
+                }
+            }
+            KeyCode::Char('s' | 'x') if view.source.is_read_only() => {
+                self.show_toast(
+                    "Read-only: the session's worktree is gone",
+                    ToastStyle::Info,
+                );
+            }
+            KeyCode::Char('s') => {
+                let DiffSource::Worktree { path, .. } = &view.source else {
+                    return Ok(());
+                };
+                if let Some(file) = view.files.get(view.file_index) {
+                    let result = diff::stage_file(path, &file.path);
+                    let name = file.path.clone();
+                    match result.and_then(|()| self.reload_diff_view()) {
+                        Ok(()) => self.show_toast(format!("Staged {name}"), ToastStyle::Success),
+                        Err(e) => self.show_toast(format!("Stage failed: {e}"), ToastStyle::Error),
+                    }
+                }
+            }
+            KeyCode::Char('x') => {
+                let DiffSource::Worktree { path, base } = &view.source else {
+                    return Ok(());
+                };
+                let Some(file) = view.files.get(view.file_index) else {
+                    return Ok(());
+                };
+                if !confirm_discard {
+                    view.confirm_discard = true;
+                    let message = format!("Press x again to discard changes to {}", file.path);
+                    self.show_toast(message, ToastStyle::Info);
+                    return Ok(());
+                }
+                let result = diff::discard_file(path, base, file);
+                let name = file.path.clone();
+                match result.and_then(|()| self.reload_diff_view()) {
+                    Ok(()) => self.show_toast(format!("Discarded {name}"), ToastStyle::Success),
+                    Err(e) => self.show_toast(format!("Discard failed: {e}"), ToastStyle::Error),
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Typing a review comment for the selected file/line.
+    pub(super) fn handle_diff_comment_key(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Result<()> {
+        match code {
+            KeyCode::Esc => self.input_mode = InputMode::DiffView,
+            KeyCode::Enter => {
+                let text = self.input_buffer.trim().to_string();
+                if !text.is_empty()
+                    && let Some(view) = self.diff_view.as_mut()
+                    && let Some(file) = view.files.get(view.file_index)
+                {
+                    let line = match file.rows().get(view.row_index) {
+                        Some(DiffRow::Line(l)) => l.new_line.or(l.old_line),
+                        _ => None,
+                    };
+                    view.comments.push(ReviewComment {
+                        path: file.path.clone(),
+                        line,
+                        text,
+                    });
+                }
+                self.input_buffer.clear();
+                self.input_cursor = 0;
+                self.input_mode = InputMode::DiffView;
+            }
+            _ => {
+                apply_text_edit(
+                    &mut self.input_buffer,
+                    &mut self.input_cursor,
+                    code,
+                    modifiers,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Paste the collected comments into the session's Claude pane as one prompt.
+    fn send_review_comments(&mut self) {
+        let Some(view) = self.diff_view.as_ref() else {
+            return;
+        };
+        if view.comments.is_empty() {
+            self.show_toast("No comments to send (c to add one)", ToastStyle::Info);
+            return;
+        }
+        let Some(session_id) = view.session_id.clone() else {
+            self.show_toast(
+                "Session is closed; comments can't be sent",
+                ToastStyle::Error,
+            );
+            return;
+        };
+        let prompt = diff::review_prompt(&view.comments);
+        let count = view.comments.len();
+
+        let terminals = self.tabs.iter_mut().find_map(|tab| match tab {
+            Tab::Session {
+                session_id: sid,
+                terminals,
+                ..
+            } if *sid == session_id => Some(terminals),
+            _ => None,
+        });
+        let Some(terminals) = terminals else {
+            self.show_toast("Session tab is not open", ToastStyle::Error);
+            return;
+        };
+        let bytes = format!("\x1b[200~{prompt}\x1b[201~\r");
+        match terminals.send_to_claude(bytes.as_bytes()) {
+            Ok(()) => {
+                if let Some(view) = self.diff_view.as_mut() {
+                    view.comments.clear();
+                }
+                self.show_toast(
+                    format!("Sent {count} comment(s) to Claude"),
+                    ToastStyle::Success,
+                );
+            }
+            Err(e) => self.show_toast(format!("Send failed: {e}"), ToastStyle::Error),
+        }
+    }
+}
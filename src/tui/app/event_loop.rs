@@ -110,6 +110,7 @@ impl App {
                     self.poll_git_stats_results();
                     self.poll_scanner_results();
                     self.poll_update_results();
+                    self.poll_activity_feed();
 
                     // Slow-path tick work (DB refresh, background polls)
                     // Throttled on all tabs: dashboard ticks are now 200 ms,
@@ -118,6 +119,11 @@ impl App {
                     if run_slow {
                         self.last_slow_tick = std::time::Instant::now();
                         self.maybe_poll_pr_merges();
+                        self.maybe_flush_webhooks();
+                        self.relay_terminal_notifications();
+                        self.maybe_run_schedules();
+                        self.maybe_run_watchdog();
+                        self.deliver_queued_messages();
                         self.maybe_poll_git_stats();
                         self.maybe_scan_external_sessions();
                         self.maybe_poll_update_check();
@@ -0,0 +1,321 @@
+//! Best-of-N fan-out: launch one task as several variants with different
+//! models or effort, compare them side by side, and promote the winner.
+
+use anyhow::Result;
+use crossterm::event::KeyCode;
+
+use super::{
+    App, FanoutCompareState, FanoutLaunchState, Focus, InputMode, SessionOpResult, ToastStyle,
+    VariantConfirm,
+};
+use crate::store::{Task, TaskMode, TaskStatus, TaskVariant};
+
+impl App {
+    /// `F` on a task: open the compare view if it is (or belongs to) a
+    /// fan-out, otherwise the fan-out dialog for a pending task.
+    pub(super) fn open_fanout(&mut self) {
+        if self.focus != Focus::Tasks {
+            return;
+        }
+        let Some(task) = self
+            .visible_tasks()
+            .get(self.task_index)
+            .map(|t| (*t).clone())
+        else {
+            return;
+        };
+        let parent_id = match self.store.get_task_variant(&task.id) {
+            Ok(Some(variant)) => Some(variant.parent_task_id),
+            Ok(None) => (!self
+                .store
+                .list_task_variants(&task.id)
+                .unwrap_or_default()
+                .is_empty())
+            .then(|| task.id.clone()),
+            Err(e) => {
+                self.show_toast(format!("Fan-out failed: {e}"), ToastStyle::Error);
+                return;
+            }
+        };
+        if let Some(parent_id) = parent_id {
+            self.open_fanout_compare(&parent_id);
+            return;
+        }
+
+        if task.status != TaskStatus::Pending || task.mode == TaskMode::Exploration {
+            self.show_toast(
+                "Only pending supervised or autonomous tasks can be fanned out",
+                ToastStyle::Info,
+            );
+            return;
+        }
+        let count = self.config.fanout.variants.len();
+        if count < 2 {
+            self.show_toast(
+                "Configure at least two [[fanout.variants]] to fan out",
+                ToastStyle::Info,
+            );
+            return;
+        }
+        self.fanout_launch = Some(FanoutLaunchState {
+            task_id: task.id,
+            project_id: task.project_id,
+            title: task.title,
+            selected: vec![true; count],
+            index: 0,
+        });
+        self.input_mode = InputMode::FanoutLaunch;
+    }
+
+    pub(super) fn handle_fanout_launch_key(&mut self, code: KeyCode) -> Result<()> {
+        let Some(launch) = self.fanout_launch.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        match code {
+            KeyCode::Esc => {
+                self.fanout_launch = None;
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                launch.index = (launch.index + 1).min(launch.selected.len().saturating_sub(1));
+            }
+            KeyCode::Char('k') | KeyCode::Up => launch.index = launch.index.saturating_sub(1),
+            KeyCode::Char(' ') => {
+                if let Some(selected) = launch.selected.get_mut(launch.index) {
+                    *selected = !*selected;
+                }
+            }
+            KeyCode::Enter => self.launch_fanout()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Create the variant tasks and queue their sessions; they launch one at
+    /// a time through the auto-launch queue.
+    fn launch_fanout(&mut self) -> Result<()> {
+        let Some(launch) = self.fanout_launch.take() else {
+            return Ok(());
+        };
+        self.input_mode = InputMode::Normal;
+        let variants: Vec<(Option<&str>, Option<&str>)> = self
+            .config
+            .fanout
+            .variants
+            .iter()
+            .zip(&launch.selected)
+            .filter(|(_, selected)| **selected)
+            .map(|(v, _)| (v.model.as_deref(), v.effort.as_deref()))
+            .collect();
+        let children = match self.store.fan_out_task(&launch.task_id, &variants) {
+            Ok(children) => children,
+            Err(e) => {
+                self.show_toast(format!("Fan-out failed: {e:#}"), ToastStyle::Error);
+                return Ok(());
+            }
+        };
+        self.show_toast(
+            format!(
+                "Fanned out '{}' into {} variants",
+                launch.title,
+                children.len()
+            ),
+            ToastStyle::Success,
+        );
+        for child in children {
+            self.startup_auto_launch
+                .push_back((launch.project_id.clone(), child));
+        }
+        self.refresh_data()?;
+        self.open_fanout_compare(&launch.task_id);
+        Ok(())
+    }
+
+    fn open_fanout_compare(&mut self, parent_task_id: &str) {
+        let title = match self.store.get_task(parent_task_id) {
+            Ok(parent) => parent.title,
+            Err(e) => {
+                self.show_toast(format!("Fan-out failed: {e}"), ToastStyle::Error);
+                return;
+            }
+        };
+        self.fanout_compare = Some(FanoutCompareState {
+            parent_task_id: parent_task_id.to_string(),
+            title,
+            index: 0,
+            confirm: None,
+        });
+        self.input_mode = InputMode::FanoutCompare;
+    }
+
+    /// The open compare view's variants with their tasks, in fan-out order.
+    pub(crate) fn fanout_rows(&self) -> Vec<(TaskVariant, Task)> {
+        let Some(compare) = self.fanout_compare.as_ref() else {
+            return Vec::new();
+        };
+        self.store
+            .list_task_variants(&compare.parent_task_id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|v| {
+                let task = self.store.get_task(&v.task_id).ok()?;
+                Some((v, task))
+            })
+            .collect()
+    }
+
+    pub(super) fn handle_fanout_compare_key(&mut self, code: KeyCode) -> Result<()> {
+        let rows = self.fanout_rows();
+        let Some(compare) = self.fanout_compare.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        if let Some(confirm) = compare.confirm.take() {
+            if matches!(code, KeyCode::Char('y' | 'Y'))
+                && let Some((variant, task)) = rows.get(compare.index).cloned()
+            {
+                match confirm {
+                    VariantConfirm::Promote => self.promote_variant(&variant, &task, &rows),
+                    VariantConfirm::Discard => self.discard_variant(&variant, &task),
+                }
+            }
+            return Ok(());
+        }
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => self.close_fanout_compare(),
+            KeyCode::Char('j') | KeyCode::Down => {
+                compare.index = (compare.index + 1).min(rows.len().saturating_sub(1));
+            }
+            KeyCode::Char('k') | KeyCode::Up => compare.index = compare.index.saturating_sub(1),
+            KeyCode::Enter | KeyCode::Char('p') if !rows.is_empty() => {
+                compare.confirm = Some(VariantConfirm::Promote);
+            }
+            KeyCode::Char('x') if !rows.is_empty() => {
+                compare.confirm = Some(VariantConfirm::Discard);
+            }
+            KeyCode::Char('t') => self.run_variant_tests(&rows),
+            KeyCode::Char('d') => {
+                if let Some((_, task)) = rows.get(compare.index) {
+                    self.open_diff_view_for(task);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Run `[fanout] test_command` in every launched variant's worktree on
+    /// background threads; results land in the store and show on redraw.
+    fn run_variant_tests(&mut self, rows: &[(TaskVariant, Task)]) {
+        let Some(command) = self.config.fanout.test_command.clone() else {
+            self.show_toast(
+                "Set [fanout] test_command to compare test results",
+                ToastStyle::Info,
+            );
+            return;
+        };
+        let launched: Vec<String> = rows
+            .iter()
+            .filter(|(_, task)| task.session_id.is_some())
+            .map(|(v, _)| v.task_id.clone())
+            .collect();
+        if launched.is_empty() {
+            self.show_toast("No variant has been launched yet", ToastStyle::Info);
+            return;
+        }
+        self.show_toast(
+            format!("Running tests in {} variants...", launched.len()),
+            ToastStyle::Info,
+        );
+        for task_id in launched {
+            let command = command.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = crate::store::Store::open().and_then(|store| {
+                    crate::session::fanout::run_variant_tests(&store, &task_id, &command)
+                }) {
+                    tracing::warn!(%task_id, "fan-out: test run failed: {e:#}");
+                }
+            });
+        }
+    }
+
+    /// Push the winner (opening its PR) on a background thread; the other
+    /// variants' tabs close when the op reports back.
+    fn promote_variant(&mut self, winner: &TaskVariant, task: &Task, rows: &[(TaskVariant, Task)]) {
+        if self.session_op_in_progress {
+            self.show_toast("Session operation in progress...", ToastStyle::Info);
+            return;
+        }
+        if task.session_id.is_none() {
+            self.show_toast("This variant has not been launched yet", ToastStyle::Info);
+            return;
+        }
+        // Variants still waiting to launch must not start after the promotion.
+        self.startup_auto_launch
+            .retain(|(_, queued)| rows.iter().all(|(_, t)| t.id != queued.id));
+        self.close_fanout_compare();
+        self.session_op_in_progress = true;
+        self.show_toast(
+            format!("Promoting variant #{}...", winner.variant),
+            ToastStyle::Info,
+        );
+
+        let tx = self.session_op_tx.clone();
+        let task_id = task.id.clone();
+        let n = winner.variant;
+        std::thread::spawn(move || {
+            let result = crate::store::Store::open()
+                .and_then(|store| crate::session::fanout::promote_variant(&store, &task_id));
+            let _ = tx.send(match result {
+                Ok(promotion) => SessionOpResult::Promoted {
+                    message: match promotion.parent.pr_url {
+                        Some(url) => format!("Promoted variant #{n}: {url}"),
+                        None => format!("Promoted variant #{n}: branch pushed"),
+                    },
+                    closed_sessions: promotion.closed_sessions,
+                },
+                Err(e) => SessionOpResult::Error {
+                    message: format!("Promotion failed: {e:#}"),
+                },
+            });
+        });
+    }
+
+    fn discard_variant(&mut self, variant: &TaskVariant, task: &Task) {
+        if self.session_op_in_progress {
+            self.show_toast("Session operation in progress...", ToastStyle::Info);
+            return;
+        }
+        self.startup_auto_launch
+            .retain(|(_, queued)| queued.id != task.id);
+        if let Some(ref sid) = task.session_id {
+            self.remove_session_tab(sid);
+        }
+        if let Some(compare) = self.fanout_compare.as_mut() {
+            compare.index = compare.index.saturating_sub(1);
+        }
+        self.session_op_in_progress = true;
+
+        let tx = self.session_op_tx.clone();
+        let task_id = task.id.clone();
+        let n = variant.variant;
+        std::thread::spawn(move || {
+            let result = crate::store::Store::open()
+                .and_then(|store| crate::session::fanout::discard_variant(&store, &task_id));
+            let _ = tx.send(match result {
+                Ok(()) => SessionOpResult::TornDown {
+                    message: format!("Discarded variant #{n}"),
+                },
+                Err(e) => SessionOpResult::Error {
+                    message: format!("Discard failed: {e:#}"),
+                },
+            });
+        });
+    }
+
+    fn close_fanout_compare(&mut self) {
+        self.fanout_compare = None;
+        self.input_mode = InputMode::Normal;
+    }
+}
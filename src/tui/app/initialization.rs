@@ -59,6 +59,18 @@ impl App {
             }
         }
 
+        // Catch up on schedules that came due while claustre was closed. Each
+        // fires once; auto-launch clones are picked up by the startup queue below.
+        if let Err(e) = crate::scheduler::run_due(&store, chrono::Utc::now()) {
+            tracing::warn!("scheduled tasks: {e:#}");
+        }
+
+        // Move long-done tasks out of the queue (`[gc] archive_after_days`)
+        let config = crate::config::load().unwrap_or_default();
+        if let Err(e) = crate::gc::archive_due(&store, &config.gc) {
+            tracing::warn!("archiving done tasks: {e:#}");
+        }
+
         let (sessions, tasks) = if let Some(project) = projects.first() {
             let sessions = store.list_sessions_for_project(&project.id)?;
             let tasks = store.list_tasks_for_project(&project.id)?;
@@ -71,7 +83,7 @@ impl App {
             .first()
             .and_then(|p| store.project_stats(&p.id).ok());
 
-        let palette_items = vec![
+        let mut palette_items = vec![
             PaletteItem {
                 label: "New Task".into(),
                 action: PaletteAction::NewTask,
@@ -108,14 +120,27 @@ impl App {
                 label: "Sprint Board".into(),
                 action: PaletteAction::SprintBoard,
             },
+            PaletteItem {
+                label: "Prompt Inbox".into(),
+                action: PaletteAction::PromptInbox,
+            },
+            PaletteItem {
+                label: "Review Task Diff".into(),
+                action: PaletteAction::ReviewDiff,
+            },
+            PaletteItem {
+                label: "All Projects".into(),
+                action: PaletteAction::AllProjects,
+            },
             PaletteItem {
                 label: "Quit".into(),
                 action: PaletteAction::Quit,
             },
         ];
-        let palette_filtered: Vec<usize> = (0..palette_items.len()).collect();
 
         let project_summaries = build_project_summaries(&store, &projects);
+        // Only relay notifications recorded while this TUI is running.
+        let last_notify_event_id = store.last_task_event_id().unwrap_or(0);
         let rate_limit_state = store.get_rate_limit_state().unwrap_or_default();
         let external_sessions = store.list_external_sessions().unwrap_or_default();
 
@@ -134,8 +159,19 @@ impl App {
         let (sc_tx, sc_rx) = mpsc::channel();
         let (so_tx, so_rx) = mpsc::channel();
         let (up_tx, up_rx) = mpsc::channel();
+        let (af_tx, af_rx) = mpsc::channel();
 
-        let config = crate::config::load().unwrap_or_default();
+        palette_items.extend(
+            config
+                .views
+                .iter()
+                .enumerate()
+                .map(|(i, view)| PaletteItem {
+                    label: format!("View: {}", view.name),
+                    action: PaletteAction::ApplyView(i),
+                }),
+        );
+        let palette_filtered: Vec<usize> = (0..palette_items.len()).collect();
         let theme = config.theme.build();
         let board_columns: Vec<String> = config
             .board
@@ -196,8 +232,10 @@ impl App {
             confirm_entity_id: String::new(),
             confirm_delete_kind: DeleteTarget::Project,
             editing_task_id: None,
+            all_projects: false,
             task_filter: String::new(),
             task_filter_cursor: 0,
+            task_filter_error: None,
             subtasks: vec![],
             subtask_index: 0,
             subtask_counts: HashMap::new(),
@@ -226,6 +264,10 @@ impl App {
             pr_poll_tx: pr_tx,
             pr_poll_rx: pr_rx,
             last_pr_poll: Instant::now(),
+            webhook_flush_in_progress: Arc::new(AtomicBool::new(false)),
+            last_webhook_flush: Instant::now(),
+            last_notify_event_id,
+            last_schedule_check: Instant::now(),
             git_stats_in_progress: Arc::new(AtomicBool::new(false)),
             git_stats_tx: gs_tx,
             git_stats_rx: gs_rx,
@@ -247,10 +289,31 @@ impl App {
             prev_task_statuses,
             notified_in_review: HashSet::new(),
             review_loop_spawned: HashSet::new(),
+            ci_fix_spawned: HashSet::new(),
+            conflict_resolution_spawned: HashSet::new(),
+            auto_merge_reported: HashSet::new(),
             last_slow_tick: Instant::now(),
             last_terminal_area: Rect::default(),
             paused_sessions: HashSet::new(),
             waiting_sessions: HashSet::new(),
+            replied_sessions: HashSet::new(),
+            inbox_index: 0,
+            diff_view: None,
+            pane_picker: None,
+            pane_drag: None,
+            activity_feeds: HashMap::new(),
+            activity_in_progress: Arc::new(AtomicBool::new(false)),
+            activity_tx: af_tx,
+            activity_rx: af_rx,
+            last_activity_poll: Instant::now(),
+            last_transcript_lookup: Instant::now(),
+            message: None,
+            watchdog: HashMap::new(),
+            last_watchdog_check: Instant::now(),
+            template_picker: None,
+            retry: None,
+            fanout_launch: None,
+            fanout_compare: None,
             cached_visible_indices: Vec::new(),
             update_check_in_progress: Arc::new(AtomicBool::new(false)),
             config_warning,
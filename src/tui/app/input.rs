@@ -4,11 +4,13 @@ use ratatui::layout::Rect;
 
 use crate::pty::SplitDirection;
 
+use super::super::clipboard;
 use super::super::form::apply_text_edit;
 use super::super::ui;
+use super::pane_resize::RESIZE_STEP;
 use super::{
-    App, DeleteTarget, Focus, InputMode, PaletteAction, ProjectSummary, Tab, ToastStyle,
-    compute_pane_sizes_for_resize, fallback_title,
+    App, DeleteTarget, Focus, InboxEntry, InputMode, PaletteAction, ProjectSummary, PromptKind,
+    PromptReply, Tab, ToastStyle, compute_pane_sizes_for_resize, fallback_title,
 };
 
 impl App {
@@ -51,6 +53,14 @@ impl App {
             InputMode::BoardView => self.handle_board_key(code, modifiers)?,
             InputMode::MilestoneFilter => self.handle_milestone_filter_key(code)?,
             InputMode::BoardFilter => self.handle_board_filter_key(code, modifiers)?,
+            InputMode::Inbox => self.handle_inbox_key(code)?,
+            InputMode::DiffView => self.handle_diff_view_key(code)?,
+            InputMode::DiffComment => self.handle_diff_comment_key(code, modifiers)?,
+            InputMode::TemplatePicker => self.handle_template_picker_key(code, modifiers)?,
+            InputMode::RetryTask => self.handle_retry_key(code, modifiers)?,
+            InputMode::MessageSession => self.handle_message_key(code, modifiers),
+            InputMode::FanoutLaunch => self.handle_fanout_launch_key(code)?,
+            InputMode::FanoutCompare => self.handle_fanout_compare_key(code)?,
         }
         Ok(())
     }
@@ -62,14 +72,35 @@ impl App {
         code: KeyCode,
         modifiers: KeyModifiers,
     ) -> Result<()> {
+        if self.handle_pane_picker_key(code)? {
+            return Ok(());
+        }
+        if self.handle_copy_mode_key(code, modifiers)? {
+            return Ok(());
+        }
+        if self.handle_session_search_key(code, modifiers)? {
+            return Ok(());
+        }
         if let Some(action) = self.keymap.lookup_session(code, modifiers) {
             self.execute_session_action(action)?;
             return Ok(());
         }
+        // `/` searches while the focused pane is scrolled back into history
+        if code == KeyCode::Char('/')
+            && modifiers.is_empty()
+            && let Some(Tab::Session { terminals, .. }) = self.tabs.get_mut(self.active_tab)
+            && terminals
+                .focused_terminal()
+                .is_some_and(|t| t.scrollback() > 0)
+        {
+            self.open_session_search();
+            return Ok(());
+        }
 
         // Forward to focused PTY, clear selection, and snap back to live screen
         if let Some(Tab::Session { terminals, .. }) = self.tabs.get_mut(self.active_tab) {
             terminals.selection = None;
+            terminals.search = None;
             if let Some(term) = terminals.focused_terminal() {
                 term.reset_scrollback();
                 let key_bytes = keycode_to_bytes(code, modifiers);
@@ -88,14 +119,17 @@ impl App {
             Action::ReturnToDashboard => {
                 self.active_tab = 0;
             }
-            Action::FocusPrevPane => {
-                if let Some(Tab::Session { terminals, .. }) = self.tabs.get_mut(self.active_tab) {
-                    terminals.focus_prev();
-                }
-            }
-            Action::FocusNextPane => {
+            Action::FocusPrevPane | Action::FocusNextPane => {
                 if let Some(Tab::Session { terminals, .. }) = self.tabs.get_mut(self.active_tab) {
-                    terminals.focus_next();
+                    let was_zoomed = terminals.zoomed;
+                    if action == Action::FocusPrevPane {
+                        terminals.focus_prev();
+                    } else {
+                        terminals.focus_next();
+                    }
+                    if was_zoomed {
+                        self.fit_session_panes();
+                    }
                 }
             }
             Action::ScrollToBottom => {
@@ -125,48 +159,22 @@ impl App {
             }
             Action::PrevTab => self.prev_tab(),
             Action::NextTab => self.next_tab(),
-            Action::SplitRight => {
-                let term_size = crossterm::terminal::size().unwrap_or((80, 24));
-                let rows = term_size.1.saturating_sub(2);
-                let cols = term_size.0;
-                let split_err = if let Some(Tab::Session { terminals, .. }) =
-                    self.tabs.get_mut(self.active_tab)
-                {
-                    let err = terminals
-                        .split_focused(SplitDirection::Horizontal, rows, cols)
-                        .err();
-                    let sizes =
-                        compute_pane_sizes_for_resize(&terminals.layout, term_size.0, term_size.1);
-                    let _ = terminals.resize_panes_with_clear(&sizes);
-                    err
-                } else {
-                    None
-                };
-                if let Some(e) = split_err {
-                    self.show_toast(format!("Split failed: {e}"), ToastStyle::Error);
-                }
+            Action::SplitRight => self.split_focused_pane(SplitDirection::Horizontal, None)?,
+            Action::SplitDown => self.split_focused_pane(SplitDirection::Vertical, None)?,
+            Action::SplitWithCommand => self.open_pane_picker(),
+            Action::ResizePaneLeft => {
+                self.resize_focused_pane(SplitDirection::Horizontal, -RESIZE_STEP)?;
             }
-            Action::SplitDown => {
-                let term_size = crossterm::terminal::size().unwrap_or((80, 24));
-                let rows = term_size.1.saturating_sub(2);
-                let cols = term_size.0;
-                let split_err = if let Some(Tab::Session { terminals, .. }) =
-                    self.tabs.get_mut(self.active_tab)
-                {
-                    let err = terminals
-                        .split_focused(SplitDirection::Vertical, rows, cols)
-                        .err();
-                    let sizes =
-                        compute_pane_sizes_for_resize(&terminals.layout, term_size.0, term_size.1);
-                    let _ = terminals.resize_panes_with_clear(&sizes);
-                    err
-                } else {
-                    None
-                };
-                if let Some(e) = split_err {
-                    self.show_toast(format!("Split failed: {e}"), ToastStyle::Error);
-                }
+            Action::ResizePaneRight => {
+                self.resize_focused_pane(SplitDirection::Horizontal, RESIZE_STEP)?;
+            }
+            Action::ResizePaneUp => {
+                self.resize_focused_pane(SplitDirection::Vertical, -RESIZE_STEP)?;
             }
+            Action::ResizePaneDown => {
+                self.resize_focused_pane(SplitDirection::Vertical, RESIZE_STEP)?;
+            }
+            Action::ZoomPane => self.toggle_pane_zoom(),
             Action::ClosePane => {
                 let close_result = if let Some(Tab::Session { terminals, .. }) =
                     self.tabs.get_mut(self.active_tab)
@@ -189,10 +197,14 @@ impl App {
                 } else {
                     None
                 };
-                if close_result == Some(false) {
-                    self.show_toast("Cannot close this pane", ToastStyle::Info);
+                match close_result {
+                    Some(true) => self.save_session_layout()?,
+                    Some(false) => self.show_toast("Cannot close this pane", ToastStyle::Info),
+                    None => {}
                 }
             }
+            Action::SearchScrollback => self.open_session_search(),
+            Action::CopyMode => self.open_copy_mode(),
             // Normal-mode-only actions are no-ops in session mode
             _ => {}
         }
@@ -247,7 +259,10 @@ impl App {
                 self.search_results.clear();
                 self.skill_status_message.clear();
             }
-            InputMode::SkillAdd | InputMode::SubtaskPanel => {
+            InputMode::SkillAdd
+            | InputMode::SubtaskPanel
+            | InputMode::RetryTask
+            | InputMode::MessageSession => {
                 self.input_buffer
                     .insert_str(self.input_cursor.min(self.input_buffer.len()), text);
                 self.input_cursor = (self.input_cursor + text.len()).min(self.input_buffer.len());
@@ -282,7 +297,7 @@ impl App {
     pub(super) fn handle_resize(&mut self, cols: u16, rows: u16) {
         for tab in &mut self.tabs {
             if let Tab::Session { terminals, .. } = tab {
-                let sizes = compute_pane_sizes_for_resize(&terminals.layout, cols, rows);
+                let sizes = compute_pane_sizes_for_resize(&terminals.visible_layout(), cols, rows);
                 let _ = terminals.resize_panes_with_clear(&sizes);
             }
         }
@@ -296,21 +311,25 @@ impl App {
     fn session_pane_inner_areas(&self) -> Vec<(crate::pty::PaneId, Rect)> {
         use super::collect_pane_inner_areas;
 
+        if let Some(Tab::Session { terminals, .. }) = self.tabs.get(self.active_tab) {
+            collect_pane_inner_areas(&terminals.visible_layout(), self.session_panes_area())
+        } else {
+            vec![]
+        }
+    }
+
+    /// Screen area holding the session tab's panes (between the tab bar and
+    /// the hint bar), in absolute screen coordinates.
+    pub(super) fn session_panes_area(&self) -> Rect {
         let size = self.last_terminal_area;
         let has_tab_bar = self.tabs.len() > 1;
         let tab_bar_height = u16::from(has_tab_bar);
 
-        let term_area = Rect {
+        Rect {
             x: 0,
             y: tab_bar_height,
             width: size.width,
             height: size.height.saturating_sub(tab_bar_height + 1),
-        };
-
-        if let Some(Tab::Session { terminals, .. }) = self.tabs.get(self.active_tab) {
-            collect_pane_inner_areas(&terminals.layout, term_area)
-        } else {
-            vec![]
         }
     }
 
@@ -367,6 +386,12 @@ impl App {
                 }
             }
 
+            // Dragging a divider between panes resizes the split, even when
+            // the pointer passes over a pane that tracks the mouse.
+            if self.handle_divider_mouse(&mouse)? {
+                return Ok(());
+            }
+
             // Determine target pane and check mouse protocol.
             // `should_forward_mouse()` returns false when the process has
             // exited — preventing scroll events from being silently consumed
@@ -406,10 +431,14 @@ impl App {
                             self.tabs.get_mut(self.active_tab)
                         {
                             terminals.focused = pane;
+                            let line = terminals
+                                .terminal(pane)
+                                .map_or(0, |term| term.viewport_top() + usize::from(vt_row));
                             terminals.selection = Some(crate::pty::Selection {
                                 pane,
-                                start: (vt_row, vt_col),
-                                end: (vt_row, vt_col),
+                                start: (line, vt_col),
+                                end: (line, vt_col),
+                                kind: crate::pty::SelectionKind::Char,
                             });
                         }
                     } else {
@@ -426,16 +455,26 @@ impl App {
                     // Compute pane areas before mutable borrow
                     let pane_areas = self.session_pane_inner_areas();
                     if let Some(Tab::Session { terminals, .. }) = self.tabs.get_mut(self.active_tab)
-                        && let Some(ref mut sel) = terminals.selection
+                        && let Some(mut sel) = terminals.selection
                         && let Some((_, inner)) = pane_areas.iter().find(|(id, _)| *id == sel.pane)
+                        && let Some(term) = terminals.terminal_mut(sel.pane)
                     {
+                        // Dragging past the top or bottom edge scrolls one
+                        // line so the selection can extend across scrollback.
+                        let top = term.viewport_top();
+                        if row < inner.y {
+                            term.reveal_line(top.saturating_sub(1));
+                        } else if row >= inner.y + inner.height {
+                            term.reveal_line(top + usize::from(inner.height));
+                        }
                         let vt_row = row
                             .saturating_sub(inner.y)
                             .min(inner.height.saturating_sub(1));
                         let vt_col = col
                             .saturating_sub(inner.x)
                             .min(inner.width.saturating_sub(1));
-                        sel.end = (vt_row, vt_col);
+                        sel.end = (term.viewport_top() + usize::from(vt_row), vt_col);
+                        terminals.selection = Some(sel);
                     }
                     return Ok(());
                 }
@@ -454,19 +493,11 @@ impl App {
                                 // Plain click — no drag occurred
                                 true
                             } else if let Some(term) = terminals.terminal_mut(sel.pane) {
-                                // Set the parser to the user's scroll offset so
-                                // screen.cell() reads from the scrolled viewport
-                                // (not the live screen).  Without this, copying
-                                // while scrolled back extracts text from the
-                                // bottom of the output instead of the visible
-                                // region.
-                                term.prepare_for_render();
-                                let text = sel.extract_text(term.screen());
-                                term.restore_after_render();
-                                if !text.is_empty()
-                                    && let Ok(mut clipboard) = arboard::Clipboard::new()
-                                {
-                                    let _ = clipboard.set_text(&text);
+                                // Selections address whole-history lines, so
+                                // they may reach beyond the visible screen.
+                                let text = sel.extract_text(&term.history());
+                                if !text.is_empty() {
+                                    clipboard::copy(&text);
                                 }
                                 false
                             } else {
@@ -659,7 +690,7 @@ impl App {
             Action::MoveDown => self.move_down(),
             Action::MoveUp => self.move_up(),
             Action::ReorderTaskDown => {
-                if self.focus == Focus::Tasks {
+                if self.focus == Focus::Tasks && !self.all_projects {
                     let visible = self.visible_tasks();
                     if let (Some(current), Some(next)) = (
                         visible.get(self.task_index),
@@ -675,7 +706,7 @@ impl App {
                 }
             }
             Action::ReorderTaskUp => {
-                if self.focus == Focus::Tasks && self.task_index > 0 {
+                if self.focus == Focus::Tasks && !self.all_projects && self.task_index > 0 {
                     let visible = self.visible_tasks();
                     if let (Some(current), Some(prev)) = (
                         visible.get(self.task_index),
@@ -692,6 +723,7 @@ impl App {
             }
             Action::Select => match self.focus {
                 Focus::Projects => {
+                    self.all_projects = false;
                     self.refresh_data()?;
                     self.task_index = 0;
                 }
@@ -715,10 +747,9 @@ impl App {
                                     "Session operation in progress...",
                                     ToastStyle::Info,
                                 );
-                            } else if let Some(project_id) =
-                                self.selected_project().map(|p| p.id.clone())
-                            {
-                                let task_id = task.id.clone();
+                            } else {
+                                let (task_id, project_id) =
+                                    (task.id.clone(), task.project_id.clone());
                                 self.launch_task(task_id, project_id)?;
                             }
                         }
@@ -834,6 +865,11 @@ impl App {
                         )
                     {
                         let sid = sid.clone();
+                        self.store.record_task_event(
+                            &task.id,
+                            crate::store::TaskEventKind::Action,
+                            "session killed",
+                        )?;
                         self.store
                             .update_task_status(&task.id, crate::store::TaskStatus::Pending)?;
                         self.store.unassign_task_from_session(&task.id)?;
@@ -869,9 +905,8 @@ impl App {
                     self.focus = Focus::Tasks;
                 } else if self.session_op_in_progress {
                     self.show_toast("Session operation in progress...", ToastStyle::Info);
-                } else if let Some(task) = self.visible_tasks().get(self.task_index).copied()
-                    && let Some(project_id) = self.selected_project().map(|p| p.id.clone())
-                {
+                } else if let Some(task) = self.visible_tasks().get(self.task_index).copied() {
+                    let project_id = task.project_id.clone();
                     if matches!(
                         task.status,
                         crate::store::TaskStatus::Pending | crate::store::TaskStatus::Draft
@@ -889,6 +924,11 @@ impl App {
                     {
                         // Relaunch: tear down old session, then auto-launch fresh
                         let sid = sid.clone();
+                        self.store.record_task_event(
+                            &task.id,
+                            crate::store::TaskEventKind::Action,
+                            "relaunched in a new session",
+                        )?;
                         self.store
                             .update_task_status(&task.id, crate::store::TaskStatus::Pending)?;
                         self.store.unassign_task_from_session(&task.id)?;
@@ -958,6 +998,12 @@ impl App {
                     }
                 }
             }
+            Action::OpenInbox => self.open_inbox(),
+            Action::OpenDiff => self.open_diff_view(),
+            Action::ToggleAllProjects => self.toggle_all_projects()?,
+            Action::RetryTask => self.open_retry_dialog(),
+            Action::MessageSession => self.open_message_dialog(),
+            Action::FanOut => self.open_fanout(),
             // Session-only actions are no-ops in normal mode
             Action::ReturnToDashboard
             | Action::FocusPrevPane
@@ -967,7 +1013,15 @@ impl App {
             | Action::ScrollPageDown
             | Action::SplitRight
             | Action::SplitDown
-            | Action::ClosePane => {}
+            | Action::ClosePane
+            | Action::SearchScrollback
+            | Action::CopyMode
+            | Action::SplitWithCommand
+            | Action::ResizePaneLeft
+            | Action::ResizePaneRight
+            | Action::ResizePaneUp
+            | Action::ResizePaneDown
+            | Action::ZoomPane => {}
         }
         Ok(())
     }
@@ -1146,6 +1200,10 @@ impl App {
         code: KeyCode,
         modifiers: KeyModifiers,
     ) -> Result<()> {
+        if code == KeyCode::Char('t') && modifiers.contains(KeyModifiers::CONTROL) {
+            self.open_template_picker();
+            return Ok(());
+        }
         if self.handle_task_form_shared_key(code, modifiers) {
             return Ok(());
         }
@@ -1260,7 +1318,7 @@ impl App {
         Ok(())
     }
 
-    fn save_current_task_field(&mut self) {
+    pub(super) fn save_current_task_field(&mut self) {
         match self.new_task_field {
             0 => self.new_task_description.clone_from(&self.input_buffer),
             2 => self.new_task_base.clone_from(&self.input_buffer),
@@ -1269,7 +1327,7 @@ impl App {
         }
     }
 
-    fn load_current_task_field(&mut self) {
+    pub(super) fn load_current_task_field(&mut self) {
         match self.new_task_field {
             0 => {
                 self.input_buffer.clone_from(&self.new_task_description);
@@ -1751,7 +1809,7 @@ impl App {
         }
     }
 
-    fn execute_palette_action(&mut self, action: PaletteAction) -> Result<()> {
+    pub(super) fn execute_palette_action(&mut self, action: PaletteAction) -> Result<()> {
         match action {
             PaletteAction::NewTask => {
                 if self.selected_project().is_some() {
@@ -1799,6 +1857,19 @@ impl App {
                     }
                 }
             }
+            PaletteAction::PromptInbox => self.open_inbox(),
+            PaletteAction::ReviewDiff => self.open_diff_view(),
+            PaletteAction::AllProjects => self.toggle_all_projects()?,
+            PaletteAction::ApplyView(i) => {
+                if let Some(view) = self.config.views.get(i) {
+                    self.task_filter = view.query.clone();
+                    self.task_filter_cursor = self.task_filter.len();
+                    // Reload rather than just re-filter: the view may ask for archived tasks
+                    self.refresh_data()?;
+                    self.task_index = 0;
+                    self.focus = Focus::Tasks;
+                }
+            }
             PaletteAction::Quit => self.should_quit = true,
             PaletteAction::Configure => {
                 self.cached_config_status =
@@ -1947,6 +2018,110 @@ impl App {
         Ok(())
     }
 
+    fn open_inbox(&mut self) {
+        self.inbox_index = 0;
+        self.input_mode = InputMode::Inbox;
+    }
+
+    /// Handle keys in the prompt inbox: navigate, reply to the selected
+    /// session, batch-approve identical permission prompts, or jump to its tab.
+    pub(super) fn handle_inbox_key(&mut self, code: KeyCode) -> Result<()> {
+        let entries = self.inbox_entries();
+        self.inbox_index = self.inbox_index.min(entries.len().saturating_sub(1));
+        let selected = entries.get(self.inbox_index);
+
+        let reply = match code {
+            KeyCode::Esc | KeyCode::Char('p' | 'q') => {
+                self.input_mode = InputMode::Normal;
+                None
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                if self.inbox_index + 1 < entries.len() {
+                    self.inbox_index += 1;
+                }
+                None
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.inbox_index = self.inbox_index.saturating_sub(1);
+                None
+            }
+            KeyCode::Enter => {
+                if let Some(entry) = selected
+                    && let Some(idx) = self.tabs.iter().position(|t| {
+                        matches!(t, Tab::Session { session_id, .. } if *session_id == entry.session_id)
+                    })
+                {
+                    self.active_tab = idx;
+                    self.input_mode = InputMode::Normal;
+                    self.flush_all_pty_output();
+                }
+                None
+            }
+            KeyCode::Char('y') => Some(PromptReply::Approve),
+            KeyCode::Char('a') if selected.is_some_and(|e| e.kind == PromptKind::Permission) => {
+                Some(PromptReply::AlwaysAllow)
+            }
+            KeyCode::Char('n') => Some(PromptReply::Deny),
+            KeyCode::Char(c @ '1'..='9') => {
+                let n = c as usize - '0' as usize;
+                selected
+                    .filter(|e| e.options.is_empty() || n <= e.options.len())
+                    .map(|_| PromptReply::Choose(n))
+            }
+            KeyCode::Char('Y') => {
+                if let Some(entry) = selected {
+                    self.batch_approve(&entries, entry);
+                }
+                None
+            }
+            _ => None,
+        };
+
+        if let (Some(reply), Some(entry)) = (reply, selected) {
+            match self.reply_to_prompt(entry, reply) {
+                Ok(()) => {
+                    self.show_toast(
+                        format!("Replied to {}", entry.tab_label),
+                        ToastStyle::Success,
+                    );
+                }
+                Err(e) => self.show_toast(format!("Reply failed: {e}"), ToastStyle::Error),
+            }
+        }
+        Ok(())
+    }
+
+    /// Approve every permission prompt identical to `selected`.
+    fn batch_approve(&mut self, entries: &[InboxEntry], selected: &InboxEntry) {
+        if selected.kind != PromptKind::Permission {
+            self.show_toast(
+                "Batch approve only applies to permission prompts",
+                ToastStyle::Info,
+            );
+            return;
+        }
+        let mut approved = 0;
+        for entry in entries
+            .iter()
+            .filter(|e| e.kind == PromptKind::Permission && e.prompt == selected.prompt)
+        {
+            match self.reply_to_prompt(entry, PromptReply::Approve) {
+                Ok(()) => approved += 1,
+                Err(e) => {
+                    self.show_toast(
+                        format!("Reply to {} failed: {e}", entry.tab_label),
+                        ToastStyle::Error,
+                    );
+                    return;
+                }
+            }
+        }
+        self.show_toast(
+            format!("Approved {approved} identical prompt(s)"),
+            ToastStyle::Success,
+        );
+    }
+
     pub(super) fn handle_milestone_filter_key(&mut self, code: KeyCode) -> Result<()> {
         match code {
             KeyCode::Esc => {
@@ -2437,9 +2612,9 @@ impl App {
                             self.new_task_mode,
                             crate::store::TaskMode::Autonomous
                                 | crate::store::TaskMode::Exploration
-                        ) && let Some(project_id) = self.selected_project().map(|p| p.id.clone())
+                        ) && let Ok(task) = self.store.get_task(task_id)
                         {
-                            self.launch_task(task_id.clone(), project_id)?;
+                            self.launch_task(task_id.clone(), task.project_id)?;
                         } else {
                             self.spawn_title_generation(
                                 task_id.clone(),
@@ -2517,6 +2692,8 @@ impl App {
             KeyCode::Enter => {
                 self.input_mode = InputMode::Normal;
                 self.task_index = 0;
+                // Pick up archived tasks right away if the filter asks for them
+                self.refresh_data()?;
             }
             KeyCode::Esc => {
                 self.task_filter.clear();
@@ -0,0 +1,171 @@
+//! Follow-up prompts for a task's session without attaching to it: the
+//! dashboard's message dialog (`m`) and delivery of prompts queued by
+//! `claustre send`. Every message is recorded on the task.
+
+use anyhow::{Context, Result};
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use crate::store::TaskEventKind;
+
+use super::super::form::apply_text_edit;
+use super::{App, Focus, InputMode, MessageState, Tab, ToastStyle};
+
+impl App {
+    /// Open the message dialog for the selected task's session (`m`).
+    pub(super) fn open_message_dialog(&mut self) {
+        if self.focus != Focus::Tasks {
+            return;
+        }
+        let Some(task) = self.visible_tasks().get(self.task_index).copied() else {
+            return;
+        };
+        let Some(session_id) = task.session_id.clone() else {
+            self.show_toast("Task has no session to message", ToastStyle::Info);
+            return;
+        };
+        self.message = Some(MessageState {
+            task_id: task.id.clone(),
+            session_id,
+            title: task.title.clone(),
+        });
+        self.input_buffer.clear();
+        self.input_cursor = 0;
+        self.input_mode = InputMode::MessageSession;
+    }
+
+    pub(super) fn handle_message_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Esc => self.close_message_dialog(),
+            KeyCode::Enter => self.submit_message(),
+            _ => {
+                apply_text_edit(
+                    &mut self.input_buffer,
+                    &mut self.input_cursor,
+                    code,
+                    modifiers,
+                );
+            }
+        }
+    }
+
+    fn submit_message(&mut self) {
+        let text = self.input_buffer.trim().to_string();
+        let Some(target) = self.message.take() else {
+            return;
+        };
+        self.close_message_dialog();
+        if text.is_empty() {
+            return;
+        }
+        let delivered = match self.send_prompt_to_session(&target.session_id, &text) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::debug!(session_id = %target.session_id, "message queued: {e:#}");
+                false
+            }
+        };
+        if let Err(e) = self.store.record_task_message(
+            &target.task_id,
+            Some(&target.session_id),
+            &text,
+            delivered,
+        ) {
+            self.show_toast(
+                format!("Failed to record message: {e:#}"),
+                ToastStyle::Error,
+            );
+            return;
+        }
+        if delivered {
+            self.show_toast(format!("Sent to '{}'", target.title), ToastStyle::Success);
+        } else {
+            self.show_toast(
+                format!(
+                    "Queued for '{}' until its session tab is open",
+                    target.title
+                ),
+                ToastStyle::Info,
+            );
+        }
+    }
+
+    fn close_message_dialog(&mut self) {
+        self.message = None;
+        self.input_buffer.clear();
+        self.input_cursor = 0;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Deliver messages queued by `claustre send` to sessions with an open
+    /// tab. The rest stay queued until their tab is restored.
+    pub(super) fn deliver_queued_messages(&mut self) {
+        let Ok(queued) = self.store.undelivered_task_messages() else {
+            return;
+        };
+        for message in queued {
+            let Some(session_id) = message.session_id.as_deref() else {
+                continue;
+            };
+            if !self.has_session_tab(session_id) {
+                continue;
+            }
+            match self.send_prompt_to_session(session_id, &message.body) {
+                Ok(()) => {
+                    if let Err(e) = self.store.mark_task_message_delivered(&message.id) {
+                        tracing::warn!(message_id = %message.id, "{e:#}");
+                    }
+                    let title = self
+                        .tasks
+                        .iter()
+                        .find(|t| t.id == message.task_id)
+                        .map_or("task", |t| t.title.as_str());
+                    let toast = format!("Delivered message to '{title}'");
+                    self.show_toast(toast, ToastStyle::Info);
+                }
+                Err(e) => tracing::warn!(%session_id, "message delivery failed: {e:#}"),
+            }
+        }
+    }
+
+    fn has_session_tab(&self, session_id: &str) -> bool {
+        self.tabs
+            .iter()
+            .any(|t| matches!(t, Tab::Session { session_id: sid, .. } if sid == session_id))
+    }
+
+    /// Type a prompt plus Enter into a session's Claude pane. When Claude has
+    /// quit, the pane is replaced by `claude --resume` with the prompt.
+    fn send_prompt_to_session(&mut self, session_id: &str, text: &str) -> Result<()> {
+        let session = self
+            .sessions
+            .iter()
+            .find(|s| s.id == session_id)
+            .cloned()
+            .with_context(|| format!("session '{session_id}' is not active"))?;
+        let command = self.resume_claude_command(&session, Some(text));
+        let terminals = self
+            .tabs
+            .iter_mut()
+            .find_map(|tab| match tab {
+                Tab::Session {
+                    session_id: sid,
+                    terminals,
+                    ..
+                } if sid == session_id => Some(terminals),
+                _ => None,
+            })
+            .context("session tab is not open")?;
+        if terminals.claude_exited() {
+            let (rows, cols) = terminals.claude_size().unwrap_or((24, 80));
+            let terminal = crate::pty::EmbeddedTerminal::spawn(command, rows, cols)?;
+            terminals.replace_claude(terminal);
+            self.record_session_event(
+                session_id,
+                TaskEventKind::Resume,
+                "claude resumed to deliver a message",
+            );
+            return Ok(());
+        }
+        terminals.send_to_claude(format!("\x1b[200~{text}\x1b[201~\r").as_bytes())
+    }
+}
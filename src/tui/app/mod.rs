@@ -3,13 +3,23 @@
 //! Contains the `App` struct (all mutable state), key/mouse handlers,
 //! data refresh logic, and background task coordination.
 
+mod copy_mode;
 mod data_refresh;
+mod diff_view;
 mod event_loop;
+mod fanout;
 mod initialization;
 mod input;
+mod message;
+mod pane_picker;
+mod pane_resize;
 mod polling;
 mod pty_management;
+mod retry;
+mod search;
 mod session_lifecycle;
+mod template_picker;
+mod watchdog;
 
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
@@ -82,6 +92,14 @@ pub(crate) enum InputMode {
     BoardView,
     MilestoneFilter,
     BoardFilter,
+    Inbox,
+    DiffView,
+    DiffComment,
+    TemplatePicker,
+    RetryTask,
+    MessageSession,
+    FanoutLaunch,
+    FanoutCompare,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -107,9 +125,141 @@ pub(crate) enum PaletteAction {
     UpdateSkills,
     Configure,
     SprintBoard,
+    PromptInbox,
+    ReviewDiff,
+    /// Apply `config.views[i]` as the task filter.
+    ApplyView(usize),
+    AllProjects,
     Quit,
 }
 
+/// What a blocked session is waiting on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PromptKind {
+    /// Tool-approval dialog ("Allow Bash?").
+    Permission,
+    /// `AskUserQuestion` selector.
+    Question,
+}
+
+/// One row of the prompt inbox: a blocked session and the prompt scraped from its screen.
+#[derive(Debug, Clone)]
+pub(crate) struct InboxEntry {
+    pub session_id: String,
+    pub tab_label: String,
+    pub kind: PromptKind,
+    pub prompt: String,
+    pub options: Vec<String>,
+}
+
+/// A reply sent from the inbox to a blocked session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PromptReply {
+    /// Accept the focused (first) option.
+    Approve,
+    /// Pick the "Always" / "Yes, and don't ask again" option.
+    AlwaysAllow,
+    /// Dismiss the prompt (Esc).
+    Deny,
+    /// Pick the 1-based option `n`.
+    Choose(usize),
+}
+
+impl PromptReply {
+    /// Keystrokes that select this reply in Claude's selector, given the
+    /// option labels scraped from the screen. `None` when the prompt has no
+    /// always-allow option.
+    ///
+    /// The selector always opens with the cursor on the first option, so
+    /// choosing option `n` is `n - 1` Down arrows followed by Enter.
+    fn keystrokes(self, options: &[String]) -> Option<Vec<u8>> {
+        let downs = match self {
+            Self::Deny => return Some(b"\x1b".to_vec()),
+            Self::Approve => 0,
+            Self::AlwaysAllow => options.iter().position(|option| {
+                let option = option.to_lowercase();
+                option.contains("always") || option.contains("don't ask again")
+            })?,
+            Self::Choose(n) => n.saturating_sub(1),
+        };
+        let mut bytes = b"\x1b[B".repeat(downs);
+        bytes.push(b'\r');
+        Some(bytes)
+    }
+}
+
+/// State of the diff/review viewer (`InputMode::DiffView`).
+pub(crate) struct DiffViewState {
+    pub task_title: String,
+    /// Session whose Claude pane receives review comments.
+    pub session_id: Option<String>,
+    pub source: crate::session::diff::DiffSource,
+    pub files: Vec<crate::session::diff::FileDiff>,
+    pub staged: HashSet<String>,
+    pub file_index: usize,
+    /// Selected row (hunk header or line) within the current file.
+    pub row_index: usize,
+    /// Whether j/k move through the file list rather than the hunks.
+    pub files_focused: bool,
+    pub comments: Vec<crate::session::diff::ReviewComment>,
+    /// Set after the first `x` so a second press confirms the discard.
+    pub confirm_discard: bool,
+}
+
+/// State of the new-task template picker (`InputMode::TemplatePicker`).
+pub(crate) struct TemplatePickerState {
+    pub templates: Vec<crate::templates::TaskTemplate>,
+    pub index: usize,
+    /// Variable being prompted for once a template is chosen; `None` while picking.
+    pub var_index: Option<usize>,
+    pub values: HashMap<String, String>,
+}
+
+/// State of the retry dialog (`InputMode::RetryTask`). The note is typed
+/// into `input_buffer`.
+pub(crate) struct RetryState {
+    pub task_id: String,
+    pub project_id: String,
+    pub title: String,
+    pub status: TaskStatus,
+    /// Fetch CI logs and PR feedback into the next prompt.
+    pub include_failure: bool,
+}
+
+/// Target of the message dialog (`InputMode::MessageSession`). The text is
+/// typed into `input_buffer`.
+pub(crate) struct MessageState {
+    pub task_id: String,
+    pub session_id: String,
+    pub title: String,
+}
+
+/// State of the fan-out dialog (`InputMode::FanoutLaunch`): one checkbox per
+/// `[[fanout.variants]]` entry.
+pub(crate) struct FanoutLaunchState {
+    pub task_id: String,
+    pub project_id: String,
+    pub title: String,
+    pub selected: Vec<bool>,
+    pub index: usize,
+}
+
+/// A compare-view action waiting for `y` confirmation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VariantConfirm {
+    Promote,
+    Discard,
+}
+
+/// State of the variant compare view (`InputMode::FanoutCompare`). Rows are
+/// read from the store on each draw so test results show up as they land.
+pub(crate) struct FanoutCompareState {
+    pub parent_task_id: String,
+    pub title: String,
+    pub index: usize,
+    pub confirm: Option<VariantConfirm>,
+}
+
 /// Pre-fetched per-project summary for the sidebar (avoids DB queries during rendering).
 #[derive(Debug, Clone, Default)]
 pub(crate) struct ProjectSummary {
@@ -126,6 +276,11 @@ enum SessionOpResult {
     CreatedNoTask { message: String },
     /// Teardown completed.
     TornDown { message: String },
+    /// A fan-out variant was promoted; the other variants' sessions are gone.
+    Promoted {
+        message: String,
+        closed_sessions: Vec<String>,
+    },
     /// An operation failed.
     Error { message: String },
 }
@@ -140,6 +295,37 @@ enum PrStatus {
     Open,
 }
 
+/// PR state plus the review data auto-merge needs.
+struct PrSnapshot {
+    status: PrStatus,
+    /// GitHub reports the PR as `MERGEABLE` (not conflicting, not still computing).
+    mergeable: bool,
+    /// Reviewers whose latest review approves, or 0 while changes are requested.
+    approvals: u32,
+    /// Every check concluded `SUCCESS`, `NEUTRAL` or `SKIPPED`; `None` when
+    /// the PR has no checks.
+    checks_green: Option<bool>,
+}
+
+impl PrSnapshot {
+    fn open() -> Self {
+        Self {
+            status: PrStatus::Open,
+            mergeable: false,
+            approvals: 0,
+            checks_green: None,
+        }
+    }
+
+    /// Mergeable with every check green. A PR without checks only counts
+    /// when `allow_no_checks` is set (`[auto_merge] allow_no_checks`).
+    fn is_green(&self, allow_no_checks: bool) -> bool {
+        self.mergeable
+            && matches!(self.status, PrStatus::CiPassed | PrStatus::Open)
+            && self.checks_green.unwrap_or(allow_no_checks)
+    }
+}
+
 /// Result from a background PR status check.
 enum PrPollResult {
     /// PR was merged — task should be marked done.
@@ -156,6 +342,18 @@ enum PrPollResult {
     CiFailed { task_id: String, task_title: String },
     /// Previously failed CI checks are now passing — task goes back to `in_review`.
     CiRecovered { task_id: String, task_title: String },
+    /// Auto-merge dry run: the PR satisfies its merge policy but was left alone.
+    AutoMergeDryRun {
+        task_id: String,
+        task_title: String,
+        policy: crate::store::MergePolicy,
+    },
+    /// `gh pr merge` failed for a PR that satisfied its merge policy.
+    AutoMergeFailed {
+        task_id: String,
+        task_title: String,
+        error: String,
+    },
     /// CI status changed (running or passed) — update the `ci_status` field without changing task status.
     CiStatusChanged {
         task_id: String,
@@ -254,9 +452,14 @@ pub(crate) struct App {
     // Editing task state
     pub editing_task_id: Option<String>,
 
+    /// Task queue shows every project's tasks instead of the selected project's.
+    pub all_projects: bool,
+
     // Task filter state
     pub task_filter: String,
     pub task_filter_cursor: usize,
+    /// Parse error for `task_filter`; while set, no tasks match.
+    pub task_filter_error: Option<String>,
 
     // Subtask state
     pub subtasks: Vec<crate::store::Subtask>,
@@ -297,7 +500,8 @@ pub(crate) struct App {
     pub pending_titles: HashSet<String>,
     // Tasks waiting for title generation before auto-launching (task_id → project_id)
     pending_auto_launch: HashMap<String, String>,
-    // Pending autonomous tasks to auto-launch on startup (project_id, task)
+    // Pending autonomous tasks to auto-launch on startup or when a schedule
+    // fires (project_id, task)
     startup_auto_launch: VecDeque<(String, Task)>,
 
     // PR status polling (merge + conflict detection)
@@ -306,6 +510,16 @@ pub(crate) struct App {
     pr_poll_rx: mpsc::Receiver<PrPollResult>,
     last_pr_poll: Instant,
 
+    // Outbound webhook queue flushing
+    webhook_flush_in_progress: Arc<AtomicBool>,
+    last_webhook_flush: Instant,
+
+    // Newest `notify` timeline event already relayed to terminal backends
+    last_notify_event_id: i64,
+
+    // Recurring task schedules
+    last_schedule_check: Instant,
+
     // Git stats polling
     git_stats_in_progress: Arc<AtomicBool>,
     git_stats_tx: mpsc::Sender<GitStatsResult>,
@@ -340,6 +554,12 @@ pub(crate) struct App {
     notified_in_review: HashSet<String>,
     // Tasks that have already had a review loop spawned
     review_loop_spawned: HashSet<String>,
+    // Tasks with a running CI fix loop pane (cleared when they leave ci_failed)
+    ci_fix_spawned: HashSet<String>,
+    // Tasks with a running conflict resolution (cleared when they leave conflict)
+    conflict_resolution_spawned: HashSet<String>,
+    // In-review tasks whose auto-merge already ran dry or failed (cleared when they leave in_review)
+    auto_merge_reported: HashSet<String>,
 
     // Slow-tick tracking for session tabs (DB refresh, PR polling, etc.)
     last_slow_tick: Instant,
@@ -353,6 +573,47 @@ pub(crate) struct App {
     // Sessions where Claude asked a question and is waiting for user answer (detected from PTY screen)
     pub waiting_sessions: HashSet<String>,
 
+    // Blocked sessions already answered from the inbox. Hidden from the inbox
+    // and not re-notified until a detection pass no longer sees the prompt.
+    pub replied_sessions: HashSet<String>,
+
+    // Selected row in the prompt inbox overlay
+    pub inbox_index: usize,
+
+    // Diff/review viewer state (Some while InputMode::DiffView is open)
+    pub diff_view: Option<DiffViewState>,
+
+    // Split-with-command picker on a session tab (selected row while open)
+    pub pane_picker: Option<usize>,
+
+    // Divider being dragged with the mouse on a session tab
+    pub pane_drag: Option<pane_resize::PaneDrag>,
+
+    // Tool-call feeds tailed from session transcripts (session_id → feed)
+    pub activity_feeds: HashMap<String, crate::scanner::activity::ActivityFeed>,
+    activity_in_progress: Arc<AtomicBool>,
+    activity_tx: mpsc::Sender<Vec<(String, crate::scanner::activity::ActivityFeed)>>,
+    activity_rx: mpsc::Receiver<Vec<(String, crate::scanner::activity::ActivityFeed)>>,
+    last_activity_poll: Instant,
+    last_transcript_lookup: Instant,
+
+    // Stuck-session watchdog state (session_id → state)
+    pub watchdog: HashMap<String, watchdog::WatchState>,
+    last_watchdog_check: Instant,
+
+    // Template picker state (Some while InputMode::TemplatePicker is open)
+    pub template_picker: Option<TemplatePickerState>,
+
+    // Retry dialog state (Some while InputMode::RetryTask is open)
+    pub retry: Option<RetryState>,
+
+    // Message dialog target (Some while InputMode::MessageSession is open)
+    pub message: Option<MessageState>,
+
+    // Fan-out dialog and compare view state (Some while their mode is open)
+    pub fanout_launch: Option<FanoutLaunchState>,
+    pub fanout_compare: Option<FanoutCompareState>,
+
     // Cached result of visible_tasks() — indices into self.tasks, filtered and sorted.
     // Recomputed by recompute_visible_tasks() after data changes.
     cached_visible_indices: Vec<usize>,
@@ -565,6 +826,122 @@ fn screen_shows_question_prompt(screen: &vt100::Screen) -> bool {
         .any(|line| line.trim().starts_with("Other"))
 }
 
+/// Maximum prompt lines kept per inbox entry.
+const INBOX_PROMPT_LINES: usize = 4;
+
+/// Pull the prompt text and option labels out of a blocked session's screen.
+///
+/// Scans the same bottom rows as the detectors above. Option lines carry the
+/// `❯` cursor, a `N.` prefix, or (for permission dialogs) inline "Yes  No"
+/// choices; the prompt is the text directly above them with box-drawing
+/// borders stripped. Permission prompts start at the "Allow <Tool>" line when
+/// one is present.
+fn extract_prompt(contents: &str, kind: PromptKind) -> (String, Vec<String>) {
+    let lines: Vec<&str> = contents.lines().collect();
+    let window = match kind {
+        PromptKind::Permission => 20,
+        PromptKind::Question => 25,
+    };
+    let bottom: Vec<&str> = lines[lines.len().saturating_sub(window)..]
+        .iter()
+        .map(|line| strip_box_chars(line))
+        .collect();
+
+    let Some(first_option) = bottom.iter().position(|l| is_option_line(l, kind)) else {
+        return (String::new(), Vec::new());
+    };
+
+    // Options run until the first blank line. When some lines are numbered,
+    // the unnumbered ones are option descriptions and are skipped.
+    let block: Vec<&str> = bottom[first_option..]
+        .iter()
+        .take_while(|l| !l.is_empty())
+        .copied()
+        .collect();
+    let numbered = block.iter().any(|l| numbered_option(l).is_some());
+    let options = block
+        .iter()
+        .filter(|l| !numbered || numbered_option(l).is_some())
+        .flat_map(|l| option_labels(l, kind))
+        .collect();
+
+    let above = &bottom[..first_option];
+    let anchor = match kind {
+        PromptKind::Permission => above.iter().rposition(|l| l.contains("Allow ")),
+        PromptKind::Question => None,
+    };
+    let prompt_lines: Vec<&str> = if let Some(start) = anchor {
+        above[start..]
+            .iter()
+            .filter(|l| !l.is_empty())
+            .take(INBOX_PROMPT_LINES)
+            .copied()
+            .collect()
+    } else {
+        // Walk upwards: skip the blank gap above the options, then collect
+        // the nearest paragraph (questions) or nearest lines (permissions).
+        let mut collected = Vec::new();
+        for line in above.iter().rev() {
+            if line.is_empty() {
+                if collected.is_empty() || kind == PromptKind::Permission {
+                    continue;
+                }
+                break;
+            }
+            collected.push(*line);
+            if collected.len() == INBOX_PROMPT_LINES {
+                break;
+            }
+        }
+        collected.reverse();
+        collected
+    };
+
+    (prompt_lines.join("\n"), options)
+}
+
+/// Trim whitespace and box-drawing borders from a screen line.
+fn strip_box_chars(line: &str) -> &str {
+    line.trim()
+        .trim_matches(|c: char| matches!(c, '│' | '╭' | '╮' | '╰' | '╯' | '─') || c.is_whitespace())
+}
+
+/// The label of a `N. label` option line (cursor already stripped).
+fn numbered_option(line: &str) -> Option<&str> {
+    let line = line.trim_start_matches('\u{276f}').trim_start();
+    let digits = line.find(|c: char| !c.is_ascii_digit())?;
+    if digits == 0 {
+        return None;
+    }
+    line[digits..].strip_prefix(". ").map(str::trim)
+}
+
+fn is_option_line(line: &str, kind: PromptKind) -> bool {
+    line.starts_with('\u{276f}')
+        || numbered_option(line).is_some()
+        || (kind == PromptKind::Permission
+            && (line.contains("Yes") || line.contains("yes"))
+            && (line.contains("No") || line.contains("no")))
+}
+
+/// Option labels on one line: inline permission choices ("Yes  No  Always")
+/// are split on runs of spaces, everything else is a single label.
+fn option_labels(line: &str, kind: PromptKind) -> Vec<String> {
+    if let Some(label) = numbered_option(line) {
+        return vec![label.to_string()];
+    }
+    let line = line.trim_start_matches('\u{276f}').trim();
+    if kind == PromptKind::Permission {
+        return line
+            .split("  ")
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect();
+    }
+    vec![line.to_string()]
+}
+
 fn build_project_summaries(store: &Store, projects: &[Project]) -> HashMap<String, ProjectSummary> {
     let mut summaries = HashMap::with_capacity(projects.len());
     for project in projects {
@@ -639,29 +1016,81 @@ fn parse_git_diff_stat(worktree_path: &str, default_branch: &str) -> Option<(i64
     Some((files, added, removed))
 }
 
-fn check_pr_status(pr_url: &str) -> PrStatus {
+fn check_pr_status(pr_url: &str) -> PrSnapshot {
     let Ok(output) = std::process::Command::new("gh")
         .args([
             "pr",
             "view",
             pr_url,
             "--json",
-            "state,mergeable,statusCheckRollup",
+            "state,mergeable,statusCheckRollup,reviewDecision,latestReviews",
         ])
         .output()
     else {
-        return PrStatus::Open;
+        return PrSnapshot::open();
     };
     if !output.status.success() {
-        return PrStatus::Open;
+        return PrSnapshot::open();
     }
-    let raw = String::from_utf8_lossy(&output.stdout);
+    parse_pr_view(&String::from_utf8_lossy(&output.stdout))
+}
 
-    // Parse JSON: {"state":"OPEN","mergeable":"CONFLICTING","statusCheckRollup":[...]}
+/// Parse `gh pr view --json state,mergeable,statusCheckRollup,reviewDecision,latestReviews`.
+fn parse_pr_view(raw: &str) -> PrSnapshot {
+    // Parse JSON: {"state":"OPEN","mergeable":"CONFLICTING","statusCheckRollup":[...],...}
     let Ok(json) = serde_json::from_str::<serde_json::Value>(raw.trim()) else {
-        return PrStatus::Open;
+        return PrSnapshot::open();
     };
 
+    let mergeable = json["mergeable"].as_str().unwrap_or("");
+    let approvals = if json["reviewDecision"]
+        .as_str()
+        .is_some_and(|d| d.eq_ignore_ascii_case("CHANGES_REQUESTED"))
+    {
+        0
+    } else {
+        json["latestReviews"].as_array().map_or(0, |reviews| {
+            let approved = reviews
+                .iter()
+                .filter(|r| {
+                    r["state"]
+                        .as_str()
+                        .is_some_and(|s| s.eq_ignore_ascii_case("APPROVED"))
+                })
+                .count();
+            u32::try_from(approved).unwrap_or(u32::MAX)
+        })
+    };
+    let checks_green = json["statusCheckRollup"]
+        .as_array()
+        .filter(|checks| !checks.is_empty())
+        .map(|checks| {
+            checks.iter().all(|check| {
+                let outcome = check_outcome(check);
+                ["SUCCESS", "NEUTRAL", "SKIPPED"]
+                    .iter()
+                    .any(|green| outcome.eq_ignore_ascii_case(green))
+            })
+        });
+    PrSnapshot {
+        status: pr_status_from_json(&json),
+        mergeable: mergeable.eq_ignore_ascii_case("MERGEABLE"),
+        approvals,
+        checks_green,
+    }
+}
+
+/// A rollup entry's result: `conclusion` for check runs, `state` for commit
+/// status contexts (which have no conclusion). Empty while a check run is
+/// still going.
+fn check_outcome(check: &serde_json::Value) -> &str {
+    match check["conclusion"].as_str() {
+        Some(conclusion) if !conclusion.is_empty() => conclusion,
+        _ => check["state"].as_str().unwrap_or(""),
+    }
+}
+
+fn pr_status_from_json(json: &serde_json::Value) -> PrStatus {
     let state = json["state"].as_str().unwrap_or("");
     if state.eq_ignore_ascii_case("MERGED") {
         return PrStatus::Merged;
@@ -678,17 +1107,20 @@ fn check_pr_status(pr_url: &str) -> PrStatus {
     {
         // Any completed check with FAILURE/ERROR conclusion means CI failed
         let has_failure = checks.iter().any(|check| {
-            let conclusion = check["conclusion"].as_str().unwrap_or("");
-            conclusion.eq_ignore_ascii_case("FAILURE") || conclusion.eq_ignore_ascii_case("ERROR")
+            let outcome = check_outcome(check);
+            outcome.eq_ignore_ascii_case("FAILURE") || outcome.eq_ignore_ascii_case("ERROR")
         });
         if has_failure {
             return PrStatus::CiFailed;
         }
 
-        // Check if all checks have completed (non-empty conclusion)
+        // Check if all checks have completed (status contexts stay
+        // PENDING/EXPECTED until they report)
         let all_done = checks.iter().all(|check| {
-            let conclusion = check["conclusion"].as_str().unwrap_or("");
-            !conclusion.is_empty()
+            let outcome = check_outcome(check);
+            !outcome.is_empty()
+                && !outcome.eq_ignore_ascii_case("PENDING")
+                && !outcome.eq_ignore_ascii_case("EXPECTED")
         });
 
         return if all_done {
@@ -919,6 +1351,14 @@ mod tests {
             InputMode::BoardView => app.handle_board_key(code, modifiers).unwrap(),
             InputMode::MilestoneFilter => app.handle_milestone_filter_key(code).unwrap(),
             InputMode::BoardFilter => app.handle_board_filter_key(code, modifiers).unwrap(),
+            InputMode::Inbox => app.handle_inbox_key(code).unwrap(),
+            InputMode::DiffView => app.handle_diff_view_key(code).unwrap(),
+            InputMode::DiffComment => app.handle_diff_comment_key(code, modifiers).unwrap(),
+            InputMode::TemplatePicker => app.handle_template_picker_key(code, modifiers).unwrap(),
+            InputMode::RetryTask => app.handle_retry_key(code, modifiers).unwrap(),
+            InputMode::MessageSession => app.handle_message_key(code, modifiers),
+            InputMode::FanoutLaunch => app.handle_fanout_launch_key(code).unwrap(),
+            InputMode::FanoutCompare => app.handle_fanout_compare_key(code).unwrap(),
         }
     }
 
@@ -1452,6 +1892,207 @@ mod tests {
         assert_eq!(app.visible_tasks()[0].title, "Task Gamma");
     }
 
+    #[test]
+    fn filter_query_syntax_and_saved_views() {
+        let mut app = test_app_with_tasks();
+        let task_id = app.tasks[0].id.clone();
+        app.store
+            .update_task_status(&task_id, TaskStatus::Working)
+            .unwrap();
+        app.refresh_data().unwrap();
+
+        press(&mut app, KeyCode::Char('/'));
+        type_str(&mut app, "status:working");
+        assert_eq!(app.visible_tasks().len(), 1);
+        assert_eq!(app.visible_tasks()[0].id, task_id);
+
+        type_str(&mut app, "x");
+        assert!(app.task_filter_error.is_some());
+        assert!(app.visible_tasks().is_empty());
+        press(&mut app, KeyCode::Esc);
+        assert!(app.task_filter_error.is_none());
+
+        app.config.views.push(crate::config::SavedView {
+            name: "Not started".into(),
+            query: "-status:working".into(),
+        });
+        app.execute_palette_action(PaletteAction::ApplyView(0))
+            .unwrap();
+        assert_eq!(app.task_filter, "-status:working");
+        assert_eq!(app.visible_tasks().len(), app.tasks.len() - 1);
+    }
+
+    #[test]
+    fn all_projects_view_aggregates_and_prioritises_attention() {
+        let mut app = test_app_with_tasks();
+        let other = app
+            .store
+            .create_project("other-project", "/tmp/other-repo", "main", true)
+            .unwrap();
+        let review = app
+            .store
+            .create_task(
+                &other.id,
+                "Other Review",
+                "",
+                TaskMode::Supervised,
+                None,
+                None,
+                crate::store::PushMode::Pr,
+                false,
+            )
+            .unwrap();
+        app.store
+            .update_task_status(&review.id, TaskStatus::Working)
+            .unwrap();
+        app.store
+            .update_task_status(&review.id, TaskStatus::InReview)
+            .unwrap();
+        app.refresh_data().unwrap();
+        let single_project = app.visible_task_count();
+
+        press(&mut app, KeyCode::Char('A'));
+        assert!(app.all_projects);
+        assert_eq!(app.visible_task_count(), 4);
+        let first = app.visible_task_at(0).unwrap();
+        assert_eq!(first.title, "Other Review");
+        assert_eq!(app.project_name(&first.project_id), "other-project");
+
+        press(&mut app, KeyCode::Char('A'));
+        assert!(!app.all_projects);
+        assert_eq!(app.visible_task_count(), single_project);
+    }
+
+    #[test]
+    fn fanout_launches_variants_and_opens_compare() {
+        let mut app = test_app_with_tasks();
+        app.focus = Focus::Tasks;
+        let parent_id = app.visible_task_at(0).unwrap().id.clone();
+
+        press(&mut app, KeyCode::Char('F'));
+        assert_eq!(app.input_mode, InputMode::FanoutLaunch);
+        assert_eq!(
+            app.fanout_launch.as_ref().unwrap().selected,
+            [true, true, true]
+        );
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Enter);
+
+        assert_eq!(app.input_mode, InputMode::FanoutCompare);
+        let rows = app.fanout_rows();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].0.effort.as_deref(), Some("medium"));
+        let queued = app
+            .startup_auto_launch
+            .iter()
+            .filter(|(_, t)| rows.iter().any(|(v, _)| v.task_id == t.id))
+            .count();
+        assert_eq!(queued, 2);
+        assert_eq!(
+            app.store.get_task(&parent_id).unwrap().status,
+            TaskStatus::Working
+        );
+        let output = render_to_string(&mut app, 120, 40);
+        assert!(output.contains("Compare Variants"));
+        assert!(output.contains("queued"));
+
+        // F on a variant opens the compare view for its parent.
+        press(&mut app, KeyCode::Esc);
+        let child_id = rows[0].1.id.clone();
+        app.task_index = app
+            .visible_tasks()
+            .iter()
+            .position(|t| t.id == child_id)
+            .unwrap();
+        press(&mut app, KeyCode::Char('F'));
+        assert_eq!(
+            app.fanout_compare.as_ref().unwrap().parent_task_id,
+            parent_id
+        );
+    }
+
+    #[test]
+    fn message_dialog_queues_prompt_for_session_without_tab() {
+        let mut app = test_app_with_tasks();
+        app.focus = Focus::Tasks;
+        let task = app.visible_task_at(0).unwrap().clone();
+
+        // No session yet: nothing to message.
+        press(&mut app, KeyCode::Char('m'));
+        assert_eq!(app.input_mode, InputMode::Normal);
+
+        let session = app
+            .store
+            .create_session(&task.project_id, "task/alpha", "/tmp/wt-alpha", "alpha")
+            .unwrap();
+        app.store
+            .assign_task_to_session(&task.id, &session.id)
+            .unwrap();
+        app.refresh_data().unwrap();
+        app.task_index = app
+            .visible_tasks()
+            .iter()
+            .position(|t| t.id == task.id)
+            .unwrap();
+
+        press(&mut app, KeyCode::Char('m'));
+        assert_eq!(app.input_mode, InputMode::MessageSession);
+        type_str(&mut app, "add a test");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.message.is_none());
+
+        let messages = app.store.list_task_messages(&task.id).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].body, "add a test");
+        assert_eq!(messages[0].session_id.as_deref(), Some(session.id.as_str()));
+        assert!(messages[0].delivered_at.is_none());
+
+        // Still no tab: the queue is left alone.
+        app.deliver_queued_messages();
+        assert_eq!(app.store.undelivered_task_messages().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn retry_dialog_only_opens_for_failed_tasks() {
+        let mut app = test_app_with_tasks();
+        app.focus = Focus::Tasks;
+        let task_id = app.visible_task_at(0).unwrap().id.clone();
+
+        // Pending tasks have nothing to retry.
+        press(&mut app, KeyCode::Char('R'));
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.retry.is_none());
+
+        app.store
+            .update_task_status(&task_id, TaskStatus::Working)
+            .unwrap();
+        app.store
+            .update_task_status(&task_id, TaskStatus::Error)
+            .unwrap();
+        app.refresh_data().unwrap();
+        app.task_index = app
+            .visible_tasks()
+            .iter()
+            .position(|t| t.id == task_id)
+            .unwrap();
+
+        press(&mut app, KeyCode::Char('R'));
+        assert_eq!(app.input_mode, InputMode::RetryTask);
+        type_str(&mut app, "use the cache");
+        press(&mut app, KeyCode::Tab);
+        let retry = app.retry.as_ref().unwrap();
+        assert_eq!(retry.task_id, task_id);
+        assert!(!retry.include_failure);
+        assert_eq!(app.input_buffer, "use the cache");
+
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.retry.is_none());
+        assert!(app.input_buffer.is_empty());
+    }
+
     #[test]
     fn visible_tasks_includes_done() {
         let mut app = test_app_with_tasks();
@@ -2581,6 +3222,218 @@ mod tests {
         assert!(screen_shows_question_prompt(parser.screen()));
     }
 
+    // ── Prompt inbox tests ──
+
+    #[test]
+    fn extract_permission_prompt_from_allow_line() {
+        let mut parser = vt100::Parser::new(24, 80, 0);
+        parser.process(b"Working on your task...\r\n\r\n");
+        parser.process(b"  \xe2\x94\x82 Allow Bash \xe2\x94\x82\r\n");
+        parser.process(b"  \xe2\x94\x82 cargo test --workspace \xe2\x94\x82\r\n");
+        parser.process(b"  Yes  No  Always\r\n");
+        let (prompt, options) = extract_prompt(&parser.screen().contents(), PromptKind::Permission);
+        assert_eq!(prompt, "Allow Bash\ncargo test --workspace");
+        assert_eq!(options, vec!["Yes", "No", "Always"]);
+    }
+
+    #[test]
+    fn extract_numbered_permission_prompt() {
+        let mut parser = vt100::Parser::new(24, 80, 0);
+        parser.process(b" Bash command\r\n\r\n");
+        parser.process(b"   rm -rf target\r\n\r\n");
+        parser.process(b" Do you want to proceed?\r\n");
+        parser.process(b" \xe2\x9d\xaf 1. Yes\r\n");
+        parser.process(b"   2. Yes, and don't ask again\r\n");
+        parser.process(b"   3. No\r\n");
+        let (prompt, options) = extract_prompt(&parser.screen().contents(), PromptKind::Permission);
+        assert_eq!(
+            prompt,
+            "Bash command\nrm -rf target\nDo you want to proceed?"
+        );
+        assert_eq!(options, vec!["Yes", "Yes, and don't ask again", "No"]);
+    }
+
+    #[test]
+    fn extract_question_skips_option_descriptions() {
+        let mut parser = vt100::Parser::new(24, 80, 0);
+        parser.process(b"Earlier output\r\n\r\n");
+        parser.process(b"  Which approach should we take?\r\n\r\n");
+        parser.process(b"  \xe2\x9d\xaf 1. Option A\r\n");
+        parser.process(b"       Faster but riskier\r\n");
+        parser.process(b"    2. Option B\r\n");
+        parser.process(b"    3. Other\r\n");
+        let (prompt, options) = extract_prompt(&parser.screen().contents(), PromptKind::Question);
+        assert_eq!(prompt, "Which approach should we take?");
+        assert_eq!(options, vec!["Option A", "Option B", "Other"]);
+    }
+
+    #[test]
+    fn extract_prompt_without_options_is_empty() {
+        let (prompt, options) = extract_prompt("just some output\n", PromptKind::Question);
+        assert!(prompt.is_empty());
+        assert!(options.is_empty());
+    }
+
+    #[test]
+    fn prompt_reply_keystrokes() {
+        let numbered: Vec<String> = [
+            "Yes",
+            "Yes, and don't ask again for cargo commands",
+            "No, and tell Claude what to do differently",
+        ]
+        .map(String::from)
+        .to_vec();
+        let inline: Vec<String> = ["Yes", "No", "Always"].map(String::from).to_vec();
+        let keys = |reply: PromptReply, options: &[String]| reply.keystrokes(options).unwrap();
+        assert_eq!(keys(PromptReply::Approve, &numbered), b"\r");
+        assert_eq!(keys(PromptReply::AlwaysAllow, &numbered), b"\x1b[B\r");
+        assert_eq!(keys(PromptReply::AlwaysAllow, &inline), b"\x1b[B\x1b[B\r");
+        assert_eq!(keys(PromptReply::Deny, &inline), b"\x1b");
+        assert_eq!(keys(PromptReply::Choose(3), &[]), b"\x1b[B\x1b[B\r");
+        // Never guess when there's no always-allow option.
+        let plain: Vec<String> = ["Yes", "No"].map(String::from).to_vec();
+        assert!(PromptReply::AlwaysAllow.keystrokes(&plain).is_none());
+    }
+
+    #[test]
+    fn inbox_opens_and_closes() {
+        let mut app = test_app_with_project();
+        press(&mut app, KeyCode::Char('p'));
+        assert_eq!(app.input_mode, InputMode::Inbox);
+        assert!(app.inbox_entries().is_empty());
+        // Replies with nothing selected are ignored
+        press(&mut app, KeyCode::Char('y'));
+        assert_eq!(app.input_mode, InputMode::Inbox);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    // ── Diff viewer tests ──
+
+    fn sample_diff_view() -> DiffViewState {
+        let files = crate::session::diff::parse_unified_diff(
+            "diff --git a/src/lib.rs b/src/lib.rs\n\
+             --- a/src/lib.rs\n\
+             +++ b/src/lib.rs\n\
+             @@ -1,2 +1,2 @@\n\
+             \x20fn keep() {}\n\
+             -fn old() {}\n\
+             +fn renamed() {}\n",
+        );
+        DiffViewState {
+            task_title: "Refactor".into(),
+            session_id: None,
+            source: crate::session::diff::DiffSource::Branch {
+                repo_path: "/nonexistent".into(),
+                base: "main".into(),
+                branch: "refactor".into(),
+            },
+            files,
+            staged: HashSet::new(),
+            file_index: 0,
+            row_index: 0,
+            files_focused: true,
+            comments: Vec::new(),
+            confirm_discard: false,
+        }
+    }
+
+    #[test]
+    fn diff_view_renders_and_collects_comments() {
+        let mut app = test_app_with_project();
+        app.diff_view = Some(sample_diff_view());
+        app.input_mode = InputMode::DiffView;
+
+        let output = render_to_string(&mut app, 100, 20);
+        assert!(output.contains("src/lib.rs"));
+        assert!(output.contains("fn renamed() {}"));
+        assert!(output.contains("[read-only]"));
+
+        // Move to the added line and comment on it
+        press(&mut app, KeyCode::Char('l'));
+        for _ in 0..3 {
+            press(&mut app, KeyCode::Char('j'));
+        }
+        press(&mut app, KeyCode::Char('c'));
+        assert_eq!(app.input_mode, InputMode::DiffComment);
+        type_str(&mut app, "keep the old name");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.input_mode, InputMode::DiffView);
+        let view = app.diff_view.as_ref().unwrap();
+        assert_eq!(view.comments.len(), 1);
+        assert_eq!(view.comments[0].line, Some(2));
+
+        // Read-only diffs refuse to stage; closing drops the state
+        press(&mut app, KeyCode::Char('s'));
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.diff_view.is_none());
+    }
+
+    #[test]
+    fn open_diff_without_worktree_or_branch_shows_error() {
+        let mut app = test_app_with_tasks();
+        press(&mut app, KeyCode::Char('2'));
+        press(&mut app, KeyCode::Char('D'));
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.diff_view.is_none());
+        assert_eq!(app.toast_style, ToastStyle::Error);
+    }
+
+    // ── Template picker tests ──
+
+    #[test]
+    fn template_picker_prompts_vars_and_fills_form() {
+        let repo = tempfile::tempdir().unwrap();
+        let dir = repo.path().join(".claustre").join("templates");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("zz-bugfix.toml"),
+            r#"
+description = "Fix {{area}}: {{details}}"
+mode = "supervised"
+push_mode = "push"
+review_loop = true
+subtasks = ["Reproduce in {{area}}"]
+
+[[vars]]
+name = "area"
+default = "api"
+
+[[vars]]
+name = "details"
+"#,
+        )
+        .unwrap();
+        let store = Store::open_in_memory().unwrap();
+        store
+            .create_project("test-project", repo.path().to_str().unwrap(), "main", true)
+            .unwrap();
+        let mut app = App::new(store).unwrap();
+
+        press(&mut app, KeyCode::Char('n'));
+        press_mod(&mut app, KeyCode::Char('t'), KeyModifiers::CONTROL);
+        assert_eq!(app.input_mode, InputMode::TemplatePicker);
+        // Global templates sort first; the repo template is last.
+        let last = app.template_picker.as_ref().unwrap().templates.len() - 1;
+        app.template_picker.as_mut().unwrap().index = last;
+
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.input_buffer, "api");
+        press(&mut app, KeyCode::Enter);
+        type_str(&mut app, "login loops");
+        press(&mut app, KeyCode::Enter);
+
+        assert_eq!(app.input_mode, InputMode::NewTask);
+        assert!(app.template_picker.is_none());
+        assert_eq!(app.new_task_description, "Fix api: login loops");
+        assert_eq!(app.input_buffer, "Fix api: login loops");
+        assert_eq!(app.new_task_mode, TaskMode::Supervised);
+        assert_eq!(app.new_task_push_mode, crate::store::PushMode::Push);
+        assert!(app.new_task_review_loop);
+        assert_eq!(app.new_task_subtasks, ["Reproduce in api"]);
+    }
+
     // ── Modified special key encoding tests ──
 
     #[test]
@@ -2740,4 +3593,83 @@ mod tests {
         // Release: button=3+32=35, x=1+32=33, y=1+32=33
         assert_eq!(bytes, Some(vec![0x1b, b'[', b'M', 35, 33, 33]));
     }
+
+    // ── PR view parsing tests ──
+
+    #[test]
+    fn parse_pr_view_counts_latest_approvals() {
+        let snap = parse_pr_view(
+            r#"{"state":"OPEN","mergeable":"MERGEABLE","reviewDecision":"APPROVED",
+                "statusCheckRollup":[{"conclusion":"SUCCESS"}],
+                "latestReviews":[{"state":"APPROVED"},{"state":"COMMENTED"},{"state":"APPROVED"}]}"#,
+        );
+        assert!(matches!(snap.status, PrStatus::CiPassed));
+        assert!(snap.is_green(false));
+        assert_eq!(snap.approvals, 2);
+    }
+
+    #[test]
+    fn parse_pr_view_changes_requested_zeroes_approvals() {
+        let snap = parse_pr_view(
+            r#"{"state":"OPEN","mergeable":"MERGEABLE","reviewDecision":"CHANGES_REQUESTED",
+                "statusCheckRollup":[],
+                "latestReviews":[{"state":"APPROVED"},{"state":"CHANGES_REQUESTED"}]}"#,
+        );
+        assert!(matches!(snap.status, PrStatus::Open));
+        assert_eq!(snap.approvals, 0);
+        // No checks: only green when configured to merge without them
+        assert!(!snap.is_green(false));
+        assert!(snap.is_green(true));
+    }
+
+    #[test]
+    fn parse_pr_view_only_success_neutral_skipped_are_green() {
+        let snap = parse_pr_view(
+            r#"{"state":"OPEN","mergeable":"MERGEABLE","statusCheckRollup":[
+                {"__typename":"CheckRun","status":"COMPLETED","conclusion":"SUCCESS"},
+                {"__typename":"CheckRun","status":"COMPLETED","conclusion":"NEUTRAL"},
+                {"__typename":"CheckRun","status":"COMPLETED","conclusion":"SKIPPED"},
+                {"__typename":"StatusContext","state":"SUCCESS"}]}"#,
+        );
+        assert!(matches!(snap.status, PrStatus::CiPassed));
+        assert!(snap.is_green(false));
+
+        for conclusion in ["CANCELLED", "TIMED_OUT", "ACTION_REQUIRED", "STALE"] {
+            let snap = parse_pr_view(&format!(
+                r#"{{"state":"OPEN","mergeable":"MERGEABLE",
+                    "statusCheckRollup":[{{"conclusion":"SUCCESS"}},{{"conclusion":"{conclusion}"}}]}}"#
+            ));
+            assert!(!snap.is_green(true), "{conclusion} counted as green");
+        }
+    }
+
+    #[test]
+    fn parse_pr_view_reads_status_context_state() {
+        let pending = parse_pr_view(
+            r#"{"state":"OPEN","mergeable":"MERGEABLE","statusCheckRollup":[
+                {"conclusion":"SUCCESS"},{"__typename":"StatusContext","state":"PENDING"}]}"#,
+        );
+        assert!(matches!(pending.status, PrStatus::CiRunning));
+        assert!(!pending.is_green(false));
+
+        let failed = parse_pr_view(
+            r#"{"state":"OPEN","mergeable":"MERGEABLE","statusCheckRollup":[
+                {"__typename":"StatusContext","state":"FAILURE"}]}"#,
+        );
+        assert!(matches!(failed.status, PrStatus::CiFailed));
+        assert!(!failed.is_green(false));
+    }
+
+    #[test]
+    fn parse_pr_view_running_or_unknown_is_not_green() {
+        let running = parse_pr_view(
+            r#"{"state":"OPEN","mergeable":"MERGEABLE","statusCheckRollup":[{"conclusion":""}]}"#,
+        );
+        assert!(matches!(running.status, PrStatus::CiRunning));
+        assert!(!running.is_green(true));
+
+        let unknown = parse_pr_view(r#"{"state":"OPEN","mergeable":"UNKNOWN"}"#);
+        assert!(!unknown.is_green(true));
+        assert!(!parse_pr_view("not json").is_green(true));
+    }
 }
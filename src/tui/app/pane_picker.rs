@@ -0,0 +1,107 @@
+//! Pane management on session tabs: splitting with a shell or a configured
+//! `[[panes]]` command (`Ctrl+N` picker), and saving the resulting layout so
+//! `restore_session_tab` rebuilds the same panes.
+
+use anyhow::Result;
+use crossterm::event::KeyCode;
+
+use crate::config::PaneCommand;
+use crate::pty::SplitDirection;
+
+use super::{App, Tab, ToastStyle, compute_pane_sizes_for_resize};
+
+impl App {
+    /// Open the split-with-command picker on the active session tab.
+    pub(super) fn open_pane_picker(&mut self) {
+        if matches!(self.tabs.get(self.active_tab), Some(Tab::Session { .. })) {
+            self.pane_picker = Some(0);
+        }
+    }
+
+    /// Picker rows: a plain shell followed by each `[[panes]]` entry.
+    pub(crate) fn pane_picker_entries(&self) -> Vec<(&str, &str)> {
+        std::iter::once(("shell", "$SHELL"))
+            .chain(
+                self.config
+                    .panes
+                    .iter()
+                    .map(|p| (p.pane.as_str(), p.cmd.as_str())),
+            )
+            .collect()
+    }
+
+    /// Handle a key while the pane picker is open. Returns `false` when it
+    /// is closed.
+    pub(super) fn handle_pane_picker_key(&mut self, code: KeyCode) -> Result<bool> {
+        let Some(index) = self.pane_picker else {
+            return Ok(false);
+        };
+        let last = self.config.panes.len();
+        let direction = match code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.pane_picker = Some((index + 1).min(last));
+                return Ok(true);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.pane_picker = Some(index.saturating_sub(1));
+                return Ok(true);
+            }
+            KeyCode::Enter | KeyCode::Char('r') => SplitDirection::Horizontal,
+            KeyCode::Char('b') => SplitDirection::Vertical,
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.pane_picker = None;
+                return Ok(true);
+            }
+            _ => return Ok(true),
+        };
+        self.pane_picker = None;
+        let command = index
+            .checked_sub(1)
+            .and_then(|i| self.config.panes.get(i))
+            .cloned();
+        self.split_focused_pane(direction, command.as_ref())?;
+        Ok(true)
+    }
+
+    /// Split the focused pane of the active session tab with a shell or a
+    /// pane command, then save the new layout.
+    pub(super) fn split_focused_pane(
+        &mut self,
+        direction: SplitDirection,
+        command: Option<&PaneCommand>,
+    ) -> Result<()> {
+        let term_size = crossterm::terminal::size().unwrap_or((80, 24));
+        let rows = term_size.1.saturating_sub(2);
+        let cols = term_size.0;
+        let Some(Tab::Session { terminals, .. }) = self.tabs.get_mut(self.active_tab) else {
+            return Ok(());
+        };
+        let result = match command {
+            Some(command) => terminals.split_with_pane(direction, rows, cols, command),
+            None => terminals.split_focused(direction, rows, cols),
+        };
+        let sizes = compute_pane_sizes_for_resize(&terminals.layout, term_size.0, term_size.1);
+        let _ = terminals.resize_panes_with_clear(&sizes);
+        match result {
+            Ok(()) => self.save_session_layout(),
+            Err(e) => {
+                self.show_toast(format!("Split failed: {e}"), ToastStyle::Error);
+                Ok(())
+            }
+        }
+    }
+
+    /// Save the active session tab's layout for `restore_session_tab`.
+    pub(super) fn save_session_layout(&self) -> Result<()> {
+        if let Some(Tab::Session {
+            session_id,
+            terminals,
+            ..
+        }) = self.tabs.get(self.active_tab)
+            && let Some(layout) = terminals.layout_config()
+        {
+            self.store.set_session_layout(session_id, &layout)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+//! Resizing and zooming session panes: `Alt`+arrows and divider drags move
+//! split ratios (saved with the session's layout), `Alt+Z` zooms the
+//! focused pane until it is toggled off or focus moves.
+
+use anyhow::Result;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+
+use crate::pty::{LayoutNode, SplitDirection};
+
+use super::{App, Tab, compute_pane_sizes_for_resize};
+
+/// Percent a split ratio moves per resize key press.
+pub(super) const RESIZE_STEP: i16 = 5;
+
+/// A split whose divider is being dragged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaneDrag {
+    /// Path from the layout root to the split (`true` = second child).
+    path: Vec<bool>,
+    direction: SplitDirection,
+    /// Screen area the split divides.
+    area: Rect,
+}
+
+impl App {
+    /// Move the divider nearest the focused pane, then refit and save.
+    pub(super) fn resize_focused_pane(
+        &mut self,
+        direction: SplitDirection,
+        delta: i16,
+    ) -> Result<()> {
+        let Some(Tab::Session { terminals, .. }) = self.tabs.get_mut(self.active_tab) else {
+            return Ok(());
+        };
+        if terminals.resize_focused(direction, delta) {
+            self.fit_session_panes();
+            self.save_session_layout()?;
+        }
+        Ok(())
+    }
+
+    /// Zoom the focused pane to fill the tab, or restore the layout.
+    pub(super) fn toggle_pane_zoom(&mut self) {
+        if let Some(Tab::Session { terminals, .. }) = self.tabs.get_mut(self.active_tab) {
+            terminals.toggle_zoom();
+            self.fit_session_panes();
+        }
+    }
+
+    /// Resize the active tab's PTYs to the panes as currently drawn.
+    pub(super) fn fit_session_panes(&mut self) {
+        let term_size = crossterm::terminal::size().unwrap_or((80, 24));
+        if let Some(Tab::Session { terminals, .. }) = self.tabs.get_mut(self.active_tab) {
+            let sizes = compute_pane_sizes_for_resize(
+                &terminals.visible_layout(),
+                term_size.0,
+                term_size.1,
+            );
+            let _ = terminals.resize_panes_with_clear(&sizes);
+        }
+    }
+
+    /// Start, continue or finish dragging a divider. Returns `true` if the
+    /// event was part of a drag.
+    pub(super) fn handle_divider_mouse(&mut self, mouse: &MouseEvent) -> Result<bool> {
+        let area = self.session_panes_area();
+        let Some(Tab::Session { terminals, .. }) = self.tabs.get_mut(self.active_tab) else {
+            return Ok(false);
+        };
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let mut path = Vec::new();
+                self.pane_drag = find_divider(
+                    &terminals.visible_layout(),
+                    area,
+                    mouse.column,
+                    mouse.row,
+                    &mut path,
+                );
+                Ok(self.pane_drag.is_some())
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                let Some(drag) = &self.pane_drag else {
+                    return Ok(false);
+                };
+                let (offset, length) = match drag.direction {
+                    SplitDirection::Horizontal => {
+                        (mouse.column.saturating_sub(drag.area.x), drag.area.width)
+                    }
+                    SplitDirection::Vertical => {
+                        (mouse.row.saturating_sub(drag.area.y), drag.area.height)
+                    }
+                };
+                // Place the divider under the pointer: the first child's
+                // border is its last column (or row).
+                let ratio = (u32::from(offset) + 1) * 100 / u32::from(length.max(1));
+                terminals.set_ratio(&drag.path, u16::try_from(ratio).unwrap_or(100));
+                Ok(true)
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                if self.pane_drag.take().is_none() {
+                    return Ok(false);
+                }
+                self.fit_session_panes();
+                self.save_session_layout()?;
+                Ok(true)
+            }
+            _ => Ok(self.pane_drag.is_some()),
+        }
+    }
+}
+
+/// Find the split whose divider is at (`col`, `row`), laying `node` out in
+/// `area` the same way the session tab is drawn. Outer splits win where
+/// borders meet.
+fn find_divider(
+    node: &LayoutNode,
+    area: Rect,
+    col: u16,
+    row: u16,
+    path: &mut Vec<bool>,
+) -> Option<PaneDrag> {
+    let LayoutNode::Split {
+        direction,
+        ratio,
+        first,
+        second,
+    } = node
+    else {
+        return None;
+    };
+    let chunks = Layout::default()
+        .direction(match direction {
+            SplitDirection::Horizontal => Direction::Horizontal,
+            SplitDirection::Vertical => Direction::Vertical,
+        })
+        .constraints([
+            Constraint::Percentage(*ratio),
+            Constraint::Percentage(100 - *ratio),
+        ])
+        .split(area);
+    let (first_area, second_area) = (chunks[0], chunks[1]);
+
+    let on_divider = match direction {
+        SplitDirection::Horizontal => {
+            (area.y..area.bottom()).contains(&row)
+                && (col + 1 == first_area.right() || col == second_area.x)
+        }
+        SplitDirection::Vertical => {
+            (area.x..area.right()).contains(&col)
+                && (row + 1 == first_area.bottom() || row == second_area.y)
+        }
+    };
+    if on_divider {
+        return Some(PaneDrag {
+            path: path.clone(),
+            direction: *direction,
+            area,
+        });
+    }
+
+    for (child, child_area, step) in [(first, first_area, false), (second, second_area, true)] {
+        path.push(step);
+        let found = find_divider(child, child_area, col, row, path);
+        path.pop();
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_divider_hits_split_borders() {
+        // 0 | (1 / 2) in a 100x40 area
+        let node = LayoutNode::Split {
+            direction: SplitDirection::Horizontal,
+            ratio: 50,
+            first: Box::new(LayoutNode::Pane(0)),
+            second: Box::new(LayoutNode::Split {
+                direction: SplitDirection::Vertical,
+                ratio: 50,
+                first: Box::new(LayoutNode::Pane(1)),
+                second: Box::new(LayoutNode::Pane(2)),
+            }),
+        };
+        let area = Rect::new(0, 1, 100, 40);
+        let find = |col, row| find_divider(&node, area, col, row, &mut Vec::new());
+
+        let outer = find(49, 10).unwrap();
+        assert_eq!(outer.path, Vec::<bool>::new());
+        assert_eq!(outer.direction, SplitDirection::Horizontal);
+        assert_eq!(find(50, 30), Some(outer));
+
+        let inner = find(75, 20).unwrap();
+        assert_eq!(inner.path, vec![true]);
+        assert_eq!(inner.direction, SplitDirection::Vertical);
+        assert_eq!(inner.area, Rect::new(50, 1, 50, 40));
+
+        assert!(find(20, 10).is_none());
+        assert!(find(75, 10).is_none());
+    }
+}
@@ -3,7 +3,8 @@ use std::time::Duration;
 
 use anyhow::Result;
 
-use crate::store::TaskStatus;
+use crate::notify::{Notification, NotifyEvent};
+use crate::store::{EventSource, MergePolicy, TaskStatus};
 
 use super::{
     App, GitStatsResult, PrPollResult, PrStatus, SessionOpResult, ToastStyle, check_pr_status,
@@ -142,12 +143,30 @@ impl App {
             return;
         }
 
-        // Collect task info for the background thread
+        // Collect task info for the background thread. Auto-merge only
+        // applies to in_review tasks not already reported this review round.
         let check_list: Vec<_> = tasks
             .into_iter()
             .filter_map(|t| {
                 let url = t.pr_url?;
-                Some((t.id, t.session_id, url, t.title, t.status, t.ci_status))
+                let auto_merge = if t.status == TaskStatus::InReview
+                    && !self.auto_merge_reported.contains(&t.id)
+                {
+                    let own = self.store.effective_merge_policy(&t.id).ok().flatten();
+                    own.or(self.config.auto_merge.policy)
+                } else {
+                    None
+                }
+                .filter(|p| *p != MergePolicy::Never);
+                Some((
+                    t.id,
+                    t.session_id,
+                    url,
+                    t.title,
+                    t.status,
+                    t.ci_status,
+                    auto_merge,
+                ))
             })
             .collect();
 
@@ -158,10 +177,16 @@ impl App {
         let flag = self.pr_poll_in_progress.clone();
         flag.store(true, Ordering::SeqCst);
         let tx = self.pr_poll_tx.clone();
+        let merge_flag = self.config.auto_merge.method.flag();
+        let dry_run = self.config.auto_merge.dry_run;
+        let allow_no_checks = self.config.auto_merge.allow_no_checks;
 
         std::thread::spawn(move || {
-            for (task_id, session_id, pr_url, title, task_status, current_ci) in check_list {
-                let pr_status = check_pr_status(&pr_url);
+            for (task_id, session_id, pr_url, title, task_status, current_ci, auto_merge) in
+                check_list
+            {
+                let snapshot = check_pr_status(&pr_url);
+                let pr_status = &snapshot.status;
 
                 // Derive CI status from the PR check result
                 let new_ci = match pr_status {
@@ -181,6 +206,35 @@ impl App {
                     });
                 }
 
+                // Merge PRs that satisfy their policy; a successful merge goes
+                // through the regular `Merged` path.
+                if let Some(policy) = auto_merge
+                    && policy.allows(snapshot.is_green(allow_no_checks), snapshot.approvals)
+                {
+                    let result = if dry_run {
+                        PrPollResult::AutoMergeDryRun {
+                            task_id,
+                            task_title: title,
+                            policy,
+                        }
+                    } else {
+                        match crate::github::merge_pr(&pr_url, merge_flag) {
+                            Ok(()) => PrPollResult::Merged {
+                                task_id,
+                                session_id,
+                                task_title: title,
+                            },
+                            Err(e) => PrPollResult::AutoMergeFailed {
+                                task_id,
+                                task_title: title,
+                                error: format!("{e:#}"),
+                            },
+                        }
+                    };
+                    let _ = tx.send(result);
+                    continue;
+                }
+
                 // Handle task status transitions.
                 // For `working` tasks, only track ci_status changes (handled above)
                 // — don't transition the task status since the user is actively
@@ -239,6 +293,14 @@ impl App {
     /// poll result already transitioned the task). We use `try_update_task_status`
     /// so stale results are silently skipped instead of crashing the TUI.
     pub(super) fn poll_pr_merge_results(&mut self) -> Result<()> {
+        // Changes made here are the poller's doing, not the user's
+        let previous = self.store.set_event_source(EventSource::Poller);
+        let result = self.apply_pr_poll_results();
+        self.store.set_event_source(previous);
+        result
+    }
+
+    fn apply_pr_poll_results(&mut self) -> Result<()> {
         while let Ok(result) = self.pr_poll_rx.try_recv() {
             match result {
                 PrPollResult::Merged {
@@ -253,6 +315,7 @@ impl App {
                         if let Some(ref sid) = session_id {
                             self.spawn_teardown_session(sid.clone());
                         }
+                        self.notify_task_event(NotifyEvent::Done, &task_id);
                         self.show_toast(
                             format!("PR merged — task done: {task_title}"),
                             ToastStyle::Success,
@@ -267,6 +330,8 @@ impl App {
                         .store
                         .try_update_task_status(&task_id, crate::store::TaskStatus::Conflict)?
                     {
+                        self.notify_task_event(NotifyEvent::Conflict, &task_id);
+                        self.maybe_spawn_conflict_resolution(&task_id);
                         self.show_toast(
                             format!("PR has conflicts: {task_title}"),
                             ToastStyle::Error,
@@ -295,6 +360,8 @@ impl App {
                         .store
                         .try_update_task_status(&task_id, crate::store::TaskStatus::CiFailed)?
                     {
+                        self.notify_task_event(NotifyEvent::CiFailed, &task_id);
+                        self.maybe_spawn_ci_fix_loop(&task_id);
                         self.show_toast(
                             format!("CI checks failed: {task_title}"),
                             ToastStyle::Error,
@@ -317,6 +384,30 @@ impl App {
                         );
                     }
                 }
+                PrPollResult::AutoMergeDryRun {
+                    task_id,
+                    task_title,
+                    policy,
+                } => {
+                    tracing::info!(task_id = %task_id, %policy, "auto-merge dry run: would merge");
+                    self.auto_merge_reported.insert(task_id);
+                    self.show_toast(
+                        format!("Would auto-merge ({policy}): {task_title}"),
+                        ToastStyle::Info,
+                    );
+                }
+                PrPollResult::AutoMergeFailed {
+                    task_id,
+                    task_title,
+                    error,
+                } => {
+                    tracing::warn!(task_id = %task_id, "auto-merge failed: {error}");
+                    self.auto_merge_reported.insert(task_id);
+                    self.show_toast(
+                        format!("Auto-merge failed: {task_title}"),
+                        ToastStyle::Error,
+                    );
+                }
                 PrPollResult::CiStatusChanged { task_id, ci_status } => {
                     self.store
                         .update_task_ci_status(&task_id, Some(ci_status))?;
@@ -326,6 +417,119 @@ impl App {
         Ok(())
     }
 
+    /// Fire the configured notification backends and queue outbound
+    /// webhooks for a task event. Failures are logged — a missing
+    /// notification must never interrupt the TUI.
+    pub(super) fn notify_task_event(&self, event: NotifyEvent, task_id: &str) {
+        let Ok(task) = self.store.get_task(task_id) else {
+            return;
+        };
+        let project = self.store.get_project(&task.project_id).ok();
+        self.config.notifications.notify(&Notification {
+            event,
+            task_title: &task.title,
+            project: project.as_ref().map(|p| p.name.as_str()),
+            pr_url: task.pr_url.as_deref(),
+        });
+        if let Err(e) = crate::webhook::enqueue(&self.store, &self.config.webhooks, event, task_id)
+        {
+            tracing::warn!("failed to queue webhooks for {task_id}: {e:#}");
+        }
+    }
+
+    /// Fire terminal-bound backends for notifications that hooks and session
+    /// loops recorded on the task timeline. Those run inside a session's
+    /// PTY, where the bell or OSC sequence would land in Claude's pane.
+    pub(super) fn relay_terminal_notifications(&mut self) {
+        let events = match self
+            .store
+            .list_notify_events_since(self.last_notify_event_id)
+        {
+            Ok(events) => events,
+            Err(e) => {
+                tracing::warn!("failed to read notify events: {e:#}");
+                return;
+            }
+        };
+        for event in events {
+            self.last_notify_event_id = event.id;
+            let (Ok(kind), Ok(task)) = (
+                event.detail.parse::<NotifyEvent>(),
+                self.store.get_task(&event.task_id),
+            ) else {
+                continue;
+            };
+            let project = self.store.get_project(&task.project_id).ok();
+            self.config
+                .notifications
+                .notify_host_terminal(&Notification {
+                    event: kind,
+                    task_title: &task.title,
+                    project: project.as_ref().map(|p| p.name.as_str()),
+                    pr_url: task.pr_url.as_deref(),
+                });
+        }
+    }
+
+    /// Deliver queued webhooks every ~10 seconds on a background thread.
+    /// The thread opens its own store connection; the queue lives in the DB
+    /// so nothing is lost if the TUI exits mid-delivery.
+    pub(super) fn maybe_flush_webhooks(&mut self) {
+        const WEBHOOK_FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+        if self.last_webhook_flush.elapsed() < WEBHOOK_FLUSH_INTERVAL {
+            return;
+        }
+        self.last_webhook_flush = std::time::Instant::now();
+
+        if self.webhook_flush_in_progress.load(Ordering::SeqCst) {
+            return;
+        }
+        let now = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        match self.store.list_due_webhook_deliveries(&now, 1) {
+            Ok(due) if !due.is_empty() => {}
+            _ => return,
+        }
+
+        let flag = self.webhook_flush_in_progress.clone();
+        flag.store(true, Ordering::SeqCst);
+        std::thread::spawn(move || {
+            match crate::store::Store::open().and_then(|store| crate::webhook::deliver_due(&store))
+            {
+                Ok(report) => tracing::debug!(?report, "webhook flush finished"),
+                Err(e) => tracing::warn!("webhook flush failed: {e:#}"),
+            }
+            flag.store(false, Ordering::SeqCst);
+        });
+    }
+
+    /// Fire due task schedules every ~30 seconds. Clones appear on the next
+    /// refresh; auto-launch clones join the launch queue.
+    pub(super) fn maybe_run_schedules(&mut self) {
+        const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+        if self.last_schedule_check.elapsed() < SCHEDULE_CHECK_INTERVAL {
+            return;
+        }
+        self.last_schedule_check = std::time::Instant::now();
+
+        match crate::scheduler::run_due(&self.store, chrono::Utc::now()) {
+            Ok(runs) if !runs.is_empty() => {
+                let titles: Vec<&str> = runs.iter().map(|r| r.clone.title.as_str()).collect();
+                self.show_toast(
+                    format!("Scheduled: {}", titles.join(", ")),
+                    ToastStyle::Info,
+                );
+                for run in runs.into_iter().filter(|r| r.auto_launch) {
+                    self.startup_auto_launch
+                        .push_back((run.clone.project_id.clone(), run.clone));
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("scheduled tasks: {e:#}"),
+        }
+    }
+
     /// Poll git diff stats for all active sessions every ~5 seconds.
     pub(super) fn maybe_poll_git_stats(&mut self) {
         const GIT_STATS_INTERVAL: Duration = Duration::from_secs(5);
@@ -428,6 +632,82 @@ impl App {
         }
     }
 
+    /// Tail the transcripts of the session shown in the detail panel and of
+    /// the sessions the watchdog is checking, on a background thread.
+    ///
+    /// Finished polls are drained every tick and a new one starts every
+    /// second. Transcript paths (a directory scan) are looked up again every
+    /// 10s, so a new Claude conversation in the same worktree (e.g. after
+    /// `/clear`) starts a fresh feed. Feeds of sessions that no longer exist
+    /// are dropped.
+    pub(super) fn poll_activity_feed(&mut self) {
+        const ACTIVITY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+        const TRANSCRIPT_LOOKUP_INTERVAL: Duration = Duration::from_secs(10);
+
+        while let Ok(feeds) = self.activity_rx.try_recv() {
+            self.activity_feeds.extend(feeds);
+        }
+        let sessions = &self.sessions;
+        self.activity_feeds
+            .retain(|id, _| sessions.iter().any(|s| &s.id == id));
+
+        if self.last_activity_poll.elapsed() < ACTIVITY_POLL_INTERVAL
+            || self.activity_in_progress.load(Ordering::SeqCst)
+        {
+            return;
+        }
+        self.last_activity_poll = std::time::Instant::now();
+
+        let selected = self.session_for_selected_task().map(|s| s.id.clone());
+        let jobs: Vec<_> = self
+            .sessions
+            .iter()
+            .filter(|s| selected.as_ref() == Some(&s.id) || self.watchdog.contains_key(&s.id))
+            .map(|s| {
+                (
+                    s.id.clone(),
+                    std::path::PathBuf::from(&s.worktree_path),
+                    self.activity_feeds.get(&s.id).cloned(),
+                )
+            })
+            .collect();
+        if jobs.is_empty() {
+            return;
+        }
+        let lookup = self.last_transcript_lookup.elapsed() >= TRANSCRIPT_LOOKUP_INTERVAL;
+        if lookup {
+            self.last_transcript_lookup = std::time::Instant::now();
+        }
+
+        let flag = self.activity_in_progress.clone();
+        flag.store(true, Ordering::SeqCst);
+        let tx = self.activity_tx.clone();
+
+        std::thread::spawn(move || {
+            let feeds = jobs
+                .into_iter()
+                .filter_map(|(session_id, worktree, feed)| {
+                    let mut feed = match feed {
+                        Some(feed) if !lookup => feed,
+                        feed => {
+                            let path = crate::scanner::activity::transcript_path(&worktree)?;
+                            match feed {
+                                Some(feed) if feed.path() == path => feed,
+                                _ => crate::scanner::activity::ActivityFeed::new(path, &worktree),
+                            }
+                        }
+                    };
+                    if let Err(e) = feed.poll() {
+                        tracing::debug!("activity feed poll failed: {e:#}");
+                    }
+                    Some((session_id, feed))
+                })
+                .collect();
+            let _ = tx.send(feeds);
+            flag.store(false, Ordering::SeqCst);
+        });
+    }
+
     /// Drain background session operation results, spawn PTYs for new sessions, and show toasts.
     pub(super) fn poll_session_ops(&mut self) {
         while let Ok(result) = self.session_op_rx.try_recv() {
@@ -458,6 +738,7 @@ impl App {
                                     claude,
                                     &setup.worktree_path,
                                     layout_config,
+                                    &self.config.panes,
                                     rows,
                                     cols,
                                 )
@@ -508,6 +789,15 @@ impl App {
                 | SessionOpResult::TornDown { message, .. } => {
                     self.show_toast(message, ToastStyle::Success);
                 }
+                SessionOpResult::Promoted {
+                    message,
+                    closed_sessions,
+                } => {
+                    for session_id in &closed_sessions {
+                        self.remove_session_tab(session_id);
+                    }
+                    self.show_toast(message, ToastStyle::Success);
+                }
                 SessionOpResult::Error { message } => {
                     self.show_toast(message, ToastStyle::Error);
                     // Clear any pending relaunch — the operation failed
@@ -1,16 +1,50 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 
-use crate::store::TaskStatus;
+use crate::notify::NotifyEvent;
+use crate::store::{TaskEventKind, TaskStatus};
 
 use super::{
-    App, Tab, ToastStyle, compute_pane_sizes_for_resize, screen_shows_permission_prompt,
-    screen_shows_question_prompt,
+    App, InboxEntry, PromptKind, PromptReply, Tab, ToastStyle, compute_pane_sizes_for_resize,
+    extract_prompt, screen_shows_permission_prompt, screen_shows_question_prompt,
 };
 
 impl App {
     /// Restore a session tab for an active session whose PTY was lost (e.g. after
     /// Claustre was closed and reopened). Spawns `claude --continue` as a normal
     /// local PTY in the worktree.
+    /// Command that resumes a session's Claude conversation in its worktree,
+    /// optionally with a first prompt.
+    ///
+    /// Uses `--resume <id>` when the Claude session ID is known for exact
+    /// conversation resumption, otherwise falls back to `--continue`. Passes
+    /// the configured model and effort flags for consistency.
+    pub(super) fn resume_claude_command(
+        &self,
+        session: &crate::store::Session,
+        prompt: Option<&str>,
+    ) -> portable_pty::CommandBuilder {
+        let mut claude_args = vec![
+            "claude".to_string(),
+            "--model".to_string(),
+            self.config.claude.model.clone(),
+            "--effort".to_string(),
+            self.config.claude.effort.clone(),
+        ];
+        if let Some(ref csid) = session.claude_session_id {
+            claude_args.extend(["--resume".to_string(), csid.clone()]);
+        } else {
+            claude_args.push("--continue".to_string());
+        }
+        claude_args.extend(prompt.map(str::to_string));
+        let wrapped = crate::session::wrap_cmd_with_shell_fallback(claude_args);
+        let mut claude_builder = portable_pty::CommandBuilder::new(&wrapped[0]);
+        for arg in &wrapped[1..] {
+            claude_builder.arg(arg);
+        }
+        claude_builder.cwd(&session.worktree_path);
+        claude_builder
+    }
+
     pub(super) fn restore_session_tab(&mut self, session: &crate::store::Session) -> Result<()> {
         let worktree = std::path::Path::new(&session.worktree_path);
         if !worktree.exists() {
@@ -22,44 +56,22 @@ impl App {
         let cols = term_size.0;
         let rows = term_size.1.saturating_sub(2);
 
-        // Spawn claude in the worktree — use --resume <id> if we have the Claude
-        // session ID for exact conversation resumption, otherwise fall back to --continue.
-        // Pass configured model and effort flags for consistency.
-        let model = &self.config.claude.model;
-        let effort = &self.config.claude.effort;
-        let claude_args = if let Some(ref csid) = session.claude_session_id {
-            vec![
-                "claude".to_string(),
-                "--model".to_string(),
-                model.clone(),
-                "--effort".to_string(),
-                effort.clone(),
-                "--resume".to_string(),
-                csid.clone(),
-            ]
-        } else {
-            vec![
-                "claude".to_string(),
-                "--model".to_string(),
-                model.clone(),
-                "--effort".to_string(),
-                effort.clone(),
-                "--continue".to_string(),
-            ]
-        };
-        let wrapped = crate::session::wrap_cmd_with_shell_fallback(claude_args);
-        let mut claude_builder = portable_pty::CommandBuilder::new(&wrapped[0]);
-        for arg in &wrapped[1..] {
-            claude_builder.arg(arg);
-        }
-        claude_builder.cwd(&session.worktree_path);
-        let claude_terminal = crate::pty::EmbeddedTerminal::spawn(claude_builder, rows, cols / 2)?;
+        let claude_terminal = crate::pty::EmbeddedTerminal::spawn(
+            self.resume_claude_command(session, None),
+            rows,
+            cols / 2,
+        )?;
 
-        let mut terminals = if let Some(ref layout_config) = self.config.layout {
+        // Rebuild the panes the tab had last time, else the configured layout
+        let saved_layout = self.store.session_layout(&session.id).ok().flatten();
+        let mut terminals = if let Some(layout_config) =
+            saved_layout.as_ref().or(self.config.layout.as_ref())
+        {
             crate::pty::SessionTerminals::from_layout(
                 claude_terminal,
                 &session.worktree_path,
                 layout_config,
+                &self.config.panes,
                 rows,
                 cols,
             )?
@@ -81,6 +93,11 @@ impl App {
         self.add_session_tab(session.id.clone(), Box::new(terminals), label);
         // Switch to the newly added tab
         self.active_tab = self.tabs.len() - 1;
+        self.record_session_event(
+            &session.id,
+            TaskEventKind::Resume,
+            "session tab reopened, claude resumed",
+        );
 
         // Restore session + task status based on task state
         if let Some(task) = self.tasks.iter().find(|t| {
@@ -224,9 +241,10 @@ impl App {
     /// - `waiting_sessions` — Claude asked a question via `AskUserQuestion` and awaits an answer
     ///
     /// Both are in-memory overrides: the DB still shows `working`.
+    /// Sessions that newly enter either set fire a `needs_input` notification.
     pub(super) fn detect_paused_sessions(&mut self) {
-        self.paused_sessions.clear();
-        self.waiting_sessions.clear();
+        let prev_paused = std::mem::take(&mut self.paused_sessions);
+        let prev_waiting = std::mem::take(&mut self.waiting_sessions);
         for tab in &self.tabs {
             if let Tab::Session {
                 session_id,
@@ -265,5 +283,94 @@ impl App {
                 }
             }
         }
+
+        // A reply is settled once its prompt has left the screen.
+        let (paused, waiting) = (&self.paused_sessions, &self.waiting_sessions);
+        self.replied_sessions
+            .retain(|sid| paused.contains(sid) || waiting.contains(sid));
+
+        let newly_blocked: Vec<String> = self
+            .paused_sessions
+            .iter()
+            .chain(&self.waiting_sessions)
+            .filter(|sid| {
+                !prev_paused.contains(*sid)
+                    && !prev_waiting.contains(*sid)
+                    && !self.replied_sessions.contains(*sid)
+            })
+            .cloned()
+            .collect();
+        for session_id in newly_blocked {
+            if let Ok(Some(task)) = self.store.working_task_for_session(&session_id) {
+                self.notify_task_event(NotifyEvent::NeedsInput, &task.id);
+            }
+        }
+    }
+
+    /// Blocked sessions with the prompt text scraped from their Claude pane,
+    /// in tab order. Backs the prompt inbox overlay.
+    pub(crate) fn inbox_entries(&self) -> Vec<InboxEntry> {
+        self.tabs
+            .iter()
+            .filter_map(|tab| {
+                let Tab::Session {
+                    session_id,
+                    terminals,
+                    label,
+                } = tab
+                else {
+                    return None;
+                };
+                if self.replied_sessions.contains(session_id) {
+                    return None;
+                }
+                let kind = if self.paused_sessions.contains(session_id) {
+                    PromptKind::Permission
+                } else if self.waiting_sessions.contains(session_id) {
+                    PromptKind::Question
+                } else {
+                    return None;
+                };
+                let (prompt, options) = terminals
+                    .with_claude_live_screen(|screen| extract_prompt(&screen.contents(), kind))
+                    .unwrap_or_default();
+                Some(InboxEntry {
+                    session_id: session_id.clone(),
+                    tab_label: label.clone(),
+                    kind,
+                    prompt,
+                    options,
+                })
+            })
+            .collect()
+    }
+
+    /// Type a reply into a blocked session's Claude pane.
+    ///
+    /// The session stays in the paused/waiting sets until a detection pass
+    /// no longer sees the prompt, and is marked replied meanwhile: that hides
+    /// it from the inbox so the reply can't be sent twice, and stops a scan
+    /// that still catches the prompt before Claude redraws from notifying
+    /// `NeedsInput` again.
+    pub(super) fn reply_to_prompt(&mut self, entry: &InboxEntry, reply: PromptReply) -> Result<()> {
+        let session_id = entry.session_id.as_str();
+        let keystrokes = reply
+            .keystrokes(&entry.options)
+            .context("this prompt has no \"always allow\" option")?;
+        let terminals = self
+            .tabs
+            .iter_mut()
+            .find_map(|tab| match tab {
+                Tab::Session {
+                    session_id: sid,
+                    terminals,
+                    ..
+                } if sid == session_id => Some(terminals),
+                _ => None,
+            })
+            .context("session tab is no longer open")?;
+        terminals.send_to_claude(&keystrokes)?;
+        self.replied_sessions.insert(session_id.to_string());
+        Ok(())
     }
 }
@@ -0,0 +1,123 @@
+//! Retry dialog: record the selected task's failed run as an attempt, reset
+//! it to pending with an optional failure summary, and relaunch it.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use super::super::form::apply_text_edit;
+use super::{App, Focus, InputMode, RetryState, SessionOpResult, ToastStyle};
+use crate::store::AttemptOutcome;
+
+impl App {
+    /// Open the retry dialog for the selected task (`R`).
+    pub(super) fn open_retry_dialog(&mut self) {
+        if self.focus != Focus::Tasks {
+            return;
+        }
+        let Some(task) = self.visible_tasks().get(self.task_index).copied() else {
+            return;
+        };
+        if AttemptOutcome::for_status(task.status).is_none() {
+            self.show_toast(
+                format!("A {} task has nothing to retry", task.status),
+                ToastStyle::Info,
+            );
+            return;
+        }
+        self.retry = Some(RetryState {
+            task_id: task.id.clone(),
+            project_id: task.project_id.clone(),
+            title: task.title.clone(),
+            status: task.status,
+            include_failure: true,
+        });
+        self.input_buffer.clear();
+        self.input_cursor = 0;
+        self.input_mode = InputMode::RetryTask;
+    }
+
+    pub(super) fn handle_retry_key(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Result<()> {
+        let Some(retry) = self.retry.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        match code {
+            KeyCode::Esc => self.close_retry_dialog(),
+            KeyCode::Tab => retry.include_failure = !retry.include_failure,
+            KeyCode::Enter => self.submit_retry(),
+            _ => {
+                apply_text_edit(
+                    &mut self.input_buffer,
+                    &mut self.input_cursor,
+                    code,
+                    modifiers,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the retry on a background thread (it may call `gh`), tearing down
+    /// the old session, then relaunch the task once the op completes.
+    fn submit_retry(&mut self) {
+        if self.session_op_in_progress {
+            self.show_toast("Session operation in progress...", ToastStyle::Info);
+            return;
+        }
+        let Some(retry) = self.retry.take() else {
+            return;
+        };
+        let note = std::mem::take(&mut self.input_buffer);
+        self.close_retry_dialog();
+
+        let session_id = self
+            .tasks
+            .iter()
+            .find(|t| t.id == retry.task_id)
+            .and_then(|t| t.session_id.clone());
+        if let Some(ref sid) = session_id {
+            self.remove_session_tab(sid);
+        }
+        self.session_op_in_progress = true;
+        self.pending_relaunch = Some((retry.task_id.clone(), retry.project_id));
+        self.show_toast(format!("Retrying '{}'...", retry.title), ToastStyle::Info);
+
+        let tx = self.session_op_tx.clone();
+        std::thread::spawn(move || {
+            let result = crate::store::Store::open().and_then(|store| {
+                let attempt = crate::session::retry::retry_task(
+                    &store,
+                    &retry.task_id,
+                    Some(note.as_str()),
+                    retry.include_failure,
+                )?;
+                if let Some(sid) = session_id {
+                    crate::session::teardown_session(&store, &sid)?;
+                }
+                Ok(attempt)
+            });
+            let _ = tx.send(match result {
+                Ok(attempt) => SessionOpResult::TornDown {
+                    message: format!(
+                        "Attempt {} recorded ({}), relaunching",
+                        attempt.attempt, attempt.outcome
+                    ),
+                },
+                Err(e) => SessionOpResult::Error {
+                    message: format!("Retry failed: {e:#}"),
+                },
+            });
+        });
+    }
+
+    fn close_retry_dialog(&mut self) {
+        self.retry = None;
+        self.input_buffer.clear();
+        self.input_cursor = 0;
+        self.input_mode = InputMode::Normal;
+    }
+}
@@ -0,0 +1,125 @@
+//! Scrollback search in session panes: `Ctrl+F` (or `/` while scrolled
+//! back) types a pattern with incremental highlighting, `n`/`N` walk the
+//! matches through scrollback, `y`/`Y` copy the match or its block.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use crate::pty::{Search, SessionTerminals};
+use crate::tui::clipboard;
+
+use super::{App, Tab, ToastStyle};
+
+impl App {
+    /// Start a search in the focused pane of the active session tab.
+    pub(super) fn open_session_search(&mut self) {
+        if let Some(Tab::Session { terminals, .. }) = self.tabs.get_mut(self.active_tab) {
+            let pane = terminals.focused;
+            let Some(term) = terminals.terminal_mut(pane) else {
+                return;
+            };
+            let origin = term.viewport_top() + usize::from(term.screen().size().0);
+            let history = term.history();
+            terminals.selection = None;
+            terminals.search = Some(Search::new(pane, origin, history));
+        }
+    }
+
+    /// Handle a key while a search is open on the active session tab.
+    /// Returns `false` when the key closed the search and should be handled
+    /// as usual.
+    pub(super) fn handle_session_search_key(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Result<bool> {
+        let Some(Tab::Session { terminals, .. }) = self.tabs.get_mut(self.active_tab) else {
+            return Ok(false);
+        };
+        let Some(mut search) = terminals.search.take() else {
+            return Ok(false);
+        };
+
+        if search.editing {
+            match code {
+                KeyCode::Esc => return Ok(true),
+                KeyCode::Enter if search.query.is_empty() => return Ok(true),
+                KeyCode::Enter => search.editing = false,
+                KeyCode::Backspace => {
+                    let mut query = std::mem::take(&mut search.query);
+                    query.pop();
+                    search.set_query(query);
+                    jump_to_first(terminals, &mut search);
+                }
+                KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
+                    let mut query = std::mem::take(&mut search.query);
+                    query.push(c);
+                    search.set_query(query);
+                    jump_to_first(terminals, &mut search);
+                }
+                _ => {}
+            }
+            terminals.search = Some(search);
+            return Ok(true);
+        }
+
+        let copied = match code {
+            KeyCode::Char('n' | 'N') => {
+                step(terminals, &mut search, code == KeyCode::Char('n'));
+                None
+            }
+            KeyCode::Char('y') => search.match_text(),
+            KeyCode::Char('Y') => search.block_text(),
+            KeyCode::Char('/') => {
+                search.set_query(String::new());
+                search.current = None;
+                search.position = (0, 0);
+                search.editing = true;
+                None
+            }
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(true),
+            // Anything else closes the search and goes to the pane.
+            _ => return Ok(false),
+        };
+        terminals.search = Some(search);
+
+        if let Some(text) = copied {
+            let lines = text.lines().count();
+            if clipboard::copy(&text) {
+                let what = if lines > 1 {
+                    format!("{lines} lines")
+                } else {
+                    "match".to_string()
+                };
+                self.show_toast(format!("Copied {what}"), ToastStyle::Success);
+            } else {
+                self.show_toast("Clipboard unavailable", ToastStyle::Error);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Re-run the search from where it started and show the nearest match
+/// above that point.
+fn jump_to_first(terminals: &mut SessionTerminals, search: &mut Search) {
+    search.current = None;
+    let Some(term) = terminals.terminal_mut(search.pane) else {
+        return;
+    };
+    if let Some(found) = search.step(search.origin, true) {
+        term.scroll_to_line(found.line);
+    } else {
+        search.position = (0, 0);
+    }
+}
+
+/// Move to the next older (`n`) or newer (`N`) match.
+fn step(terminals: &mut SessionTerminals, search: &mut Search, older: bool) {
+    let Some(term) = terminals.terminal_mut(search.pane) else {
+        return;
+    };
+    if let Some(found) = search.step(search.origin, older) {
+        term.scroll_to_line(found.line);
+    }
+}
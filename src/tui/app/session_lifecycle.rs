@@ -3,7 +3,7 @@ use std::time::Instant;
 use anyhow::Result;
 
 use crate::pty::SessionTerminals;
-use crate::store::{Task, TaskStatus};
+use crate::store::{Task, TaskEventKind, TaskStatus};
 
 use super::{
     App, SessionOpResult, TOAST_DURATION, Tab, ToastStyle, compute_pane_sizes_for_resize,
@@ -95,6 +95,15 @@ impl App {
     pub(super) fn launch_task(&mut self, task_id: String, project_id: String) -> Result<()> {
         let task = self.store.get_task(&task_id)?;
 
+        // A scheduled task is a template; only its clones run.
+        if self.store.get_task_schedule(&task_id)?.is_some() {
+            self.show_toast(
+                "Scheduled task is a template; its clones launch on schedule",
+                ToastStyle::Info,
+            );
+            return Ok(());
+        }
+
         // Promote draft → pending
         if task.status == crate::store::TaskStatus::Draft {
             self.store
@@ -161,6 +170,14 @@ impl App {
         });
     }
 
+    /// Add an event to the timeline of the session's tasks. Failures are
+    /// logged — the timeline must never block the action it describes.
+    pub(super) fn record_session_event(&self, session_id: &str, kind: TaskEventKind, detail: &str) {
+        if let Err(e) = self.store.record_session_event(session_id, kind, detail) {
+            tracing::warn!(session_id, "failed to record {kind} event: {e:#}");
+        }
+    }
+
     /// If a task has `review_loop` enabled and just transitioned to `InReview`,
     /// split down a pane in its session tab and run `claustre review-loop`.
     pub(super) fn maybe_spawn_review_loop(&mut self, task_id: &str) {
@@ -176,25 +193,123 @@ impl App {
             return;
         }
 
-        let session_id = match task.session_id {
-            Some(ref id) => id.clone(),
-            None => return,
+        let Some(session_id) = task.session_id.clone() else {
+            return;
+        };
+
+        if self.spawn_session_loop_pane(
+            &session_id,
+            &["review-loop", "--session-id", &session_id],
+            "Review Loop",
+        ) {
+            self.review_loop_spawned.insert(task_id.to_string());
+            self.record_session_event(
+                &session_id,
+                TaskEventKind::ReviewLoop,
+                "started in the session tab",
+            );
+            self.show_toast("Review loop started", ToastStyle::Info);
+        }
+    }
+
+    /// If `[ci_fix]` is enabled and a task just transitioned to `CiFailed`,
+    /// split down a pane in its session tab and run `claustre ci-fix`.
+    /// Each run makes one attempt and exits after pushing, so it is spawned
+    /// once per `ci_failed` stint and skipped once the attempt budget is spent.
+    pub(super) fn maybe_spawn_ci_fix_loop(&mut self, task_id: &str) {
+        if !self.config.ci_fix.enabled || self.ci_fix_spawned.contains(task_id) {
+            return;
+        }
+
+        let Ok(task) = self.store.get_task(task_id) else {
+            return;
+        };
+        if task.status != TaskStatus::CiFailed {
+            return;
+        }
+        let attempts = self.store.count_ci_fix_attempts(task_id).unwrap_or(0);
+        if attempts >= i64::from(self.config.ci_fix.max_attempts) {
+            return;
+        }
+        let Some(session_id) = task.session_id else {
+            return;
+        };
+
+        if self.spawn_session_loop_pane(
+            &session_id,
+            &["ci-fix", "--session-id", &session_id],
+            "CI Fix",
+        ) {
+            self.ci_fix_spawned.insert(task_id.to_string());
+            self.show_toast(
+                format!("CI fix loop started: {}", task.title),
+                ToastStyle::Info,
+            );
+        }
+    }
+
+    /// Start automatic conflict resolution for a task whose PR just became
+    /// conflicting. Runs in a pane of the session tab when one is open,
+    /// otherwise as a detached headless process.
+    pub(super) fn maybe_spawn_conflict_resolution(&mut self, task_id: &str) {
+        if !self.config.conflict_resolution.enabled
+            || self.conflict_resolution_spawned.contains(task_id)
+        {
+            return;
+        }
+
+        let Ok(task) = self.store.get_task(task_id) else {
+            return;
+        };
+        if task.status != TaskStatus::Conflict {
+            return;
+        }
+        let attempts = self.store.count_conflict_attempts(task_id).unwrap_or(0);
+        if attempts >= i64::from(self.config.conflict_resolution.max_attempts) {
+            return;
+        }
+        let Some(session_id) = task.session_id else {
+            return;
+        };
+
+        let args = ["resolve-conflicts", "--task-id", task_id];
+        let started = self.spawn_session_loop_pane(&session_id, &args, "Resolve Conflicts") || {
+            let claustre_exe =
+                std::env::current_exe().unwrap_or_else(|_| std::path::PathBuf::from("claustre"));
+            std::process::Command::new(claustre_exe)
+                .args(args)
+                .arg("--headless")
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .spawn()
+                .is_ok()
         };
+        if started {
+            self.conflict_resolution_spawned.insert(task_id.to_string());
+            self.show_toast(
+                format!("Resolving conflicts: {}", task.title),
+                ToastStyle::Info,
+            );
+        }
+    }
 
+    /// Split a pane into the session's tab running `claustre <args>` in the
+    /// worktree. Returns false if the session has no open tab or the split
+    /// failed (a toast is shown for failures).
+    fn spawn_session_loop_pane(&mut self, session_id: &str, args: &[&str], title: &str) -> bool {
         // Find the session tab
         let Some(tab_idx) = self.tabs.iter().position(
-            |tab| matches!(tab, Tab::Session { session_id: sid, .. } if *sid == session_id),
+            |tab| matches!(tab, Tab::Session { session_id: sid, .. } if sid == session_id),
         ) else {
-            return;
+            return false;
         };
 
-        // Build the review-loop command
+        // Build the loop command
         let claustre_exe =
             std::env::current_exe().unwrap_or_else(|_| std::path::PathBuf::from("claustre"));
         let mut cmd = portable_pty::CommandBuilder::new(claustre_exe.to_string_lossy().as_ref());
-        cmd.arg("review-loop");
-        cmd.arg("--session-id");
-        cmd.arg(&session_id);
+        cmd.args(args);
 
         // Set working directory to the worktree
         if let Tab::Session { terminals, .. } = &self.tabs[tab_idx] {
@@ -209,23 +324,20 @@ impl App {
         let cols = term_size.0;
 
         if let Tab::Session { terminals, .. } = &mut self.tabs[tab_idx] {
-            // Focus the claude pane (first pane, typically) before splitting
             if let Err(e) = terminals.split_with_command(
                 crate::pty::SplitDirection::Vertical,
                 rows,
                 cols,
                 cmd,
-                "Review Loop",
+                title,
             ) {
-                self.show_toast(format!("Review loop failed: {e}"), ToastStyle::Error);
-                return;
+                self.show_toast(format!("{title} failed: {e}"), ToastStyle::Error);
+                return false;
             }
             let sizes = compute_pane_sizes_for_resize(&terminals.layout, term_size.0, term_size.1);
             let _ = terminals.resize_panes_with_clear(&sizes);
         }
-
-        self.review_loop_spawned.insert(task_id.to_string());
-        self.show_toast("Review loop started", ToastStyle::Info);
+        true
     }
 
     pub fn show_toast(&mut self, message: impl Into<String>, style: ToastStyle) {
@@ -0,0 +1,145 @@
+//! Template picker for the new-task form: choose a task template, fill in
+//! its variables, and pre-fill the form from the rendered result.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use super::super::form::apply_text_edit;
+use super::{App, InputMode, TemplatePickerState, ToastStyle};
+
+impl App {
+    /// Open the picker over the new-task form (Ctrl+T).
+    pub(super) fn open_template_picker(&mut self) {
+        let repo = self.selected_project().map(|p| p.repo_path.clone());
+        match crate::templates::load_templates(repo.as_deref().map(Path::new)) {
+            Ok(templates) if templates.is_empty() => self.show_toast(
+                "No templates in ~/.claustre/templates/ or <repo>/.claustre/templates/",
+                ToastStyle::Info,
+            ),
+            Ok(templates) => {
+                self.save_current_task_field();
+                self.template_picker = Some(TemplatePickerState {
+                    templates,
+                    index: 0,
+                    var_index: None,
+                    values: HashMap::new(),
+                });
+                self.input_mode = InputMode::TemplatePicker;
+            }
+            Err(e) => self.show_toast(format!("Templates: {e:#}"), ToastStyle::Error),
+        }
+    }
+
+    pub(super) fn handle_template_picker_key(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Result<()> {
+        let Some(picker) = self.template_picker.as_mut() else {
+            self.input_mode = InputMode::NewTask;
+            return Ok(());
+        };
+
+        // Prompting for variable values
+        if let Some(var_index) = picker.var_index {
+            let template = &picker.templates[picker.index];
+            match code {
+                KeyCode::Esc => {
+                    picker.var_index = None;
+                    picker.values.clear();
+                }
+                KeyCode::Enter => {
+                    let name = template.vars[var_index].name.clone();
+                    let value = std::mem::take(&mut self.input_buffer);
+                    picker.values.insert(name, value);
+                    if var_index + 1 < template.vars.len() {
+                        picker.var_index = Some(var_index + 1);
+                        self.prefill_template_var();
+                    } else {
+                        self.apply_template();
+                    }
+                }
+                _ => {
+                    apply_text_edit(
+                        &mut self.input_buffer,
+                        &mut self.input_cursor,
+                        code,
+                        modifiers,
+                    );
+                }
+            }
+            return Ok(());
+        }
+
+        match code {
+            KeyCode::Esc => self.close_template_picker(),
+            KeyCode::Char('j') | KeyCode::Down => {
+                picker.index = (picker.index + 1).min(picker.templates.len().saturating_sub(1));
+            }
+            KeyCode::Char('k') | KeyCode::Up => picker.index = picker.index.saturating_sub(1),
+            KeyCode::Enter => {
+                if picker.templates[picker.index].vars.is_empty() {
+                    self.apply_template();
+                } else {
+                    picker.var_index = Some(0);
+                    self.prefill_template_var();
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Put the current variable's default in the input buffer for editing.
+    fn prefill_template_var(&mut self) {
+        let default = self.template_picker.as_ref().and_then(|p| {
+            let var = &p.templates[p.index].vars[p.var_index?];
+            var.default.clone()
+        });
+        self.input_buffer = default.unwrap_or_default();
+        self.input_cursor = self.input_buffer.len();
+    }
+
+    /// Render the chosen template and copy its fields into the new-task form.
+    fn apply_template(&mut self) {
+        let Some(picker) = self.template_picker.as_ref() else {
+            return;
+        };
+        let template = &picker.templates[picker.index];
+        let name = template.name.clone();
+        match template.render(&picker.values) {
+            Ok(rendered) => {
+                self.new_task_description = rendered.description;
+                if let Some(mode) = rendered.mode {
+                    self.new_task_mode = mode;
+                }
+                if let Some(push_mode) = rendered.push_mode {
+                    self.new_task_push_mode = push_mode;
+                }
+                if let Some(review_loop) = rendered.review_loop {
+                    self.new_task_review_loop = review_loop;
+                }
+                self.new_task_base = rendered.base.unwrap_or_default();
+                self.new_task_branch = rendered.branch.unwrap_or_default();
+                self.new_task_subtasks = rendered.subtasks;
+                self.new_task_subtask_index = 0;
+                self.new_task_field = 0;
+                self.close_template_picker();
+                self.show_toast(format!("Template '{name}' applied"), ToastStyle::Success);
+            }
+            Err(e) => {
+                self.close_template_picker();
+                self.show_toast(format!("Template '{name}': {e:#}"), ToastStyle::Error);
+            }
+        }
+    }
+
+    fn close_template_picker(&mut self) {
+        self.template_picker = None;
+        self.input_mode = InputMode::NewTask;
+        self.load_current_task_field();
+    }
+}
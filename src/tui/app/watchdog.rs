@@ -0,0 +1,410 @@
+//! Stuck-session watchdog: flags sessions with a working task that stopped
+//! producing output, keep repeating the same tool call, or spend tokens
+//! without changing their diff, then nudges, interrupts or kills them as
+//! `[watchdog]` says.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::config::{WatchdogAction, WatchdogConfig};
+use crate::notify::NotifyEvent;
+use crate::scanner::activity::ToolCall;
+use crate::store::{TaskEventKind, TaskStatus};
+
+use super::{App, Tab, ToastStyle};
+
+/// How often working sessions are checked.
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Message pasted for `action = "nudge"` when `nudge_message` is unset.
+const DEFAULT_NUDGE: &str = "The claustre watchdog thinks you are stuck ({reason}). \
+Stop repeating what you were doing, explain what is blocking you, and try a different approach.";
+
+/// Why the watchdog flagged a session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StuckReason {
+    /// No Claude pane output or hook activity for this many minutes.
+    Idle { minutes: u64 },
+    /// The last `count` tool calls were identical.
+    Looping {
+        tool: String,
+        summary: String,
+        count: usize,
+    },
+    /// Tokens spent since the diff last changed.
+    Burning { tokens: i64 },
+}
+
+impl fmt::Display for StuckReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Idle { minutes } => write!(f, "no output for {minutes}m"),
+            Self::Looping {
+                tool,
+                summary,
+                count,
+            } => write!(f, "{tool} `{summary}` {count} times in a row"),
+            Self::Burning { tokens } => write!(f, "{tokens} tokens without changing files"),
+        }
+    }
+}
+
+/// Per-session bookkeeping between checks.
+#[derive(Debug)]
+pub struct WatchState {
+    /// `(files_changed, lines_added, lines_removed)` at the last check.
+    diff: (i64, i64, i64),
+    /// Task token total when `diff` last changed.
+    tokens_at_diff: i64,
+    pub reason: Option<StuckReason>,
+    /// Whether the configured action already ran for the current `reason`.
+    acted: bool,
+}
+
+/// What the watchdog measured for one session.
+struct Signals<'a> {
+    idle: Duration,
+    calls: &'a VecDeque<ToolCall>,
+    tokens_without_progress: i64,
+}
+
+/// First configured check that the session fails, if any.
+fn assess(signals: &Signals<'_>, config: &WatchdogConfig) -> Option<StuckReason> {
+    if config.idle_minutes > 0 && signals.idle >= Duration::from_secs(config.idle_minutes * 60) {
+        return Some(StuckReason::Idle {
+            minutes: signals.idle.as_secs() / 60,
+        });
+    }
+    if config.repeat_threshold > 0
+        && let Some(last) = signals.calls.back()
+        && !last.summary.is_empty()
+    {
+        let count = signals
+            .calls
+            .iter()
+            .rev()
+            .take_while(|c| c.tool == last.tool && c.summary == last.summary)
+            .count();
+        if count >= config.repeat_threshold {
+            return Some(StuckReason::Looping {
+                tool: last.tool.clone(),
+                summary: last.summary.clone(),
+                count,
+            });
+        }
+    }
+    if config.token_budget > 0 && signals.tokens_without_progress >= config.token_budget {
+        return Some(StuckReason::Burning {
+            tokens: signals.tokens_without_progress,
+        });
+    }
+    None
+}
+
+/// A session tab with a working task, as seen by the watchdog.
+struct Watched {
+    session_id: String,
+    task_id: String,
+    last_output: Option<Instant>,
+}
+
+impl App {
+    /// Why the watchdog currently considers a session stuck.
+    pub fn stuck_reason(&self, session_id: &str) -> Option<&StuckReason> {
+        self.watchdog.get(session_id)?.reason.as_ref()
+    }
+
+    /// Check session tabs with a working task every [`WATCHDOG_INTERVAL`].
+    ///
+    /// Sessions blocked on a recognized prompt are left to the prompt inbox.
+    /// A newly stuck session gets a toast and a `stuck` notification, then
+    /// the configured action runs once until the session recovers.
+    pub(super) fn maybe_run_watchdog(&mut self) {
+        if !self.config.watchdog.enabled {
+            self.watchdog.clear();
+            return;
+        }
+        if self.last_watchdog_check.elapsed() < WATCHDOG_INTERVAL {
+            return;
+        }
+        self.last_watchdog_check = Instant::now();
+
+        let watched: Vec<Watched> = self
+            .tabs
+            .iter()
+            .filter_map(|tab| {
+                let Tab::Session {
+                    session_id,
+                    terminals,
+                    ..
+                } = tab
+                else {
+                    return None;
+                };
+                if self.paused_sessions.contains(session_id)
+                    || self.waiting_sessions.contains(session_id)
+                {
+                    return None;
+                }
+                let task = self.tasks.iter().find(|t| {
+                    t.status == TaskStatus::Working
+                        && t.session_id.as_deref() == Some(session_id.as_str())
+                })?;
+                if !self.sessions.iter().any(|s| &s.id == session_id) {
+                    return None;
+                }
+                Some(Watched {
+                    session_id: session_id.clone(),
+                    task_id: task.id.clone(),
+                    last_output: terminals.claude_last_output(),
+                })
+            })
+            .collect();
+        self.watchdog
+            .retain(|id, _| watched.iter().any(|w| &w.session_id == id));
+
+        let mut due = Vec::new();
+        for w in &watched {
+            let (Some(session), Some(task)) = (
+                self.sessions.iter().find(|s| s.id == w.session_id),
+                self.tasks.iter().find(|t| t.id == w.task_id),
+            ) else {
+                continue;
+            };
+            let diff = (
+                session.files_changed,
+                session.lines_added,
+                session.lines_removed,
+            );
+            let tokens = task.input_tokens + task.output_tokens;
+            let state = self
+                .watchdog
+                .entry(w.session_id.clone())
+                .or_insert_with(|| WatchState {
+                    diff,
+                    tokens_at_diff: tokens,
+                    reason: None,
+                    acted: false,
+                });
+            if state.diff != diff {
+                state.diff = diff;
+                state.tokens_at_diff = tokens;
+            }
+
+            let since_output = w.last_output.map_or(Duration::ZERO, |t| t.elapsed());
+            let since_hook = chrono::DateTime::parse_from_rfc3339(&session.last_activity_at)
+                .ok()
+                .and_then(|t| {
+                    (chrono::Utc::now() - t.with_timezone(&chrono::Utc))
+                        .to_std()
+                        .ok()
+                })
+                .unwrap_or(since_output);
+            let empty = VecDeque::new();
+            let signals = Signals {
+                idle: since_output.min(since_hook),
+                calls: self
+                    .activity_feeds
+                    .get(&w.session_id)
+                    .map_or(&empty, |feed| feed.calls()),
+                tokens_without_progress: tokens - state.tokens_at_diff,
+            };
+
+            match assess(&signals, &self.config.watchdog) {
+                None => {
+                    state.reason = None;
+                    state.acted = false;
+                }
+                Some(reason) => {
+                    let newly = state.reason.is_none();
+                    state.reason = Some(reason.clone());
+                    if !state.acted {
+                        due.push((w.session_id.clone(), w.task_id.clone(), reason, newly));
+                    }
+                }
+            }
+        }
+
+        for (session_id, task_id, reason, newly) in due {
+            if newly {
+                tracing::warn!(%session_id, %task_id, "watchdog: session stuck: {reason}");
+                let detail = format!(
+                    "watchdog ({}): {reason}",
+                    self.config.watchdog.action.as_str()
+                );
+                if let Err(e) =
+                    self.store
+                        .record_task_event(&task_id, TaskEventKind::Action, &detail)
+                {
+                    tracing::warn!(%task_id, "watchdog: failed to record event: {e:#}");
+                }
+                self.notify_task_event(NotifyEvent::Stuck, &task_id);
+                self.show_toast(format!("Session stuck: {reason}"), ToastStyle::Error);
+            }
+            if self.apply_watchdog_action(&session_id, &task_id, &reason)
+                && let Some(state) = self.watchdog.get_mut(&session_id)
+            {
+                state.acted = true;
+            }
+        }
+    }
+
+    /// Run the configured action on a stuck session. Returns false when it
+    /// could not run yet and should be retried on the next check.
+    fn apply_watchdog_action(
+        &mut self,
+        session_id: &str,
+        task_id: &str,
+        reason: &StuckReason,
+    ) -> bool {
+        let action = self.config.watchdog.action;
+        let bytes = match action {
+            WatchdogAction::Flag => return true,
+            WatchdogAction::Kill => {
+                // Stop Claude only: the session and its worktree stay so the
+                // uncommitted work can be inspected or salvaged.
+                let terminals = self.tabs.iter_mut().find_map(|tab| match tab {
+                    Tab::Session {
+                        session_id: sid,
+                        terminals,
+                        ..
+                    } if sid == session_id => Some(terminals),
+                    _ => None,
+                });
+                match terminals.map(|t| t.stop_claude()) {
+                    Some(Ok(())) => {}
+                    Some(Err(e)) => {
+                        tracing::warn!(%session_id, "watchdog: kill failed: {e:#}");
+                        return false;
+                    }
+                    None => return false,
+                }
+                if let Err(e) = self.store.update_task_status(task_id, TaskStatus::Error) {
+                    tracing::warn!(%task_id, "watchdog: failed to mark task error: {e:#}");
+                }
+                self.show_toast(
+                    format!("Watchdog stopped Claude in stuck session (worktree kept): {reason}"),
+                    ToastStyle::Error,
+                );
+                return true;
+            }
+            WatchdogAction::Interrupt => "\x1b".to_string(),
+            WatchdogAction::Nudge => {
+                let message = self
+                    .config
+                    .watchdog
+                    .nudge_message
+                    .as_deref()
+                    .unwrap_or(DEFAULT_NUDGE)
+                    .replace("{reason}", &reason.to_string());
+                format!("\x1b[200~{message}\x1b[201~\r")
+            }
+        };
+        let terminals = self.tabs.iter_mut().find_map(|tab| match tab {
+            Tab::Session {
+                session_id: sid,
+                terminals,
+                ..
+            } if sid == session_id => Some(terminals),
+            _ => None,
+        });
+        match terminals.map(|t| t.send_to_claude(bytes.as_bytes())) {
+            Some(Ok(())) => true,
+            Some(Err(e)) => {
+                tracing::warn!(%session_id, "watchdog: {} failed: {e:#}", action.as_str());
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(tool: &str, summary: &str) -> ToolCall {
+        ToolCall {
+            id: String::new(),
+            tool: tool.into(),
+            summary: summary.into(),
+            started_at: None,
+            finished_at: None,
+            failed: false,
+            error: None,
+        }
+    }
+
+    fn signals(calls: &VecDeque<ToolCall>) -> Signals<'_> {
+        Signals {
+            idle: Duration::from_secs(60),
+            calls,
+            tokens_without_progress: 0,
+        }
+    }
+
+    #[test]
+    fn healthy_session_is_not_flagged() {
+        let calls: VecDeque<_> = [call("Bash", "cargo test"), call("Edit", "src/lib.rs")].into();
+        assert_eq!(assess(&signals(&calls), &WatchdogConfig::default()), None);
+    }
+
+    #[test]
+    fn idle_session_is_flagged_unless_disabled() {
+        let calls = VecDeque::new();
+        let mut s = signals(&calls);
+        s.idle = Duration::from_secs(25 * 60);
+        let mut config = WatchdogConfig::default();
+        assert_eq!(assess(&s, &config), Some(StuckReason::Idle { minutes: 25 }));
+        config.idle_minutes = 0;
+        assert_eq!(assess(&s, &config), None);
+    }
+
+    #[test]
+    fn trailing_run_of_identical_calls_is_a_loop() {
+        let config = WatchdogConfig {
+            repeat_threshold: 3,
+            ..WatchdogConfig::default()
+        };
+        let mut calls: VecDeque<_> = [
+            call("Bash", "cargo test"),
+            call("Bash", "cargo build"),
+            call("Bash", "cargo build"),
+        ]
+        .into();
+        assert_eq!(assess(&signals(&calls), &config), None);
+
+        calls.push_back(call("Bash", "cargo build"));
+        assert_eq!(
+            assess(&signals(&calls), &config),
+            Some(StuckReason::Looping {
+                tool: "Bash".into(),
+                summary: "cargo build".into(),
+                count: 3,
+            })
+        );
+
+        // Breaking the run clears it; calls without a summary never count.
+        calls.push_back(call("Edit", "src/lib.rs"));
+        assert_eq!(assess(&signals(&calls), &config), None);
+        let blanks: VecDeque<_> = (0..5).map(|_| call("TodoWrite", "")).collect();
+        assert_eq!(assess(&signals(&blanks), &config), None);
+    }
+
+    #[test]
+    fn token_burn_without_diff_change_is_flagged() {
+        let calls = VecDeque::new();
+        let mut s = signals(&calls);
+        let config = WatchdogConfig::default();
+        s.tokens_without_progress = config.token_budget - 1;
+        assert_eq!(assess(&s, &config), None);
+        s.tokens_without_progress = config.token_budget;
+        assert_eq!(
+            assess(&s, &config),
+            Some(StuckReason::Burning {
+                tokens: config.token_budget
+            })
+        );
+    }
+}
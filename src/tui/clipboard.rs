@@ -0,0 +1,67 @@
+//! Clipboard access for copied pane text.
+//!
+//! Uses the system clipboard through `arboard` when there is one. Over SSH,
+//! or when no clipboard is reachable (headless hosts, no display server),
+//! falls back to an OSC 52 escape so the outer terminal emulator sets its
+//! own clipboard.
+
+use std::io::{Write, stdout};
+
+/// Copy `text` to the clipboard. Returns false if no method succeeded.
+pub fn copy(text: &str) -> bool {
+    if !over_ssh()
+        && arboard::Clipboard::new().is_ok_and(|mut clipboard| clipboard.set_text(text).is_ok())
+    {
+        return true;
+    }
+    let mut out = stdout();
+    out.write_all(osc52(text).as_bytes())
+        .and_then(|()| out.flush())
+        .is_ok()
+}
+
+/// A system clipboard on an SSH host belongs to the remote machine, not the
+/// user, so sessions over SSH always go through the terminal.
+fn over_ssh() -> bool {
+    std::env::var_os("SSH_TTY").is_some() || std::env::var_os("SSH_CONNECTION").is_some()
+}
+
+fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn osc52_encodes_text_as_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foob"), "Zm9vYg==");
+        assert_eq!(osc52("hi\n"), "\x1b]52;c;aGkK\x07");
+    }
+}
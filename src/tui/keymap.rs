@@ -41,6 +41,18 @@ pub enum Action {
     Configure,
     // Board
     OpenBoard,
+    // Prompt inbox
+    OpenInbox,
+    // Diff viewer
+    OpenDiff,
+    // Cross-project task view
+    ToggleAllProjects,
+    // Retry a failed task with context
+    RetryTask,
+    // Send a follow-up prompt to the task's session
+    MessageSession,
+    // Best-of-N fan-out and variant comparison
+    FanOut,
     // Session-only
     ReturnToDashboard,
     FocusPrevPane,
@@ -50,7 +62,15 @@ pub enum Action {
     ScrollPageDown,
     SplitRight,
     SplitDown,
+    SplitWithCommand,
     ClosePane,
+    ResizePaneLeft,
+    ResizePaneRight,
+    ResizePaneUp,
+    ResizePaneDown,
+    ZoomPane,
+    SearchScrollback,
+    CopyMode,
 }
 
 // ── Help categories ──────────────────────────────────────────────────
@@ -218,6 +238,13 @@ impl KeyMap {
 // ── Lookup helper ────────────────────────────────────────────────────
 
 fn lookup(bindings: &[KeyBinding], code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+    // Terminals report uppercase letters with SHIFT set; the case already
+    // carries that information, so bindings like `J` are declared without it.
+    let modifiers = if matches!(code, KeyCode::Char(c) if c.is_ascii_uppercase()) {
+        modifiers - KeyModifiers::SHIFT
+    } else {
+        modifiers
+    };
     bindings
         .iter()
         .find(|kb| kb.code == code && kb.modifiers == modifiers)
@@ -469,6 +496,54 @@ fn default_normal_bindings() -> Vec<KeyBinding> {
             description: "Sprint board",
             category: Tasks,
         },
+        KeyBinding {
+            code: KeyCode::Char('p'),
+            modifiers: KeyModifiers::NONE,
+            action: OpenInbox,
+            label: "  p",
+            description: "Prompt inbox (approve / answer)",
+            category: Tasks,
+        },
+        KeyBinding {
+            code: KeyCode::Char('D'),
+            modifiers: KeyModifiers::NONE,
+            action: OpenDiff,
+            label: "  D",
+            description: "Diff / review task changes",
+            category: Tasks,
+        },
+        KeyBinding {
+            code: KeyCode::Char('A'),
+            modifiers: KeyModifiers::NONE,
+            action: ToggleAllProjects,
+            label: "  A",
+            description: "All projects view (toggle)",
+            category: Tasks,
+        },
+        KeyBinding {
+            code: KeyCode::Char('R'),
+            modifiers: KeyModifiers::NONE,
+            action: RetryTask,
+            label: "  R",
+            description: "Retry task with failure context",
+            category: Tasks,
+        },
+        KeyBinding {
+            code: KeyCode::Char('m'),
+            modifiers: KeyModifiers::NONE,
+            action: MessageSession,
+            label: "  m",
+            description: "Message session (send a prompt)",
+            category: Tasks,
+        },
+        KeyBinding {
+            code: KeyCode::Char('F'),
+            modifiers: KeyModifiers::NONE,
+            action: FanOut,
+            label: "  F",
+            description: "Fan out task / compare variants",
+            category: Tasks,
+        },
         KeyBinding {
             code: KeyCode::Char('i'),
             modifiers: KeyModifiers::NONE,
@@ -576,6 +651,14 @@ fn default_session_bindings() -> Vec<KeyBinding> {
             description: "Split down (bottom)",
             category: SessionTab,
         },
+        KeyBinding {
+            code: KeyCode::Char('n'),
+            modifiers: KeyModifiers::CONTROL,
+            action: SplitWithCommand,
+            label: "  Ctrl+N",
+            description: "Split with a command from [[panes]]",
+            category: SessionTab,
+        },
         KeyBinding {
             code: KeyCode::Char('w'),
             modifiers: KeyModifiers::CONTROL,
@@ -584,6 +667,62 @@ fn default_session_bindings() -> Vec<KeyBinding> {
             description: "Close pane",
             category: SessionTab,
         },
+        KeyBinding {
+            code: KeyCode::Left,
+            modifiers: KeyModifiers::ALT,
+            action: ResizePaneLeft,
+            label: "  Alt+Left",
+            description: "Move the nearest vertical divider left",
+            category: SessionTab,
+        },
+        KeyBinding {
+            code: KeyCode::Right,
+            modifiers: KeyModifiers::ALT,
+            action: ResizePaneRight,
+            label: "  Alt+Right",
+            description: "Move the nearest vertical divider right",
+            category: SessionTab,
+        },
+        KeyBinding {
+            code: KeyCode::Up,
+            modifiers: KeyModifiers::ALT,
+            action: ResizePaneUp,
+            label: "  Alt+Up",
+            description: "Move the nearest horizontal divider up",
+            category: SessionTab,
+        },
+        KeyBinding {
+            code: KeyCode::Down,
+            modifiers: KeyModifiers::ALT,
+            action: ResizePaneDown,
+            label: "  Alt+Down",
+            description: "Move the nearest horizontal divider down",
+            category: SessionTab,
+        },
+        KeyBinding {
+            code: KeyCode::Char('z'),
+            modifiers: KeyModifiers::ALT,
+            action: ZoomPane,
+            label: "  Alt+Z",
+            description: "Zoom the focused pane (toggle)",
+            category: SessionTab,
+        },
+        KeyBinding {
+            code: KeyCode::Char('f'),
+            modifiers: KeyModifiers::CONTROL,
+            action: SearchScrollback,
+            label: "  Ctrl+F",
+            description: "Search scrollback (n/N: older/newer, y/Y: copy match/block)",
+            category: SessionTab,
+        },
+        KeyBinding {
+            code: KeyCode::Char('s'),
+            modifiers: KeyModifiers::CONTROL,
+            action: CopyMode,
+            label: "  Ctrl+S",
+            description: "Copy mode (vi motions, v/V/Ctrl+V select, y yank)",
+            category: SessionTab,
+        },
     ]
 }
 
@@ -652,6 +791,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn lookup_normal_uppercase_ignores_shift() {
+        let km = KeyMap::default_keymap();
+        assert_eq!(
+            km.lookup_normal(KeyCode::Char('J'), KeyModifiers::SHIFT),
+            Some(Action::ReorderTaskDown)
+        );
+        assert_eq!(
+            km.lookup_normal(KeyCode::Char('D'), KeyModifiers::NONE),
+            Some(Action::OpenDiff)
+        );
+    }
+
     #[test]
     fn normal_bindings_has_expected_count() {
         let km = KeyMap::default_keymap();
@@ -662,6 +814,6 @@ mod tests {
     #[test]
     fn session_bindings_has_expected_count() {
         let km = KeyMap::default_keymap();
-        assert_eq!(km.session.len(), 11);
+        assert_eq!(km.session.len(), 19);
     }
 }
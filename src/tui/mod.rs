@@ -4,6 +4,7 @@
 //! for the dashboard, session tabs, and overlay panels.
 
 mod app;
+mod clipboard;
 mod event;
 pub mod form;
 pub mod keymap;
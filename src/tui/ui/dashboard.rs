@@ -147,10 +147,16 @@ fn draw_active_impl(frame: &mut Frame, app: &mut App, size: Rect) {
                 Span::raw(tf_before.to_string()),
                 Span::styled("\u{2588}", Style::default().fg(app.theme.accent_secondary)),
                 Span::raw(tf_after.to_string()),
-                Span::styled(
-                    "  Enter:apply  Esc:clear",
-                    Style::default().fg(app.theme.text_secondary),
-                ),
+                match &app.task_filter_error {
+                    Some(err) => Span::styled(
+                        format!("  {err}"),
+                        Style::default().fg(app.theme.status_error),
+                    ),
+                    None => Span::styled(
+                        "  Enter:apply  Esc:clear  (status: mode: has: ci: created:<7d is:archived)",
+                        Style::default().fg(app.theme.text_secondary),
+                    ),
+                },
             ])),
             bottom[0],
         );
@@ -364,6 +370,16 @@ fn draw_session_detail(frame: &mut Frame, app: &App, area: Rect) {
         ]),
     ];
 
+    if let Some(reason) = app.stuck_reason(&session.id) {
+        lines.push(Line::from(vec![
+            Span::styled("  Stuck: ", Style::default().fg(app.theme.text_secondary)),
+            Span::styled(
+                reason.to_string(),
+                Style::default().fg(app.theme.status_error),
+            ),
+        ]));
+    }
+
     if !session.status_message.is_empty() {
         lines.push(Line::from(vec![
             Span::styled("  Message: ", Style::default().fg(app.theme.text_secondary)),
@@ -455,12 +471,113 @@ fn draw_session_detail(frame: &mut Frame, app: &App, area: Rect) {
         ]));
     }
 
+    // Recent tool calls from the transcript, newest first, in whatever rows remain
+    if let Some(feed) = app.activity_feeds.get(&session.id)
+        && !feed.calls().is_empty()
+    {
+        let rows = usize::from(area.height.saturating_sub(2));
+        let room = rows.saturating_sub(lines.len() + 2);
+        if room > 0 {
+            let failed = feed.calls().iter().filter(|c| c.failed).count();
+            lines.push(Line::from(""));
+            let mut header = vec![Span::styled(
+                format!("  Activity: ({} calls)", feed.calls().len()),
+                Style::default().fg(app.theme.text_secondary),
+            )];
+            if failed > 0 {
+                header.push(Span::styled(
+                    format!(" {failed} failed"),
+                    Style::default().fg(app.theme.status_error),
+                ));
+            }
+            lines.push(Line::from(header));
+            let working = session.claude_status == ClaudeStatus::Working;
+            let mut used = 0;
+            for call in feed.calls().iter().rev() {
+                let extra = usize::from(call.error.is_some());
+                if used + 1 + extra > room {
+                    break;
+                }
+                used += 1 + extra;
+                lines.extend(activity_lines(app, call, working));
+            }
+        }
+    }
+
     let detail = Paragraph::new(lines)
         .block(block)
         .wrap(Wrap { trim: false });
     frame.render_widget(detail, area);
 }
 
+/// One row per tool call (time, outcome, tool, summary, duration), plus the
+/// first error line under failed calls.
+fn activity_lines<'a>(
+    app: &App,
+    call: &'a crate::scanner::activity::ToolCall,
+    working: bool,
+) -> Vec<Line<'a>> {
+    let (symbol, color) = if call.failed {
+        ("\u{2717}", app.theme.status_error)
+    } else if call.finished_at.is_some() {
+        ("\u{2713}", app.theme.status_working)
+    } else if working {
+        (spinner_char(), app.theme.accent_secondary)
+    } else {
+        ("\u{00b7}", app.theme.text_secondary)
+    };
+    let time = call.started_at.map_or_else(
+        || "--:--:--".to_string(),
+        |ts| {
+            ts.with_timezone(&chrono::Local)
+                .format("%H:%M:%S")
+                .to_string()
+        },
+    );
+    let mut spans = vec![
+        Span::styled(
+            format!("    {time} "),
+            Style::default().fg(app.theme.text_secondary),
+        ),
+        Span::styled(format!("{symbol} "), Style::default().fg(color)),
+        Span::styled(
+            format!("{} ", call.tool),
+            Style::default().fg(app.theme.text_accent),
+        ),
+        Span::styled(
+            call.summary.as_str(),
+            Style::default().fg(app.theme.text_primary),
+        ),
+    ];
+    if let Some(duration) = call.duration() {
+        spans.push(Span::styled(
+            format!(" ({})", format_call_duration(duration)),
+            Style::default().fg(app.theme.text_secondary),
+        ));
+    }
+    let mut lines = vec![Line::from(spans)];
+    if let Some(error) = &call.error {
+        lines.push(Line::from(Span::styled(
+            format!("               {error}"),
+            Style::default().fg(app.theme.status_error),
+        )));
+    }
+    lines
+}
+
+/// Compact tool-call duration: "0.4s", "12s", "3m05s".
+fn format_call_duration(duration: chrono::Duration) -> String {
+    let ms = duration.num_milliseconds().max(0);
+    if ms < 10_000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else if ms < 60_000 {
+        format!("{}s", ms / 1000)
+    } else {
+        let secs = ms / 1000;
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
 fn draw_task_queue(frame: &mut Frame, app: &mut App, area: Rect) {
     let focused = app.focus == Focus::Tasks;
     let border_style = if focused {
@@ -474,16 +591,34 @@ fn draw_task_queue(frame: &mut Frame, app: &mut App, area: Rect) {
         let visible_tasks = app.visible_tasks();
         let count = visible_tasks.len();
 
+        let queue = if app.all_projects {
+            "All Projects"
+        } else {
+            "Task Queue"
+        };
         let title = if app.task_filter.is_empty() {
             if count > 0 {
                 // Show scroll position when there are tasks
                 let pos = app.task_index + 1;
-                format!(" Task Queue ({pos}/{count}) ")
+                format!(" {queue} ({pos}/{count}) ")
             } else {
-                " Task Queue ".to_string()
+                format!(" {queue} ")
             }
+        } else if app.task_filter_error.is_some() {
+            format!(" {queue} [/{}] (invalid query) ", app.task_filter)
         } else {
-            format!(" Task Queue [/{}] ({count}) ", app.task_filter)
+            format!(" {queue} [/{}] ({count}) ", app.task_filter)
+        };
+
+        // Project column width for the all-projects view
+        let project_width = if app.all_projects {
+            visible_tasks
+                .iter()
+                .map(|t| app.project_name(&t.project_id).chars().count())
+                .max()
+                .unwrap_or(0)
+        } else {
+            0
         };
 
         let items: Vec<ListItem> = visible_tasks
@@ -495,11 +630,19 @@ fn draw_task_queue(frame: &mut Frame, app: &mut App, area: Rect) {
                     && session_id.is_some_and(|sid| app.paused_sessions.contains(sid));
                 let is_waiting = task.status == TaskStatus::Working
                     && session_id.is_some_and(|sid| app.waiting_sessions.contains(sid));
+                let is_stuck = task.status == TaskStatus::Working
+                    && session_id.is_some_and(|sid| app.stuck_reason(sid).is_some());
 
                 let (status_symbol, status_label, status_style) = if is_paused {
                     ("\u{23f8}", "paused", app.theme.paused_style())
                 } else if is_waiting {
                     ("\u{23f3}", "waiting", app.theme.waiting_style())
+                } else if is_stuck {
+                    (
+                        "\u{26a0}",
+                        "stuck",
+                        Style::default().fg(app.theme.status_error),
+                    )
                 } else {
                     (
                         task.status.symbol(),
@@ -512,6 +655,12 @@ fn draw_task_queue(frame: &mut Frame, app: &mut App, area: Rect) {
 
                 spans.push(Span::styled(status_symbol, status_style));
                 spans.push(Span::raw(" "));
+                if app.all_projects {
+                    spans.push(Span::styled(
+                        format!("{:<project_width$}  ", app.project_name(&task.project_id)),
+                        Style::default().fg(app.theme.accent_secondary),
+                    ));
+                }
                 if app.pending_titles.contains(&task.id) {
                     spans.push(Span::styled(
                         spinner_char(),
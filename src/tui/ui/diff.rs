@@ -0,0 +1,494 @@
+//! Diff/review viewer rendering -- file list, comments, and colored hunks.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::session::diff::{DiffRow, FileDiff, LineKind};
+
+use super::super::app::{App, DiffViewState, InputMode};
+use super::super::form::{format_with_cursor, render_hints};
+use super::super::theme::Theme;
+
+/// Draw the diff viewer over the whole frame.
+pub(super) fn draw_diff_view(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(view) = app.diff_view.as_ref() else {
+        return;
+    };
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(area);
+    draw_header(frame, app, view, layout[0]);
+
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+        .split(layout[1]);
+    draw_sidebar(frame, app, view, body[0]);
+    draw_hunks(frame, app, view, body[1]);
+    draw_footer(frame, app, view, layout[2]);
+}
+
+fn draw_header(frame: &mut Frame, app: &App, view: &DiffViewState, area: Rect) {
+    let theme = &app.theme;
+    let added: usize = view.files.iter().map(FileDiff::added).sum();
+    let removed: usize = view.files.iter().map(FileDiff::removed).sum();
+    let mut spans = vec![
+        Span::styled(
+            " Diff ",
+            Style::default()
+                .fg(theme.text_accent)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!("{} ", view.task_title),
+            Style::default().fg(theme.text_primary),
+        ),
+        Span::styled(
+            format!("({})  ", view.source.describe()),
+            Style::default().fg(theme.text_secondary),
+        ),
+        Span::styled(
+            format!("{} files ", view.files.len()),
+            Style::default().fg(theme.text_secondary),
+        ),
+        Span::styled(
+            format!("+{added} "),
+            Style::default().fg(theme.status_ci_passed),
+        ),
+        Span::styled(
+            format!("-{removed}"),
+            Style::default().fg(theme.status_error),
+        ),
+    ];
+    if view.source.is_read_only() {
+        spans.push(Span::styled(
+            "  [read-only]",
+            Style::default().fg(theme.accent_secondary),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+fn draw_sidebar(frame: &mut Frame, app: &App, view: &DiffViewState, area: Rect) {
+    let theme = &app.theme;
+    let comment_height = if view.comments.is_empty() {
+        0
+    } else {
+        (view.comments.len() as u16 + 2).min(area.height / 2)
+    };
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(comment_height)])
+        .split(area);
+
+    let border = if view.files_focused {
+        theme.border_focused
+    } else {
+        theme.border_unfocused
+    };
+    let block = Block::default()
+        .title(" Files ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border));
+    let inner = block.inner(layout[0]);
+    frame.render_widget(block, layout[0]);
+
+    let visible = inner.height as usize;
+    let offset = (view.file_index + 1).saturating_sub(visible);
+    let lines: Vec<Line<'_>> = view
+        .files
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(visible)
+        .map(|(i, file)| {
+            let selected = i == view.file_index;
+            let name_style = if selected {
+                Style::default()
+                    .fg(theme.text_primary)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.text_secondary)
+            };
+            let staged = if view.staged.contains(&file.path) {
+                "\u{2713}"
+            } else {
+                " "
+            };
+            Line::from(vec![
+                Span::styled(
+                    if selected { "\u{25b8}" } else { " " },
+                    Style::default().fg(theme.selection_indicator),
+                ),
+                Span::styled(staged, Style::default().fg(theme.status_ci_passed)),
+                Span::styled(
+                    format!("{} ", file.status.symbol()),
+                    Style::default().fg(theme.accent_secondary),
+                ),
+                Span::styled(file.path.as_str(), name_style),
+                Span::styled(
+                    format!(" +{}", file.added()),
+                    Style::default().fg(theme.status_ci_passed),
+                ),
+                Span::styled(
+                    format!(" -{}", file.removed()),
+                    Style::default().fg(theme.status_error),
+                ),
+            ])
+        })
+        .collect();
+    if lines.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                " No changes",
+                Style::default().fg(theme.form_dim),
+            )),
+            inner,
+        );
+    } else {
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+
+    if comment_height > 0 {
+        let block = Block::default()
+            .title(format!(" Comments ({}) ", view.comments.len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border_unfocused));
+        let lines: Vec<Line<'_>> = view
+            .comments
+            .iter()
+            .map(|c| {
+                let location = c
+                    .line
+                    .map_or_else(|| c.path.clone(), |l| format!("{}:{l}", c.path));
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {location} "),
+                        Style::default().fg(theme.text_accent),
+                    ),
+                    Span::styled(c.text.as_str(), Style::default().fg(theme.text_primary)),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines).block(block), layout[1]);
+    }
+}
+
+fn draw_hunks(frame: &mut Frame, app: &App, view: &DiffViewState, area: Rect) {
+    let theme = &app.theme;
+    let file = view.files.get(view.file_index);
+    let border = if view.files_focused {
+        theme.border_unfocused
+    } else {
+        theme.border_focused
+    };
+    let block = Block::default()
+        .title(format!(" {} ", file.map_or("", |f| f.path.as_str())))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let Some(file) = file else {
+        return;
+    };
+    if file.binary {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                " Binary file",
+                Style::default().fg(theme.form_dim),
+            )),
+            inner,
+        );
+        return;
+    }
+
+    let ext = file.path.rsplit_once('.').map_or("", |(_, ext)| ext);
+    let commented: Vec<u32> = view
+        .comments
+        .iter()
+        .filter(|c| c.path == file.path)
+        .filter_map(|c| c.line)
+        .collect();
+    let rows = file.rows();
+    let visible = inner.height as usize;
+    let offset = (view.row_index + 1).saturating_sub(visible);
+    let dim = Style::default().fg(theme.form_dim);
+
+    let lines: Vec<Line<'_>> = rows
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(visible)
+        .map(|(i, row)| {
+            let cursor = if i == view.row_index && !view.files_focused {
+                Span::styled("\u{25b8}", Style::default().fg(theme.selection_indicator))
+            } else {
+                Span::raw(" ")
+            };
+            match row {
+                DiffRow::Header(header) => Line::from(vec![
+                    cursor,
+                    Span::styled(*header, Style::default().fg(theme.accent_secondary)),
+                ]),
+                DiffRow::Line(line) => {
+                    let number =
+                        |n: Option<u32>| n.map_or_else(|| "    ".into(), |n| format!("{n:>4}"));
+                    let has_comment = line
+                        .new_line
+                        .or(line.old_line)
+                        .is_some_and(|n| commented.contains(&n));
+                    let (marker, tint) = match line.kind {
+                        LineKind::Added => ("+", Some(theme.status_ci_passed)),
+                        LineKind::Removed => ("-", Some(theme.status_error)),
+                        LineKind::Context => (" ", None),
+                    };
+                    let mut spans = vec![
+                        cursor,
+                        Span::styled(
+                            if has_comment { "\u{25cf}" } else { " " },
+                            Style::default().fg(theme.text_accent),
+                        ),
+                        Span::styled(number(line.old_line), dim),
+                        Span::styled(number(line.new_line), dim),
+                        Span::styled(
+                            format!(" {marker}"),
+                            tint.map_or(dim, |c| Style::default().fg(c)),
+                        ),
+                    ];
+                    for (token, text) in highlight_code(&line.text, ext) {
+                        spans.push(Span::styled(text, token_style(token, tint, theme)));
+                    }
+                    Line::from(spans)
+                }
+            }
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn draw_footer(frame: &mut Frame, app: &App, view: &DiffViewState, area: Rect) {
+    let theme = &app.theme;
+    let key = Style::default().fg(theme.form_highlight);
+    let dim = Style::default().fg(theme.form_dim);
+    if app.input_mode == InputMode::DiffComment {
+        let location = view
+            .files
+            .get(view.file_index)
+            .map_or("", |f| f.path.as_str());
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(format!(" Comment on {location}: "), key),
+                Span::styled(
+                    format_with_cursor(&app.input_buffer, app.input_cursor),
+                    Style::default().fg(theme.text_primary),
+                ),
+            ])),
+            area,
+        );
+        return;
+    }
+    let mut hints = vec![
+        (" j/k", ":move  "),
+        ("h/l", ":files/hunks  "),
+        ("[/]", ":file  "),
+    ];
+    if !view.source.is_read_only() {
+        hints.extend([("s", ":stage  "), ("x", ":discard  ")]);
+    }
+    hints.extend([
+        ("c", ":comment  "),
+        ("S", ":send comments  "),
+        ("r", ":reload  "),
+        ("Esc", ":close"),
+    ]);
+    render_hints(frame, area, &hints, key, dim);
+}
+
+/// Coarse token classes for diff syntax coloring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Plain,
+    Keyword,
+    Str,
+    Comment,
+}
+
+fn token_style(token: Token, tint: Option<Color>, theme: &Theme) -> Style {
+    let base = Style::default().fg(tint.unwrap_or(theme.text_primary));
+    match token {
+        Token::Plain => base,
+        Token::Keyword => base.add_modifier(Modifier::BOLD),
+        Token::Str => Style::default().fg(theme.accent_tertiary),
+        Token::Comment => Style::default().fg(theme.form_dim),
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "as",
+    "async",
+    "await",
+    "break",
+    "case",
+    "class",
+    "const",
+    "continue",
+    "def",
+    "else",
+    "enum",
+    "export",
+    "false",
+    "fn",
+    "for",
+    "from",
+    "func",
+    "function",
+    "if",
+    "impl",
+    "import",
+    "in",
+    "interface",
+    "let",
+    "loop",
+    "match",
+    "mod",
+    "mut",
+    "None",
+    "null",
+    "package",
+    "pub",
+    "return",
+    "self",
+    "Self",
+    "static",
+    "struct",
+    "trait",
+    "true",
+    "type",
+    "use",
+    "var",
+    "where",
+    "while",
+];
+
+/// Split a line of code into strings, line comments, keywords and plain text.
+///
+/// A lightweight per-line tokenizer keyed by file extension: good enough to
+/// make hunks readable without pulling in a full syntax highlighter.
+fn highlight_code<'a>(text: &'a str, ext: &str) -> Vec<(Token, &'a str)> {
+    let comment = match ext {
+        "md" | "txt" | "" => {
+            let mut tokens = Vec::new();
+            push_plain(&mut tokens, text);
+            return tokens;
+        }
+        "py" | "sh" | "bash" | "toml" | "yaml" | "yml" | "rb" => "#",
+        "sql" | "lua" => "--",
+        _ => "//",
+    };
+    let single_quote_strings = matches!(ext, "py" | "js" | "jsx" | "ts" | "tsx" | "rb" | "sh");
+
+    let mut tokens = Vec::new();
+    let mut plain_start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if text[i..].starts_with(comment) {
+            push_plain(&mut tokens, &text[plain_start..i]);
+            tokens.push((Token::Comment, &text[i..]));
+            return tokens;
+        }
+        if c == '"' || (c == '\'' && single_quote_strings) {
+            let mut end = text.len();
+            let mut escaped = false;
+            for (j, d) in chars.by_ref() {
+                if escaped {
+                    escaped = false;
+                } else if d == '\\' {
+                    escaped = true;
+                } else if d == c {
+                    end = j + d.len_utf8();
+                    break;
+                }
+            }
+            push_plain(&mut tokens, &text[plain_start..i]);
+            tokens.push((Token::Str, &text[i..end]));
+            plain_start = end;
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' {
+            let mut end = i + c.len_utf8();
+            while let Some(&(j, d)) = chars.peek() {
+                if d.is_alphanumeric() || d == '_' {
+                    end = j + d.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let word = &text[i..end];
+            if KEYWORDS.contains(&word) {
+                push_plain(&mut tokens, &text[plain_start..i]);
+                tokens.push((Token::Keyword, word));
+                plain_start = end;
+            }
+        }
+    }
+    push_plain(&mut tokens, &text[plain_start..]);
+    tokens
+}
+
+fn push_plain<'a>(tokens: &mut Vec<(Token, &'a str)>, text: &'a str) {
+    if !text.is_empty() {
+        tokens.push((Token::Plain, text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlight_splits_keywords_strings_and_comments() {
+        let tokens = highlight_code(r#"let s = "a \"b\""; // note"#, "rs");
+        assert_eq!(
+            tokens,
+            vec![
+                (Token::Keyword, "let"),
+                (Token::Plain, " s = "),
+                (Token::Str, r#""a \"b\"""#),
+                (Token::Plain, "; "),
+                (Token::Comment, "// note"),
+            ]
+        );
+    }
+
+    #[test]
+    fn highlight_uses_hash_comments_for_python() {
+        let tokens = highlight_code("x = 'hi'  # done", "py");
+        assert_eq!(
+            tokens,
+            vec![
+                (Token::Plain, "x = "),
+                (Token::Str, "'hi'"),
+                (Token::Plain, "  "),
+                (Token::Comment, "# done"),
+            ]
+        );
+    }
+
+    #[test]
+    fn highlight_leaves_prose_plain() {
+        let tokens = highlight_code("if this // that", "md");
+        assert_eq!(tokens, vec![(Token::Plain, "if this // that")]);
+    }
+}
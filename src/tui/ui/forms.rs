@@ -502,7 +502,9 @@ pub(super) fn draw_task_form_panel(frame: &mut Frame, app: &App, title: &str) {
                 Span::styled("Esc", highlight),
             ];
             if app.input_mode == InputMode::NewTask {
-                spans.push(Span::styled(":draft", dim));
+                spans.push(Span::styled(":draft  ", dim));
+                spans.push(Span::styled("Ctrl+T", highlight));
+                spans.push(Span::styled(":template", dim));
             } else {
                 spans.push(Span::styled(":cancel", dim));
             }
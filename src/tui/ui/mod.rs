@@ -1,5 +1,6 @@
 mod board;
 mod dashboard;
+mod diff;
 mod forms;
 mod overlays;
 mod session;
@@ -19,10 +20,13 @@ use super::app::{App, InputMode};
 
 use board::{draw_board, draw_milestone_overlay};
 use dashboard::{draw_active, draw_active_in_area};
+use diff::draw_diff_view;
 use forms::{draw_new_project_panel, draw_task_form_panel};
 use overlays::{
-    draw_command_palette, draw_configure_wizard, draw_help_overlay, draw_skill_add_overlay,
-    draw_skill_panel, draw_skill_search_overlay, draw_subtask_panel, draw_task_details_panel,
+    draw_command_palette, draw_configure_wizard, draw_fanout_compare, draw_fanout_launch_dialog,
+    draw_help_overlay, draw_inbox_overlay, draw_message_dialog, draw_retry_dialog,
+    draw_skill_add_overlay, draw_skill_panel, draw_skill_search_overlay, draw_subtask_panel,
+    draw_task_details_panel, draw_template_picker,
 };
 use session::draw_session_tab;
 use tab_bar::draw_tab_bar;
@@ -70,6 +74,14 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
         return;
     }
 
+    // Diff viewer replaces the dashboard content
+    if matches!(app.input_mode, InputMode::DiffView | InputMode::DiffComment) {
+        let diff_area = frame.area();
+        frame.render_widget(Clear, diff_area);
+        draw_diff_view(frame, app, diff_area);
+        return;
+    }
+
     // Tab bar (only show if there are session tabs)
     if app.tabs.len() > 1 {
         let size = frame.area();
@@ -89,6 +101,10 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
     match app.input_mode {
         InputMode::CommandPalette => draw_command_palette(frame, app),
         InputMode::NewTask => draw_task_form_panel(frame, app, " New Task "),
+        InputMode::TemplatePicker => {
+            draw_task_form_panel(frame, app, " New Task ");
+            draw_template_picker(frame, app);
+        }
         InputMode::EditTask => draw_task_form_panel(frame, app, " Edit Task "),
         InputMode::NewProject => draw_new_project_panel(frame, app),
         InputMode::HelpOverlay => draw_help_overlay(frame, app),
@@ -104,6 +120,11 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
             }
         }
         InputMode::ConfigureWizard => draw_configure_wizard(frame, app),
+        InputMode::Inbox => draw_inbox_overlay(frame, app),
+        InputMode::RetryTask => draw_retry_dialog(frame, app),
+        InputMode::MessageSession => draw_message_dialog(frame, app),
+        InputMode::FanoutLaunch => draw_fanout_launch_dialog(frame, app),
+        InputMode::FanoutCompare => draw_fanout_compare(frame, app),
         _ => {}
     }
 }
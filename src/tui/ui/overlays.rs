@@ -6,10 +6,11 @@ use ratatui::{
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
 };
 
-use super::super::app::App;
+use super::super::app::{App, PromptKind, VariantConfirm};
 use super::super::form::{format_with_cursor, measure_wrapped_height, render_hints, render_modal};
 use super::super::theme::Theme;
 use super::usage::format_tokens;
+use crate::store::VariantTestStatus;
 
 pub(super) fn draw_command_palette(frame: &mut Frame, app: &App) {
     let area = frame.area();
@@ -590,6 +591,126 @@ pub(super) fn draw_task_details_panel(frame: &mut Frame, app: &App) {
         ),
     ]));
 
+    // Auto-merge policy (task, else project, else config default)
+    let merge_policy = app
+        .store
+        .effective_merge_policy(&task.id)
+        .ok()
+        .flatten()
+        .or(app.config.auto_merge.policy)
+        .unwrap_or(crate::store::MergePolicy::Never);
+    lines.push(Line::from(vec![
+        Span::styled(
+            "  Auto-merge: ",
+            Style::default()
+                .fg(theme.text_secondary)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            merge_policy.to_string(),
+            Style::default().fg(theme.text_primary),
+        ),
+    ]));
+
+    // Recurring schedule
+    if let Ok(Some(schedule)) = app.store.get_task_schedule(&task.id) {
+        let mut spec = schedule.spec.clone();
+        if schedule.auto_launch {
+            spec.push_str(" (auto-launch)");
+        }
+        let when = |ts: &Option<String>| {
+            ts.as_deref()
+                .map_or_else(|| "never".to_string(), crate::scheduler::format_local)
+        };
+        for (label, value) in [
+            ("  Schedule: ", spec),
+            ("  Last run: ", when(&schedule.last_run_at)),
+            ("  Next run: ", when(&schedule.next_run_at)),
+        ] {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    label,
+                    Style::default()
+                        .fg(theme.text_secondary)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(value, Style::default().fg(theme.text_primary)),
+            ]));
+        }
+    }
+
+    // Verification gate result
+    if let Ok(Some(verification)) = app.store.latest_task_verification(&task.id) {
+        let (result, color) = match verification.failed_command.as_deref() {
+            None => ("passed".to_string(), theme.status_ci_passed),
+            Some(command) => (format!("failed: {command}"), theme.status_ci_failed),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                "  Verification: ",
+                Style::default()
+                    .fg(theme.text_secondary)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(result, Style::default().fg(color)),
+            Span::styled(
+                format!(
+                    " ({})",
+                    crate::scheduler::format_local(&verification.created_at)
+                ),
+                Style::default().fg(theme.text_secondary),
+            ),
+        ]));
+    }
+
+    // CI fix loop attempts
+    let ci_fixes = app.store.list_ci_fix_attempts(&task.id).unwrap_or_default();
+    if let Some(last) = ci_fixes.last() {
+        lines.push(Line::from(vec![
+            Span::styled(
+                "  CI fixes: ",
+                Style::default()
+                    .fg(theme.text_secondary)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    "{}/{} (last: {})",
+                    ci_fixes.len(),
+                    app.config.ci_fix.max_attempts,
+                    last.outcome
+                ),
+                Style::default().fg(theme.text_primary),
+            ),
+        ]));
+    }
+
+    // Conflict resolution attempts
+    let conflict_fixes = app
+        .store
+        .list_conflict_attempts(&task.id)
+        .unwrap_or_default();
+    if let Some(last) = conflict_fixes.last() {
+        lines.push(Line::from(vec![
+            Span::styled(
+                "  Conflict fixes: ",
+                Style::default()
+                    .fg(theme.text_secondary)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    "{}/{} (last: {} via {})",
+                    conflict_fixes.len(),
+                    app.config.conflict_resolution.max_attempts,
+                    last.outcome,
+                    last.strategy
+                ),
+                Style::default().fg(theme.text_primary),
+            ),
+        ]));
+    }
+
     // Base (PR target branch)
     if let Some(ref base) = task.base {
         lines.push(Line::from(vec![
@@ -719,10 +840,156 @@ pub(super) fn draw_task_details_panel(frame: &mut Frame, app: &App) {
             Style::default().fg(theme.text_secondary),
         )));
         for (i, st) in subtasks.iter().enumerate() {
+            let mut spans = vec![
+                Span::raw("  "),
+                Span::styled(st.status.symbol(), theme.task_status_style(st.status)),
+                Span::styled(
+                    format!(" {}. {}", i + 1, st.title),
+                    Style::default().fg(theme.text_primary),
+                ),
+            ];
+            if let (Some(started), Some(completed)) = (&st.started_at, &st.completed_at)
+                && let Some(duration) = attempt_duration(started, completed)
+            {
+                spans.push(Span::styled(
+                    format!("  {duration}"),
+                    Style::default().fg(theme.text_secondary),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+
+    // Attempt history (one row per retry)
+    let attempts = app.store.list_task_attempts(&task.id).unwrap_or_default();
+    if !attempts.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("  Attempts ({})", attempts.len()),
+            Style::default()
+                .fg(theme.accent_secondary)
+                .add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Line::from(Span::styled(
+            "  ─".to_string() + &"─".repeat(inner.width.saturating_sub(4) as usize),
+            Style::default().fg(theme.text_secondary),
+        )));
+        for attempt in &attempts {
+            let mut parts = vec![format!("  #{} {}", attempt.attempt, attempt.outcome)];
+            parts.extend(
+                attempt
+                    .started_at
+                    .as_deref()
+                    .and_then(|started| attempt_duration(started, &attempt.finished_at)),
+            );
+            if attempt.input_tokens + attempt.output_tokens > 0 {
+                parts.push(format!(
+                    "{} in / {} out",
+                    format_tokens(attempt.input_tokens),
+                    format_tokens(attempt.output_tokens)
+                ));
+            }
+            parts.extend(attempt.branch.clone());
+            if let Some(ref session_id) = attempt.session_id {
+                parts.push(format!(
+                    "session {}",
+                    &session_id[..8.min(session_id.len())]
+                ));
+            }
+            let summary = parts.join(" · ");
             lines.push(Line::from(Span::styled(
-                format!("  {}. {}", i + 1, st.title),
+                summary,
                 Style::default().fg(theme.text_primary),
             )));
+            if let Some(first) = attempt
+                .failure_context
+                .as_deref()
+                .and_then(|c| c.lines().find(|l| !l.trim().is_empty()))
+            {
+                lines.push(Line::from(Span::styled(
+                    format!("     {first}"),
+                    Style::default().fg(theme.text_secondary),
+                )));
+            }
+        }
+    }
+
+    // Follow-up prompts sent from the dashboard or `claustre send`
+    let messages = app.store.list_task_messages(&task.id).unwrap_or_default();
+    if !messages.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("  Messages ({})", messages.len()),
+            Style::default()
+                .fg(theme.accent_secondary)
+                .add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Line::from(Span::styled(
+            "  ─".to_string() + &"─".repeat(inner.width.saturating_sub(4) as usize),
+            Style::default().fg(theme.text_secondary),
+        )));
+        for message in &messages {
+            let sent = chrono::DateTime::parse_from_rfc3339(&message.created_at).map_or_else(
+                |_| message.created_at.clone(),
+                |t| {
+                    t.with_timezone(&chrono::Local)
+                        .format("%b %d %H:%M")
+                        .to_string()
+                },
+            );
+            let mut spans = vec![Span::styled(
+                format!("  {sent}  "),
+                Style::default().fg(theme.text_secondary),
+            )];
+            if message.delivered_at.is_none() {
+                spans.push(Span::styled(
+                    "(queued) ",
+                    Style::default().fg(theme.accent_tertiary),
+                ));
+            }
+            spans.push(Span::styled(
+                message.body.clone(),
+                Style::default().fg(theme.text_primary),
+            ));
+            lines.push(Line::from(spans));
+        }
+    }
+
+    // Every recorded change, oldest first
+    let events = app.store.list_task_events(&task.id).unwrap_or_default();
+    if !events.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("  Timeline ({})", events.len()),
+            Style::default()
+                .fg(theme.accent_secondary)
+                .add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Line::from(Span::styled(
+            "  ─".to_string() + &"─".repeat(inner.width.saturating_sub(4) as usize),
+            Style::default().fg(theme.text_secondary),
+        )));
+        for event in &events {
+            let at = chrono::DateTime::parse_from_rfc3339(&event.created_at).map_or_else(
+                |_| event.created_at.clone(),
+                |t| {
+                    t.with_timezone(&chrono::Local)
+                        .format("%b %d %H:%M:%S")
+                        .to_string()
+                },
+            );
+            let detail = event.detail.lines().next().unwrap_or_default();
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {at}  {:<6} ", event.source.as_str()),
+                    Style::default().fg(theme.text_secondary),
+                ),
+                Span::styled(
+                    format!("{:<11} ", event.kind.as_str()),
+                    Style::default().fg(theme.accent_primary),
+                ),
+                Span::styled(detail.to_string(), Style::default().fg(theme.text_primary)),
+            ]));
         }
     }
 
@@ -732,6 +999,405 @@ pub(super) fn draw_task_details_panel(frame: &mut Frame, app: &App) {
     frame.render_widget(paragraph, inner);
 }
 
+/// Human-readable time between two RFC 3339 timestamps (`1h 05m`, `23m`, `40s`).
+fn attempt_duration(started: &str, finished: &str) -> Option<String> {
+    let started = chrono::DateTime::parse_from_rfc3339(started).ok()?;
+    let finished = chrono::DateTime::parse_from_rfc3339(finished).ok()?;
+    let secs = (finished - started).num_seconds().max(0);
+    Some(match secs {
+        s if s >= 3600 => format!("{}h {:02}m", s / 3600, (s % 3600) / 60),
+        s if s >= 60 => format!("{}m", s / 60),
+        s => format!("{s}s"),
+    })
+}
+
+pub(super) fn draw_retry_dialog(frame: &mut Frame, app: &App) {
+    let Some(retry) = app.retry.as_ref() else {
+        return;
+    };
+    let theme = &app.theme;
+    let inner = render_modal(
+        frame,
+        " Retry Task ",
+        Style::default().fg(theme.accent_primary),
+        70,
+        10,
+    );
+    if inner.height < 3 {
+        return;
+    }
+    let dim = Style::default().fg(theme.form_dim);
+    let highlight = Style::default().fg(theme.form_highlight);
+    let label = Style::default().fg(theme.text_primary);
+    let include = if retry.include_failure {
+        "[x] include CI failure logs and PR review feedback"
+    } else {
+        "[ ] include CI failure logs and PR review feedback"
+    };
+    let lines = vec![
+        Line::from(vec![
+            Span::styled(format!(" {} ", retry.title), highlight),
+            Span::styled(format!("({})", retry.status), dim),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(" Note: ", label),
+            Span::raw(format_with_cursor(&app.input_buffer, app.input_cursor)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(format!(" {include}"), label)),
+    ];
+    let body = Rect::new(inner.x, inner.y, inner.width, inner.height - 1);
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), body);
+    render_hints(
+        frame,
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+        &[
+            ("  Enter", ":retry  "),
+            ("Tab", ":toggle context  "),
+            ("Esc", ":cancel"),
+        ],
+        highlight,
+        dim,
+    );
+}
+
+pub(super) fn draw_message_dialog(frame: &mut Frame, app: &App) {
+    let Some(target) = app.message.as_ref() else {
+        return;
+    };
+    let theme = &app.theme;
+    let inner = render_modal(
+        frame,
+        " Message Session ",
+        Style::default().fg(theme.accent_primary),
+        70,
+        8,
+    );
+    if inner.height < 3 {
+        return;
+    }
+    let dim = Style::default().fg(theme.form_dim);
+    let highlight = Style::default().fg(theme.form_highlight);
+    let label = Style::default().fg(theme.text_primary);
+    let lines = vec![
+        Line::from(Span::styled(format!(" {} ", target.title), highlight)),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(" Prompt: ", label),
+            Span::raw(format_with_cursor(&app.input_buffer, app.input_cursor)),
+        ]),
+    ];
+    let body = Rect::new(inner.x, inner.y, inner.width, inner.height - 1);
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), body);
+    render_hints(
+        frame,
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+        &[("  Enter", ":send  "), ("Esc", ":cancel")],
+        highlight,
+        dim,
+    );
+}
+
+pub(super) fn draw_fanout_launch_dialog(frame: &mut Frame, app: &App) {
+    let Some(launch) = app.fanout_launch.as_ref() else {
+        return;
+    };
+    let theme = &app.theme;
+    let variants = &app.config.fanout.variants;
+    let inner = render_modal(
+        frame,
+        " Fan Out Task ",
+        Style::default().fg(theme.accent_primary),
+        60,
+        variants.len() as u16 + 6,
+    );
+    if inner.height < 3 {
+        return;
+    }
+    let dim = Style::default().fg(theme.form_dim);
+    let highlight = Style::default().fg(theme.form_highlight);
+    let label = Style::default().fg(theme.text_primary);
+    let claude = &app.config.claude;
+    let mut lines = vec![
+        Line::from(Span::styled(format!(" {}", launch.title), highlight)),
+        Line::from(""),
+    ];
+    for (i, (variant, selected)) in variants.iter().zip(&launch.selected).enumerate() {
+        let model = variant.model.as_deref().unwrap_or(&claude.model);
+        let effort = variant.effort.as_deref().unwrap_or(&claude.effort);
+        lines.push(Line::from(vec![
+            Span::styled(
+                if i == launch.index { "\u{25b8} " } else { "  " },
+                Style::default().fg(theme.selection_indicator),
+            ),
+            Span::styled(if *selected { "[x] " } else { "[ ] " }, label),
+            Span::styled(format!("{model} "), label),
+            Span::styled(format!("({effort})"), dim),
+        ]));
+    }
+    let body = Rect::new(inner.x, inner.y, inner.width, inner.height - 1);
+    frame.render_widget(Paragraph::new(lines), body);
+    render_hints(
+        frame,
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+        &[
+            ("  Enter", ":launch  "),
+            ("Space", ":toggle  "),
+            ("Esc", ":cancel"),
+        ],
+        highlight,
+        dim,
+    );
+}
+
+pub(super) fn draw_fanout_compare(frame: &mut Frame, app: &App) {
+    let Some(compare) = app.fanout_compare.as_ref() else {
+        return;
+    };
+    let theme = &app.theme;
+    let inner = render_modal(
+        frame,
+        &format!(" Compare Variants: {} ", compare.title),
+        Style::default().fg(theme.accent_primary),
+        90,
+        24,
+    );
+    if inner.height < 4 {
+        return;
+    }
+    let dim = Style::default().fg(theme.form_dim);
+    let highlight = Style::default().fg(theme.form_highlight);
+    let label = Style::default().fg(theme.text_primary);
+    let claude = &app.config.claude;
+    let rows = app.fanout_rows();
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "   {:<3}{:<22}{:<13}{:<20}{:<16}{}",
+            "#", "variant", "status", "diff", "tokens", "tests"
+        ),
+        dim,
+    ))];
+    if rows.is_empty() {
+        lines.push(Line::from(Span::styled("   No variants left", dim)));
+    }
+    for (i, (variant, task)) in rows.iter().enumerate() {
+        let session = task
+            .session_id
+            .as_deref()
+            .and_then(|id| app.store.get_session(id).ok());
+        let (status, diff) = match &session {
+            Some(s) => (
+                s.claude_status.to_string(),
+                format!(
+                    "{} files +{} -{}",
+                    s.files_changed, s.lines_added, s.lines_removed
+                ),
+            ),
+            None => ("queued".to_string(), "-".to_string()),
+        };
+        let tokens = format!(
+            "{} in / {} out",
+            format_tokens(task.input_tokens),
+            format_tokens(task.output_tokens)
+        );
+        let (tests, tests_color) = match variant.test_status {
+            Some(VariantTestStatus::Passed) => ("passed", theme.status_ci_passed),
+            Some(VariantTestStatus::Failed) => ("failed", theme.status_ci_failed),
+            Some(VariantTestStatus::Running) => ("running", theme.status_ci_running),
+            None => ("-", theme.form_dim),
+        };
+        let selected = i == compare.index;
+        lines.push(Line::from(vec![
+            Span::styled(
+                if selected { " \u{25b8} " } else { "   " },
+                Style::default().fg(theme.selection_indicator),
+            ),
+            Span::styled(
+                format!(
+                    "{:<3}{:<22}{:<13}{:<20}{:<16}",
+                    variant.variant,
+                    variant.label(&claude.model, &claude.effort),
+                    status,
+                    diff,
+                    tokens
+                ),
+                if selected {
+                    label.add_modifier(Modifier::BOLD)
+                } else {
+                    label
+                },
+            ),
+            Span::styled(tests, Style::default().fg(tests_color)),
+        ]));
+    }
+
+    if let Some(output) = rows
+        .get(compare.index)
+        .and_then(|(v, _)| v.test_output.as_deref())
+    {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(" Test output (tail):", dim)));
+        let room = (inner.height as usize).saturating_sub(lines.len() + 1);
+        let tail: Vec<&str> = output.lines().collect();
+        for line in &tail[tail.len().saturating_sub(room)..] {
+            lines.push(Line::from(Span::styled(format!(" {line}"), dim)));
+        }
+    }
+
+    let body = Rect::new(inner.x, inner.y, inner.width, inner.height - 1);
+    frame.render_widget(Paragraph::new(lines), body);
+    let hints_area = Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1);
+    let n = rows.get(compare.index).map_or(0, |(v, _)| v.variant);
+    match compare.confirm {
+        Some(VariantConfirm::Promote) => {
+            let prompt = format!("  Promote #{n} and tear down the others? ");
+            render_hints(
+                frame,
+                hints_area,
+                &[(&prompt, ""), ("y", ":yes  "), ("any", ":no")],
+                highlight,
+                dim,
+            );
+        }
+        Some(VariantConfirm::Discard) => {
+            let prompt = format!("  Discard #{n}? ");
+            render_hints(
+                frame,
+                hints_area,
+                &[(&prompt, ""), ("y", ":yes  "), ("any", ":no")],
+                highlight,
+                dim,
+            );
+        }
+        None => render_hints(
+            frame,
+            hints_area,
+            &[
+                ("  Enter", ":promote  "),
+                ("t", ":run tests  "),
+                ("d", ":diff  "),
+                ("x", ":discard  "),
+                ("Esc", ":close"),
+            ],
+            highlight,
+            dim,
+        ),
+    }
+}
+
+pub(super) fn draw_inbox_overlay(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let inner = render_modal(
+        frame,
+        " Prompt Inbox ",
+        Style::default().fg(theme.status_paused),
+        80,
+        30,
+    );
+    if inner.height < 3 || inner.width < 20 {
+        return;
+    }
+
+    let dim = Style::default().fg(theme.form_dim);
+    let highlight = Style::default().fg(theme.form_highlight);
+    let entries = app.inbox_entries();
+    let list_height = inner.height.saturating_sub(2) as usize;
+
+    let mut blocks: Vec<Vec<Line<'_>>> = Vec::with_capacity(entries.len());
+    for (i, entry) in entries.iter().enumerate() {
+        let selected = i == app.inbox_index;
+        let (symbol, kind_label, color) = match entry.kind {
+            PromptKind::Permission => ("\u{23f8}", "permission", theme.status_paused),
+            PromptKind::Question => ("\u{23f3}", "question", theme.status_waiting),
+        };
+        let title_style = if selected {
+            Style::default()
+                .fg(theme.text_primary)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text_primary)
+        };
+        let mut lines = vec![Line::from(vec![
+            Span::styled(
+                if selected { "\u{25b8} " } else { "  " },
+                Style::default().fg(theme.selection_indicator),
+            ),
+            Span::styled(format!("{symbol} "), Style::default().fg(color)),
+            Span::styled(entry.tab_label.clone(), title_style),
+            Span::styled(format!("  {kind_label}"), dim),
+        ])];
+        if entry.prompt.is_empty() {
+            lines.push(Line::from(Span::styled("    (prompt text not found)", dim)));
+        }
+        for prompt_line in entry.prompt.lines() {
+            lines.push(Line::from(Span::styled(
+                format!("    {prompt_line}"),
+                Style::default().fg(theme.text_secondary),
+            )));
+        }
+        if !entry.options.is_empty() {
+            let mut spans = vec![Span::raw("    ")];
+            for (n, option) in entry.options.iter().enumerate() {
+                if n > 0 {
+                    spans.push(Span::styled(" \u{b7} ", dim));
+                }
+                spans.push(Span::styled(format!("{} ", n + 1), highlight));
+                spans.push(Span::styled(
+                    option.clone(),
+                    Style::default().fg(theme.text_primary),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+        blocks.push(lines);
+    }
+
+    if blocks.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Span::styled("  No sessions are waiting for input", dim)),
+            Rect::new(inner.x, inner.y, inner.width, 1),
+        );
+    } else {
+        // Scroll so the selected entry is fully visible.
+        let selected = app.inbox_index.min(blocks.len() - 1);
+        let mut first = selected;
+        let mut used = blocks[selected].len();
+        while first > 0 && used + blocks[first - 1].len() < list_height {
+            first -= 1;
+            used += blocks[first].len() + 1;
+        }
+        let mut lines: Vec<Line<'_>> = Vec::new();
+        for (i, block) in blocks.into_iter().enumerate().skip(first) {
+            if i > first {
+                lines.push(Line::from(""));
+            }
+            lines.extend(block);
+        }
+        frame.render_widget(
+            Paragraph::new(lines),
+            Rect::new(inner.x, inner.y, inner.width, list_height as u16),
+        );
+    }
+
+    render_hints(
+        frame,
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+        &[
+            ("  y", ":approve  "),
+            ("a", ":always  "),
+            ("n", ":deny  "),
+            ("1-9", ":answer  "),
+            ("Y", ":approve identical  "),
+            ("Enter", ":open  "),
+            ("Esc", ":close"),
+        ],
+        highlight,
+        dim,
+    );
+}
+
 pub(super) fn draw_help_overlay(frame: &mut Frame, app: &App) {
     let theme = &app.theme;
     let inner = render_modal(
@@ -930,3 +1596,90 @@ pub(super) fn draw_configure_wizard(frame: &mut Frame, app: &App) {
     frame.render_widget(block, overlay_area);
     frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
 }
+
+pub(super) fn draw_template_picker(frame: &mut Frame, app: &App) {
+    let Some(picker) = app.template_picker.as_ref() else {
+        return;
+    };
+    let theme = &app.theme;
+    let inner = render_modal(
+        frame,
+        " Task Templates ",
+        Style::default().fg(theme.accent_primary),
+        64,
+        (picker.templates.len() as u16 + 4).max(8),
+    );
+    if inner.height < 3 {
+        return;
+    }
+    let dim = Style::default().fg(theme.form_dim);
+    let highlight = Style::default().fg(theme.form_highlight);
+    let body = Rect::new(inner.x, inner.y, inner.width, inner.height - 1);
+
+    if let Some(var_index) = picker.var_index {
+        let template = &picker.templates[picker.index];
+        let var = &template.vars[var_index];
+        let lines = vec![
+            Line::from(vec![
+                Span::styled(format!(" {} ", template.name), highlight),
+                Span::styled(
+                    format!("variable {}/{}", var_index + 1, template.vars.len()),
+                    dim,
+                ),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(
+                    format!(" {}: ", var.name),
+                    Style::default().fg(theme.text_primary),
+                ),
+                Span::raw(format_with_cursor(&app.input_buffer, app.input_cursor)),
+            ]),
+        ];
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), body);
+        render_hints(
+            frame,
+            Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+            &[("  Enter", ":next  "), ("Esc", ":back")],
+            highlight,
+            dim,
+        );
+        return;
+    }
+
+    let items: Vec<ListItem<'_>> = picker
+        .templates
+        .iter()
+        .enumerate()
+        .map(|(i, template)| {
+            let selected = i == picker.index;
+            let name_style = if selected {
+                Style::default()
+                    .fg(theme.text_primary)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.text_primary)
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    if selected { "\u{25b8} " } else { "  " },
+                    Style::default().fg(theme.selection_indicator),
+                ),
+                Span::styled(template.name.clone(), name_style),
+                Span::styled(format!("  {}", template.about), dim),
+            ]))
+        })
+        .collect();
+    frame.render_widget(List::new(items), body);
+    render_hints(
+        frame,
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+        &[
+            ("  j/k", ":move  "),
+            ("Enter", ":use  "),
+            ("Esc", ":cancel"),
+        ],
+        highlight,
+        dim,
+    );
+}
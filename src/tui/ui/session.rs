@@ -1,14 +1,15 @@
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
-    style::Style,
-    widgets::{Block, Borders},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem},
 };
 
-use crate::pty::{LayoutNode, PaneId, SplitDirection, TerminalWidget};
+use crate::pty::{LayoutNode, PaneId, SearchHighlight, SplitDirection, TerminalWidget};
 
 use super::super::app::{App, Tab};
-use super::super::form::render_hints;
+use super::super::form::{render_hints, render_modal};
 use super::tab_bar::draw_tab_bar;
 
 /// Draw the session terminal view with a dynamic pane layout tree.
@@ -30,7 +31,7 @@ pub(super) fn draw_session_tab(frame: &mut Frame, app: &App) {
     }) = app.tabs.get(app.active_tab)
     {
         render_layout_node(
-            &terminals.layout,
+            &terminals.visible_layout(),
             terminals,
             label,
             &app.theme,
@@ -39,7 +40,73 @@ pub(super) fn draw_session_tab(frame: &mut Frame, app: &App) {
         );
     }
 
-    // Hint bar
+    if let Some(index) = app.pane_picker {
+        draw_pane_picker(frame, app, index);
+    }
+
+    // Hint bar (copy-mode keys, or the search prompt while a search is open)
+    if let Some(Tab::Session { terminals, .. }) = app.tabs.get(app.active_tab)
+        && let Some(copy_mode) = &terminals.copy_mode
+    {
+        let hints: &[(&str, &str)] = if copy_mode.anchor.is_some() {
+            &[
+                ("  COPY", "  "),
+                ("hjkl/w/b/e", ": extend  "),
+                ("v/V/Ctrl+V", ": char/line/block  "),
+                ("y/Enter", ": yank  "),
+                ("Esc", ": clear"),
+            ]
+        } else {
+            &[
+                ("  COPY", "  "),
+                ("hjkl/w/b/e", ": move  "),
+                ("g/G", ": top/bottom  "),
+                ("v/V/Ctrl+V", ": select  "),
+                ("Y", ": yank line  "),
+                ("q/Esc", ": exit"),
+            ]
+        };
+        render_hints(
+            frame,
+            outer[2],
+            hints,
+            Style::default().fg(app.theme.accent_secondary),
+            Style::default(),
+        );
+        return;
+    }
+    if let Some(Tab::Session { terminals, .. }) = app.tabs.get(app.active_tab)
+        && let Some(search) = &terminals.search
+    {
+        let (index, total) = search.position;
+        let prompt = if search.editing {
+            format!("{}▏  ", search.query)
+        } else if total == 0 {
+            format!("{}  [no matches]  ", search.query)
+        } else {
+            format!("{}  [{index}/{total}]  ", search.query)
+        };
+        let hints: &[(&str, &str)] = if search.editing {
+            &[("  /", &prompt), ("Enter", ": done  "), ("Esc", ": cancel")]
+        } else {
+            &[
+                ("  /", &prompt),
+                ("n/N", ": older/newer  "),
+                ("y", ": copy match  "),
+                ("Y", ": copy block  "),
+                ("/", ": new search  "),
+                ("Esc", ": close"),
+            ]
+        };
+        render_hints(
+            frame,
+            outer[2],
+            hints,
+            Style::default().fg(app.theme.accent_secondary),
+            Style::default(),
+        );
+        return;
+    }
     render_hints(
         frame,
         outer[2],
@@ -49,13 +116,71 @@ pub(super) fn draw_session_tab(frame: &mut Frame, app: &App) {
             ("Ctrl+J/K", ": switch tab  "),
             ("Ctrl+G", ": scroll bottom  "),
             ("Ctrl+R/B", ": split  "),
+            ("Ctrl+N", ": split with  "),
+            ("Alt+Arrows", ": resize  "),
+            ("Alt+Z", ": zoom  "),
             ("Ctrl+W", ": close  "),
+            ("Ctrl+F", ": search  "),
+            ("Ctrl+S", ": copy mode  "),
         ],
         Style::default().fg(app.theme.accent_secondary),
         Style::default(),
     );
 }
 
+/// Draw the split-with-command picker over the panes.
+fn draw_pane_picker(frame: &mut Frame, app: &App, index: usize) {
+    let theme = &app.theme;
+    let entries = app.pane_picker_entries();
+    let inner = render_modal(
+        frame,
+        " Split With ",
+        Style::default().fg(theme.accent_primary),
+        64,
+        (entries.len() as u16 + 3).max(6),
+    );
+    if inner.height < 2 {
+        return;
+    }
+    let dim = Style::default().fg(theme.form_dim);
+    let items: Vec<ListItem<'_>> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, (name, cmd))| {
+            let selected = i == index;
+            let name_style = if selected {
+                Style::default()
+                    .fg(theme.text_primary)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.text_primary)
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    if selected { "\u{25b8} " } else { "  " },
+                    Style::default().fg(theme.selection_indicator),
+                ),
+                Span::styled((*name).to_string(), name_style),
+                Span::styled(format!("  {cmd}"), dim),
+            ]))
+        })
+        .collect();
+    let body = Rect::new(inner.x, inner.y, inner.width, inner.height - 1);
+    frame.render_widget(List::new(items), body);
+    render_hints(
+        frame,
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+        &[
+            ("  j/k", ":move  "),
+            ("Enter/r", ":split right  "),
+            ("b", ":split down  "),
+            ("Esc", ":cancel"),
+        ],
+        Style::default().fg(theme.form_highlight),
+        dim,
+    );
+}
+
 /// Recursively render a layout node tree into the given area.
 fn render_layout_node(
     node: &LayoutNode,
@@ -115,12 +240,16 @@ fn render_single_pane(
         terminals.label(id).to_string()
     };
 
+    let copy_mode = terminals.copy_mode.as_ref().filter(|c| c.pane == id);
     let scrollback = term.scrollback();
-    let title = if scrollback > 0 {
-        format!(" {base_label} [+{scrollback} lines] ")
+    let scrollback_tag = if scrollback > 0 {
+        format!("[+{scrollback} lines] ")
     } else {
-        format!(" {base_label} ")
+        String::new()
     };
+    let copy_tag = if copy_mode.is_some() { "[COPY] " } else { "" };
+    let zoom_tag = if terminals.zoomed { "[ZOOM] " } else { "" };
+    let title = format!(" {base_label} {scrollback_tag}{zoom_tag}{copy_tag}");
 
     let block = Block::default()
         .title(title)
@@ -134,11 +263,36 @@ fn render_single_pane(
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    let sel = terminals.selection.as_ref().filter(|s| s.pane == id);
+    // Copy mode owns the pane's selection and draws its own cursor in place
+    // of the terminal's.
+    let copy_selection = copy_mode.and_then(crate::pty::CopyMode::selection);
+    let sel = match copy_mode {
+        Some(_) => copy_selection.as_ref(),
+        None => terminals.selection.as_ref().filter(|s| s.pane == id),
+    };
+    let copy_cursor = copy_mode.and_then(|c| {
+        let row = c.cursor.0.checked_sub(term.viewport_top())?;
+        Some((u16::try_from(row).ok()?, c.cursor.1))
+    });
+    let search = terminals
+        .search
+        .as_ref()
+        .filter(|s| s.pane == id)
+        .map(|search| SearchHighlight {
+            search,
+            current: search.current.and_then(|m| {
+                let row = m.line.checked_sub(term.viewport_top())?;
+                let row = u16::try_from(row).ok()?;
+                (row < term.screen().size().0).then_some((row, m.start_col, m.end_col))
+            }),
+        });
 
     frame.render_widget(
-        TerminalWidget::new(term.screen(), is_focused)
+        TerminalWidget::new(term.screen(), is_focused && copy_mode.is_none())
             .with_selection(sel)
+            .with_viewport_top(term.viewport_top())
+            .with_copy_cursor(copy_cursor)
+            .with_search(search)
             .with_scrollback_offset(term.scrollback()),
         inner,
     );
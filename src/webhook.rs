@@ -0,0 +1,575 @@
+//! Outbound webhooks for task lifecycle events.
+//!
+//! Unlike the fire-and-forget `webhook` notification backend in
+//! [`crate::notify`], deliveries configured under `[[webhooks]]` are
+//! persisted in the store's `webhook_deliveries` queue before any network
+//! I/O happens. A delivery is removed once the endpoint answers with a 2xx
+//! status; otherwise it is retried with exponential backoff (surviving
+//! restarts) until [`MAX_ATTEMPTS`] is reached and it is marked `failed`.
+//!
+//! Producers call [`enqueue`]; the queue is drained by [`deliver_due`],
+//! which runs from `claustre webhooks flush` (spawned detached by hooks)
+//! and periodically from the TUI.
+
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::notify::NotifyEvent;
+use crate::store::{Project, Store, Task, WebhookDelivery};
+
+/// Give up on a delivery after this many failed attempts.
+pub const MAX_ATTEMPTS: i64 = 8;
+
+/// Maximum deliveries attempted per [`deliver_due`] call.
+const DELIVERY_BATCH: usize = 50;
+
+/// How long a claimed delivery is reserved for one flusher. Longer than
+/// `post`'s own timeout, so only a crashed flusher lets the lease lapse.
+const CLAIM_LEASE_SECS: i64 = 60;
+
+/// Body format sent to the endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    /// Structured JSON payload (see [`json_payload`]).
+    #[default]
+    Json,
+    /// Slack-compatible `{"text": ...}` message (also accepted by
+    /// Mattermost, Discord's `/slack` endpoint, and similar services).
+    Slack,
+}
+
+/// A single `[[webhooks]]` entry.
+///
+/// ```toml
+/// [[webhooks]]
+/// url = "https://hooks.slack.com/services/T000/B000/XXXX"
+/// format = "slack"
+/// events = ["in_review", "ci_failed", "done"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Events to deliver. Empty means every event.
+    #[serde(default)]
+    pub events: Vec<NotifyEvent>,
+    /// Extra `Name: value` headers (e.g. authorization).
+    #[serde(default)]
+    pub headers: Vec<String>,
+}
+
+impl WebhookConfig {
+    pub fn accepts(&self, event: NotifyEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// Outcome of a [`deliver_due`] pass.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DeliveryReport {
+    pub delivered: usize,
+    pub retrying: usize,
+    pub failed: usize,
+}
+
+/// Queue one delivery per matching webhook for `event` on `task_id`.
+/// Returns the number of deliveries queued.
+pub fn enqueue(
+    store: &Store,
+    webhooks: &[WebhookConfig],
+    event: NotifyEvent,
+    task_id: &str,
+) -> Result<usize> {
+    let matching: Vec<&WebhookConfig> = webhooks.iter().filter(|w| w.accepts(event)).collect();
+    if matching.is_empty() {
+        return Ok(0);
+    }
+
+    let task = store.get_task(task_id)?;
+    let project = store.get_project(&task.project_id)?;
+    let now = chrono::Utc::now();
+    for webhook in &matching {
+        let payload = match webhook.format {
+            WebhookFormat::Json => json_payload(event, &task, &project, now),
+            WebhookFormat::Slack => slack_payload(event, &task, &project),
+        };
+        store.enqueue_webhook_delivery(
+            &webhook.url,
+            &webhook.headers,
+            event.as_str(),
+            Some(&task.id),
+            &payload.to_string(),
+        )?;
+    }
+    Ok(matching.len())
+}
+
+/// Attempt every delivery whose retry time has come.
+pub fn deliver_due(store: &Store) -> Result<DeliveryReport> {
+    let due =
+        store.list_due_webhook_deliveries(&format_timestamp(chrono::Utc::now()), DELIVERY_BATCH)?;
+    let mut report = DeliveryReport::default();
+    for delivery in due {
+        // Hooks and the TUI flush concurrently; only the claimant posts.
+        // Earlier posts in the batch take time, so each claim gets its own
+        // lease starting now.
+        let now = chrono::Utc::now();
+        let lease_until = format_timestamp(now + chrono::Duration::seconds(CLAIM_LEASE_SECS));
+        if !store.claim_webhook_delivery(&delivery.id, &format_timestamp(now), &lease_until)? {
+            continue;
+        }
+        match post(&delivery) {
+            Ok(()) => {
+                store.delete_webhook_delivery(&delivery.id)?;
+                report.delivered += 1;
+            }
+            Err(e) => {
+                let error = format!("{e:#}");
+                let attempts = delivery.attempts + 1;
+                let retry_at = (attempts < MAX_ATTEMPTS)
+                    .then(|| format_timestamp(now + retry_delay(attempts)));
+                tracing::warn!(
+                    url = %delivery.url,
+                    attempts,
+                    "webhook delivery failed: {error}"
+                );
+                store.record_webhook_failure(&delivery.id, &error, retry_at.as_deref())?;
+                if retry_at.is_some() {
+                    report.retrying += 1;
+                } else {
+                    report.failed += 1;
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Spawn `claustre webhooks flush` as a detached process so hooks return
+/// immediately. Errors are logged, never surfaced.
+pub fn spawn_flush() {
+    match Command::new("claustre")
+        .args(["webhooks", "flush"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(_) => tracing::debug!("webhook flush spawned"),
+        Err(e) => tracing::warn!("webhook flush failed to spawn: {e}"),
+    }
+}
+
+/// Backoff before retry number `attempts` (1-based): 30s doubling, capped at 1h.
+pub fn retry_delay(attempts: i64) -> chrono::Duration {
+    let exponent = u32::try_from(attempts.saturating_sub(1).clamp(0, 16)).unwrap_or(16);
+    let secs = 30_i64.saturating_mul(1 << exponent).min(3600);
+    chrono::Duration::seconds(secs)
+}
+
+fn format_timestamp(ts: chrono::DateTime<chrono::Utc>) -> String {
+    ts.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Seconds between the task starting and completing (or `now` if still open).
+fn task_duration_secs(task: &Task, now: chrono::DateTime<chrono::Utc>) -> Option<i64> {
+    let started = chrono::DateTime::parse_from_rfc3339(task.started_at.as_deref()?).ok()?;
+    let ended = task
+        .completed_at
+        .as_deref()
+        .and_then(|c| chrono::DateTime::parse_from_rfc3339(c).ok())
+        .map_or(now, |c| c.with_timezone(&chrono::Utc));
+    Some(
+        (ended - started.with_timezone(&chrono::Utc))
+            .num_seconds()
+            .max(0),
+    )
+}
+
+/// Structured JSON body for `format = "json"`.
+pub fn json_payload(
+    event: NotifyEvent,
+    task: &Task,
+    project: &Project,
+    now: chrono::DateTime<chrono::Utc>,
+) -> serde_json::Value {
+    serde_json::json!({
+        "event": event.as_str(),
+        "timestamp": format_timestamp(now),
+        "task": {
+            "id": task.id,
+            "title": task.title,
+            "status": task.status.as_str(),
+            "mode": task.mode.as_str(),
+            "branch": task.branch,
+        },
+        "project": {
+            "id": project.id,
+            "name": project.name,
+        },
+        "pr_url": task.pr_url,
+        "tokens": {
+            "input": task.input_tokens,
+            "output": task.output_tokens,
+            "total": task.input_tokens + task.output_tokens,
+        },
+        "duration_secs": task_duration_secs(task, now),
+    })
+}
+
+/// Slack-style `{"text": ...}` body for `format = "slack"`.
+pub fn slack_payload(event: NotifyEvent, task: &Task, project: &Project) -> serde_json::Value {
+    let headline = match event {
+        NotifyEvent::InReview => "opened a PR",
+        NotifyEvent::Done => "is done",
+        NotifyEvent::CiFailed => "failed CI",
+        NotifyEvent::Conflict => "has merge conflicts",
+        NotifyEvent::RateLimited => "hit usage limits",
+        NotifyEvent::NeedsInput => "needs input",
+        NotifyEvent::Stuck => "looks stuck",
+    };
+    let mut text = format!("*{}* · {} {headline}", project.name, task.title);
+    if let Some(ref url) = task.pr_url {
+        let _ = write!(text, " (<{url}|PR>)");
+    }
+    let tokens = task.input_tokens + task.output_tokens;
+    if tokens > 0 {
+        let _ = write!(text, " — {tokens} tokens");
+    }
+    serde_json::json!({ "text": text })
+}
+
+/// POST a delivery's payload via `curl`, failing on non-2xx responses.
+fn post(delivery: &WebhookDelivery) -> Result<()> {
+    let mut cmd = Command::new("curl");
+    // Headers and body go over stdin so tokens and payloads never show up
+    // in `ps` output.
+    cmd.args(["-sS", "-f", "--max-time", "10", "-X", "POST", "-K", "-"]);
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to spawn curl")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let config =
+            crate::notify::curl_config(&delivery.headers, &delivery.payload, &delivery.url);
+        stdin
+            .write_all(config.as_bytes())
+            .context("failed to write webhook request to curl")?;
+    }
+    let output = wait_with_timeout(child, Duration::from_secs(15))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("curl failed: {}", stderr.trim());
+    }
+    Ok(())
+}
+
+/// `curl --max-time` bounds the request; this guards against curl itself hanging.
+fn wait_with_timeout(
+    mut child: std::process::Child,
+    timeout: Duration,
+) -> Result<std::process::Output> {
+    let start = std::time::Instant::now();
+    loop {
+        if child.try_wait()?.is_some() {
+            return child
+                .wait_with_output()
+                .context("failed to read curl output");
+        }
+        if start.elapsed() > timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("curl timed out after {}s", timeout.as_secs());
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead as _, BufReader, Read as _};
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::store::{PushMode, TaskMode, TaskStatus, WebhookDeliveryStatus};
+
+    /// Accept one HTTP request on a local port and answer with `status`.
+    /// The join handle yields the raw request (headers + body).
+    fn serve_once(status: u16) -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(len) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = len.trim().parse().unwrap();
+                }
+                let end = line == "\r\n";
+                request.push_str(&line);
+                if end {
+                    break;
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            request.push_str(&String::from_utf8(body).unwrap());
+            let response =
+                format!("HTTP/1.1 {status} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            request
+        });
+        (url, handle)
+    }
+
+    fn store_with_task() -> (Store, Task) {
+        let store = Store::open_in_memory().unwrap();
+        let project = store
+            .create_project("web", "/tmp/web", "main", true)
+            .unwrap();
+        let task = store
+            .create_task(
+                &project.id,
+                "fix login",
+                "",
+                TaskMode::Autonomous,
+                Some("fix-login"),
+                None,
+                PushMode::Pr,
+                false,
+            )
+            .unwrap();
+        store
+            .update_task_status(&task.id, TaskStatus::Working)
+            .unwrap();
+        store.set_task_usage(&task.id, 1200, 300).unwrap();
+        store
+            .update_task_pr_url(&task.id, "https://github.com/o/r/pull/7")
+            .unwrap();
+        let task = store.get_task(&task.id).unwrap();
+        (store, task)
+    }
+
+    fn webhook(url: &str, format: WebhookFormat, events: Vec<NotifyEvent>) -> WebhookConfig {
+        WebhookConfig {
+            url: url.to_string(),
+            format,
+            events,
+            headers: vec!["X-Claustre-Token: secret".to_string()],
+        }
+    }
+
+    #[test]
+    fn json_payload_carries_task_project_tokens_and_duration() {
+        let (store, mut task) = store_with_task();
+        let project = store.get_project(&task.project_id).unwrap();
+        task.started_at = Some("2026-01-01T00:00:00+00:00".into());
+        task.completed_at = Some("2026-01-01T00:02:30+00:00".into());
+
+        let payload = json_payload(NotifyEvent::Done, &task, &project, chrono::Utc::now());
+        assert_eq!(payload["event"], "done");
+        assert_eq!(payload["task"]["title"], "fix login");
+        assert_eq!(payload["task"]["branch"], "fix-login");
+        assert_eq!(payload["project"]["name"], "web");
+        assert_eq!(payload["pr_url"], "https://github.com/o/r/pull/7");
+        assert_eq!(payload["tokens"]["total"], 1500);
+        assert_eq!(payload["duration_secs"], 150);
+    }
+
+    #[test]
+    fn slack_payload_is_text_message() {
+        let (store, task) = store_with_task();
+        let project = store.get_project(&task.project_id).unwrap();
+        let payload = slack_payload(NotifyEvent::CiFailed, &task, &project);
+        let text = payload["text"].as_str().unwrap();
+        assert!(text.starts_with("*web* · fix login failed CI"));
+        assert!(text.contains("<https://github.com/o/r/pull/7|PR>"));
+        assert!(text.contains("1500 tokens"));
+    }
+
+    #[test]
+    fn enqueue_respects_event_filter() {
+        let (store, task) = store_with_task();
+        let hooks = vec![
+            webhook("http://a", WebhookFormat::Json, vec![NotifyEvent::CiFailed]),
+            webhook("http://b", WebhookFormat::Slack, vec![]),
+        ];
+        assert_eq!(
+            enqueue(&store, &hooks, NotifyEvent::Done, &task.id).unwrap(),
+            1
+        );
+        assert_eq!(
+            enqueue(&store, &hooks, NotifyEvent::CiFailed, &task.id).unwrap(),
+            2
+        );
+        let pending = store
+            .list_webhook_deliveries(WebhookDeliveryStatus::Pending)
+            .unwrap();
+        assert_eq!(pending.len(), 3);
+    }
+
+    #[test]
+    fn deliver_due_posts_to_local_listener() {
+        let (url, server) = serve_once(200);
+        let (store, task) = store_with_task();
+        let hooks = vec![webhook(&url, WebhookFormat::Json, vec![])];
+        enqueue(&store, &hooks, NotifyEvent::InReview, &task.id).unwrap();
+
+        let report = deliver_due(&store).unwrap();
+        assert_eq!(report.delivered, 1);
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /hook "));
+        assert!(request.contains("X-Claustre-Token: secret"));
+        let body = &request[request.find("\r\n\r\n").unwrap() + 4..];
+        let json: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(json["event"], "in_review");
+        assert_eq!(json["task"]["id"], task.id.as_str());
+
+        assert!(
+            store
+                .list_webhook_deliveries(WebhookDeliveryStatus::Pending)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn deliver_due_schedules_retry_on_server_error() {
+        let (url, server) = serve_once(500);
+        let (store, task) = store_with_task();
+        let hooks = vec![webhook(&url, WebhookFormat::Json, vec![])];
+        enqueue(&store, &hooks, NotifyEvent::CiFailed, &task.id).unwrap();
+
+        let report = deliver_due(&store).unwrap();
+        server.join().unwrap();
+        assert_eq!(report.retrying, 1);
+
+        let pending = store
+            .list_webhook_deliveries(WebhookDeliveryStatus::Pending)
+            .unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].attempts, 1);
+        assert!(pending[0].last_error.is_some());
+        // Not due again until the backoff elapses.
+        assert_eq!(deliver_due(&store).unwrap(), DeliveryReport::default());
+    }
+
+    #[test]
+    fn deliver_due_gives_up_after_max_attempts() {
+        let (url, server) = serve_once(500);
+        let store = Store::open_in_memory().unwrap();
+        let delivery = store
+            .enqueue_webhook_delivery(&url, &[], "done", None, "{}")
+            .unwrap();
+        for _ in 0..MAX_ATTEMPTS - 1 {
+            store
+                .record_webhook_failure(&delivery.id, "boom", Some("2000-01-01T00:00:00Z"))
+                .unwrap();
+        }
+
+        let report = deliver_due(&store).unwrap();
+        server.join().unwrap();
+        assert_eq!(report.failed, 1);
+        let failed = store.get_webhook_delivery(&delivery.id).unwrap();
+        assert_eq!(failed.status, WebhookDeliveryStatus::Failed);
+        assert_eq!(failed.attempts, MAX_ATTEMPTS);
+    }
+
+    #[test]
+    fn concurrent_flushers_post_once() {
+        let (url, server) = serve_once(200);
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("claustre.db");
+        let store = Store::open_at(&db_path).unwrap();
+        store.migrate().unwrap();
+        store
+            .enqueue_webhook_delivery(&url, &[], "done", None, "{}")
+            .unwrap();
+
+        let barrier = std::sync::Arc::new(std::sync::Barrier::new(2));
+        let spawn_flusher = || {
+            let barrier = std::sync::Arc::clone(&barrier);
+            let db_path = db_path.clone();
+            std::thread::spawn(move || {
+                let store = Store::open_at(&db_path).unwrap();
+                barrier.wait();
+                deliver_due(&store).unwrap()
+            })
+        };
+        let (first, second) = (spawn_flusher(), spawn_flusher());
+        let reports = [first.join().unwrap(), second.join().unwrap()];
+        server.join().unwrap();
+
+        // A second POST would hit the closed listener and show up as a retry.
+        assert_eq!(reports.iter().map(|r| r.delivered).sum::<usize>(), 1);
+        assert_eq!(reports.iter().map(|r| r.retrying).sum::<usize>(), 0);
+    }
+
+    #[test]
+    fn claimed_delivery_is_skipped_until_lease_expires() {
+        let store = Store::open_in_memory().unwrap();
+        let delivery = store
+            .enqueue_webhook_delivery("http://127.0.0.1:9/hook", &[], "done", None, "{}")
+            .unwrap();
+        let now = "2100-01-01T00:00:00Z";
+        let lease = "2100-01-01T00:01:00Z";
+        assert!(
+            store
+                .claim_webhook_delivery(&delivery.id, now, lease)
+                .unwrap()
+        );
+        assert!(
+            !store
+                .claim_webhook_delivery(&delivery.id, now, lease)
+                .unwrap()
+        );
+        assert!(
+            store
+                .list_due_webhook_deliveries(now, 10)
+                .unwrap()
+                .is_empty()
+        );
+        // After the lease lapses (the claimant crashed) the row is due again.
+        let due = store
+            .list_due_webhook_deliveries("2100-01-01T00:02:00Z", 10)
+            .unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].status, WebhookDeliveryStatus::InFlight);
+    }
+
+    #[test]
+    fn retry_delay_backs_off_and_caps() {
+        assert_eq!(retry_delay(1).num_seconds(), 30);
+        assert_eq!(retry_delay(2).num_seconds(), 60);
+        assert_eq!(retry_delay(4).num_seconds(), 240);
+        assert_eq!(retry_delay(20).num_seconds(), 3600);
+    }
+
+    #[test]
+    fn parse_webhook_config() {
+        let toml_str = r#"
+url = "https://hooks.slack.com/x"
+format = "slack"
+events = ["in_review", "done"]
+"#;
+        let cfg: WebhookConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(cfg.format, WebhookFormat::Slack);
+        assert!(cfg.accepts(NotifyEvent::Done));
+        assert!(!cfg.accepts(NotifyEvent::CiFailed));
+        assert!(cfg.headers.is_empty());
+    }
+}